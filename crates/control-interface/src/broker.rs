@@ -67,6 +67,40 @@ pub mod v1 {
         )
     }
 
+    pub fn rollback_config(
+        topic_prefix: &Option<String>,
+        lattice: &str,
+        config_name: &str,
+        revision: u64,
+    ) -> String {
+        format!(
+            "{}.config.rollback.{config_name}.{revision}",
+            prefix(topic_prefix, lattice, CTL_API_VERSION_1)
+        )
+    }
+
+    pub fn put_target_group(
+        topic_prefix: &Option<String>,
+        lattice: &str,
+        group_name: &str,
+    ) -> String {
+        format!(
+            "{}.targetgroup.put.{group_name}",
+            prefix(topic_prefix, lattice, CTL_API_VERSION_1)
+        )
+    }
+
+    pub fn delete_target_group(
+        topic_prefix: &Option<String>,
+        lattice: &str,
+        group_name: &str,
+    ) -> String {
+        format!(
+            "{}.targetgroup.del.{group_name}",
+            prefix(topic_prefix, lattice, CTL_API_VERSION_1)
+        )
+    }
+
     pub fn put_label(topic_prefix: &Option<String>, lattice: &str, host_id: &str) -> String {
         format!(
             "{}.label.put.{host_id}",
@@ -181,5 +215,46 @@ pub mod v1 {
                 prefix(topic_prefix, lattice, CTL_API_VERSION_1),
             )
         }
+
+        pub fn config_revision(
+            topic_prefix: &Option<String>,
+            lattice: &str,
+            config_name: &str,
+            revision: u64,
+        ) -> String {
+            format!(
+                "{}.config.get.{config_name}.{revision}",
+                prefix(topic_prefix, lattice, CTL_API_VERSION_1),
+            )
+        }
+
+        pub fn config_history(
+            topic_prefix: &Option<String>,
+            lattice: &str,
+            config_name: &str,
+        ) -> String {
+            format!(
+                "{}.config.history.{config_name}",
+                prefix(topic_prefix, lattice, CTL_API_VERSION_1),
+            )
+        }
+
+        pub fn target_group(
+            topic_prefix: &Option<String>,
+            lattice: &str,
+            group_name: &str,
+        ) -> String {
+            format!(
+                "{}.targetgroup.get.{group_name}",
+                prefix(topic_prefix, lattice, CTL_API_VERSION_1),
+            )
+        }
+
+        pub fn target_groups(topic_prefix: &Option<String>, lattice: &str) -> String {
+            format!(
+                "{}.targetgroup.get",
+                prefix(topic_prefix, lattice, CTL_API_VERSION_1),
+            )
+        }
     }
 }
@@ -4,20 +4,28 @@ use core::fmt::{self, Debug};
 use core::time::Duration;
 use std::collections::HashMap;
 
+use async_nats::jetstream::consumer::pull::OrderedConfig;
+use async_nats::jetstream::consumer::DeliverPolicy;
 use async_nats::Subscriber;
-use cloudevents::event::Event;
+use cloudevents::event::{AttributesReader as _, Event};
 use futures::{StreamExt, TryFutureExt};
 use serde::de::DeserializeOwned;
 use tokio::sync::mpsc::Receiver;
 use tracing::{debug, error, instrument, trace};
 
-use crate::types::link::InterfaceLinkDefinition;
+use crate::types::link::{InterfaceLinkDefinition, TargetGroup, TargetGroupMember};
 
+use crate::types::config::ConfigRevision;
+use crate::types::constraint::LabelConstraint;
 use crate::types::ctl::{
     CtlResponse, ScaleComponentCommand, StartProviderCommand, StopHostCommand, StopProviderCommand,
     UpdateComponentCommand,
 };
+use crate::types::event::{
+    host_event_stream_name, host_event_stream_subject, EventReplayStart, HostEvent, ReplayedEvent,
+};
 use crate::types::host::{Host, HostInventory, HostLabel};
+use crate::types::log::{component_log_subject, ComponentLogRecord};
 use crate::types::registry::RegistryCredential;
 use crate::types::rpc::{
     ComponentAuctionAck, ComponentAuctionRequest, DeleteInterfaceLinkDefinitionRequest,
@@ -36,6 +44,7 @@ pub struct Client {
     pub lattice: String,
     timeout: Duration,
     auction_timeout: Duration,
+    js_domain: Option<String>,
 }
 
 impl Debug for Client {
@@ -45,6 +54,7 @@ impl Debug for Client {
             .field("lattice", &self.lattice)
             .field("timeout", &self.timeout)
             .field("auction_timeout", &self.auction_timeout)
+            .field("js_domain", &self.js_domain)
             .finish_non_exhaustive()
     }
 }
@@ -56,6 +66,15 @@ impl Client {
     pub fn nats_client(&self) -> async_nats::Client {
         self.nc.clone()
     }
+
+    /// Get a JetStream context in the JetStream domain of this control client, if one is set
+    fn jetstream(&self) -> async_nats::jetstream::Context {
+        if let Some(domain) = &self.js_domain {
+            async_nats::jetstream::with_domain(self.nc.clone(), domain)
+        } else {
+            async_nats::jetstream::new(self.nc.clone())
+        }
+    }
 }
 
 /// A client builder that can be used to fluently provide configuration settings used to construct
@@ -66,6 +85,7 @@ pub struct ClientBuilder {
     lattice: String,
     timeout: Duration,
     auction_timeout: Duration,
+    js_domain: Option<String>,
 }
 
 impl ClientBuilder {
@@ -79,6 +99,7 @@ impl ClientBuilder {
             lattice: "default".to_string(),
             timeout: Duration::from_secs(2),
             auction_timeout: Duration::from_secs(5),
+            js_domain: None,
         }
     }
 
@@ -119,6 +140,16 @@ impl ClientBuilder {
         }
     }
 
+    /// Sets the JetStream domain used by the client to access JetStream resources of the lattice,
+    /// such as the host event stream. If not set, the JetStream domain of the NATS account is used
+    #[must_use]
+    pub fn js_domain(self, domain: impl Into<String>) -> ClientBuilder {
+        ClientBuilder {
+            js_domain: Some(domain.into()),
+            ..self
+        }
+    }
+
     /// Constructs the client with the given configuration from the builder
     #[must_use]
     pub fn build(self) -> Client {
@@ -128,6 +159,7 @@ impl ClientBuilder {
             lattice: self.lattice,
             timeout: self.timeout,
             auction_timeout: self.auction_timeout,
+            js_domain: self.js_domain,
         }
     }
 }
@@ -209,12 +241,35 @@ impl Client {
         component_ref: &str,
         component_id: &str,
         constraints: HashMap<String, String>,
+    ) -> Result<Vec<CtlResponse<ComponentAuctionAck>>> {
+        self.perform_component_auction_with_label_constraints(
+            component_ref,
+            component_id,
+            constraints,
+            Vec::default(),
+        )
+        .await
+    }
+
+    /// Performs an component auction within the lattice like [`Client::perform_component_auction`],
+    /// additionally requiring bidding hosts to satisfy all of `label_constraints`.
+    ///
+    /// Acknowledgements carry the capacity of the bidding host, if reported, which can be used to
+    /// choose the least-loaded host.
+    #[instrument(level = "debug", skip_all)]
+    pub async fn perform_component_auction_with_label_constraints(
+        &self,
+        component_ref: &str,
+        component_id: &str,
+        constraints: HashMap<String, String>,
+        label_constraints: Vec<LabelConstraint>,
     ) -> Result<Vec<CtlResponse<ComponentAuctionAck>>> {
         let subject = broker::v1::component_auction_subject(&self.topic_prefix, &self.lattice);
         let bytes = json_serialize(ComponentAuctionRequest {
             component_ref: parse_identifier(&IdentifierKind::ActorRef, component_ref)?,
             component_id: parse_identifier(&IdentifierKind::ComponentId, component_id)?,
             constraints,
+            label_constraints,
         })?;
         debug!("component_auction:publish {}", &subject);
         self.publish_and_wait(subject, bytes).await
@@ -231,12 +286,35 @@ impl Client {
         provider_ref: &str,
         provider_id: &str,
         constraints: HashMap<String, String>,
+    ) -> Result<Vec<CtlResponse<ProviderAuctionAck>>> {
+        self.perform_provider_auction_with_label_constraints(
+            provider_ref,
+            provider_id,
+            constraints,
+            Vec::default(),
+        )
+        .await
+    }
+
+    /// Performs a provider auction within the lattice like [`Client::perform_provider_auction`],
+    /// additionally requiring bidding hosts to satisfy all of `label_constraints`.
+    ///
+    /// Acknowledgements carry the capacity of the bidding host, if reported, which can be used to
+    /// choose the least-loaded host.
+    #[instrument(level = "debug", skip_all)]
+    pub async fn perform_provider_auction_with_label_constraints(
+        &self,
+        provider_ref: &str,
+        provider_id: &str,
+        constraints: HashMap<String, String>,
+        label_constraints: Vec<LabelConstraint>,
     ) -> Result<Vec<CtlResponse<ProviderAuctionAck>>> {
         let subject = broker::v1::provider_auction_subject(&self.topic_prefix, &self.lattice);
         let bytes = json_serialize(ProviderAuctionRequest {
             provider_ref: parse_identifier(&IdentifierKind::ProviderRef, provider_ref)?,
             provider_id: parse_identifier(&IdentifierKind::ComponentId, provider_id)?,
             constraints,
+            label_constraints,
         })?;
         debug!("provider_auction:publish {}", &subject);
         self.publish_and_wait(subject, bytes).await
@@ -452,6 +530,190 @@ impl Client {
         }
     }
 
+    /// Get the revision history of the named config item, oldest first. The number of revisions
+    /// kept is determined by the config bucket of the lattice, revisions deleting the config are
+    /// included.
+    ///
+    /// Like [`Client::get_config`], the response is successful with no value if the config item
+    /// has never existed.
+    #[instrument(level = "debug", skip_all)]
+    pub async fn get_config_history(
+        &self,
+        config_name: &str,
+    ) -> Result<CtlResponse<Vec<ConfigRevision>>> {
+        let subject =
+            broker::v1::queries::config_history(&self.topic_prefix, &self.lattice, config_name);
+        debug!(%subject, %config_name, "Getting config history");
+        match self
+            .request_timeout(subject, Vec::default(), self.timeout)
+            .await
+        {
+            Ok(msg) => json_deserialize(&msg.payload),
+            Err(e) => {
+                Err(format!("Did not receive a response to get config history request: {e}").into())
+            }
+        }
+    }
+
+    /// Get a specific revision of the named config item. The response is successful with no value
+    /// if the revision does not exist or is no longer kept.
+    #[instrument(level = "debug", skip_all)]
+    pub async fn get_config_revision(
+        &self,
+        config_name: &str,
+        revision: u64,
+    ) -> Result<CtlResponse<ConfigRevision>> {
+        let subject = broker::v1::queries::config_revision(
+            &self.topic_prefix,
+            &self.lattice,
+            config_name,
+            revision,
+        );
+        debug!(%subject, %config_name, revision, "Getting config revision");
+        match self
+            .request_timeout(subject, Vec::default(), self.timeout)
+            .await
+        {
+            Ok(msg) => json_deserialize(&msg.payload),
+            Err(e) => Err(format!(
+                "Did not receive a response to get config revision request: {e}"
+            )
+            .into()),
+        }
+    }
+
+    /// Roll the named config item back to a previous revision. The contents of that revision are
+    /// put as a new revision, so the rollback itself shows up in the history of the config.
+    #[instrument(level = "debug", skip_all)]
+    pub async fn rollback_config(
+        &self,
+        config_name: &str,
+        revision: u64,
+    ) -> Result<CtlResponse<()>> {
+        let subject =
+            broker::v1::rollback_config(&self.topic_prefix, &self.lattice, config_name, revision);
+        debug!(%subject, %config_name, revision, "Rolling back config");
+        match self
+            .request_timeout(subject, Vec::default(), self.timeout)
+            .await
+        {
+            Ok(msg) => json_deserialize(&msg.payload),
+            Err(e) => {
+                Err(format!("Did not receive a response to rollback config request: {e}").into())
+            }
+        }
+    }
+
+    /// Put a new (or replace an existing) link target group. Links with a target of
+    /// `group:<name>` spread invocations across the members of the group.
+    ///
+    /// Group names must be valid NATS subject strings and not contain any `.` or `>` characters.
+    #[instrument(level = "debug", skip_all)]
+    pub async fn put_target_group(&self, group: &TargetGroup) -> Result<CtlResponse<()>> {
+        let subject = broker::v1::put_target_group(&self.topic_prefix, &self.lattice, &group.name);
+        debug!(%subject, name = %group.name, "Putting target group");
+        let bytes = json_serialize(group)?;
+        match self.request_timeout(subject, bytes, self.timeout).await {
+            Ok(msg) => json_deserialize(&msg.payload),
+            Err(e) => {
+                Err(format!("Did not receive a response to put target group request: {e}").into())
+            }
+        }
+    }
+
+    /// Delete the named link target group. Links targeting the group fail until it is put again.
+    #[instrument(level = "debug", skip_all)]
+    pub async fn delete_target_group(&self, group_name: &str) -> Result<CtlResponse<()>> {
+        let subject =
+            broker::v1::delete_target_group(&self.topic_prefix, &self.lattice, group_name);
+        debug!(%subject, %group_name, "Deleting target group");
+        match self
+            .request_timeout(subject, Vec::default(), self.timeout)
+            .await
+        {
+            Ok(msg) => json_deserialize(&msg.payload),
+            Err(e) => Err(format!(
+                "Did not receive a response to delete target group request: {e}"
+            )
+            .into()),
+        }
+    }
+
+    /// Get the named link target group. Like [`Client::get_config`], the response is successful
+    /// with no value if the group does not exist.
+    #[instrument(level = "debug", skip_all)]
+    pub async fn get_target_group(&self, group_name: &str) -> Result<CtlResponse<TargetGroup>> {
+        let subject =
+            broker::v1::queries::target_group(&self.topic_prefix, &self.lattice, group_name);
+        debug!(%subject, %group_name, "Getting target group");
+        match self
+            .request_timeout(subject, Vec::default(), self.timeout)
+            .await
+        {
+            Ok(msg) => json_deserialize(&msg.payload),
+            Err(e) => {
+                Err(format!("Did not receive a response to get target group request: {e}").into())
+            }
+        }
+    }
+
+    /// Get all link target groups in the lattice
+    #[instrument(level = "debug", skip_all)]
+    pub async fn get_target_groups(&self) -> Result<CtlResponse<Vec<TargetGroup>>> {
+        let subject = broker::v1::queries::target_groups(&self.topic_prefix, &self.lattice);
+        debug!(%subject, "Getting target groups");
+        match self
+            .request_timeout(subject, Vec::default(), self.timeout)
+            .await
+        {
+            Ok(msg) => json_deserialize(&msg.payload),
+            Err(e) => {
+                Err(format!("Did not receive a response to get target groups request: {e}").into())
+            }
+        }
+    }
+
+    /// Add `member` to the link target group `group_name`, replacing an existing member with the
+    /// same target. The group is created with round-robin selection if it does not exist.
+    ///
+    /// Membership changes are not atomic, concurrent changes to the same group may be lost.
+    #[instrument(level = "debug", skip_all)]
+    pub async fn add_target_group_member(
+        &self,
+        group_name: &str,
+        member: TargetGroupMember,
+    ) -> Result<CtlResponse<()>> {
+        let mut group = self.get_target_group_or_default(group_name).await?;
+        group.members.retain(|m| m.target != member.target);
+        group.members.push(member);
+        self.put_target_group(&group).await
+    }
+
+    /// Remove the member with `target` from the link target group `group_name`
+    ///
+    /// Membership changes are not atomic, concurrent changes to the same group may be lost.
+    #[instrument(level = "debug", skip_all)]
+    pub async fn remove_target_group_member(
+        &self,
+        group_name: &str,
+        target: &str,
+    ) -> Result<CtlResponse<()>> {
+        let mut group = self.get_target_group_or_default(group_name).await?;
+        group.members.retain(|m| m.target != target);
+        self.put_target_group(&group).await
+    }
+
+    async fn get_target_group_or_default(&self, group_name: &str) -> Result<TargetGroup> {
+        let res = self.get_target_group(group_name).await?;
+        if !res.success {
+            return Err(res.message.into());
+        }
+        Ok(res.response.unwrap_or_else(|| TargetGroup {
+            name: group_name.to_string(),
+            ..TargetGroup::default()
+        }))
+    }
+
     /// Put a new (or update an existing) label on the given host.
     ///
     /// # Errors
@@ -687,6 +949,145 @@ impl Client {
         });
         Ok(receiver)
     }
+
+    /// Returns the receiver end of a channel that subscribes to the lattice event stream and
+    /// decodes each event into a [`HostEvent`]. Events that cannot be decoded are logged and
+    /// skipped.
+    ///
+    /// Any event types not supported by this version of the client are subscribed to, but will
+    /// never be received.
+    #[allow(clippy::missing_errors_doc)] // TODO: Document errors
+    pub async fn host_events_receiver(
+        &self,
+        event_types: Vec<String>,
+    ) -> Result<Receiver<HostEvent>> {
+        let mut events = self.events_receiver(event_types).await?;
+        let (sender, receiver) = tokio::sync::mpsc::channel(5000);
+        tokio::spawn(async move {
+            while let Some(evt) = events.recv().await {
+                let evt = match HostEvent::try_from(&evt) {
+                    Ok(evt) => evt,
+                    Err(error) => {
+                        error!(%error, ty = evt.ty(), "failed to decode host event");
+                        continue;
+                    }
+                };
+                let Ok(()) = sender.send(evt).await else {
+                    break;
+                };
+            }
+        });
+        Ok(receiver)
+    }
+
+    /// Returns the receiver end of a channel that receives log records of the component
+    /// `component_id` from all hosts in the lattice, or of all components if `None`.
+    ///
+    /// This requires hosts in the lattice to be configured to forward component logs.
+    #[allow(clippy::missing_errors_doc)] // TODO: Document errors
+    pub async fn component_logs_receiver(
+        &self,
+        component_id: Option<&str>,
+    ) -> Result<Receiver<ComponentLogRecord>> {
+        let subject = component_log_subject(&self.lattice, component_id.unwrap_or("*"));
+        let mut sub = self.nc.subscribe(subject).await?;
+        let (sender, receiver) = tokio::sync::mpsc::channel(5000);
+        tokio::spawn(async move {
+            while let Some(msg) = sub.next().await {
+                let Ok(record) = json_deserialize::<ComponentLogRecord>(&msg.payload) else {
+                    error!("Object received on component log subject was not a log record");
+                    continue;
+                };
+                let Ok(()) = sender.send(record).await else {
+                    break;
+                };
+            }
+        });
+        Ok(receiver)
+    }
+
+    /// Returns the receiver end of a channel that replays events from the lattice event stream,
+    /// starting at `start`, followed by any events published afterwards.
+    ///
+    /// This requires hosts in the lattice to be configured to write events to a JetStream stream.
+    /// If `event_types` is empty, all event types are replayed.
+    ///
+    /// # Example Usage
+    /// ```no_run
+    /// use wasmcloud_control_interface::{ClientBuilder, EventReplayStart};
+    /// async {
+    ///   let nc = async_nats::connect("127.0.0.1:4222").await.unwrap();
+    ///   let client = ClientBuilder::new(nc).build();
+    ///   let mut receiver = client
+    ///       .replay_events(EventReplayStart::Sequence(42), vec!["component_scaled".to_string()])
+    ///       .await
+    ///       .unwrap();
+    ///   while let Some(evt) = receiver.recv().await {
+    ///       println!("Event {} received: {:?}", evt.sequence, evt.event);
+    ///   }
+    /// };
+    /// ```
+    #[allow(clippy::missing_errors_doc)] // TODO: Document errors
+    pub async fn replay_events(
+        &self,
+        start: EventReplayStart,
+        event_types: Vec<String>,
+    ) -> Result<Receiver<ReplayedEvent>> {
+        let stream = self
+            .jetstream()
+            .get_stream(host_event_stream_name(&self.lattice))
+            .await?;
+        let deliver_policy = match start {
+            EventReplayStart::All => DeliverPolicy::All,
+            EventReplayStart::Sequence(start_sequence) => {
+                DeliverPolicy::ByStartSequence { start_sequence }
+            }
+            EventReplayStart::Time(start_time) => DeliverPolicy::ByStartTime {
+                start_time: start_time.into(),
+            },
+        };
+        let filter_subjects = event_types
+            .iter()
+            .map(|event_type| host_event_stream_subject(&self.lattice, event_type))
+            .collect();
+        let mut messages = stream
+            .create_consumer(OrderedConfig {
+                deliver_policy,
+                filter_subjects,
+                ..Default::default()
+            })
+            .await?
+            .messages()
+            .await?;
+        let (sender, receiver) = tokio::sync::mpsc::channel(5000);
+        tokio::spawn(async move {
+            while let Some(msg) = messages.next().await {
+                let msg = match msg {
+                    Ok(msg) => msg,
+                    Err(error) => {
+                        error!(%error, "failed to receive event from event stream");
+                        break;
+                    }
+                };
+                let sequence = match msg.info() {
+                    Ok(info) => info.stream_sequence,
+                    Err(error) => {
+                        error!(%error, "event stream message did not contain stream info");
+                        continue;
+                    }
+                };
+                let Ok(event) = json_deserialize::<Event>(&msg.payload) else {
+                    error!("Object received on event stream was not a CloudEvent");
+                    continue;
+                };
+                trace!(sequence, "replayed event: {:?}", event);
+                let Ok(()) = sender.send(ReplayedEvent { sequence, event }).await else {
+                    break;
+                };
+            }
+        });
+        Ok(receiver)
+    }
 }
 
 /// Collect results until timeout has elapsed
@@ -902,6 +1303,8 @@ mod tests {
                 interfaces: vec!["incoming-handler".to_string()],
                 source_config: vec![],
                 target_config: vec![],
+                status: None,
+                retry_policy: None,
             })
             .await
             .expect("should be able to put link");
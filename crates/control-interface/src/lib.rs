@@ -20,9 +20,16 @@ pub use client::{collect_sub_timeout, Client, ClientBuilder};
 
 mod types;
 pub use types::component::*;
+pub use types::config::*;
+pub use types::constraint::*;
 pub use types::ctl::*;
+pub use types::event::*;
 pub use types::host::*;
-pub use types::link::InterfaceLinkDefinition;
+pub use types::link::{
+    target_group_link_target, InterfaceLinkDefinition, LinkStatus, RetryPolicy, TargetGroup,
+    TargetGroupMember, TargetSelection, TARGET_GROUP_PREFIX,
+};
+pub use types::log::*;
 pub use types::provider::*;
 pub use types::registry::*;
 pub use types::rpc::*;
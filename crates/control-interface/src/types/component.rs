@@ -28,6 +28,14 @@ pub struct ComponentDescription {
     /// The maximum number of concurrent requests this instance can handle
     #[serde(default)]
     pub max_instances: u32,
+    /// The interfaces imported by this component, as recorded in its claims,
+    /// e.g. `wasi:keyvalue/store@0.2.0-draft`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub imports: Option<Vec<String>>,
+    /// The interfaces exported by this component, as recorded in its claims,
+    /// e.g. `wasi:http/incoming-handler@0.2.0`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exports: Option<Vec<String>>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
@@ -0,0 +1,21 @@
+//! Data types used when managing named configuration on a wasmCloud lattice
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// A single revision of a named config, as stored in the lattice config bucket
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ConfigRevision {
+    /// Revision number of the config, increasing with every put or delete in the lattice
+    pub revision: u64,
+    /// Time at which the revision was created, in RFC3339 format
+    #[serde(default)]
+    pub created_at: String,
+    /// Whether this revision deleted the config
+    #[serde(default)]
+    pub deleted: bool,
+    /// Contents of the config at this revision, empty if the revision deleted the config
+    #[serde(default)]
+    pub config: HashMap<String, String>,
+}
@@ -0,0 +1,182 @@
+//! Data types for expressing placement constraints on host labels during auctions
+
+use core::fmt;
+use core::str::FromStr;
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// A constraint on a single host label, which must be satisfied by a host for it to bid in an
+/// auction
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct LabelConstraint {
+    /// The label the constraint applies to
+    pub key: String,
+    /// The operator applied to the value of the label
+    #[serde(flatten)]
+    pub operator: LabelConstraintOperator,
+}
+
+/// The operator of a [`LabelConstraint`]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum LabelConstraintOperator {
+    /// The label must be set to `value`
+    Equals {
+        /// The required value of the label
+        value: String,
+    },
+    /// The label must not be set to `value`. A host without the label satisfies this constraint.
+    NotEquals {
+        /// The value the label must not be set to
+        value: String,
+    },
+    /// The label must be set to one of `values`
+    In {
+        /// The set of accepted values of the label
+        values: Vec<String>,
+    },
+    /// The label must be set, to any value
+    Exists,
+    /// The label must be set to a semantic version matching the requirement `range`,
+    /// e.g. `>=1.2, <2`. A leading `v` in the label value is ignored.
+    SemverRange {
+        /// The semantic version requirement
+        range: String,
+    },
+}
+
+impl LabelConstraint {
+    /// Returns whether `labels` satisfy this constraint
+    #[must_use]
+    pub fn matches(&self, labels: &HashMap<String, String>) -> bool {
+        let label = labels.get(&self.key);
+        match &self.operator {
+            LabelConstraintOperator::Equals { value } => label == Some(value),
+            LabelConstraintOperator::NotEquals { value } => label != Some(value),
+            LabelConstraintOperator::In { values } => label.is_some_and(|l| values.contains(l)),
+            LabelConstraintOperator::Exists => label.is_some(),
+            LabelConstraintOperator::SemverRange { range } => {
+                let Ok(range) = semver::VersionReq::parse(range) else {
+                    return false;
+                };
+                label.is_some_and(|l| {
+                    semver::Version::parse(l.strip_prefix('v').unwrap_or(l))
+                        .is_ok_and(|version| range.matches(&version))
+                })
+            }
+        }
+    }
+}
+
+/// Parses a constraint from its textual form:
+/// - `key` requires the label to exist
+/// - `key=value` requires the label to equal `value`
+/// - `key!=value` requires the label not to equal `value`
+/// - `key=a|b|c` requires the label to be one of `a`, `b` or `c`
+/// - `key~=range` requires the label to be a semantic version matching `range`
+impl FromStr for LabelConstraint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, operator) = if let Some((key, value)) = s.split_once("!=") {
+            (
+                key,
+                LabelConstraintOperator::NotEquals {
+                    value: value.to_string(),
+                },
+            )
+        } else if let Some((key, range)) = s.split_once("~=") {
+            semver::VersionReq::parse(range)
+                .map_err(|e| format!("invalid semantic version range `{range}`: {e}"))?;
+            (
+                key,
+                LabelConstraintOperator::SemverRange {
+                    range: range.to_string(),
+                },
+            )
+        } else if let Some((key, value)) = s.split_once('=') {
+            if value.contains('|') {
+                (
+                    key,
+                    LabelConstraintOperator::In {
+                        values: value.split('|').map(ToString::to_string).collect(),
+                    },
+                )
+            } else {
+                (
+                    key,
+                    LabelConstraintOperator::Equals {
+                        value: value.to_string(),
+                    },
+                )
+            }
+        } else {
+            (s, LabelConstraintOperator::Exists)
+        };
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(format!("constraint `{s}` does not specify a label"));
+        }
+        Ok(Self {
+            key: key.to_string(),
+            operator,
+        })
+    }
+}
+
+impl fmt::Display for LabelConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { key, operator } = self;
+        match operator {
+            LabelConstraintOperator::Equals { value } => write!(f, "{key}={value}"),
+            LabelConstraintOperator::NotEquals { value } => write!(f, "{key}!={value}"),
+            LabelConstraintOperator::In { values } => write!(f, "{key}={}", values.join("|")),
+            LabelConstraintOperator::Exists => write!(f, "{key}"),
+            LabelConstraintOperator::SemverRange { range } => write!(f, "{key}~={range}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::{LabelConstraint, LabelConstraintOperator};
+
+    #[test]
+    fn label_constraints() {
+        let labels = HashMap::from([
+            ("region".to_string(), "us-east-1".to_string()),
+            ("version".to_string(), "v1.4.2".to_string()),
+        ]);
+        for (constraint, expected) in [
+            ("region", true),
+            ("zone", false),
+            ("region=us-east-1", true),
+            ("region!=us-east-1", false),
+            ("zone!=a", true),
+            ("region=eu-west-1|us-east-1", true),
+            ("region=eu-west-1|eu-west-2", false),
+            ("version~=>=1.2, <2", true),
+            ("version~=^2", false),
+        ] {
+            let constraint: LabelConstraint = constraint.parse().expect("failed to parse");
+            assert_eq!(constraint.matches(&labels), expected, "{constraint}");
+        }
+        assert!("=a".parse::<LabelConstraint>().is_err());
+        assert!("version~=not a range".parse::<LabelConstraint>().is_err());
+
+        let constraint = LabelConstraint {
+            key: "region".to_string(),
+            operator: LabelConstraintOperator::In {
+                values: vec!["a".to_string(), "b".to_string()],
+            },
+        };
+        assert_eq!(
+            serde_json::to_value(&constraint).expect("failed to serialize"),
+            serde_json::json!({"key": "region", "op": "in", "values": ["a", "b"]})
+        );
+    }
+}
@@ -0,0 +1,861 @@
+//! Data types for the CloudEvents published by hosts on a wasmCloud lattice
+//!
+//! Every host event is published on `wasmbus.evt.<lattice>.<name>` as a CloudEvent of type
+//! `com.wasmcloud.lattice.<name>`, with a JSON payload described by the types in this module.
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::SystemTime;
+
+use cloudevents::{AttributesReader, Data, Event};
+use serde::{Deserialize, Serialize};
+
+use crate::types::host::{HostInventory, HostReadiness};
+use crate::types::link::InterfaceLinkDefinition;
+use crate::{ComponentId, LinkName, Result, WitNamespace, WitPackage};
+
+/// The version of the host event payload schema described by [`HostEvent`].
+///
+/// This is incremented whenever a payload changes in a way that is not backwards compatible.
+pub const HOST_EVENT_SCHEMA_VERSION: &str = "1";
+
+/// Name of the CloudEvent extension attribute carrying the [`HOST_EVENT_SCHEMA_VERSION`]
+pub const HOST_EVENT_SCHEMA_VERSION_EXTENSION: &str = "schemaversion";
+
+/// Prefix of the CloudEvent type of every host event
+pub const HOST_EVENT_TYPE_PREFIX: &str = "com.wasmcloud.lattice.";
+
+/// Returns the name of the JetStream stream hosts in `lattice` write events to, if enabled
+#[must_use]
+pub fn host_event_stream_name(lattice: &str) -> String {
+    format!("WASMBUS_EVENTS_{lattice}")
+}
+
+/// Returns the subject hosts in `lattice` write events named `name` to on the event stream.
+///
+/// This is deliberately distinct from `wasmbus.evt.<lattice>.<name>`, so that the event stream
+/// does not overlap with streams other tools may have created on the live event subjects.
+#[must_use]
+pub fn host_event_stream_subject(lattice: &str, name: &str) -> String {
+    format!("wasmbus.evtstream.{lattice}.{name}")
+}
+
+/// Where to start replaying events from the host event stream
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EventReplayStart {
+    /// Replay all events still retained in the stream
+    #[default]
+    All,
+    /// Replay events starting at the given stream sequence number
+    Sequence(u64),
+    /// Replay events published at or after the given time
+    Time(SystemTime),
+}
+
+/// An event replayed from the host event stream
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReplayedEvent {
+    /// The stream sequence number of the event, which can be used to resume a replay
+    pub sequence: u64,
+    /// The event
+    pub event: Event,
+}
+
+/// An event published by a host on a wasmCloud lattice
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HostEvent {
+    /// A host has started
+    HostStarted(HostStarted),
+    /// A host has stopped
+    HostStopped(HostStopped),
+    /// A host is alive, sent periodically
+    HostHeartbeat(HostHeartbeat),
+    /// The labels of a host changed
+    LabelsChanged(LabelsChanged),
+    /// A component was scaled, including to zero
+    ComponentScaled(ComponentScaled),
+    /// A component failed to scale
+    ComponentScaleFailed(ComponentScaleFailed),
+    /// A link was put
+    LinkdefSet(LinkdefSet),
+    /// A link failed to be put
+    LinkdefSetFailed(LinkdefSetFailed),
+    /// A link was deleted
+    LinkdefDeleted(LinkdefDeleted),
+    /// A provider was started
+    ProviderStarted(ProviderStarted),
+    /// A provider failed to start
+    ProviderStartFailed(ProviderStartFailed),
+    /// A provider was stopped
+    ProviderStopped(ProviderStopped),
+    /// A provider became healthy
+    HealthCheckPassed(ProviderHealthCheck),
+    /// A provider became unhealthy
+    HealthCheckFailed(ProviderHealthCheck),
+    /// A provider health check did not change its health status
+    HealthCheckStatus(ProviderHealthCheck),
+    /// A named config was put
+    ConfigSet(ConfigSet),
+    /// A named config was deleted
+    ConfigDeleted(ConfigDeleted),
+    /// The circuit breaker of a lattice target changed its state
+    CircuitBreakerStateChanged(CircuitBreakerStateChanged),
+    /// Secrets of a running component or provider were rotated
+    SecretsRotated(SecretsRotated),
+    /// The claims of a running component or provider are about to expire, or expired
+    ClaimsExpiring(ClaimsExpiring),
+}
+
+impl HostEvent {
+    /// The names of all host events, as used in event subjects and types
+    pub const NAMES: &'static [&'static str] = &[
+        "host_started",
+        "host_stopped",
+        "host_heartbeat",
+        "labels_changed",
+        "component_scaled",
+        "component_scale_failed",
+        "linkdef_set",
+        "linkdef_set_failed",
+        "linkdef_deleted",
+        "provider_started",
+        "provider_start_failed",
+        "provider_stopped",
+        "health_check_passed",
+        "health_check_failed",
+        "health_check_status",
+        "config_set",
+        "config_deleted",
+        "circuit_breaker_state_changed",
+        "secrets_rotated",
+        "claims_expiring",
+    ];
+
+    /// The name of this event, e.g. `component_scaled`
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::HostStarted(_) => "host_started",
+            Self::HostStopped(_) => "host_stopped",
+            Self::HostHeartbeat(_) => "host_heartbeat",
+            Self::LabelsChanged(_) => "labels_changed",
+            Self::ComponentScaled(_) => "component_scaled",
+            Self::ComponentScaleFailed(_) => "component_scale_failed",
+            Self::LinkdefSet(_) => "linkdef_set",
+            Self::LinkdefSetFailed(_) => "linkdef_set_failed",
+            Self::LinkdefDeleted(_) => "linkdef_deleted",
+            Self::ProviderStarted(_) => "provider_started",
+            Self::ProviderStartFailed(_) => "provider_start_failed",
+            Self::ProviderStopped(_) => "provider_stopped",
+            Self::HealthCheckPassed(_) => "health_check_passed",
+            Self::HealthCheckFailed(_) => "health_check_failed",
+            Self::HealthCheckStatus(_) => "health_check_status",
+            Self::ConfigSet(_) => "config_set",
+            Self::ConfigDeleted(_) => "config_deleted",
+            Self::CircuitBreakerStateChanged(_) => "circuit_breaker_state_changed",
+            Self::SecretsRotated(_) => "secrets_rotated",
+            Self::ClaimsExpiring(_) => "claims_expiring",
+        }
+    }
+
+    /// The CloudEvent type of this event, e.g. `com.wasmcloud.lattice.component_scaled`
+    #[must_use]
+    pub fn event_type(&self) -> String {
+        format!("{HOST_EVENT_TYPE_PREFIX}{}", self.name())
+    }
+
+    /// Serialize the payload of this event
+    #[allow(clippy::missing_errors_doc)]
+    pub fn to_data(&self) -> serde_json::Result<serde_json::Value> {
+        match self {
+            Self::HostStarted(ev) => serde_json::to_value(ev),
+            Self::HostStopped(ev) => serde_json::to_value(ev),
+            Self::HostHeartbeat(ev) => serde_json::to_value(ev),
+            Self::LabelsChanged(ev) => serde_json::to_value(ev),
+            Self::ComponentScaled(ev) => serde_json::to_value(ev),
+            Self::ComponentScaleFailed(ev) => serde_json::to_value(ev),
+            Self::LinkdefSet(ev) => serde_json::to_value(ev),
+            Self::LinkdefSetFailed(ev) => serde_json::to_value(ev),
+            Self::LinkdefDeleted(ev) => serde_json::to_value(ev),
+            Self::ProviderStarted(ev) => serde_json::to_value(ev),
+            Self::ProviderStartFailed(ev) => serde_json::to_value(ev),
+            Self::ProviderStopped(ev) => serde_json::to_value(ev),
+            Self::HealthCheckPassed(ev)
+            | Self::HealthCheckFailed(ev)
+            | Self::HealthCheckStatus(ev) => serde_json::to_value(ev),
+            Self::ConfigSet(ev) => serde_json::to_value(ev),
+            Self::ConfigDeleted(ev) => serde_json::to_value(ev),
+            Self::CircuitBreakerStateChanged(ev) => serde_json::to_value(ev),
+            Self::SecretsRotated(ev) => serde_json::to_value(ev),
+            Self::ClaimsExpiring(ev) => serde_json::to_value(ev),
+        }
+    }
+
+    /// Deserialize an event from its name, e.g. `component_scaled`, and payload
+    #[allow(clippy::missing_errors_doc)]
+    pub fn from_data(name: &str, data: serde_json::Value) -> Result<Self> {
+        let ev = match name {
+            "host_started" => Self::HostStarted(serde_json::from_value(data)?),
+            "host_stopped" => Self::HostStopped(serde_json::from_value(data)?),
+            "host_heartbeat" => Self::HostHeartbeat(serde_json::from_value(data)?),
+            "labels_changed" => Self::LabelsChanged(serde_json::from_value(data)?),
+            "component_scaled" => Self::ComponentScaled(serde_json::from_value(data)?),
+            "component_scale_failed" => Self::ComponentScaleFailed(serde_json::from_value(data)?),
+            "linkdef_set" => Self::LinkdefSet(serde_json::from_value(data)?),
+            "linkdef_set_failed" => Self::LinkdefSetFailed(serde_json::from_value(data)?),
+            "linkdef_deleted" => Self::LinkdefDeleted(serde_json::from_value(data)?),
+            "provider_started" => Self::ProviderStarted(serde_json::from_value(data)?),
+            "provider_start_failed" => Self::ProviderStartFailed(serde_json::from_value(data)?),
+            "provider_stopped" => Self::ProviderStopped(serde_json::from_value(data)?),
+            "health_check_passed" => Self::HealthCheckPassed(serde_json::from_value(data)?),
+            "health_check_failed" => Self::HealthCheckFailed(serde_json::from_value(data)?),
+            "health_check_status" => Self::HealthCheckStatus(serde_json::from_value(data)?),
+            "config_set" => Self::ConfigSet(serde_json::from_value(data)?),
+            "config_deleted" => Self::ConfigDeleted(serde_json::from_value(data)?),
+            "circuit_breaker_state_changed" => {
+                Self::CircuitBreakerStateChanged(serde_json::from_value(data)?)
+            }
+            "secrets_rotated" => Self::SecretsRotated(serde_json::from_value(data)?),
+            "claims_expiring" => Self::ClaimsExpiring(serde_json::from_value(data)?),
+            _ => return Err(format!("unknown host event `{name}`").into()),
+        };
+        Ok(ev)
+    }
+}
+
+impl TryFrom<&Event> for HostEvent {
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    /// Decode a [`HostEvent`] from a CloudEvent published by a host.
+    ///
+    /// Events without a schema version are assumed to be compatible with
+    /// [`HOST_EVENT_SCHEMA_VERSION`], since hosts did not always set one.
+    fn try_from(ev: &Event) -> Result<Self> {
+        let Some(name) = ev.ty().strip_prefix(HOST_EVENT_TYPE_PREFIX) else {
+            return Err(format!("`{}` is not a host event type", ev.ty()).into());
+        };
+        if let Some(version) = ev.extension(HOST_EVENT_SCHEMA_VERSION_EXTENSION) {
+            let version = version.to_string();
+            if version != HOST_EVENT_SCHEMA_VERSION {
+                return Err(format!(
+                    "unsupported host event schema version `{version}`, expected `{HOST_EVENT_SCHEMA_VERSION}`"
+                )
+                .into());
+            }
+        }
+        let data = match ev.data() {
+            Some(Data::Json(data)) => data.clone(),
+            Some(Data::String(data)) => serde_json::from_str(data)?,
+            Some(Data::Binary(data)) => serde_json::from_slice(data)?,
+            None => serde_json::Value::Null,
+        };
+        Self::from_data(name, data)
+    }
+}
+
+/// Payload of the `host_started` event
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct HostStarted {
+    /// The host's human-readable friendly name
+    #[serde(default)]
+    pub friendly_name: String,
+    /// The host's labels
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+    /// The host uptime in seconds
+    #[serde(default)]
+    pub uptime_seconds: u64,
+    /// The host version
+    #[serde(default)]
+    pub version: String,
+}
+
+/// Payload of the `host_stopped` event
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct HostStopped {
+    /// The host's labels
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+}
+
+/// Payload of the `host_heartbeat` event
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct HostHeartbeat {
+    /// The inventory of the host at the time of the heartbeat
+    #[serde(flatten)]
+    pub inventory: HostInventory,
+    /// The result of the host readiness probes, if the host reports them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub readiness: Option<HostReadiness>,
+}
+
+/// Payload of the `labels_changed` event
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct LabelsChanged {
+    /// The host's unique ID
+    #[serde(default)]
+    pub host_id: String,
+    /// The host's labels after the change
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+}
+
+/// Claims of a component, as included in host events
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ComponentClaims {
+    /// The issuer of the claims
+    #[serde(default)]
+    pub issuer: String,
+    /// Call alias of the component, if any
+    #[serde(default)]
+    pub call_alias: Option<String>,
+    /// Tags of the component, if any
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    /// Name of the component, if any
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Version of the component, if any
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Revision of the component, if any
+    #[serde(default)]
+    pub revision: Option<i32>,
+    /// Human-readable time from which the claims are valid
+    #[serde(default)]
+    pub not_before_human: String,
+    /// Human-readable time at which the claims expire
+    #[serde(default)]
+    pub expires_human: String,
+}
+
+/// Payload of the `component_scaled` event
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ComponentScaled {
+    /// The public key of the component, if it is signed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+    /// The claims of the component, if it is signed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claims: Option<ComponentClaims>,
+    /// The annotations the component was scaled with
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
+    /// The ID of the host the component was scaled on
+    #[serde(default)]
+    pub host_id: String,
+    /// Image reference of the component
+    #[serde(default)]
+    pub image_ref: String,
+    /// The maximum number of concurrent instances of the component after scaling
+    #[serde(default)]
+    pub max_instances: u32,
+    /// The unique component identifier
+    #[serde(default)]
+    pub component_id: ComponentId,
+}
+
+/// Payload of the `component_scale_failed` event
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ComponentScaleFailed {
+    /// The public key of the component, if it is signed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+    /// The unique component identifier
+    #[serde(default)]
+    pub component_id: ComponentId,
+    /// The annotations the component was scaled with
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
+    /// The ID of the host the component failed to scale on
+    #[serde(default)]
+    pub host_id: String,
+    /// Image reference of the component
+    #[serde(default)]
+    pub image_ref: String,
+    /// The requested maximum number of concurrent instances of the component
+    #[serde(default)]
+    pub max_instances: u32,
+    /// Why scaling failed
+    #[serde(default)]
+    pub error: String,
+}
+
+/// Payload of the `linkdef_set` event
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct LinkdefSet {
+    /// The link that was put
+    #[serde(flatten)]
+    pub link: InterfaceLinkDefinition,
+}
+
+/// Payload of the `linkdef_set_failed` event
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct LinkdefSetFailed {
+    /// The link that failed to be put
+    #[serde(flatten)]
+    pub link: InterfaceLinkDefinition,
+    /// Why the link failed to be put
+    #[serde(default)]
+    pub error: String,
+}
+
+/// Payload of the `linkdef_deleted` event
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct LinkdefDeleted {
+    /// Source identifier of the link
+    #[serde(default)]
+    pub source_id: ComponentId,
+    /// Name of the link
+    #[serde(default)]
+    pub name: LinkName,
+    /// WIT namespace of the link
+    #[serde(default)]
+    pub wit_namespace: WitNamespace,
+    /// WIT package of the link
+    #[serde(default)]
+    pub wit_package: WitPackage,
+}
+
+/// Claims of a provider, as included in host events
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProviderClaims {
+    /// The issuer of the claims
+    #[serde(default)]
+    pub issuer: String,
+    /// Tags of the provider, if any
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    /// Name of the provider, if any
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Version of the provider, if any
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Human-readable time from which the claims are valid
+    #[serde(default)]
+    pub not_before_human: String,
+    /// Human-readable time at which the claims expire
+    #[serde(default)]
+    pub expires_human: String,
+}
+
+/// Payload of the `provider_started` event
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProviderStarted {
+    /// The ID of the host the provider was started on
+    #[serde(default)]
+    pub host_id: String,
+    /// Image reference of the provider
+    #[serde(default)]
+    pub image_ref: String,
+    /// The unique provider identifier
+    #[serde(default)]
+    pub provider_id: String,
+    /// The annotations the provider was started with
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
+    /// The claims of the provider, if it is signed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claims: Option<ProviderClaims>,
+    /// Deprecated alias of `provider_id`, only set if the provider is signed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance_id: Option<String>,
+    /// Deprecated alias of `provider_id`, only set if the provider is signed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+    /// Deprecated, always `default` if set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_name: Option<LinkName>,
+}
+
+/// Payload of the `provider_start_failed` event
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProviderStartFailed {
+    /// Image reference of the provider
+    #[serde(default)]
+    pub provider_ref: String,
+    /// The unique provider identifier
+    #[serde(default)]
+    pub provider_id: String,
+    /// Why the provider failed to start
+    #[serde(default)]
+    pub error: String,
+    /// Deprecated, always `default` if set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_name: Option<LinkName>,
+}
+
+/// Payload of the `provider_stopped` event
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProviderStopped {
+    /// The ID of the host the provider was stopped on
+    #[serde(default)]
+    pub host_id: String,
+    /// The unique provider identifier
+    #[serde(default)]
+    pub provider_id: String,
+    /// The annotations the provider was started with
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
+    /// Why the provider stopped
+    #[serde(default)]
+    pub reason: String,
+    /// Deprecated alias of `provider_id`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance_id: Option<String>,
+    /// Deprecated alias of `provider_id`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+    /// Deprecated, always `default` if set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_name: Option<LinkName>,
+}
+
+/// Payload of the `health_check_passed`, `health_check_failed` and `health_check_status` events
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProviderHealthCheck {
+    /// The ID of the host running the provider
+    #[serde(default)]
+    pub host_id: String,
+    /// The unique provider identifier
+    #[serde(default)]
+    pub provider_id: String,
+}
+
+/// Payload of the `config_set` event
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ConfigSet {
+    /// Name of the config that was put
+    #[serde(default)]
+    pub config_name: String,
+    /// Revision of the config created by the put, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revision: Option<u64>,
+    /// Revision of the config replaced by the put, if the config existed before
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_revision: Option<u64>,
+}
+
+/// Payload of the `config_deleted` event
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ConfigDeleted {
+    /// Name of the config that was deleted
+    #[serde(default)]
+    pub config_name: String,
+}
+
+/// State of the circuit breaker of a lattice target
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitBreakerState {
+    /// Invocations are sent to the target
+    #[default]
+    Closed,
+    /// Invocations fail fast without being sent to the target
+    Open,
+    /// A single trial invocation is sent to the target to determine whether it recovered
+    HalfOpen,
+}
+
+/// Payload of the `circuit_breaker_state_changed` event
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CircuitBreakerStateChanged {
+    /// The ID of the host the circuit breaker belongs to
+    #[serde(default)]
+    pub host_id: String,
+    /// The lattice target invocations are sent to
+    #[serde(default)]
+    pub target: String,
+    /// The state before the change
+    #[serde(default)]
+    pub previous_state: CircuitBreakerState,
+    /// The state after the change
+    #[serde(default)]
+    pub state: CircuitBreakerState,
+    /// Number of consecutive failed invocations of the target
+    #[serde(default)]
+    pub consecutive_failures: u32,
+}
+
+/// Payload of the `secrets_rotated` event. Never contains secret values.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SecretsRotated {
+    /// The ID of the host running the component or provider
+    #[serde(default)]
+    pub host_id: String,
+    /// The ID of the component or provider whose secrets were rotated
+    #[serde(default)]
+    pub entity_id: String,
+    /// Names of the secrets whose values changed
+    #[serde(default)]
+    pub secret_names: Vec<String>,
+}
+
+/// Payload of the `claims_expiring` event
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ClaimsExpiring {
+    /// The ID of the host running the component or provider
+    #[serde(default)]
+    pub host_id: String,
+    /// The ID of the component or provider whose claims are expiring
+    #[serde(default)]
+    pub entity_id: String,
+    /// The public key (subject) of the expiring claims
+    #[serde(default)]
+    pub public_key: String,
+    /// The time the claims expire, as an RFC 3339 date
+    #[serde(default)]
+    pub expires_human: String,
+    /// Seconds until the claims expire, zero once they expired
+    #[serde(default)]
+    pub expires_in_seconds: u64,
+    /// Whether the claims expired already
+    #[serde(default)]
+    pub expired: bool,
+}
+
+#[cfg(test)]
+mod test {
+    use cloudevents::{EventBuilder, EventBuilderV10};
+    use serde_json::json;
+
+    use super::*;
+
+    /// Locks the wire format of host event payloads. Changing any of these payloads in an
+    /// incompatible way requires bumping [`HOST_EVENT_SCHEMA_VERSION`].
+    #[test]
+    fn host_event_wire_format() {
+        let cases = [
+            (
+                "component_scaled",
+                json!({
+                    "public_key": "MBCFOPM6JW2APJLXJD3Z5O4CN7CPYJ2B4FTKLJUR5YR5MITIU7HD3WD5",
+                    "claims": {
+                        "issuer": "ACOJJN6WUP4ODD75XEBKKTCCUJJCY5ZKQ56XVKYK4BEJWGVAOOQHZMCW",
+                        "call_alias": null,
+                        "tags": null,
+                        "name": "echo",
+                        "version": "0.1.0",
+                        "revision": 0,
+                        "not_before_human": "immediately",
+                        "expires_human": "never",
+                    },
+                    "annotations": { "wasmcloud.dev/appspec": "echo" },
+                    "host_id": "NACF2WCVCMHXNLQVCPPWZLVSHEIN3XDMLVCVT7XEE63TI4XGQBKWYSYT",
+                    "image_ref": "ghcr.io/wasmcloud/components/http-hello-world-rust:0.1.0",
+                    "max_instances": 1,
+                    "component_id": "echo",
+                }),
+            ),
+            (
+                "component_scale_failed",
+                json!({
+                    "component_id": "echo",
+                    "annotations": {},
+                    "host_id": "NACF2WCVCMHXNLQVCPPWZLVSHEIN3XDMLVCVT7XEE63TI4XGQBKWYSYT",
+                    "image_ref": "ghcr.io/wasmcloud/components/http-hello-world-rust:0.1.0",
+                    "max_instances": 1,
+                    "error": "failed to fetch component",
+                }),
+            ),
+            (
+                "linkdef_set",
+                json!({
+                    "source_id": "echo",
+                    "target": "httpserver",
+                    "name": "default",
+                    "wit_namespace": "wasi",
+                    "wit_package": "http",
+                    "interfaces": ["incoming-handler"],
+                    "source_config": [],
+                    "target_config": ["port"],
+                }),
+            ),
+            (
+                "linkdef_set_failed",
+                json!({
+                    "source_id": "echo",
+                    "target": "httpserver",
+                    "name": "default",
+                    "wit_namespace": "wasi",
+                    "wit_package": "http",
+                    "interfaces": ["incoming-handler"],
+                    "source_config": [],
+                    "target_config": [],
+                    "error": "missing config",
+                }),
+            ),
+            (
+                "linkdef_deleted",
+                json!({
+                    "source_id": "echo",
+                    "name": "default",
+                    "wit_namespace": "wasi",
+                    "wit_package": "http",
+                }),
+            ),
+            (
+                "provider_started",
+                json!({
+                    "host_id": "NACF2WCVCMHXNLQVCPPWZLVSHEIN3XDMLVCVT7XEE63TI4XGQBKWYSYT",
+                    "image_ref": "ghcr.io/wasmcloud/http-server:0.22.0",
+                    "provider_id": "httpserver",
+                    "annotations": {},
+                    "claims": {
+                        "issuer": "ACOJJN6WUP4ODD75XEBKKTCCUJJCY5ZKQ56XVKYK4BEJWGVAOOQHZMCW",
+                        "tags": null,
+                        "name": "http-server",
+                        "version": "0.22.0",
+                        "not_before_human": "immediately",
+                        "expires_human": "never",
+                    },
+                    "instance_id": "httpserver",
+                    "public_key": "httpserver",
+                    "link_name": "default",
+                }),
+            ),
+            (
+                "provider_start_failed",
+                json!({
+                    "provider_ref": "ghcr.io/wasmcloud/http-server:0.22.0",
+                    "provider_id": "httpserver",
+                    "error": "failed to fetch provider",
+                    "link_name": "default",
+                }),
+            ),
+            (
+                "provider_stopped",
+                json!({
+                    "host_id": "NACF2WCVCMHXNLQVCPPWZLVSHEIN3XDMLVCVT7XEE63TI4XGQBKWYSYT",
+                    "provider_id": "httpserver",
+                    "annotations": {},
+                    "reason": "stop",
+                    "instance_id": "httpserver",
+                    "public_key": "httpserver",
+                    "link_name": "default",
+                }),
+            ),
+            (
+                "health_check_passed",
+                json!({
+                    "host_id": "NACF2WCVCMHXNLQVCPPWZLVSHEIN3XDMLVCVT7XEE63TI4XGQBKWYSYT",
+                    "provider_id": "httpserver",
+                }),
+            ),
+            ("config_set", json!({ "config_name": "port" })),
+            (
+                "config_set",
+                json!({ "config_name": "port", "revision": 7, "previous_revision": 3 }),
+            ),
+            ("config_deleted", json!({ "config_name": "port" })),
+            (
+                "circuit_breaker_state_changed",
+                json!({
+                    "host_id": "NACF2WCVCMHXNLQVCPPWZLVSHEIN3XDMLVCVT7XEE63TI4XGQBKWYSYT",
+                    "target": "kv-redis",
+                    "previous_state": "closed",
+                    "state": "open",
+                    "consecutive_failures": 5,
+                }),
+            ),
+            (
+                "secrets_rotated",
+                json!({
+                    "host_id": "NACF2WCVCMHXNLQVCPPWZLVSHEIN3XDMLVCVT7XEE63TI4XGQBKWYSYT",
+                    "entity_id": "sqldb-postgres",
+                    "secret_names": ["password"],
+                }),
+            ),
+            (
+                "claims_expiring",
+                json!({
+                    "host_id": "NACF2WCVCMHXNLQVCPPWZLVSHEIN3XDMLVCVT7XEE63TI4XGQBKWYSYT",
+                    "entity_id": "echo",
+                    "public_key": "MBCFOPM6JW2APJLXJD3Z5O4CN7CPYJ2B4FTKLJUR5YR5MITIU7HD3WD5",
+                    "expires_human": "2026-01-01T00:00:00Z",
+                    "expires_in_seconds": 86400,
+                    "expired": false,
+                }),
+            ),
+            (
+                "labels_changed",
+                json!({
+                    "host_id": "NACF2WCVCMHXNLQVCPPWZLVSHEIN3XDMLVCVT7XEE63TI4XGQBKWYSYT",
+                    "labels": { "zone": "us-east-1" },
+                }),
+            ),
+            (
+                "host_started",
+                json!({
+                    "friendly_name": "bold-fish-4213",
+                    "labels": { "zone": "us-east-1" },
+                    "uptime_seconds": 0,
+                    "version": "1.2.0",
+                }),
+            ),
+            ("host_stopped", json!({ "labels": { "zone": "us-east-1" } })),
+            (
+                "host_heartbeat",
+                json!({
+                    "components": [],
+                    "providers": [],
+                    "host_id": "NACF2WCVCMHXNLQVCPPWZLVSHEIN3XDMLVCVT7XEE63TI4XGQBKWYSYT",
+                    "friendly_name": "bold-fish-4213",
+                    "labels": { "zone": "us-east-1" },
+                    "version": "1.2.0",
+                    "uptime_human": "1m",
+                    "uptime_seconds": 60,
+                    "readiness": {
+                        "nats_connected": true,
+                        "buckets_opened": true,
+                        "policy_reachable": true,
+                        "secrets_reachable": true,
+                        "supplemental_config_loaded": true,
+                    },
+                }),
+            ),
+        ];
+        for (name, data) in cases {
+            let ev = HostEvent::from_data(name, data.clone())
+                .unwrap_or_else(|e| panic!("failed to decode `{name}`: {e}"));
+            assert_eq!(ev.name(), name);
+            assert_eq!(
+                ev.to_data().expect("failed to encode event"),
+                data,
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn host_event_from_cloud_event() {
+        let ev = EventBuilderV10::new()
+            .id("1")
+            .source("NACF2WCVCMHXNLQVCPPWZLVSHEIN3XDMLVCVT7XEE63TI4XGQBKWYSYT")
+            .ty("com.wasmcloud.lattice.config_set")
+            .extension(
+                HOST_EVENT_SCHEMA_VERSION_EXTENSION,
+                HOST_EVENT_SCHEMA_VERSION,
+            )
+            .data("application/json", json!({ "config_name": "port" }))
+            .build()
+            .expect("failed to build event");
+        assert_eq!(
+            HostEvent::try_from(&ev).expect("failed to decode event"),
+            HostEvent::ConfigSet(ConfigSet {
+                config_name: "port".into(),
+                ..Default::default()
+            })
+        );
+
+        let ev = EventBuilderV10::new()
+            .id("1")
+            .source("NACF2WCVCMHXNLQVCPPWZLVSHEIN3XDMLVCVT7XEE63TI4XGQBKWYSYT")
+            .ty("com.wasmcloud.lattice.config_set")
+            .extension(HOST_EVENT_SCHEMA_VERSION_EXTENSION, "2")
+            .data("application/json", json!({ "config_name": "port" }))
+            .build()
+            .expect("failed to build event");
+        assert!(HostEvent::try_from(&ev).is_err());
+    }
+}
@@ -72,6 +72,38 @@ pub struct HostInventory {
     pub uptime_seconds: u64,
 }
 
+/// The result of the readiness probes of a host
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct HostReadiness {
+    /// Whether both the control interface and RPC NATS connections are established
+    #[serde(default)]
+    pub nats_connected: bool,
+    /// Whether the lattice data and config data JetStream buckets are accessible
+    #[serde(default)]
+    pub buckets_opened: bool,
+    /// Whether the last policy request, if a policy topic is configured, reached the policy server
+    #[serde(default)]
+    pub policy_reachable: bool,
+    /// Whether the secrets topic, if configured, has a responder for every backend in use
+    #[serde(default)]
+    pub secrets_reachable: bool,
+    /// Whether supplemental config was successfully loaded, if the config service is enabled
+    #[serde(default)]
+    pub supplemental_config_loaded: bool,
+}
+
+impl HostReadiness {
+    /// Returns `true` if all probes succeeded
+    #[must_use]
+    pub fn is_ready(&self) -> bool {
+        self.nats_connected
+            && self.buckets_opened
+            && self.policy_reachable
+            && self.secrets_reachable
+            && self.supplemental_config_loaded
+    }
+}
+
 /// Serde Serializer that works for sorting maps on the fly
 fn serialize_as_btreemap<S, K, V>(value: &HashMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
 where
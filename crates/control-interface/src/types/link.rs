@@ -31,9 +31,138 @@ pub struct InterfaceLinkDefinition {
     /// List of named configurations to provide to the target upon request
     #[serde(default)]
     pub target_config: Vec<KnownConfigName>,
+    /// Compatibility of the link with the WIT of the source and target, as determined by the host
+    /// when the link was put. `None` if it could not be checked, e.g. because the source or target
+    /// has not been started yet. Ignored when putting a link.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<LinkStatus>,
+    /// Policy for retrying invocations over the link, which fail to reach the target. Invocations
+    /// are not retried if this is not set.
+    ///
+    /// This should only be set for links on idempotent interfaces, since the target may have
+    /// received an invocation before it failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_policy: Option<RetryPolicy>,
+}
+
+/// Policy for retrying invocations over an [`InterfaceLinkDefinition`] with exponential backoff
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize, Hash)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first one
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// Delay before the first retry in milliseconds, doubled for every further retry
+    #[serde(default = "default_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+    /// Maximum delay between retries in milliseconds
+    #[serde(default = "default_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_max_attempts(),
+            initial_backoff_ms: default_initial_backoff_ms(),
+            max_backoff_ms: default_max_backoff_ms(),
+        }
+    }
+}
+
+fn default_max_attempts() -> u32 {
+    3
+}
+
+fn default_initial_backoff_ms() -> u64 {
+    100
+}
+
+fn default_max_backoff_ms() -> u64 {
+    2000
+}
+
+/// Compatibility of an [`InterfaceLinkDefinition`] with the WIT of its source and target
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize, Hash)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum LinkStatus {
+    /// The source imports and the target exports all interfaces of the link in compatible versions
+    Healthy,
+    /// The source does not import or the target does not export an interface of the link, or
+    /// they do so in incompatible versions
+    Unhealthy {
+        /// Description of the mismatches
+        reason: String,
+    },
 }
 
 /// Helper function to provide a default link name
 pub(crate) fn default_link_name() -> LinkName {
     "default".to_string()
 }
+
+/// Prefix of a link target referring to a [`TargetGroup`] by name, e.g. `group:echo`
+pub const TARGET_GROUP_PREFIX: &str = "group:";
+
+/// Returns the link target referring to the [`TargetGroup`] named `name`
+#[must_use]
+pub fn target_group_link_target(name: &str) -> LatticeTarget {
+    format!("{TARGET_GROUP_PREFIX}{name}")
+}
+
+/// A named group of lattice targets. Links with a target of `group:<name>` (see
+/// [`target_group_link_target`]) spread invocations across the members of the group, which
+/// allows e.g. blue/green and canary splits between component versions without relinking callers.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+pub struct TargetGroup {
+    /// Name of the group
+    pub name: String,
+    /// Strategy used to select a member for each invocation
+    #[serde(default)]
+    pub selection: TargetSelection,
+    /// Members of the group
+    #[serde(default)]
+    pub members: Vec<TargetGroupMember>,
+}
+
+/// A member of a [`TargetGroup`]
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct TargetGroupMember {
+    /// The lattice target invocations are sent to, e.g. a component ID
+    pub target: LatticeTarget,
+    /// Relative share of invocations sent to this member
+    #[serde(default = "default_member_weight")]
+    pub weight: u32,
+}
+
+impl TargetGroupMember {
+    /// Construct a new [`TargetGroupMember`] with a weight of 1
+    pub fn new(target: impl Into<LatticeTarget>) -> Self {
+        Self {
+            target: target.into(),
+            weight: default_member_weight(),
+        }
+    }
+}
+
+fn default_member_weight() -> u32 {
+    1
+}
+
+/// Strategy used to select a member of a [`TargetGroup`] for an invocation. Members are selected
+/// proportionally to their weight.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(tag = "strategy", rename_all = "snake_case")]
+pub enum TargetSelection {
+    /// Cycle through the members of the group
+    #[default]
+    RoundRobin,
+    /// Select a random member for each invocation
+    Random,
+    /// Select members by consistent (rendezvous) hashing of a caller-supplied key, so that
+    /// invocations with the same key are sent to the same member while membership is unchanged
+    ConsistentHash {
+        /// Name of the W3C baggage entry of the caller's trace context containing the key.
+        /// Invocations without the entry are hashed by their trace ID.
+        key: String,
+    },
+}
@@ -0,0 +1,87 @@
+//! Data types for component log records forwarded by hosts on a wasmCloud lattice
+
+use core::fmt;
+use core::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::ComponentId;
+
+/// Returns the subject hosts in `lattice` publish log records of the component `component_id` on
+#[must_use]
+pub fn component_log_subject(lattice: &str, component_id: &str) -> String {
+    format!("wasmbus.log.{lattice}.{component_id}")
+}
+
+/// Level of a component log record, ordered from least to most severe
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ComponentLogLevel {
+    /// Trace level
+    Trace,
+    /// Debug level
+    Debug,
+    /// Info level
+    #[default]
+    Info,
+    /// Warn level
+    Warn,
+    /// Error level
+    Error,
+    /// Critical level
+    Critical,
+}
+
+impl fmt::Display for ComponentLogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Trace => "trace",
+            Self::Debug => "debug",
+            Self::Info => "info",
+            Self::Warn => "warn",
+            Self::Error => "error",
+            Self::Critical => "critical",
+        })
+    }
+}
+
+impl FromStr for ComponentLogLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "trace" => Ok(Self::Trace),
+            "debug" => Ok(Self::Debug),
+            "info" => Ok(Self::Info),
+            "warn" | "warning" => Ok(Self::Warn),
+            "error" => Ok(Self::Error),
+            "critical" => Ok(Self::Critical),
+            _ => Err(format!("invalid component log level `{s}`")),
+        }
+    }
+}
+
+/// A log record emitted by a component through `wasi:logging`
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ComponentLogRecord {
+    /// The unique identifier of the component that emitted the record
+    #[serde(default)]
+    pub component_id: ComponentId,
+    /// The ID of the host running the component
+    #[serde(default)]
+    pub host_id: String,
+    /// Level of the record
+    #[serde(default)]
+    pub level: ComponentLogLevel,
+    /// Context supplied by the component, usually the name of a logger
+    #[serde(default)]
+    pub context: String,
+    /// The logged message
+    #[serde(default)]
+    pub message: String,
+    /// The ID of the trace the record was emitted in, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
+}
@@ -1,9 +1,13 @@
 //! Collection of types that are commonly used/necessary in control interface operations
 
 pub mod component;
+pub mod config;
+pub mod constraint;
 pub mod ctl;
+pub mod event;
 pub mod host;
 pub mod link;
+pub mod log;
 pub mod provider;
 pub mod registry;
 pub mod rpc;
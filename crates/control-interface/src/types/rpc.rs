@@ -4,7 +4,7 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
-use crate::{ComponentId, LinkName, WitNamespace, WitPackage};
+use crate::{ComponentId, LabelConstraint, LinkName, WitNamespace, WitPackage};
 
 /// A host response to a request to start an component, confirming the host
 /// has enough capacity to start the component
@@ -22,6 +22,12 @@ pub struct ComponentAuctionAck {
     /// Constraints that were used in the auction
     #[serde(default)]
     pub constraints: HashMap<String, String>,
+    /// Label constraints that were used in the auction
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub label_constraints: Vec<LabelConstraint>,
+    /// Capacity of the bidding host, which can be used to choose the least-loaded host
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capacity: Option<HostCapacity>,
 }
 
 /// A request to locate suitable hosts for a given component
@@ -35,6 +41,9 @@ pub struct ComponentAuctionRequest {
     pub component_id: ComponentId,
     /// The set of constraints that must match the labels of a suitable target host
     pub constraints: HashMap<String, String>,
+    /// Additional constraints on labels, which must all be satisfied by a suitable target host
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub label_constraints: Vec<LabelConstraint>,
 }
 
 /// A host response to a request to start a provider, confirming the host
@@ -54,6 +63,12 @@ pub struct ProviderAuctionAck {
     /// The constraints provided for the auction
     #[serde(default)]
     pub constraints: HashMap<String, String>,
+    /// The label constraints provided for the auction
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub label_constraints: Vec<LabelConstraint>,
+    /// Capacity of the bidding host, which can be used to choose the least-loaded host
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capacity: Option<HostCapacity>,
 }
 
 /// A request to locate a suitable host for a capability provider. The
@@ -63,6 +78,9 @@ pub struct ProviderAuctionAck {
 pub struct ProviderAuctionRequest {
     /// The set of constraints that must match the labels of a suitable target host
     pub constraints: HashMap<String, String>,
+    /// Additional constraints on labels, which must all be satisfied by a suitable target host
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub label_constraints: Vec<LabelConstraint>,
     /// The image reference, file or OCI, for this provider.
     #[serde(default)]
     pub provider_ref: String,
@@ -71,6 +89,28 @@ pub struct ProviderAuctionRequest {
     pub provider_id: ComponentId,
 }
 
+/// Capacity hints reported by a host when bidding in an auction
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct HostCapacity {
+    /// The maximum number of component instances the host can run simultaneously
+    #[serde(default)]
+    pub max_components: u32,
+    /// The number of component instances the host can still be scaled up by, i.e.
+    /// `max_components` minus `running_instances`
+    #[serde(default)]
+    pub free_component_slots: u32,
+    /// The total number of instances the components running on the host are scaled to
+    #[serde(default)]
+    pub running_instances: u32,
+    /// The number of capability providers running on the host
+    #[serde(default)]
+    pub running_providers: u32,
+    /// The linear memory in bytes still available to new component instances, i.e.
+    /// `free_component_slots` times the maximum linear memory of a single instance
+    #[serde(default)]
+    pub linear_memory_headroom: u64,
+}
+
 /// A request to remove a link definition and detach the relevant component
 /// from the given provider
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
@@ -46,6 +46,23 @@ pub struct OtelConfig {
     /// The level of tracing to enable.
     #[serde(default = "default_trace_level")]
     pub trace_level: Level,
+    /// Ratio of traces to sample, between `0.0` and `1.0`. All traces are sampled if not set.
+    #[serde(default)]
+    pub traces_sampling_ratio: Option<f64>,
+    /// Determine whether the sampling decision of a parent span should be respected, only applying
+    /// `traces_sampling_ratio` to root spans.
+    #[serde(default)]
+    pub traces_sampling_parent_based: bool,
+    /// If provided, traces are written as OTLP JSON lines to this file instead of being exported
+    /// to an OpenTelemetry endpoint.
+    #[serde(default)]
+    pub traces_file: Option<PathBuf>,
+    /// The size in bytes at which the traces file is rotated, defaults to 100 MiB.
+    #[serde(default)]
+    pub traces_file_max_bytes: Option<u64>,
+    /// The number of rotated traces files to keep, defaults to 5.
+    #[serde(default)]
+    pub traces_file_max_files: Option<usize>,
 }
 
 fn default_trace_level() -> Level {
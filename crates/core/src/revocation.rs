@@ -0,0 +1,87 @@
+//! Revocation of claims across a lattice.
+//!
+//! Revocations are stored in the JetStream KV bucket returned by [`revocation_bucket`], keyed by
+//! [`RevocationKind::key`], with a JSON encoded [`Revocation`] as value. Hosts refuse to start
+//! components and providers whose claims are revoked. Deleting a key lifts the revocation.
+
+use serde::{Deserialize, Serialize};
+
+/// The name of the JetStream KV bucket holding the revocation list of `lattice`
+#[must_use]
+pub fn revocation_bucket(lattice: &str) -> String {
+    format!("REVOCATIONS_{lattice}")
+}
+
+/// The claims field a revocation applies to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RevocationKind {
+    /// Revokes all claims with this subject, e.g. the public key of a component
+    Subject,
+    /// Revokes all claims issued by this public key, e.g. an account
+    Issuer,
+    /// Revokes the single JWT with this ID (`jti`)
+    Jti,
+}
+
+impl RevocationKind {
+    fn prefix(self) -> &'static str {
+        match self {
+            Self::Subject => "subject",
+            Self::Issuer => "issuer",
+            Self::Jti => "jti",
+        }
+    }
+
+    /// The key of the revocation of `id` in the revocation bucket
+    #[must_use]
+    pub fn key(self, id: &str) -> String {
+        format!("{}.{id}", self.prefix())
+    }
+
+    /// Parse a key of the revocation bucket into the kind of revocation and the revoked ID
+    #[must_use]
+    pub fn parse_key(key: &str) -> Option<(Self, &str)> {
+        let (prefix, id) = key.split_once('.')?;
+        let kind = [Self::Subject, Self::Issuer, Self::Jti]
+            .into_iter()
+            .find(|kind| kind.prefix() == prefix)?;
+        Some((kind, id))
+    }
+}
+
+/// A revocation stored in the revocation bucket
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Revocation {
+    /// Why the claims were revoked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// When the claims were revoked, in seconds since the Unix epoch
+    #[serde(default)]
+    pub revoked_at: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RevocationKind;
+
+    #[test]
+    fn keys_round_trip() {
+        for kind in [
+            RevocationKind::Subject,
+            RevocationKind::Issuer,
+            RevocationKind::Jti,
+        ] {
+            let key = kind.key("MBCFOPM6JW2APJLXJD3Z5O4CN7CPYJ2B4FTKLJUR5YR5MITIU7HD3WD5");
+            assert_eq!(
+                RevocationKind::parse_key(&key),
+                Some((
+                    kind,
+                    "MBCFOPM6JW2APJLXJD3Z5O4CN7CPYJ2B4FTKLJUR5YR5MITIU7HD3WD5"
+                ))
+            );
+        }
+        assert_eq!(RevocationKind::parse_key("other.foo"), None);
+        assert_eq!(RevocationKind::parse_key("subject"), None);
+    }
+}
@@ -66,3 +66,14 @@ pub fn shutdown_subject(lattice: &str, provider_key: &str, link_name: &str) -> S
 pub fn provider_config_update_subject(lattice: &str, provider_key: &str) -> String {
     format!("wasmbus.rpc.{lattice}.{provider_key}.config.update")
 }
+
+/// Generate the wasmbus RPC subject for delivering rotated secrets to a given provider
+///
+/// When messages are published on this subject, providers receive their full set of secrets,
+/// serialized and encrypted for the provider's xkey like the secrets of links.
+///
+/// NOTE that the NATS message body limits (default 1MiB) apply to these messages
+#[must_use]
+pub fn provider_secrets_update_subject(lattice: &str, provider_key: &str) -> String {
+    format!("wasmbus.rpc.{lattice}.{provider_key}.secrets.update")
+}
@@ -42,7 +42,6 @@ use anyhow::{anyhow, bail, ensure, Context as _};
 use tokio::fs;
 use tracing::{debug, instrument, warn};
 use url::Url;
-use wascap::jwt;
 
 #[derive(PartialEq)]
 enum ResourceRef<'a> {
@@ -144,7 +143,7 @@ pub async fn fetch_provider(
     host_id: impl AsRef<str>,
     allow_file_load: bool,
     registry_config: &HashMap<String, RegistryConfig>,
-) -> anyhow::Result<(PathBuf, Option<jwt::Token<jwt::CapabilityProvider>>)> {
+) -> anyhow::Result<par::Extracted> {
     match ResourceRef::try_from(provider_ref.as_ref())? {
         ResourceRef::File(provider_path) => {
             ensure!(
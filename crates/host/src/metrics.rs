@@ -10,6 +10,10 @@ pub struct HostMetrics {
     pub component_invocations: Counter<u64>,
     /// The count of the number of times an component invocation resulted in an error.
     pub component_errors: Counter<u64>,
+    /// The count of the number of times an invocation sent by a component was retried.
+    pub invocation_retries: Counter<u64>,
+    /// The count of the number of times the circuit breaker of a lattice target changed its state.
+    pub circuit_breaker_state_changes: Counter<u64>,
 
     /// The host's ID.
     // TODO this is actually configured as an InstrumentationScope attribute on the global meter,
@@ -42,10 +46,22 @@ impl HostMetrics {
             .with_description("Number of component errors")
             .init();
 
+        let invocation_retry_count = meter
+            .u64_counter("wasmcloud_host.invocation.retries")
+            .with_description("Number of retried invocations sent by components")
+            .init();
+
+        let circuit_breaker_state_change_count = meter
+            .u64_counter("wasmcloud_host.circuit_breaker.state_changes")
+            .with_description("Number of circuit breaker state changes")
+            .init();
+
         Self {
             handle_rpc_message_duration_ns: wasmcloud_host_handle_rpc_message_duration_ns,
             component_invocations: component_invocation_count,
             component_errors: component_error_count,
+            invocation_retries: invocation_retry_count,
+            circuit_breaker_state_changes: circuit_breaker_state_change_count,
             host_id,
             lattice_id,
         }
@@ -65,4 +81,14 @@ impl HostMetrics {
             self.component_errors.add(1, attributes);
         }
     }
+
+    /// Record a retry of an invocation sent by a component
+    pub(crate) fn record_invocation_retry(&self, attributes: &[KeyValue]) {
+        self.invocation_retries.add(1, attributes);
+    }
+
+    /// Record a state change of the circuit breaker of a lattice target
+    pub(crate) fn record_circuit_breaker_state_change(&self, attributes: &[KeyValue]) {
+        self.circuit_breaker_state_changes.add(1, attributes);
+    }
 }
@@ -16,7 +16,6 @@ use oci_wasm::{WASM_LAYER_MEDIA_TYPE, WASM_MANIFEST_MEDIA_TYPE};
 use serde::{Deserialize, Serialize};
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
-use wascap::jwt;
 use wasmcloud_core::tls;
 
 const PROVIDER_ARCHIVE_MEDIA_TYPE: &str = "application/vnd.wasmcloud.provider.archive.layer.v1+par";
@@ -268,7 +267,7 @@ impl Fetcher {
         &self,
         oci_ref: impl AsRef<str>,
         host_id: impl AsRef<str>,
-    ) -> anyhow::Result<(PathBuf, Option<jwt::Token<jwt::CapabilityProvider>>)> {
+    ) -> anyhow::Result<par::Extracted> {
         let path = self
             .fetch_path(
                 oci_ref.as_ref(),
@@ -9,16 +9,29 @@ use std::str;
 use anyhow::{anyhow, Context};
 use provider_archive::ProviderArchive;
 use tokio::fs::{self, File, OpenOptions};
-use tokio::io::AsyncWriteExt;
+use tracing::warn;
+use ulid::Ulid;
 use wascap::jwt;
 
+/// The path to a cached provider binary, with the claims, the detached co-signatures and the
+/// encoded WIT package of the provider archive it was extracted from
+pub type Extracted = (
+    PathBuf,
+    Option<jwt::Token<jwt::CapabilityProvider>>,
+    Vec<jwt::Token<jwt::CoSignature>>,
+    Option<Vec<u8>>,
+);
+
 fn normalize_for_filename(input: &str) -> String {
     input
         .to_lowercase()
         .replace(|c: char| !c.is_ascii_alphanumeric(), "_")
 }
 
-pub(super) async fn create(path: impl AsRef<Path>) -> anyhow::Result<Option<File>> {
+/// Creates a temporary file next to `path` to stream a provider binary to, unless `path` exists.
+/// The temporary file is only renamed to `path` once the binary was verified, so that `path`
+/// never contains an unverified or partially written binary.
+pub(super) async fn create(path: impl AsRef<Path>) -> anyhow::Result<Option<(File, PathBuf)>> {
     let path = path.as_ref();
     // Check if the file exists and return
     if fs::metadata(path).await.is_ok() {
@@ -28,16 +41,21 @@ pub(super) async fn create(path: impl AsRef<Path>) -> anyhow::Result<Option<File
     fs::create_dir_all(dir)
         .await
         .context("failed to create parent directory")?;
+    let name = path
+        .file_name()
+        .context("failed to determine file name")?
+        .to_string_lossy();
+    let tmp = dir.join(format!(".{name}.{}.tmp", Ulid::new()));
 
     let mut open_opts = OpenOptions::new();
-    open_opts.create(true).truncate(true).write(true);
+    open_opts.create_new(true).write(true);
     #[cfg(unix)]
     open_opts.mode(0o755);
-    open_opts
-        .open(path)
+    let file = open_opts
+        .open(&tmp)
         .await
-        .map(Some)
-        .context("failed to open path")
+        .context("failed to open temporary path")?;
+    Ok(Some((file, tmp)))
 }
 
 fn native_target() -> String {
@@ -69,25 +87,104 @@ pub fn cache_path(host_id: impl AsRef<str>, provider_ref: impl AsRef<str>) -> Pa
 /// * `host_id` - The host ID this provider is starting on. Required in order to isolate provider caches
 ///           for different hosts
 /// * `provider_ref` - The reference to the provider (e.g. file or OCI). Required to cache provider for future fetches
+///
+/// Returns the path to the cached provider binary, the claims, the detached co-signatures and the
+/// encoded WIT package of the archive
 pub async fn read(
     path: impl AsRef<Path>,
     host_id: impl AsRef<str>,
     provider_ref: impl AsRef<str>,
-) -> anyhow::Result<(PathBuf, Option<jwt::Token<jwt::CapabilityProvider>>)> {
-    let par = ProviderArchive::try_load_target_from_file(path, &native_target())
-        .await
-        .map_err(|e| anyhow!(e).context("failed to load provider archive"))?;
+) -> anyhow::Result<Extracted> {
+    let target = native_target();
+    let exe = cache_path(host_id, provider_ref);
+    // Only write the file if it doesn't exist, but always verify the archive. The target binary is
+    // streamed to a temporary file without buffering it in memory, and only moved to the cache once
+    // it was verified.
+    let par = match create(&exe).await? {
+        Some((mut file, tmp)) => {
+            let par = ProviderArchive::extract_target_from_file(path, &target, &mut file).await;
+            drop(file);
+            match par {
+                Ok(par) => {
+                    if let Err(err) = fs::rename(&tmp, &exe).await {
+                        remove_tmp(&tmp).await;
+                        // Another start of the same provider may have won the race
+                        if fs::metadata(&exe).await.is_err() {
+                            return Err(
+                                anyhow!(err).context("failed to move provider binary to cache")
+                            );
+                        }
+                    }
+                    par
+                }
+                Err(e) => {
+                    remove_tmp(&tmp).await;
+                    return Err(anyhow!(e).context("failed to load provider archive"));
+                }
+            }
+        }
+        None => ProviderArchive::extract_target_from_file(path, &target, &mut tokio::io::sink())
+            .await
+            .map_err(|e| anyhow!(e).context("failed to load provider archive"))?,
+    };
     let claims = par.claims_token();
+    let cosignatures = par.cosignatures().to_vec();
+    let wit = par.wit().map(<[u8]>::to_vec);
+    Ok((exe, claims, cosignatures, wit))
+}
 
-    let exe = cache_path(host_id, provider_ref);
-    // Only write the file if it doesn't exist
-    if let Some(mut file) = create(&exe).await? {
-        let target = native_target();
-        let buf = par
-            .target_bytes(&target)
-            .with_context(|| format!("target `{target}` not found"))?;
-        file.write_all(&buf).await.context("failed to write")?;
-        file.flush().await.context("failed to flush")?;
+async fn remove_tmp(path: &Path) {
+    if let Err(err) = fs::remove_file(path).await {
+        warn!(?err, path = %path.display(), "failed to remove temporary provider binary");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use nkeys::KeyPair;
+
+    use super::*;
+
+    async fn write_archive(path: &Path, target: &str) -> anyhow::Result<()> {
+        let mut par = ProviderArchive::new("test", "wasmCloud", None, None);
+        par.add_library(target, b"provider")
+            .map_err(|e| anyhow!(e.to_string()))?;
+        par.write(
+            path,
+            &KeyPair::new_account(),
+            &KeyPair::new_service(),
+            false,
+        )
+        .await
+        .map_err(|e| anyhow!(e.to_string()))
+    }
+
+    #[tokio::test]
+    async fn read_caches_only_verified_binaries() -> anyhow::Result<()> {
+        let host_id = Ulid::new().to_string();
+        let dir = temp_dir().join(format!("wasmcloud-par-test-{host_id}"));
+        fs::create_dir_all(&dir).await?;
+
+        let invalid = dir.join("invalid.par");
+        write_archive(&invalid, "unsupported-target").await?;
+        assert!(read(&invalid, &host_id, "invalid").await.is_err());
+        let cache = cache_path(&host_id, "invalid");
+        assert!(fs::metadata(&cache).await.is_err());
+        let mut entries = fs::read_dir(cache.parent().context("missing cache dir")?).await?;
+        assert!(
+            entries.next_entry().await?.is_none(),
+            "temporary files should be removed"
+        );
+
+        let valid = dir.join("valid.par");
+        write_archive(&valid, &native_target()).await?;
+        let (exe, claims, _, _) = read(&valid, &host_id, "valid").await?;
+        assert_eq!(exe, cache_path(&host_id, "valid"));
+        assert_eq!(fs::read(&exe).await?, b"provider");
+        assert!(claims.is_some());
+
+        fs::remove_dir_all(&dir).await?;
+        fs::remove_dir_all(exe.parent().context("missing cache dir")?).await?;
+        Ok(())
     }
-    Ok((exe, claims))
 }
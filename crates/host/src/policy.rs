@@ -5,7 +5,7 @@ use std::hash::Hash;
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use anyhow::Context;
+use anyhow::{bail, Context};
 use futures::{
     stream::{AbortHandle, Abortable},
     StreamExt,
@@ -38,6 +38,12 @@ pub struct PolicyClaims {
     pub expires_at: Option<u64>,
     /// Whether the claims have expired already. This is included in case the policy server is fulfilled by an component, which cannot access the system clock
     pub expired: bool,
+    /// The interfaces imported by the component, if recorded in its claims
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub imports: Option<Vec<String>>,
+    /// The interfaces exported by the component, if recorded in its claims
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exports: Option<Vec<String>>,
 }
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Hash)]
@@ -211,6 +217,14 @@ impl From<&jwt::Claims<jwt::Component>> for PolicyClaims {
             issued_at: claims.issued_at.to_string(),
             expires_at: claims.expires,
             expired: claims.expires.is_some_and(is_expired),
+            imports: claims
+                .metadata
+                .as_ref()
+                .and_then(|component| component.imports.clone()),
+            exports: claims
+                .metadata
+                .as_ref()
+                .and_then(|component| component.exports.clone()),
         }
     }
 }
@@ -223,6 +237,8 @@ impl From<&jwt::Claims<jwt::CapabilityProvider>> for PolicyClaims {
             issued_at: claims.issued_at.to_string(),
             expires_at: claims.expires,
             expired: claims.expires.is_some_and(is_expired),
+            imports: None,
+            exports: None,
         }
     }
 }
@@ -236,6 +252,8 @@ pub struct Manager {
     policy_timeout: Duration,
     decision_cache: Arc<RwLock<HashMap<RequestKey, Response>>>,
     request_to_key: Arc<RwLock<HashMap<String, RequestKey>>>,
+    /// The error of the last policy request, if it failed to reach the policy server
+    request_error: RwLock<Option<String>>,
     /// An abort handle for the policy changes subscription
     pub policy_changes: AbortHandle,
 }
@@ -261,6 +279,7 @@ impl Manager {
             policy_timeout: policy_timeout.unwrap_or(DEFAULT_POLICY_TIMEOUT),
             decision_cache: Arc::default(),
             request_to_key: Arc::default(),
+            request_error: RwLock::default(),
             policy_changes: policy_changes_abort,
         };
         let manager = Arc::new(manager);
@@ -390,11 +409,9 @@ impl Manager {
         let request = async_nats::Request::new()
             .payload(payload.into())
             .timeout(Some(self.policy_timeout));
-        let res = self
-            .nats
-            .send_request(policy_topic, request)
-            .await
-            .context("policy request failed")?;
+        let res = self.nats.send_request(policy_topic, request).await;
+        *self.request_error.write().await = res.as_ref().err().map(ToString::to_string);
+        let res = res.context("policy request failed")?;
         let decision = serde_json::from_slice::<Response>(&res.payload)
             .context("failed to deserialize policy response")?;
 
@@ -409,6 +426,18 @@ impl Manager {
         Ok(decision)
     }
 
+    /// Checks whether the policy server was reachable on the last policy request.
+    ///
+    /// No request is sent to the policy server, so this succeeds until a policy request fails to
+    /// reach it, and once a policy request reaches it again.
+    #[instrument(level = "trace", skip_all)]
+    pub async fn probe(&self) -> anyhow::Result<()> {
+        if let Some(err) = self.request_error.read().await.as_ref() {
+            bail!("policy topic unreachable: {err}");
+        }
+        Ok(())
+    }
+
     #[instrument(skip(self))]
     async fn override_decision(&self, msg: async_nats::Message) -> anyhow::Result<()> {
         let Response {
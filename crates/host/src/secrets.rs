@@ -1,17 +1,80 @@
 //! Module with structs for use in managing and accessing secrets in a wasmCloud lattice
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{bail, ensure, Context as _};
 use async_nats::{jetstream::kv::Store, Client};
+use bytes::Bytes;
 use futures::stream;
 use futures::stream::{StreamExt, TryStreamExt};
-use secrecy::Secret;
+use secrecy::{ExposeSecret as _, Secret};
 use tokio::sync::RwLock;
 use tracing::instrument;
 use wasmcloud_runtime::capability::secrets::store::SecretValue;
 use wasmcloud_secrets_client::Client as WasmcloudSecretsClient;
-use wasmcloud_secrets_types::{Secret as WasmcloudSecret, SecretConfig};
+use wasmcloud_secrets_types::{
+    Secret as WasmcloudSecret, SecretConfig, SECRET_API_VERSION, SECRET_PREFIX,
+};
+
+/// The secret references of a running entity, kept to re-fetch its secrets when they are rotated
+#[derive(Clone, Debug, Default)]
+pub struct SecretRefs {
+    /// Names of the secret references in the config store
+    pub names: Vec<String>,
+    /// The JWT of the entity the secrets are fetched for
+    pub entity_jwt: Option<String>,
+    /// The name of the application the entity is a part of, if any
+    pub application: Option<String>,
+}
+
+impl SecretRefs {
+    /// Collect the secret references from the given config names of an entity
+    pub fn new(
+        config_names: &[String],
+        entity_jwt: Option<&String>,
+        application: Option<&String>,
+    ) -> Self {
+        Self {
+            names: config_names
+                .iter()
+                .filter(|name| name.starts_with(SECRET_PREFIX))
+                .cloned()
+                .collect(),
+            entity_jwt: entity_jwt.cloned(),
+            application: application.cloned(),
+        }
+    }
+
+    /// Returns `true` if the entity does not reference any secrets
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+/// Returns the names of secrets, which were added, removed or changed value between `old` and `new`, sorted
+pub fn changed_secrets(
+    old: &HashMap<String, Secret<SecretValue>>,
+    new: &HashMap<String, Secret<SecretValue>>,
+) -> Vec<String> {
+    let mut changed: Vec<String> = new
+        .iter()
+        .filter(|(name, value)| {
+            let Some(old) = old.get(*name) else {
+                return true;
+            };
+            match (old.expose_secret(), value.expose_secret()) {
+                (SecretValue::String(old), SecretValue::String(new)) => old != new,
+                (SecretValue::Bytes(old), SecretValue::Bytes(new)) => old != new,
+                _ => true,
+            }
+        })
+        .map(|(name, _)| name.clone())
+        .chain(old.keys().filter(|name| !new.contains_key(*name)).cloned())
+        .collect();
+    changed.sort_unstable();
+    changed
+}
 
 #[derive(Debug)]
 /// A manager for fetching secrets from a secret store, caching secrets clients for efficiency.
@@ -80,6 +143,31 @@ impl Manager {
         Ok(client)
     }
 
+    /// Checks whether the configured secret store topic has a responder for every backend
+    /// this manager has created a client for.
+    ///
+    /// Returns successfully if no secret store topic is configured.
+    #[instrument(level = "trace", skip_all)]
+    pub async fn probe(&self, timeout: Duration) -> anyhow::Result<()> {
+        let Some(secret_store_topic) = self.secret_store_topic.as_ref() else {
+            return Ok(());
+        };
+        let backends: Vec<_> = self.backend_clients.read().await.keys().cloned().collect();
+        for backend in backends {
+            let request = async_nats::Request::new()
+                .payload(Bytes::new())
+                .timeout(Some(timeout));
+            self.nats_client
+                .send_request(
+                    format!("{secret_store_topic}.{SECRET_API_VERSION}.{backend}.server_xkey"),
+                    request,
+                )
+                .await
+                .with_context(|| format!("secrets backend `{backend}` unreachable"))?;
+        }
+        Ok(())
+    }
+
     /// Fetches secret references from the CONFIGDATA bucket by name and then fetches the actual secrets
     /// from the configured secret store. Any error returned from this function should result in a failure
     /// to start a component, start a provider, or establish a link as a missing secret is a critical
@@ -172,3 +260,49 @@ impl Manager {
         Ok(secrets)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changed_secrets_detects_updates_additions_and_removals() {
+        let old = HashMap::from([
+            (
+                "same".to_string(),
+                Secret::new(SecretValue::String("a".into())),
+            ),
+            (
+                "updated".to_string(),
+                Secret::new(SecretValue::Bytes(vec![1])),
+            ),
+            (
+                "removed".to_string(),
+                Secret::new(SecretValue::String("b".into())),
+            ),
+        ]);
+        let new = HashMap::from([
+            (
+                "same".to_string(),
+                Secret::new(SecretValue::String("a".into())),
+            ),
+            (
+                "updated".to_string(),
+                Secret::new(SecretValue::Bytes(vec![2])),
+            ),
+            (
+                "added".to_string(),
+                Secret::new(SecretValue::String("c".into())),
+            ),
+        ]);
+        assert_eq!(
+            changed_secrets(&old, &new),
+            vec![
+                "added".to_string(),
+                "removed".to_string(),
+                "updated".to_string()
+            ]
+        );
+        assert!(changed_secrets(&new, &new).is_empty());
+    }
+}
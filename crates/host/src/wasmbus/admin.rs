@@ -0,0 +1,59 @@
+//! Optional HTTP admin listener exposing liveness, readiness and inventory of a [Host]
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use tokio::net::TcpListener;
+use tokio::spawn;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, instrument};
+
+use super::Host;
+
+/// Start serving the admin HTTP endpoints for `host` on `addr`
+#[instrument(level = "debug", skip(host))]
+pub(crate) async fn serve(addr: SocketAddr, host: Arc<Host>) -> anyhow::Result<JoinHandle<()>> {
+    let listener = TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("failed to bind admin HTTP listener on `{addr}`"))?;
+    let app = Router::new()
+        .route("/livez", get(livez))
+        .route("/readyz", get(readyz))
+        .route("/inventory", get(inventory))
+        .with_state(host);
+    debug!(%addr, "serving admin HTTP endpoints");
+    Ok(spawn(async move {
+        if let Err(err) = axum::serve(listener, app).await {
+            error!(?err, "admin HTTP listener failed");
+        }
+    }))
+}
+
+async fn livez(State(host): State<Arc<Host>>) -> Response {
+    // The stop channel is written to once the host is asked to stop or one of its tasks exits
+    if host.stop_rx.has_changed().unwrap_or(true) {
+        (StatusCode::SERVICE_UNAVAILABLE, "stopping").into_response()
+    } else {
+        (StatusCode::OK, "ok").into_response()
+    }
+}
+
+async fn readyz(State(host): State<Arc<Host>>) -> Response {
+    let readiness = host.readiness().await;
+    let status = if readiness.is_ready() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(readiness)).into_response()
+}
+
+async fn inventory(State(host): State<Arc<Host>>) -> Response {
+    Json(host.inventory().await).into_response()
+}
@@ -0,0 +1,304 @@
+//! Per-target circuit breakers for invocations sent by components
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::bail;
+use cloudevents::EventBuilderV10;
+use tokio::spawn;
+use tracing::{info, warn};
+use wasmcloud_control_interface::{CircuitBreakerState, CircuitBreakerStateChanged, HostEvent};
+use wasmcloud_tracing::KeyValue;
+
+use super::event;
+use super::host_config::CircuitBreaker as CircuitBreakerConfig;
+use crate::metrics::HostMetrics;
+
+#[derive(Debug, Default)]
+struct Breaker {
+    state: CircuitBreakerState,
+    consecutive_failures: u32,
+    /// When an open breaker allows a trial invocation
+    retry_at: Option<Instant>,
+}
+
+/// Publisher of host events from outside of the [`Host`](super::Host)
+#[derive(Clone, Debug)]
+pub struct EventPublisher {
+    pub event_builder: EventBuilderV10,
+    pub ctl_nats: async_nats::Client,
+    pub event_stream: Option<async_nats::jetstream::Context>,
+    pub lattice: Arc<str>,
+}
+
+impl EventPublisher {
+    /// Publish `event` in the background, logging failures
+    fn publish_in_background(&self, event: HostEvent) {
+        let publisher = self.clone();
+        spawn(async move {
+            if let Err(err) = event::publish(
+                &publisher.event_builder,
+                &publisher.ctl_nats,
+                publisher.event_stream.as_ref(),
+                &publisher.lattice,
+                &event,
+            )
+            .await
+            {
+                warn!(?err, "failed to publish `{}` event", event.name());
+            }
+        });
+    }
+}
+
+/// Circuit breakers of the lattice targets invoked by components on this host. A breaker opens
+/// after a number of consecutive failed invocations of its target, failing further invocations
+/// fast. Once it has been open for a while, a single trial invocation is let through, which
+/// closes the breaker again if it succeeds.
+#[derive(Debug)]
+pub struct CircuitBreakers {
+    config: Option<CircuitBreakerConfig>,
+    breakers: Mutex<HashMap<String, Breaker>>,
+    host_id: String,
+    publisher: EventPublisher,
+    metrics: Arc<HostMetrics>,
+}
+
+impl CircuitBreakers {
+    /// Construct new [`CircuitBreakers`], which let all invocations through if `config` is `None`
+    pub fn new(
+        config: Option<CircuitBreakerConfig>,
+        host_id: impl Into<String>,
+        publisher: EventPublisher,
+        metrics: Arc<HostMetrics>,
+    ) -> Self {
+        Self {
+            config,
+            breakers: Mutex::default(),
+            host_id: host_id.into(),
+            publisher,
+            metrics,
+        }
+    }
+
+    fn transition(&self, target: &str, breaker: &mut Breaker, state: CircuitBreakerState) {
+        let previous_state = breaker.state;
+        breaker.state = state;
+        info!(
+            target,
+            ?previous_state,
+            ?state,
+            "circuit breaker state changed"
+        );
+        let state_name = match state {
+            CircuitBreakerState::Closed => "closed",
+            CircuitBreakerState::Open => "open",
+            CircuitBreakerState::HalfOpen => "half_open",
+        };
+        self.metrics.record_circuit_breaker_state_change(&[
+            KeyValue::new("target", target.to_string()),
+            KeyValue::new("state", state_name),
+        ]);
+        self.publisher
+            .publish_in_background(HostEvent::CircuitBreakerStateChanged(
+                CircuitBreakerStateChanged {
+                    host_id: self.host_id.clone(),
+                    target: target.to_string(),
+                    previous_state,
+                    state,
+                    consecutive_failures: breaker.consecutive_failures,
+                },
+            ));
+    }
+
+    /// Check whether an invocation of `target` may be sent, failing if the breaker is open
+    pub fn check(&self, target: &str) -> anyhow::Result<()> {
+        let Some(CircuitBreakerConfig { open_duration, .. }) = self.config else {
+            return Ok(());
+        };
+        let Ok(mut breakers) = self.breakers.lock() else {
+            return Ok(());
+        };
+        let Some(breaker) = breakers.get_mut(target) else {
+            return Ok(());
+        };
+        let now = Instant::now();
+        match breaker.state {
+            CircuitBreakerState::Closed => Ok(()),
+            // Let a single trial invocation through. Another one is let through if the outcome of
+            // the trial is not recorded in time, e.g. because the invocation was cancelled.
+            _ if breaker.retry_at.is_some_and(|t| t <= now) => {
+                breaker.retry_at = Some(now + open_duration);
+                if breaker.state == CircuitBreakerState::Open {
+                    self.transition(target, breaker, CircuitBreakerState::HalfOpen);
+                }
+                Ok(())
+            }
+            CircuitBreakerState::Open | CircuitBreakerState::HalfOpen => {
+                bail!("circuit breaker for target `{target}` is open")
+            }
+        }
+    }
+
+    /// Record the outcome of an invocation of `target`
+    pub fn record(&self, target: &str, success: bool) {
+        let Some(config) = &self.config else {
+            return;
+        };
+        let Ok(mut breakers) = self.breakers.lock() else {
+            return;
+        };
+        if success {
+            if let Some(mut breaker) = breakers.remove(target) {
+                if breaker.state != CircuitBreakerState::Closed {
+                    breaker.consecutive_failures = 0;
+                    self.transition(target, &mut breaker, CircuitBreakerState::Closed);
+                }
+            }
+            return;
+        }
+        let breaker = breakers.entry(target.to_string()).or_default();
+        breaker.consecutive_failures = breaker.consecutive_failures.saturating_add(1);
+        let open = match breaker.state {
+            CircuitBreakerState::Closed => breaker.consecutive_failures >= config.failure_threshold,
+            // The trial invocation failed
+            CircuitBreakerState::HalfOpen => true,
+            CircuitBreakerState::Open => false,
+        };
+        if open {
+            breaker.retry_at = Some(Instant::now() + config.open_duration);
+            self.transition(target, breaker, CircuitBreakerState::Open);
+        }
+    }
+}
+
+/// Returns the delay before retry number `retry` (starting at 1) with exponential backoff
+pub fn retry_backoff(initial: Duration, max: Duration, retry: u32) -> Duration {
+    initial
+        .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+        .min(max)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use cloudevents::{EventBuilder as _, EventBuilderV10};
+    use wasmcloud_control_interface::CircuitBreakerState;
+    use wasmcloud_tracing::global;
+
+    use super::{retry_backoff, CircuitBreakers, EventPublisher};
+    use crate::metrics::HostMetrics;
+    use crate::wasmbus::host_config::CircuitBreaker as CircuitBreakerConfig;
+
+    const TARGET: &str = "target";
+
+    async fn breakers(failure_threshold: u32, open_duration: Duration) -> CircuitBreakers {
+        // Events are published in the background, it does not matter that nothing listens
+        let ctl_nats = async_nats::ConnectOptions::new()
+            .retry_on_initial_connect()
+            .connect("127.0.0.1:1")
+            .await
+            .expect("failed to build NATS client");
+        CircuitBreakers::new(
+            Some(CircuitBreakerConfig {
+                failure_threshold,
+                open_duration,
+            }),
+            "host",
+            EventPublisher {
+                event_builder: EventBuilderV10::new(),
+                ctl_nats,
+                event_stream: None,
+                lattice: Arc::from("default"),
+            },
+            Arc::new(HostMetrics::new(
+                &global::meter("test"),
+                "host".to_string(),
+                "default".to_string(),
+            )),
+        )
+    }
+
+    fn state(breakers: &CircuitBreakers) -> CircuitBreakerState {
+        breakers
+            .breakers
+            .lock()
+            .expect("breakers lock poisoned")
+            .get(TARGET)
+            .map(|breaker| breaker.state)
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn backoff_is_exponential_and_capped() {
+        let initial = Duration::from_millis(100);
+        let max = Duration::from_millis(1000);
+        assert_eq!(retry_backoff(initial, max, 1), Duration::from_millis(100));
+        assert_eq!(retry_backoff(initial, max, 2), Duration::from_millis(200));
+        assert_eq!(retry_backoff(initial, max, 4), Duration::from_millis(800));
+        assert_eq!(retry_backoff(initial, max, 5), max);
+        assert_eq!(retry_backoff(initial, max, 64), max);
+    }
+
+    #[tokio::test]
+    async fn opens_after_consecutive_failures() {
+        let breakers = breakers(3, Duration::from_secs(60)).await;
+        breakers.record(TARGET, false);
+        breakers.record(TARGET, false);
+        // A success resets the consecutive failures
+        breakers.record(TARGET, true);
+        breakers.record(TARGET, false);
+        breakers.record(TARGET, false);
+        assert_eq!(state(&breakers), CircuitBreakerState::Closed);
+        assert!(breakers.check(TARGET).is_ok());
+
+        breakers.record(TARGET, false);
+        assert_eq!(state(&breakers), CircuitBreakerState::Open);
+        assert!(breakers.check(TARGET).is_err());
+        // Other targets are not affected
+        assert!(breakers.check("other").is_ok());
+    }
+
+    #[tokio::test]
+    async fn half_open_trial_closes_or_reopens() {
+        let open_duration = Duration::from_millis(50);
+        let breakers = breakers(1, open_duration).await;
+        breakers.record(TARGET, false);
+        assert_eq!(state(&breakers), CircuitBreakerState::Open);
+        assert!(breakers.check(TARGET).is_err());
+
+        // A single trial invocation is let through once the breaker was open for long enough
+        tokio::time::sleep(open_duration * 2).await;
+        assert!(breakers.check(TARGET).is_ok());
+        assert_eq!(state(&breakers), CircuitBreakerState::HalfOpen);
+        assert!(breakers.check(TARGET).is_err());
+
+        // A failed trial opens the breaker again
+        breakers.record(TARGET, false);
+        assert_eq!(state(&breakers), CircuitBreakerState::Open);
+        assert!(breakers.check(TARGET).is_err());
+
+        // A successful trial closes it
+        tokio::time::sleep(open_duration * 2).await;
+        assert!(breakers.check(TARGET).is_ok());
+        assert_eq!(state(&breakers), CircuitBreakerState::HalfOpen);
+        breakers.record(TARGET, true);
+        assert_eq!(state(&breakers), CircuitBreakerState::Closed);
+        assert!(breakers.check(TARGET).is_ok());
+    }
+
+    #[tokio::test]
+    async fn disabled_breakers_let_everything_through() {
+        let breakers = breakers(1, Duration::from_secs(60)).await;
+        let breakers = CircuitBreakers {
+            config: None,
+            ..breakers
+        };
+        breakers.record(TARGET, false);
+        breakers.record(TARGET, false);
+        assert!(breakers.check(TARGET).is_ok());
+    }
+}
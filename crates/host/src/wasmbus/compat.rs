@@ -0,0 +1,183 @@
+//! Compatibility checks of links against the WIT imports and exports of their source and target
+
+use anyhow::{bail, Context as _};
+use semver::Version;
+use wasmcloud_control_interface::{InterfaceLinkDefinition, LinkStatus};
+use wit_component::DecodedWasm;
+use wit_parser::WorldItem;
+
+/// Splits an instance name like `wasi:http/incoming-handler@0.2.0` into the name without the
+/// version and the version, if any
+fn split_version(instance: &str) -> (&str, Option<&str>) {
+    match instance.split_once('@') {
+        Some((name, version)) => (name, Some(version)),
+        None => (instance, None),
+    }
+}
+
+/// Returns whether an import of `import` can be satisfied by an export of `export`, following
+/// semver compatibility rules. Versions which are not valid semver must match exactly.
+fn versions_compatible(import: &str, export: &str) -> bool {
+    match (Version::parse(import), Version::parse(export)) {
+        (Ok(import), Ok(export)) => match (import.major, import.minor) {
+            (0, 0) => export.major == 0 && export.minor == 0 && import.patch == export.patch,
+            (0, minor) => export.major == 0 && export.minor == minor,
+            (major, _) => export.major == major,
+        },
+        _ => import == export,
+    }
+}
+
+/// Find the instance named `name` (without version) in `instances`
+fn find_instance<'a>(instances: &'a [String], name: &str) -> Option<(&'a str, Option<&'a str>)> {
+    instances
+        .iter()
+        .map(|instance| split_version(instance))
+        .find(|(instance, _)| *instance == name)
+}
+
+/// Returns the names of the instances imported and exported by the worlds of an encoded WIT
+/// package, like the one bundled in provider archives
+pub fn wit_package_instances(wit: &[u8]) -> anyhow::Result<(Vec<String>, Vec<String>)> {
+    let DecodedWasm::WitPackage(resolve, package) =
+        wit_component::decode(wit).context("failed to decode WIT package")?
+    else {
+        bail!("expected an encoded WIT package, found a component");
+    };
+    let instances = |items: &mut dyn Iterator<Item = &WorldItem>| {
+        let mut names: Vec<String> = items
+            .filter_map(|item| match item {
+                WorldItem::Interface { id, .. } => resolve.id_of(*id),
+                WorldItem::Function(_) | WorldItem::Type(_) => None,
+            })
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    };
+    let worlds = || {
+        resolve.packages[package]
+            .worlds
+            .values()
+            .map(|id| &resolve.worlds[*id])
+    };
+    let imports = instances(&mut worlds().flat_map(|world| world.imports.values()));
+    let exports = instances(&mut worlds().flat_map(|world| world.exports.values()));
+    Ok((imports, exports))
+}
+
+/// Check `link` against the instances imported by its source and exported by its target.
+///
+/// `None` is passed for ends of the link, whose WIT is not known, e.g. providers without a
+/// bundled WIT package or components that were not started yet. Returns `None` if neither end
+/// is known.
+pub fn check_link(
+    link: &InterfaceLinkDefinition,
+    source_imports: Option<&[String]>,
+    target_exports: Option<&[String]>,
+) -> Option<LinkStatus> {
+    if source_imports.is_none() && target_exports.is_none() {
+        return None;
+    }
+    let mut problems = Vec::new();
+    for interface in &link.interfaces {
+        let name = format!("{}:{}/{interface}", link.wit_namespace, link.wit_package);
+        let import = source_imports.map(|imports| find_instance(imports, &name));
+        let export = target_exports.map(|exports| find_instance(exports, &name));
+        if let Some(None) = import {
+            problems.push(format!(
+                "source `{}` does not import `{name}`",
+                link.source_id
+            ));
+        }
+        if let Some(None) = export {
+            problems.push(format!("target `{}` does not export `{name}`", link.target));
+        }
+        if let (Some(Some((_, Some(import)))), Some(Some((_, Some(export))))) = (import, export) {
+            if !versions_compatible(import, export) {
+                problems.push(format!(
+                    "source `{}` imports `{name}@{import}`, which is incompatible with `{name}@{export}` exported by target `{}`",
+                    link.source_id, link.target
+                ));
+            }
+        }
+    }
+    if problems.is_empty() {
+        Some(LinkStatus::Healthy)
+    } else {
+        Some(LinkStatus::Unhealthy {
+            reason: problems.join(", "),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wasmcloud_control_interface::{InterfaceLinkDefinition, LinkStatus};
+
+    use super::{check_link, wit_package_instances};
+
+    #[test]
+    fn checks_links() {
+        let link = InterfaceLinkDefinition {
+            source_id: "http".to_string(),
+            target: "echo".to_string(),
+            wit_namespace: "wasi".to_string(),
+            wit_package: "http".to_string(),
+            interfaces: vec!["incoming-handler".to_string()],
+            ..Default::default()
+        };
+        let imports = ["wasi:http/incoming-handler@0.2.1".to_string()];
+        assert_eq!(check_link(&link, None, None), None);
+        assert_eq!(
+            check_link(
+                &link,
+                Some(&imports),
+                Some(&["wasi:http/incoming-handler@0.2.0".to_string()])
+            ),
+            Some(LinkStatus::Healthy)
+        );
+        assert!(matches!(
+            check_link(
+                &link,
+                Some(&imports),
+                Some(&["wasi:http/incoming-handler@0.3.0".to_string()])
+            ),
+            Some(LinkStatus::Unhealthy { .. })
+        ));
+        assert!(matches!(
+            check_link(&link, None, Some(&["wasi:cli/run@0.2.0".to_string()])),
+            Some(LinkStatus::Unhealthy { .. })
+        ));
+    }
+
+    #[test]
+    fn decodes_wit_package_instances() -> anyhow::Result<()> {
+        let mut resolve = wit_parser::Resolve::default();
+        let package = resolve.push_str(
+            "provider.wit",
+            r#"
+            package wasmcloud:example@0.1.0;
+
+            interface store {
+                get: func(key: string) -> option<string>;
+            }
+
+            interface handler {
+                handle: func(key: string);
+            }
+
+            world provider {
+                import handler;
+                export store;
+            }
+            "#,
+        )?;
+        let wit = wit_component::encode(None, &resolve, package)?;
+        let (imports, exports) = wit_package_instances(&wit)?;
+        assert_eq!(imports, ["wasmcloud:example/handler@0.1.0"]);
+        assert_eq!(exports, ["wasmcloud:example/store@0.1.0"]);
+        assert!(wit_package_instances(b"not wasm").is_err());
+        Ok(())
+    }
+}
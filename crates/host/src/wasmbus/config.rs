@@ -15,7 +15,7 @@ type LockedConfig = Arc<RwLock<HashMap<String, String>>>;
 type WatchCache = Arc<RwLock<HashMap<String, Receiver<HashMap<String, String>>>>>;
 
 /// A struct used for mapping a config name to a receiver for logging/tracing purposes
-struct ConfigReceiver {
+pub(crate) struct ConfigReceiver {
     pub name: String,
     pub receiver: Receiver<HashMap<String, String>>,
 }
@@ -90,7 +90,7 @@ impl ConfigBundle {
     ///
     /// This is only called internally.
     #[must_use]
-    async fn new(receivers: Vec<ConfigReceiver>) -> Self {
+    pub(crate) async fn new(receivers: Vec<ConfigReceiver>) -> Self {
         // Generate the initial abort handles so we can construct the bundle
         let (abort_handles, mut registrations): (Vec<_>, Vec<_>) =
             std::iter::repeat_with(AbortHandle::new_pair)
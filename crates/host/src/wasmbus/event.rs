@@ -1,35 +1,43 @@
 use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use anyhow::Context;
+use bytes::Bytes;
 use cloudevents::{EventBuilder, EventBuilderV10};
-use serde_json::json;
 use time::{format_description::well_known::Rfc3339, OffsetDateTime};
-use tracing::instrument;
+use tracing::{instrument, warn};
 use ulid::Ulid;
 use uuid::Uuid;
 use wascap::jwt;
+use wasmcloud_control_interface::{
+    host_event_stream_subject, ClaimsExpiring, ComponentClaims, ComponentScaleFailed,
+    ComponentScaled, ConfigDeleted, ConfigSet, HostEvent, InterfaceLinkDefinition, LabelsChanged,
+    LinkdefDeleted, LinkdefSet, LinkdefSetFailed, ProviderClaims, ProviderHealthCheck,
+    ProviderStartFailed, ProviderStarted, ProviderStopped, SecretsRotated,
+    HOST_EVENT_SCHEMA_VERSION, HOST_EVENT_SCHEMA_VERSION_EXTENSION,
+};
 
-fn format_component_claims(claims: &jwt::Claims<jwt::Component>) -> serde_json::Value {
-    let issuer = &claims.issuer;
-    let not_before_human = "TODO";
-    let expires_human = "TODO";
-    if let Some(component) = &claims.metadata {
-        json!({
-            "call_alias": component.call_alias,
-            "issuer": issuer,
-            "tags": component.tags,
-            "name": component.name,
-            "version": component.ver,
-            "revision": component.rev,
-            "not_before_human": not_before_human,
-            "expires_human": expires_human,
-        })
-    } else {
-        json!({
-            "issuer": issuer,
-            "not_before_human": not_before_human,
-            "expires_human": expires_human,
-        })
+/// Format a JWT timestamp as an RFC 3339 date, or `default` if it is not set
+fn format_timestamp(stamp: Option<u64>, default: &str) -> String {
+    stamp
+        .and_then(|stamp| UNIX_EPOCH.checked_add(Duration::from_secs(stamp)))
+        .map_or_else(
+            || default.to_string(),
+            |time: SystemTime| humantime::format_rfc3339_seconds(time).to_string(),
+        )
+}
+
+fn format_component_claims(claims: &jwt::Claims<jwt::Component>) -> ComponentClaims {
+    let metadata = claims.metadata.as_ref();
+    ComponentClaims {
+        issuer: claims.issuer.clone(),
+        call_alias: metadata.and_then(|component| component.call_alias.clone()),
+        tags: metadata.and_then(|component| component.tags.clone()),
+        name: metadata.and_then(|component| component.name.clone()),
+        version: metadata.and_then(|component| component.ver.clone()),
+        revision: metadata.and_then(|component| component.rev),
+        not_before_human: format_timestamp(claims.not_before, "immediately"),
+        expires_human: format_timestamp(claims.expires, "never"),
     }
 }
 
@@ -40,26 +48,16 @@ pub fn component_scaled(
     max_instances: impl Into<usize>,
     image_ref: impl AsRef<str>,
     component_id: impl AsRef<str>,
-) -> serde_json::Value {
-    if let Some(claims) = claims {
-        json!({
-            "public_key": claims.subject,
-            "claims": format_component_claims(claims),
-            "annotations": annotations,
-            "host_id": host_id.as_ref(),
-            "image_ref": image_ref.as_ref(),
-            "max_instances": max_instances.into(),
-            "component_id": component_id.as_ref(),
-        })
-    } else {
-        json!({
-            "annotations": annotations,
-            "host_id": host_id.as_ref(),
-            "image_ref": image_ref.as_ref(),
-            "max_instances": max_instances.into(),
-            "component_id": component_id.as_ref(),
-        })
-    }
+) -> HostEvent {
+    HostEvent::ComponentScaled(ComponentScaled {
+        public_key: claims.map(|claims| claims.subject.clone()),
+        claims: claims.map(format_component_claims),
+        annotations: annotations.clone(),
+        host_id: host_id.as_ref().to_string(),
+        image_ref: image_ref.as_ref().to_string(),
+        max_instances: max_instances.into().try_into().unwrap_or(u32::MAX),
+        component_id: component_id.as_ref().to_string(),
+    })
 }
 
 pub fn component_scale_failed(
@@ -70,58 +68,26 @@ pub fn component_scale_failed(
     component_id: impl AsRef<str>,
     max_instances: u32,
     error: &anyhow::Error,
-) -> serde_json::Value {
-    if let Some(claims) = claims {
-        json!({
-            "public_key": claims.subject,
-            "component_id": component_id.as_ref(),
-            "annotations": annotations,
-            "host_id": host_id.as_ref(),
-            "image_ref": image_ref.as_ref(),
-            "max_instances": max_instances,
-            "error": format!("{error:#}"),
-        })
-    } else {
-        json!({
-            "annotations": annotations,
-            "component_id": component_id.as_ref(),
-            "host_id": host_id.as_ref(),
-            "image_ref": image_ref.as_ref(),
-            "max_instances": max_instances,
-            "error": format!("{error:#}"),
-        })
-    }
+) -> HostEvent {
+    HostEvent::ComponentScaleFailed(ComponentScaleFailed {
+        public_key: claims.map(|claims| claims.subject.clone()),
+        component_id: component_id.as_ref().to_string(),
+        annotations: annotations.clone(),
+        host_id: host_id.as_ref().to_string(),
+        image_ref: image_ref.as_ref().to_string(),
+        max_instances,
+        error: format!("{error:#}"),
+    })
 }
 
-pub fn linkdef_set(
-    link: &wasmcloud_control_interface::InterfaceLinkDefinition,
-) -> serde_json::Value {
-    json!({
-        "source_id": link.source_id,
-        "target": link.target,
-        "name": link.name,
-        "wit_namespace": link.wit_namespace,
-        "wit_package": link.wit_package,
-        "interfaces": link.interfaces,
-        "source_config": link.source_config,
-        "target_config": link.target_config,
-    })
+pub fn linkdef_set(link: &InterfaceLinkDefinition) -> HostEvent {
+    HostEvent::LinkdefSet(LinkdefSet { link: link.clone() })
 }
 
-pub fn linkdef_set_failed(
-    link: &wasmcloud_control_interface::InterfaceLinkDefinition,
-    error: &anyhow::Error,
-) -> serde_json::Value {
-    json!({
-        "source_id": link.source_id,
-        "target": link.target,
-        "name": link.name,
-        "wit_namespace": link.wit_namespace,
-        "wit_package": link.wit_package,
-        "interfaces": link.interfaces,
-        "source_config": link.source_config,
-        "target_config": link.target_config,
-        "error": format!("{error:#}"),
+pub fn linkdef_set_failed(link: &InterfaceLinkDefinition, error: &anyhow::Error) -> HostEvent {
+    HostEvent::LinkdefSetFailed(LinkdefSetFailed {
+        link: link.clone(),
+        error: format!("{error:#}"),
     })
 }
 
@@ -130,12 +96,12 @@ pub fn linkdef_deleted(
     name: impl AsRef<str>,
     wit_namespace: impl AsRef<str>,
     wit_package: impl AsRef<str>,
-) -> serde_json::Value {
-    json!({
-        "source_id": source_id.as_ref(),
-        "name": name.as_ref(),
-        "wit_namespace": wit_namespace.as_ref(),
-        "wit_package": wit_package.as_ref(),
+) -> HostEvent {
+    HostEvent::LinkdefDeleted(LinkdefDeleted {
+        source_id: source_id.as_ref().to_string(),
+        name: name.as_ref().to_string(),
+        wit_namespace: wit_namespace.as_ref().to_string(),
+        wit_package: wit_package.as_ref().to_string(),
     })
 }
 
@@ -145,48 +111,45 @@ pub fn provider_started(
     host_id: impl AsRef<str>,
     image_ref: impl AsRef<str>,
     provider_id: impl AsRef<str>,
-) -> serde_json::Value {
-    if let Some(claims) = claims {
+) -> HostEvent {
+    let provider_id = provider_id.as_ref();
+    let claims = claims.map(|claims| {
         let metadata = claims.metadata.as_ref();
-        json!({
-            "host_id": host_id.as_ref(),
-            "image_ref": image_ref.as_ref(),
-            "provider_id": provider_id.as_ref(),
-            "annotations": annotations,
-            "claims": {
-                "issuer": &claims.issuer,
-                "tags": None::<Vec<()>>, // present in OTP, but hardcoded to `None`
-                "name": metadata.map(|jwt::CapabilityProvider { name, .. }| name),
-                "version": metadata.map(|jwt::CapabilityProvider { ver, .. }| ver),
-                "not_before_human": "TODO",
-                "expires_human": "TODO",
-            },
-            // TODO(#1548): remove these fields when we don't depend on them
-            "instance_id": provider_id.as_ref(),
-            "public_key": provider_id.as_ref(),
-            "link_name": "default",
-        })
-    } else {
-        json!({
-            "host_id": host_id.as_ref(),
-            "image_ref": image_ref.as_ref(),
-            "provider_id": provider_id.as_ref(),
-            "annotations": annotations,
-        })
-    }
+        ProviderClaims {
+            issuer: claims.issuer.clone(),
+            // present in OTP, but hardcoded to `None`
+            tags: None,
+            name: metadata.and_then(|provider| provider.name.clone()),
+            version: metadata.and_then(|provider| provider.ver.clone()),
+            not_before_human: format_timestamp(claims.not_before, "immediately"),
+            expires_human: format_timestamp(claims.expires, "never"),
+        }
+    });
+    // TODO(#1548): remove the legacy fields when we don't depend on them
+    let legacy = claims.is_some();
+    HostEvent::ProviderStarted(ProviderStarted {
+        host_id: host_id.as_ref().to_string(),
+        image_ref: image_ref.as_ref().to_string(),
+        provider_id: provider_id.to_string(),
+        annotations: annotations.clone(),
+        claims,
+        instance_id: legacy.then(|| provider_id.to_string()),
+        public_key: legacy.then(|| provider_id.to_string()),
+        link_name: legacy.then(|| "default".to_string()),
+    })
 }
 
 pub fn provider_start_failed(
     provider_ref: impl AsRef<str>,
     provider_id: impl AsRef<str>,
     error: &anyhow::Error,
-) -> serde_json::Value {
-    json!({
-        "provider_ref": provider_ref.as_ref(),
-        "provider_id": provider_id.as_ref(),
-        "error": format!("{error:#}"),
+) -> HostEvent {
+    HostEvent::ProviderStartFailed(ProviderStartFailed {
+        provider_ref: provider_ref.as_ref().to_string(),
+        provider_id: provider_id.as_ref().to_string(),
+        error: format!("{error:#}"),
         // TODO(#1548): remove this field when we don't depend on it
-        "link_name": "default",
+        link_name: Some("default".to_string()),
     })
 }
 
@@ -195,73 +158,133 @@ pub fn provider_stopped(
     host_id: impl AsRef<str>,
     provider_id: impl AsRef<str>,
     reason: impl AsRef<str>,
-) -> serde_json::Value {
-    json!({
-        "host_id": host_id.as_ref(),
-        "provider_id": provider_id.as_ref(),
-        "annotations": annotations,
-        "reason": reason.as_ref(),
+) -> HostEvent {
+    HostEvent::ProviderStopped(ProviderStopped {
+        host_id: host_id.as_ref().to_string(),
+        provider_id: provider_id.as_ref().to_string(),
+        annotations: annotations.clone(),
+        reason: reason.as_ref().to_string(),
         // TODO(#1548): remove these fields when we don't depend on them
-        "instance_id": provider_id.as_ref(),
-        "public_key": provider_id.as_ref(),
-        "link_name": "default",
+        instance_id: Some(provider_id.as_ref().to_string()),
+        public_key: Some(provider_id.as_ref().to_string()),
+        link_name: Some("default".to_string()),
     })
 }
 
 pub fn provider_health_check(
     host_id: impl AsRef<str>,
     provider_id: impl AsRef<str>,
-) -> serde_json::Value {
-    json!({
-        "host_id": host_id.as_ref(),
-        "provider_id": provider_id.as_ref(),
-    })
+) -> ProviderHealthCheck {
+    ProviderHealthCheck {
+        host_id: host_id.as_ref().to_string(),
+        provider_id: provider_id.as_ref().to_string(),
+    }
 }
 
-pub fn config_set(config_name: impl AsRef<str>) -> serde_json::Value {
-    json!({
-        "config_name": config_name.as_ref(),
+pub fn config_set(
+    config_name: impl AsRef<str>,
+    revision: Option<u64>,
+    previous_revision: Option<u64>,
+) -> HostEvent {
+    HostEvent::ConfigSet(ConfigSet {
+        config_name: config_name.as_ref().to_string(),
+        revision,
+        previous_revision,
     })
 }
 
-pub fn config_deleted(config_name: impl AsRef<str>) -> serde_json::Value {
-    json!({
-        "config_name": config_name.as_ref(),
+pub fn config_deleted(config_name: impl AsRef<str>) -> HostEvent {
+    HostEvent::ConfigDeleted(ConfigDeleted {
+        config_name: config_name.as_ref().to_string(),
     })
 }
 
 pub fn labels_changed(
     host_id: impl AsRef<str>,
     labels: impl Into<HashMap<String, String>>,
-) -> serde_json::Value {
-    json!({
-        "host_id": host_id.as_ref(),
-        "labels": labels.into(),
+) -> HostEvent {
+    HostEvent::LabelsChanged(LabelsChanged {
+        host_id: host_id.as_ref().to_string(),
+        labels: labels.into(),
+    })
+}
+
+pub fn secrets_rotated(
+    host_id: impl AsRef<str>,
+    entity_id: impl AsRef<str>,
+    secret_names: Vec<String>,
+) -> HostEvent {
+    HostEvent::SecretsRotated(SecretsRotated {
+        host_id: host_id.as_ref().to_string(),
+        entity_id: entity_id.as_ref().to_string(),
+        secret_names,
     })
 }
 
-#[instrument(level = "debug", skip(event_builder, ctl_nats, data))]
+pub fn claims_expiring(
+    host_id: impl AsRef<str>,
+    entity_id: impl AsRef<str>,
+    public_key: impl AsRef<str>,
+    expires: u64,
+    now: u64,
+) -> HostEvent {
+    HostEvent::ClaimsExpiring(ClaimsExpiring {
+        host_id: host_id.as_ref().to_string(),
+        entity_id: entity_id.as_ref().to_string(),
+        public_key: public_key.as_ref().to_string(),
+        expires_human: format_timestamp(Some(expires), "never"),
+        expires_in_seconds: expires.saturating_sub(now),
+        expired: now >= expires,
+    })
+}
+
+#[instrument(level = "debug", skip(event_builder, ctl_nats, event_stream, event), fields(name = event.name()))]
 pub(crate) async fn publish(
     event_builder: &EventBuilderV10,
     ctl_nats: &async_nats::Client,
+    event_stream: Option<&async_nats::jetstream::Context>,
     lattice: &str,
-    name: &str,
-    data: serde_json::Value,
+    event: &HostEvent,
 ) -> anyhow::Result<()> {
+    let name = event.name();
     let now = OffsetDateTime::now_utc()
         .format(&Rfc3339)
         .context("failed to format current time")?;
+    let data = event
+        .to_data()
+        .with_context(|| format!("failed to serialize `{name}` event data"))?;
     let ev = event_builder
         .clone()
-        .ty(format!("com.wasmcloud.lattice.{name}"))
+        .ty(event.event_type())
         .id(Uuid::from_u128(Ulid::new().into()).to_string())
         .time(now)
+        .extension(
+            HOST_EVENT_SCHEMA_VERSION_EXTENSION,
+            HOST_EVENT_SCHEMA_VERSION,
+        )
         .data("application/json", data)
         .build()
         .context("failed to build cloud event")?;
-    let ev = serde_json::to_vec(&ev).context("failed to serialize event")?;
+    let ev: Bytes = serde_json::to_vec(&ev)
+        .context("failed to serialize event")?
+        .into();
     ctl_nats
-        .publish(format!("wasmbus.evt.{lattice}.{name}"), ev.into())
+        .publish(format!("wasmbus.evt.{lattice}.{name}"), ev.clone())
         .await
-        .with_context(|| format!("failed to publish `{name}` event"))
+        .with_context(|| format!("failed to publish `{name}` event"))?;
+    // The event stream is best-effort, live subscribers must not miss events because of it
+    if let Some(event_stream) = event_stream {
+        match event_stream
+            .publish(host_event_stream_subject(lattice, name), ev)
+            .await
+        {
+            Ok(ack) => {
+                if let Err(err) = ack.await {
+                    warn!(?err, name, "event stream did not acknowledge event");
+                }
+            }
+            Err(err) => warn!(?err, name, "failed to write event to event stream"),
+        }
+    }
+    Ok(())
 }
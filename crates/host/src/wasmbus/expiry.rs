@@ -0,0 +1,89 @@
+//! Tracking of the expiry of claims of running components and providers
+
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+/// The expiry notification last sent for the claims of an entity
+#[derive(Debug)]
+struct Notified {
+    /// The expiry of the claims the notification was sent for, in seconds since the epoch
+    expires: u64,
+    /// The lead time the notification was sent for, zero once the claims expired
+    lead_time: u64,
+}
+
+/// Tracks the `claims_expiring` notifications sent for running components and providers, so that
+/// each lead time is only notified once per entity
+#[derive(Debug, Default)]
+pub struct ExpiryTracker {
+    notified: HashMap<String, Notified>,
+}
+
+impl ExpiryTracker {
+    /// Returns whether a notification is due for the entity `id`, whose claims expire at `expires`,
+    /// at time `now`, both in seconds since the epoch. A notification is due once the claims expire
+    /// within a lead time in `lead_times` they were not notified for yet, and once they expired.
+    pub fn due(&mut self, id: &str, expires: u64, now: u64, lead_times: &[Duration]) -> bool {
+        let remaining = expires.saturating_sub(now);
+        // The smallest lead time the claims are within, zero once they expired
+        let Some(lead_time) = lead_times
+            .iter()
+            .map(Duration::as_secs)
+            .chain([0])
+            .filter(|lead_time| remaining <= *lead_time)
+            .min()
+        else {
+            return false;
+        };
+        match self.notified.get(id) {
+            // Claims with a different expiry, e.g. of an updated component, are notified anew
+            Some(notified) if notified.expires == expires && notified.lead_time <= lead_time => {
+                false
+            }
+            _ => {
+                self.notified
+                    .insert(id.to_string(), Notified { expires, lead_time });
+                true
+            }
+        }
+    }
+
+    /// Forget entities that are not in `running`
+    pub fn retain<'a>(&mut self, running: impl IntoIterator<Item = &'a str>) {
+        let running: HashSet<&str> = running.into_iter().collect();
+        self.notified.retain(|id, _| running.contains(id.as_str()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::ExpiryTracker;
+
+    #[test]
+    fn notifies_each_lead_time_once() {
+        const DAY: u64 = 24 * 60 * 60;
+        let lead_times = [Duration::from_secs(7 * DAY), Duration::from_secs(DAY)];
+        let expires = 100 * DAY;
+        let mut tracker = ExpiryTracker::default();
+
+        assert!(!tracker.due("echo", expires, expires - 8 * DAY, &lead_times));
+        assert!(tracker.due("echo", expires, expires - 7 * DAY, &lead_times));
+        assert!(!tracker.due("echo", expires, expires - 6 * DAY, &lead_times));
+        assert!(tracker.due("echo", expires, expires - DAY / 2, &lead_times));
+        assert!(!tracker.due("echo", expires, expires - 1, &lead_times));
+        assert!(tracker.due("echo", expires, expires, &lead_times));
+        assert!(!tracker.due("echo", expires, expires + DAY, &lead_times));
+
+        // Entities crossing several lead times at once are notified once
+        assert!(tracker.due("kv", expires, expires - DAY / 2, &lead_times));
+        assert!(!tracker.due("kv", expires, expires - DAY / 3, &lead_times));
+
+        // Renewed claims are notified anew
+        assert!(tracker.due("echo", expires + DAY, expires, &lead_times));
+
+        tracker.retain(["kv"]);
+        assert!(tracker.due("echo", expires, expires, &lead_times));
+    }
+}
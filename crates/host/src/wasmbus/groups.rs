@@ -0,0 +1,233 @@
+//! Resolution of link targets referring to a [`TargetGroup`]
+
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::{ensure, Context as _};
+use rand::Rng as _;
+use tokio::sync::RwLock;
+use wasmcloud_control_interface::{TargetGroup, TargetSelection, TARGET_GROUP_PREFIX};
+
+/// Validate a [`TargetGroup`] before it is stored
+pub fn validate_target_group(group: &TargetGroup) -> anyhow::Result<()> {
+    ensure!(
+        !group.name.is_empty(),
+        "target group name must not be empty"
+    );
+    ensure!(
+        !group.name.contains(['.', '>', '*', ' ']),
+        "target group name must be a valid NATS subject token"
+    );
+    for member in &group.members {
+        ensure!(
+            !member.target.is_empty(),
+            "target group member target must not be empty"
+        );
+        ensure!(
+            !member.target.starts_with(TARGET_GROUP_PREFIX),
+            "target group members must not be target groups"
+        );
+        ensure!(
+            member.weight > 0,
+            "weight of target group member `{}` must be positive",
+            member.target
+        );
+    }
+    if let TargetSelection::ConsistentHash { key } = &group.selection {
+        ensure!(!key.is_empty(), "consistent hash key must not be empty");
+    }
+    Ok(())
+}
+
+#[derive(Debug)]
+struct Entry {
+    group: TargetGroup,
+    /// Invocation counter used for round-robin selection
+    next: AtomicU64,
+}
+
+/// Cache of the [`TargetGroup`]s in the lattice, kept up to date from the lattice data bucket
+#[derive(Debug, Default)]
+pub struct TargetGroups {
+    groups: RwLock<HashMap<String, Arc<Entry>>>,
+}
+
+impl TargetGroups {
+    /// Insert or replace a group
+    pub async fn put(&self, group: TargetGroup) {
+        let entry = Arc::new(Entry {
+            group,
+            next: AtomicU64::default(),
+        });
+        self.groups
+            .write()
+            .await
+            .insert(entry.group.name.clone(), entry);
+    }
+
+    /// Remove the group `name`
+    pub async fn remove(&self, name: &str) {
+        self.groups.write().await.remove(name);
+    }
+
+    /// Returns the group `name`, if it exists
+    pub async fn get(&self, name: &str) -> Option<TargetGroup> {
+        self.groups
+            .read()
+            .await
+            .get(name)
+            .map(|entry| entry.group.clone())
+    }
+
+    /// Returns all groups
+    pub async fn list(&self) -> Vec<TargetGroup> {
+        self.groups
+            .read()
+            .await
+            .values()
+            .map(|entry| entry.group.clone())
+            .collect()
+    }
+
+    /// Select a member of group `name` for an invocation. `key` is called to obtain the hash key
+    /// for groups using [`TargetSelection::ConsistentHash`] with the name of the key.
+    pub async fn resolve(
+        &self,
+        name: &str,
+        key: impl FnOnce(&str) -> String,
+    ) -> anyhow::Result<String> {
+        let entry = self
+            .groups
+            .read()
+            .await
+            .get(name)
+            .cloned()
+            .with_context(|| format!("target group `{name}` not found"))?;
+        let members = &entry.group.members;
+        let total: u64 = members.iter().map(|m| u64::from(m.weight)).sum();
+        ensure!(total > 0, "target group `{name}` has no members");
+        let member = match &entry.group.selection {
+            TargetSelection::RoundRobin => {
+                let n = entry.next.fetch_add(1, Ordering::Relaxed) % total;
+                select_weighted(members.iter().map(|m| m.weight), n)
+            }
+            TargetSelection::Random => {
+                let n = rand::thread_rng().gen_range(0..total);
+                select_weighted(members.iter().map(|m| m.weight), n)
+            }
+            TargetSelection::ConsistentHash { key: key_name } => {
+                let key = key(key_name);
+                rendezvous(members.iter().map(|m| (m.target.as_str(), m.weight)), &key)
+            }
+        };
+        Ok(members[member].target.clone())
+    }
+}
+
+/// Returns the index of the member the `n`th unit of the cumulative weights falls into
+fn select_weighted(weights: impl IntoIterator<Item = u32>, mut n: u64) -> usize {
+    let mut last = 0;
+    for (i, weight) in weights.into_iter().enumerate() {
+        let weight = u64::from(weight);
+        if n < weight {
+            return i;
+        }
+        n -= weight;
+        last = i;
+    }
+    last
+}
+
+/// Returns the index of the member with the highest weighted rendezvous score for `key`, see
+/// <https://en.wikipedia.org/wiki/Rendezvous_hashing#Weighted_rendezvous_hash>
+fn rendezvous<'a>(members: impl IntoIterator<Item = (&'a str, u32)>, key: &str) -> usize {
+    let mut best = (0, f64::NEG_INFINITY);
+    for (i, (target, weight)) in members.into_iter().enumerate() {
+        let hash = fnv1a([key.as_bytes(), b"\0", target.as_bytes()]);
+        // Map the hash into (0, 1) and compute `-weight / ln(h)`
+        #[allow(clippy::cast_precision_loss)]
+        let h = ((hash >> 11) as f64 + 0.5) / (1u64 << 53) as f64;
+        let score = -f64::from(weight) / h.ln();
+        if score > best.1 {
+            best = (i, score);
+        }
+    }
+    best.0
+}
+
+/// 64-bit FNV-1a hash, which is stable across hosts and releases, unlike the std hasher
+fn fnv1a<'a>(parts: impl IntoIterator<Item = &'a [u8]>) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for b in parts.into_iter().flatten() {
+        hash ^= u64::from(*b);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    // FNV-1a has poor avalanche on short, similar inputs, so finish with a 64-bit mixer
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    hash ^ (hash >> 33)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use wasmcloud_control_interface::{TargetGroup, TargetGroupMember, TargetSelection};
+
+    use super::TargetGroups;
+
+    fn group(selection: TargetSelection) -> TargetGroup {
+        TargetGroup {
+            name: "echo".into(),
+            selection,
+            members: vec![
+                TargetGroupMember {
+                    target: "blue".into(),
+                    weight: 3,
+                },
+                TargetGroupMember::new("green"),
+            ],
+        }
+    }
+
+    #[tokio::test]
+    async fn round_robin_is_weighted() -> anyhow::Result<()> {
+        let groups = TargetGroups::default();
+        groups.put(group(TargetSelection::RoundRobin)).await;
+        let mut counts = HashMap::<String, usize>::new();
+        for _ in 0..8 {
+            *counts
+                .entry(groups.resolve("echo", |_| unreachable!()).await?)
+                .or_default() += 1;
+        }
+        assert_eq!(counts["blue"], 6);
+        assert_eq!(counts["green"], 2);
+        assert!(groups.resolve("missing", |_| unreachable!()).await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn consistent_hash_is_sticky() -> anyhow::Result<()> {
+        let groups = TargetGroups::default();
+        groups
+            .put(group(TargetSelection::ConsistentHash {
+                key: "user".into(),
+            }))
+            .await;
+        let mut counts = HashMap::<String, usize>::new();
+        for i in 0..1000 {
+            let key = format!("user-{i}");
+            let first = groups.resolve("echo", |_| key.clone()).await?;
+            let second = groups.resolve("echo", |_| key.clone()).await?;
+            assert_eq!(first, second);
+            *counts.entry(first).or_default() += 1;
+        }
+        // Weighted 3:1, allow for some variance
+        assert!((650..850).contains(&counts["blue"]), "{counts:?}");
+        Ok(())
+    }
+}
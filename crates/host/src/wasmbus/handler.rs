@@ -1,15 +1,20 @@
 use core::ops::Deref;
+use core::pin::Pin;
+use core::task::{Context, Poll};
 
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use anyhow::{bail, Context as _};
+use anyhow::{bail, ensure, Context as _};
 use async_trait::async_trait;
 use bytes::Bytes;
 use secrecy::Secret;
+use tokio::io::{AsyncRead, ReadBuf};
 use tokio::sync::RwLock;
-use tracing::{debug, error, instrument};
+use tokio::time::sleep;
+use tracing::{debug, error, instrument, warn};
+use wasmcloud_control_interface::{RetryPolicy, TARGET_GROUP_PREFIX};
 use wasmcloud_runtime::capability::config::runtime::ConfigError;
 use wasmcloud_runtime::capability::logging::logging;
 use wasmcloud_runtime::capability::secrets::store::SecretValue;
@@ -19,10 +24,20 @@ use wasmcloud_runtime::component::{
     Secrets,
 };
 use wasmcloud_tracing::context::TraceContextInjector;
+use wasmcloud_tracing::KeyValue;
 use wrpc_transport::InvokeExt as _;
 
+use super::breaker::{retry_backoff, CircuitBreakers};
 use super::config::ConfigBundle;
+use super::groups::TargetGroups;
 use super::injector_to_headers;
+use super::logs::LogForwarder;
+use crate::metrics::HostMetrics;
+use crate::secrets::SecretRefs;
+
+/// Name of the invocation header carrying the deadline of an invocation in milliseconds since the
+/// UNIX epoch. Hosts compare it against their wall clock, so it is subject to clock skew.
+pub const DEADLINE_HEADER: &str = "deadline";
 
 #[derive(Clone, Debug)]
 pub struct Handler {
@@ -35,6 +50,8 @@ pub struct Handler {
     /// backend for each request. The [`SecretValue`] is wrapped in the [`Secret`] type from the `secrecy`
     /// crate to ensure that it is not accidentally logged or exposed in error messages.
     pub secrets: Arc<RwLock<HashMap<String, Secret<SecretValue>>>>,
+    /// References of the cached secrets, used to re-fetch them when they are rotated
+    pub secret_refs: Arc<RwLock<SecretRefs>>,
     /// The lattice this handler will use for RPC
     pub lattice: Arc<str>,
     /// The identifier of the component that this handler is associated with
@@ -45,6 +62,9 @@ pub struct Handler {
     /// The current trace context of the handler, required to propagate trace context
     /// when crossing the Wasm guest/host boundary
     pub trace_ctx: Arc<RwLock<Vec<(String, String)>>>,
+    /// The deadline of the invocation served by the component instance using this handler, set
+    /// when the instance is created. Invocations sent by the component inherit the remaining time.
+    pub invocation_deadline: Option<SystemTime>,
 
     /// Map of link names -> instance -> Target
     ///
@@ -53,15 +73,101 @@ pub struct Handler {
     ///
     /// Lattice entities could be:
     /// - A (single) Component ID
-    /// - A routing group
+    /// - A target group, referred to as `group:<name>`, see [`TargetGroups`]
     /// - Some other opaque string
     #[allow(clippy::type_complexity)]
     pub instance_links: Arc<RwLock<HashMap<Box<str>, HashMap<Box<str>, Box<str>>>>>,
 
+    /// Map of link names -> instance -> retry policy, for links that have one
+    #[allow(clippy::type_complexity)]
+    pub retry_policies: Arc<RwLock<HashMap<Box<str>, HashMap<Box<str>, RetryPolicy>>>>,
+
     pub invocation_timeout: Duration,
+
+    /// Circuit breakers of the lattice targets invoked by components on the host
+    pub circuit_breakers: Arc<CircuitBreakers>,
+
+    pub metrics: Arc<HostMetrics>,
+
+    /// Link target groups in the lattice, used to resolve `group:<name>` link targets
+    pub target_groups: Arc<TargetGroups>,
+
+    /// Forwarder of component log records to the lattice, if log forwarding is enabled
+    pub log_forwarder: Option<Arc<LogForwarder>>,
+}
+
+/// Results of an invocation sent by a component, which record the outcome of the invocation with
+/// the circuit breaker of its target once they are read
+pub struct BreakerIncoming {
+    inner: <wrpc_transport_nats::Client as wrpc_transport::Invoke>::Incoming,
+    /// Circuit breakers and target to record the outcome with, `None` for nested streams and
+    /// once the outcome was recorded
+    breaker: Option<(Arc<CircuitBreakers>, Box<str>)>,
+}
+
+impl BreakerIncoming {
+    fn record(&mut self, success: bool) {
+        if let Some((breakers, target)) = self.breaker.take() {
+            breakers.record(&target, success);
+        }
+    }
+}
+
+impl AsyncRead for BreakerIncoming {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let res = Pin::new(&mut self.inner).poll_read(cx, buf);
+        match &res {
+            Poll::Ready(Ok(())) => self.record(true),
+            Poll::Ready(Err(_)) => self.record(false),
+            Poll::Pending => {}
+        }
+        res
+    }
+}
+
+impl wrpc_transport::Index<Self> for BreakerIncoming {
+    fn index(&self, path: &[usize]) -> anyhow::Result<Self> {
+        let inner = self.inner.index(path)?;
+        Ok(Self {
+            inner,
+            breaker: None,
+        })
+    }
 }
 
 impl Handler {
+    /// Returns the consistent hashing key for a target group invocation, which is the value of the
+    /// `key` W3C baggage entry of the trace context, falling back to the trace ID and finally the
+    /// component ID
+    fn target_group_key(&self, trace_ctx: &[(String, String)], key: &str) -> String {
+        let header = |name: &str| {
+            trace_ctx
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.as_str())
+        };
+        header("baggage")
+            .into_iter()
+            .flat_map(|baggage| baggage.split(','))
+            .filter_map(|entry| {
+                let (k, v) = entry.split_once('=')?;
+                // Strip baggage entry properties
+                let v = v.split(';').next().unwrap_or_default();
+                (k.trim() == key).then(|| v.trim().to_string())
+            })
+            .next()
+            .or_else(|| {
+                header("traceparent")
+                    .and_then(|traceparent| traceparent.split('-').nth(1))
+                    .map(ToString::to_string)
+            })
+            .unwrap_or_else(|| self.component_id.to_string())
+    }
+
     /// Used for creating a new handler from an existing one. This is different than clone because
     /// some fields shouldn't be copied between component instances such as link targets.
     pub fn copy_for_new(&self) -> Self {
@@ -69,12 +175,19 @@ impl Handler {
             nats: self.nats.clone(),
             config_data: self.config_data.clone(),
             secrets: self.secrets.clone(),
+            secret_refs: self.secret_refs.clone(),
             lattice: self.lattice.clone(),
             component_id: self.component_id.clone(),
             targets: Arc::default(),
             trace_ctx: Arc::default(),
+            invocation_deadline: None,
             instance_links: self.instance_links.clone(),
+            retry_policies: self.retry_policies.clone(),
             invocation_timeout: self.invocation_timeout,
+            circuit_breakers: self.circuit_breakers.clone(),
+            metrics: self.metrics.clone(),
+            target_groups: self.target_groups.clone(),
+            log_forwarder: self.log_forwarder.clone(),
         }
     }
 }
@@ -123,7 +236,7 @@ impl Bus for Handler {
 impl wrpc_transport::Invoke for Handler {
     type Context = Option<ReplacedInstanceTarget>;
     type Outgoing = <wrpc_transport_nats::Client as wrpc_transport::Invoke>::Outgoing;
-    type Incoming = <wrpc_transport_nats::Client as wrpc_transport::Invoke>::Incoming;
+    type Incoming = BreakerIncoming;
 
     async fn invoke<P>(
         &self,
@@ -136,15 +249,8 @@ impl wrpc_transport::Invoke for Handler {
     where
         P: AsRef<[Option<usize>]> + Send + Sync,
     {
-        // Reading a trace context should _never_ block because writing happens once at the beginning of a component
-        // invocation. If it does block here, it's a bug in the runtime, and it's better to deal with a
-        // disconnected trace than to block on the invocation for an extended period of time.
-        if let Ok(trace_context) = self.trace_ctx.try_read() {
-            wasmcloud_tracing::context::attach_span_context(&trace_context);
-        }
-
-        let links = self.instance_links.read().await;
-        let targets = self.targets.read().await;
+        let trace_ctx = self.trace_ctx.read().await.clone();
+        wasmcloud_tracing::context::attach_span_context(&trace_ctx);
 
         let target_instance = match target_instance {
             Some(
@@ -158,37 +264,136 @@ impl wrpc_transport::Invoke for Handler {
             None => instance.split_once('@').map_or(instance, |(l, _)| l),
         };
 
-        let link_name = targets
-            .get(target_instance)
-            .map_or("default", AsRef::as_ref);
+        // Copy out the link name and target, so that links can be updated while this invocation
+        // is retried
+        let (link_name, id) = {
+            let links = self.instance_links.read().await;
+            let targets = self.targets.read().await;
+
+            let link_name = targets
+                .get(target_instance)
+                .map_or("default", AsRef::as_ref);
+
+            let instances = links.get(link_name).with_context(|| {
+                format!("link `{link_name}` not found for instance `{target_instance}`")
+            })?;
+
+            // Determine the lattice target ID we should be sending to
+            let id = instances.get(target_instance).with_context(||{
+                debug!(
+                    ?links,
+                    instance,
+                    ?target_instance,
+                    ?self.component_id,
+                    "component is not linked to a lattice target for the given instance"
+                );
+                format!("failed to call `{func}` in instance `{instance}` (failed to find a configured link with name `{link_name}` from component `{id}`, please check your configuration)", id = self.component_id)
+            })?;
+            (Box::<str>::from(link_name), id.clone())
+        };
+        let link_name = &*link_name;
+        let id = match id.strip_prefix(TARGET_GROUP_PREFIX) {
+            Some(group) => self
+                .target_groups
+                .resolve(group, |key| self.target_group_key(&trace_ctx, key))
+                .await
+                .with_context(|| {
+                    format!("failed to call `{func}` in instance `{instance}` (failed to resolve target group `{group}`)")
+                })?
+                .into_boxed_str(),
+            None => id.clone(),
+        };
 
-        let instances = links.get(link_name).with_context(|| {
-            format!("link `{link_name}` not found for instance `{target_instance}`")
-        })?;
+        let retry_policy = self
+            .retry_policies
+            .read()
+            .await
+            .get(link_name)
+            .and_then(|policies| policies.get(target_instance))
+            .cloned();
+        let deadline = self.invocation_deadline;
+        let mut attempt = 1;
+        loop {
+            // Never wait longer than the remaining time of the invocation being handled
+            let timeout = match deadline {
+                Some(deadline) => {
+                    let remaining = deadline
+                        .duration_since(SystemTime::now())
+                        .unwrap_or_default();
+                    ensure!(
+                        !remaining.is_zero(),
+                        "failed to call `{func}` in instance `{instance}` (invocation deadline exceeded)"
+                    );
+                    remaining.min(self.invocation_timeout)
+                }
+                None => self.invocation_timeout,
+            };
+            self.circuit_breakers
+                .check(&id)
+                .with_context(|| format!("failed to call `{func}` in instance `{instance}`"))?;
 
-        // Determine the lattice target ID we should be sending to
-        let id = instances.get(target_instance).with_context(||{
-            debug!(
-                ?links,
+            let mut headers = injector_to_headers(&TraceContextInjector::default_with_span());
+            headers.insert("source-id", &*self.component_id);
+            headers.insert("link-name", link_name);
+            if let Ok(call_deadline) = (SystemTime::now() + timeout).duration_since(UNIX_EPOCH) {
+                headers.insert(
+                    DEADLINE_HEADER,
+                    call_deadline.as_millis().to_string().as_str(),
+                );
+            }
+            let res = wrpc_transport_nats::Client::new(
+                Arc::clone(&self.nats),
+                format!("{}.{id}", self.lattice),
+                None,
+            )
+            .timeout(timeout)
+            .invoke(
+                Some(headers),
                 instance,
-                ?target_instance,
-                ?self.component_id,
-                "component is not linked to a lattice target for the given instance"
-            );
-            format!("failed to call `{func}` in instance `{instance}` (failed to find a configured link with name `{link_name}` from component `{id}`, please check your configuration)", id = self.component_id)
-        })?;
-
-        let mut headers = injector_to_headers(&TraceContextInjector::default_with_span());
-        headers.insert("source-id", &*self.component_id);
-        headers.insert("link-name", link_name);
-        wrpc_transport_nats::Client::new(
-            Arc::clone(&self.nats),
-            format!("{}.{id}", &self.lattice),
-            None,
-        )
-        .timeout(self.invocation_timeout)
-        .invoke(Some(headers), instance, func, params, paths)
-        .await
+                func,
+                params.clone(),
+                paths.as_ref(),
+            )
+            .await
+            .map(|(outgoing, incoming)| {
+                (
+                    outgoing,
+                    BreakerIncoming {
+                        inner: incoming,
+                        breaker: Some((Arc::clone(&self.circuit_breakers), id.clone())),
+                    },
+                )
+            });
+            // Successful invocations are recorded once their results are read
+            if res.is_err() {
+                self.circuit_breakers.record(&id, false);
+            }
+            match (res, &retry_policy) {
+                (Err(err), Some(policy)) if attempt < policy.max_attempts => {
+                    let backoff = retry_backoff(
+                        Duration::from_millis(policy.initial_backoff_ms),
+                        Duration::from_millis(policy.max_backoff_ms),
+                        attempt,
+                    );
+                    warn!(
+                        ?err,
+                        attempt,
+                        ?backoff,
+                        target = &*id,
+                        instance,
+                        func,
+                        "invocation failed, retrying"
+                    );
+                    self.metrics.record_invocation_retry(&[
+                        KeyValue::new("target", id.to_string()),
+                        KeyValue::new("operation", format!("{instance}/{func}")),
+                    ]);
+                    sleep(backoff).await;
+                    attempt += 1;
+                }
+                (res, _) => return res,
+            }
+        }
     }
 }
 
@@ -281,6 +486,20 @@ impl Logging for Handler {
                 );
             }
         };
+        if let Some(log_forwarder) = &self.log_forwarder {
+            // The trace ID is the second field of a W3C `traceparent` header value
+            let trace_id = self
+                .trace_ctx
+                .read()
+                .await
+                .iter()
+                .find(|(k, _)| k == "traceparent")
+                .and_then(|(_, v)| v.split('-').nth(1))
+                .map(ToString::to_string);
+            log_forwarder
+                .forward(level, &context, &message, trace_id)
+                .await;
+        }
         Ok(())
     }
 }
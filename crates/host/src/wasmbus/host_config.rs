@@ -1,6 +1,7 @@
 use crate::OciConfig;
 
 use std::collections::HashMap;
+use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -59,6 +60,9 @@ pub struct Host {
     pub policy_service_config: PolicyService,
     /// topic for wasmCloud secrets backend
     pub secrets_topic_prefix: Option<String>,
+    /// The interval at which secrets of running components and providers are re-fetched, if set.
+    /// Secrets are always re-fetched when a secrets backend publishes a change notification.
+    pub secrets_refresh_interval: Option<Duration>,
     /// The semver version of the host. This is used by a consumer of this crate to indicate the
     /// host version (which may differ from the crate version)
     pub version: String,
@@ -72,6 +76,28 @@ pub struct Host {
     pub max_components: u32,
     /// The interval at which the Host will send heartbeats
     pub heartbeat_interval: Option<Duration>,
+    /// If provided, the address to serve the `/livez`, `/readyz` and `/inventory` admin HTTP endpoints on
+    pub http_admin: Option<SocketAddr>,
+    /// If provided, events are additionally written to a JetStream stream with this configuration
+    pub event_stream: Option<EventStream>,
+    /// If provided, logs of components are forwarded to the lattice with this configuration
+    pub log_forwarding: Option<LogForwarding>,
+    /// Whether to reject links incompatible with the WIT of their source or target, instead of
+    /// storing them marked as unhealthy
+    pub enforce_link_compatibility: bool,
+    /// If provided, invocations sent by components fail fast once their target failed repeatedly
+    pub circuit_breaker: Option<CircuitBreaker>,
+    /// The number of revisions of each named config kept in the lattice config bucket, at most 64
+    pub config_history: u8,
+    /// If provided, only components and providers issued by these trusted accounts are started
+    pub trusted_issuers: Option<TrustedIssuers>,
+    /// Whether to stop running components and providers once their claims are revoked. Revoked
+    /// components and providers are never started.
+    pub stop_revoked: bool,
+    /// If provided, the expiry of claims of running components and providers is monitored
+    pub claims_expiry: Option<ClaimsExpiry>,
+    /// If provided, only components and providers signed by enough trusted signers are started
+    pub required_signatures: Option<RequiredSignatures>,
 }
 
 /// Configuration for wasmCloud policy service
@@ -85,6 +111,122 @@ pub struct PolicyService {
     pub policy_timeout_ms: Option<Duration>,
 }
 
+/// Configuration for the JetStream stream host events are written to
+#[derive(Clone, Debug, Default)]
+pub struct EventStream {
+    /// The maximum age of events retained in the stream. If not set, an existing stream keeps
+    /// its retention and a new one uses [`Self::DEFAULT_MAX_AGE`]
+    pub max_age: Option<Duration>,
+    /// The maximum number of events retained in the stream. If not set, an existing stream keeps
+    /// its retention and a new one uses [`Self::DEFAULT_MAX_MESSAGES`]
+    pub max_messages: Option<i64>,
+    /// The maximum size of the stream in bytes. If not set, an existing stream keeps its
+    /// retention and a new one uses [`Self::DEFAULT_MAX_BYTES`]
+    pub max_bytes: Option<i64>,
+}
+
+impl EventStream {
+    /// The default maximum age of events retained in a new stream, one week
+    pub const DEFAULT_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+    /// The default maximum number of events retained in a new stream
+    pub const DEFAULT_MAX_MESSAGES: i64 = 1_000_000;
+    /// The default maximum size of a new stream in bytes, 1 GiB
+    pub const DEFAULT_MAX_BYTES: i64 = 1024 * 1024 * 1024;
+}
+
+/// Configuration for forwarding of component logs to `wasmbus.log.<lattice>.<component_id>`
+#[derive(Clone, Debug)]
+pub struct LogForwarding {
+    /// The maximum number of log records forwarded per second for each component, excess
+    /// records are dropped
+    pub max_records_per_second: u32,
+}
+
+impl Default for LogForwarding {
+    fn default() -> Self {
+        Self {
+            max_records_per_second: 100,
+        }
+    }
+}
+
+/// Configuration for the circuit breakers of lattice targets invoked by components
+#[derive(Clone, Copy, Debug)]
+pub struct CircuitBreaker {
+    /// The number of consecutive failed invocations of a target after which the breaker opens
+    pub failure_threshold: u32,
+    /// How long a breaker stays open before a trial invocation is let through
+    pub open_duration: Duration,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            open_duration: Duration::from_secs(30),
+        }
+    }
+}
+
+/// The operators and accounts trusted to issue components and providers
+#[derive(Clone, Debug, Default)]
+pub struct TrustedIssuers {
+    /// Public keys of operators trusted to issue accounts
+    pub operators: Vec<String>,
+    /// Public keys of accounts trusted to issue components and providers
+    pub accounts: Vec<String>,
+    /// JWTs of accounts, which must be issued by one of `operators`. These accounts and their
+    /// signing keys are trusted in addition to `accounts`.
+    pub account_jwts: Vec<String>,
+}
+
+/// The signers components and providers must be signed by, counting both the issuer of their
+/// claims and the issuers of their detached co-signatures
+#[derive(Clone, Debug, Default)]
+pub struct RequiredSignatures {
+    /// Public keys of trusted signers
+    pub signers: Vec<String>,
+    /// The number of distinct trusted signers whose valid signatures are required, at least 1
+    pub threshold: usize,
+}
+
+/// What the host does once the claims of a running component or provider expire
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ClaimsExpiryAction {
+    /// Keep the component or provider running and only publish a `claims_expiring` event
+    #[default]
+    Warn,
+    /// Stop the component or provider
+    Stop,
+    /// Keep the component or provider running, but refuse to start or scale components and
+    /// providers with expired claims
+    Block,
+}
+
+/// Configuration for monitoring the expiry of claims of running components and providers
+#[derive(Clone, Debug)]
+pub struct ClaimsExpiry {
+    /// How often the claims of running components and providers are checked
+    pub check_interval: Duration,
+    /// How long before claims expire `claims_expiring` events are published, once per lead time
+    pub lead_times: Vec<Duration>,
+    /// What to do once claims expired
+    pub action: ClaimsExpiryAction,
+}
+
+impl Default for ClaimsExpiry {
+    fn default() -> Self {
+        Self {
+            check_interval: Duration::from_secs(60),
+            lead_times: vec![
+                Duration::from_secs(7 * 24 * 60 * 60),
+                Duration::from_secs(24 * 60 * 60),
+            ],
+            action: ClaimsExpiryAction::default(),
+        }
+    }
+}
+
 impl Default for Host {
     fn default() -> Self {
         Self {
@@ -113,6 +255,7 @@ impl Default for Host {
             otel_config: OtelConfig::default(),
             policy_service_config: PolicyService::default(),
             secrets_topic_prefix: None,
+            secrets_refresh_interval: None,
             version: env!("CARGO_PKG_VERSION").to_string(),
             max_execution_time: Duration::from_millis(10 * 60 * 1000),
             // 10 MB
@@ -121,6 +264,16 @@ impl Default for Host {
             max_component_size: MAX_COMPONENT_SIZE,
             max_components: MAX_COMPONENTS,
             heartbeat_interval: None,
+            http_admin: None,
+            event_stream: None,
+            log_forwarding: None,
+            enforce_link_compatibility: false,
+            circuit_breaker: None,
+            config_history: 10,
+            trusted_issuers: None,
+            stop_revoked: false,
+            claims_expiry: None,
+            required_signatures: None,
         }
     }
 }
@@ -0,0 +1,141 @@
+//! Forwarding of component `wasi:logging` records to the lattice
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tracing::{instrument, warn};
+use wasmcloud_control_interface::{component_log_subject, ComponentLogLevel, ComponentLogRecord};
+use wasmcloud_runtime::capability::logging::logging;
+
+/// Token bucket limiting the rate log records of a single component are forwarded at
+#[derive(Debug)]
+struct RateLimiter {
+    /// Maximum number of records forwarded per second, also the bucket capacity
+    rate: f64,
+    tokens: f64,
+    last_refill: Instant,
+    /// Number of records dropped since a drop was last reported
+    dropped: u64,
+    last_drop_report: Option<Instant>,
+}
+
+impl RateLimiter {
+    fn new(rate: u32) -> Self {
+        let rate = f64::from(rate.max(1));
+        Self {
+            rate,
+            tokens: rate,
+            last_refill: Instant::now(),
+            dropped: 0,
+            last_drop_report: None,
+        }
+    }
+
+    /// Attempt to take a token. Returns the number of records dropped since the last report if
+    /// the caller should report them.
+    fn acquire(&mut self) -> Result<(), Option<u64>> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.rate);
+        self.last_refill = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return Ok(());
+        }
+        self.dropped = self.dropped.saturating_add(1);
+        match self.last_drop_report {
+            Some(last) if now.duration_since(last) < Duration::from_secs(1) => Err(None),
+            _ => {
+                self.last_drop_report = Some(now);
+                Err(Some(core::mem::take(&mut self.dropped)))
+            }
+        }
+    }
+}
+
+/// Publishes log records of a single component on `wasmbus.log.<lattice>.<component_id>`
+#[derive(Debug)]
+pub struct LogForwarder {
+    nats: async_nats::Client,
+    subject: String,
+    host_id: String,
+    component_id: String,
+    limiter: Mutex<RateLimiter>,
+}
+
+impl LogForwarder {
+    /// Construct a new [`LogForwarder`] for component `component_id`, forwarding at most
+    /// `max_records_per_second` records per second
+    pub fn new(
+        nats: async_nats::Client,
+        lattice: &str,
+        host_id: impl Into<String>,
+        component_id: impl Into<String>,
+        max_records_per_second: u32,
+    ) -> Self {
+        let component_id = component_id.into();
+        Self {
+            nats,
+            subject: component_log_subject(lattice, &component_id),
+            host_id: host_id.into(),
+            component_id,
+            limiter: Mutex::new(RateLimiter::new(max_records_per_second)),
+        }
+    }
+
+    /// Forward a log record. Records exceeding the rate limit are dropped and failures to publish
+    /// are only logged, since logging must never fail a component invocation.
+    #[instrument(level = "trace", skip(self, message))]
+    pub async fn forward(
+        &self,
+        level: logging::Level,
+        context: &str,
+        message: &str,
+        trace_id: Option<String>,
+    ) {
+        let acquired = self
+            .limiter
+            .lock()
+            .map_or(Ok(()), |mut limiter| limiter.acquire());
+        match acquired {
+            Ok(()) => {}
+            Err(Some(dropped)) => {
+                warn!(
+                    component_id = self.component_id,
+                    dropped, "component log rate limit exceeded, dropping records"
+                );
+                return;
+            }
+            Err(None) => return,
+        }
+        let record = ComponentLogRecord {
+            component_id: self.component_id.clone(),
+            host_id: self.host_id.clone(),
+            level: match level {
+                logging::Level::Trace => ComponentLogLevel::Trace,
+                logging::Level::Debug => ComponentLogLevel::Debug,
+                logging::Level::Info => ComponentLogLevel::Info,
+                logging::Level::Warn => ComponentLogLevel::Warn,
+                logging::Level::Error => ComponentLogLevel::Error,
+                logging::Level::Critical => ComponentLogLevel::Critical,
+            },
+            context: context.to_string(),
+            message: message.to_string(),
+            trace_id,
+        };
+        let payload = match serde_json::to_vec(&record) {
+            Ok(payload) => payload,
+            Err(err) => {
+                warn!(?err, "failed to serialize component log record");
+                return;
+            }
+        };
+        if let Err(err) = self
+            .nats
+            .publish(self.subject.clone(), payload.into())
+            .await
+        {
+            warn!(?err, "failed to forward component log record");
+        }
+    }
+}
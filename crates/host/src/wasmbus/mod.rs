@@ -1,5 +1,12 @@
+mod admin;
+mod breaker;
+mod compat;
 mod event;
+mod expiry;
+mod groups;
 mod handler;
+mod logs;
+mod trust;
 
 pub mod config;
 /// wasmCloud host configuration
@@ -7,6 +14,13 @@ pub mod host_config;
 
 pub use self::host_config::Host as HostConfig;
 
+use self::breaker::{CircuitBreakers, EventPublisher};
+use self::expiry::ExpiryTracker;
+use self::groups::{validate_target_group, TargetGroups};
+use self::host_config::{ClaimsExpiry, ClaimsExpiryAction, EventStream as EventStreamConfig};
+use self::logs::LogForwarder;
+use self::trust::{verify_signatures, TrustChain};
+
 use std::collections::hash_map::{self, Entry};
 use std::collections::{BTreeMap, HashMap};
 use std::env;
@@ -17,9 +31,10 @@ use std::ops::Deref;
 use std::pin::Pin;
 use std::process::Stdio;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::task::{Context, Poll};
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use anyhow::{anyhow, bail, ensure, Context as _};
 use async_nats::jetstream::kv::{Entry as KvEntry, Operation, Store};
@@ -34,8 +49,9 @@ use nkeys::{KeyPair, KeyPairType, XKey};
 use secrecy::Secret;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use time::format_description::well_known::Rfc3339;
 use tokio::io::{AsyncWrite, AsyncWriteExt};
-use tokio::sync::{broadcast, mpsc, watch, RwLock, Semaphore};
+use tokio::sync::{broadcast, mpsc, watch, Mutex, RwLock, Semaphore};
 use tokio::task::{JoinHandle, JoinSet};
 use tokio::time::{interval_at, timeout_at, Instant};
 use tokio::{process, select, spawn};
@@ -44,30 +60,34 @@ use tracing::{debug, error, info, instrument, trace, warn, Instrument as _};
 use uuid::Uuid;
 use wascap::{jwt, prelude::ClaimsBuilder};
 use wasmcloud_control_interface::{
-    ComponentAuctionAck, ComponentAuctionRequest, ComponentDescription, CtlResponse,
-    DeleteInterfaceLinkDefinitionRequest, HostInventory, HostLabel, InterfaceLinkDefinition,
-    ProviderAuctionAck, ProviderAuctionRequest, ProviderDescription, RegistryCredential,
-    ScaleComponentCommand, StartProviderCommand, StopHostCommand, StopProviderCommand,
-    UpdateComponentCommand,
+    host_event_stream_name, host_event_stream_subject, ComponentAuctionAck,
+    ComponentAuctionRequest, ComponentDescription, ConfigRevision, CtlResponse,
+    DeleteInterfaceLinkDefinitionRequest, HostCapacity, HostEvent, HostHeartbeat, HostInventory,
+    HostLabel, HostReadiness, HostStarted, HostStopped, InterfaceLinkDefinition, LabelConstraint,
+    LinkStatus, ProviderAuctionAck, ProviderAuctionRequest, ProviderDescription,
+    RegistryCredential, RetryPolicy, ScaleComponentCommand, StartProviderCommand, StopHostCommand,
+    StopProviderCommand, TargetGroup, UpdateComponentCommand, TARGET_GROUP_PREFIX,
 };
+use wasmcloud_core::revocation::{revocation_bucket, Revocation, RevocationKind};
 use wasmcloud_core::{
-    provider_config_update_subject, ComponentId, HealthCheckResponse, HostData, OtelConfig,
-    CTL_API_VERSION_1,
+    provider_config_update_subject, provider_secrets_update_subject, ComponentId,
+    HealthCheckResponse, HostData, OtelConfig, CTL_API_VERSION_1,
 };
 use wasmcloud_runtime::capability::secrets::store::SecretValue;
 use wasmcloud_runtime::component::WrpcServeEvent;
 use wasmcloud_runtime::Runtime;
-use wasmcloud_secrets_types::SECRET_PREFIX;
+use wasmcloud_secrets_types::{SECRETS_CHANGED_OPERATION, SECRET_API_VERSION, SECRET_PREFIX};
 use wasmcloud_tracing::context::TraceContextInjector;
 use wasmcloud_tracing::{global, KeyValue};
 
+use crate::secrets::{changed_secrets, SecretRefs};
 use crate::{
     fetch_component, HostMetrics, OciConfig, PolicyHostInfo, PolicyManager, PolicyResponse,
     RegistryAuth, RegistryConfig, RegistryType, SecretsManager,
 };
 
 use self::config::{BundleGenerator, ConfigBundle};
-use self::handler::Handler;
+use self::handler::{Handler, DEADLINE_HEADER};
 
 #[derive(Debug)]
 struct Queue {
@@ -212,8 +232,25 @@ struct WrpcServer {
     metrics: Arc<HostMetrics>,
 }
 
+/// Context of an invocation served by a component
+struct InvocationContext {
+    start_at: Instant,
+    attributes: Vec<KeyValue>,
+    /// The deadline of the invocation, passed in the [`DEADLINE_HEADER`] invocation header
+    deadline: Option<SystemTime>,
+}
+
+impl wasmcloud_runtime::component::InvocationContext<Handler> for InvocationContext {
+    fn handler(&self, handler: &Handler) -> Handler {
+        Handler {
+            invocation_deadline: self.deadline,
+            ..handler.clone()
+        }
+    }
+}
+
 impl wrpc_transport::Serve for WrpcServer {
-    type Context = (Instant, Vec<KeyValue>);
+    type Context = InvocationContext;
     type Outgoing = <wrpc_transport_nats::Client as wrpc_transport::Serve>::Outgoing;
     type Incoming = <wrpc_transport_nats::Client as wrpc_transport::Serve>::Incoming;
 
@@ -299,17 +336,24 @@ impl wrpc_transport::Serve for WrpcServer {
                         .iter()
                         .map(|(k, v)| (k.to_string(), v.to_string()))
                         .collect();
+
+                    let deadline = cx
+                        .as_ref()
+                        .and_then(|cx| cx.get(DEADLINE_HEADER))
+                        .and_then(|deadline| deadline.as_str().parse().ok())
+                        .map(|ms| UNIX_EPOCH + Duration::from_millis(ms));
                     Ok((
-                        (
-                            Instant::now(),
+                        InvocationContext {
+                            start_at: Instant::now(),
                             // TODO(metrics): insert information about the source once we have concrete context data
-                            vec![
+                            attributes: vec![
                                 KeyValue::new("component.ref", image_reference),
                                 KeyValue::new("lattice", metrics.lattice_id.clone()),
                                 KeyValue::new("host", metrics.host_id.clone()),
                                 KeyValue::new("operation", format!("{instance}/name")),
                             ],
-                        ),
+                            deadline,
+                        },
                         tx,
                         rx,
                     ))
@@ -334,6 +378,10 @@ struct Provider {
     /// Config bundle for the aggregated configuration being watched by the provider
     #[allow(unused)]
     config: Arc<RwLock<ConfigBundle>>,
+    /// Secrets the provider was started with, updated when they are rotated
+    secrets: Arc<RwLock<HashMap<String, Secret<SecretValue>>>>,
+    /// References of the provider secrets, used to re-fetch them when they are rotated
+    secret_refs: SecretRefs,
 }
 
 impl Drop for Provider {
@@ -347,6 +395,8 @@ impl Drop for Provider {
 pub struct Host {
     components: RwLock<HashMap<ComponentId, Arc<Component>>>,
     event_builder: EventBuilderV10,
+    /// JetStream context to write events to the event stream with, if enabled
+    event_stream: Option<async_nats::jetstream::Context>,
     friendly_name: String,
     heartbeat: AbortHandle,
     host_config: HostConfig,
@@ -377,10 +427,26 @@ pub struct Host {
     queue: AbortHandle,
     // Component ID -> All Links
     links: RwLock<HashMap<String, Vec<InterfaceLinkDefinition>>>,
+    /// Link target groups in the lattice, shared with component handlers
+    target_groups: Arc<TargetGroups>,
+    /// Circuit breakers of lattice targets, shared with component handlers
+    circuit_breakers: Arc<CircuitBreakers>,
+    /// If set, only components and providers issued by accounts in this chain are started
+    trust_chain: Option<TrustChain>,
+    /// Revoked subjects, issuers and JWT IDs, by their key in the revocation bucket
+    revocations: RwLock<HashMap<String, Revocation>>,
+    /// Expiry notifications sent for running components and providers
+    claims_expiry: Mutex<ExpiryTracker>,
     component_claims: Arc<RwLock<HashMap<ComponentId, jwt::Claims<jwt::Component>>>>, // TODO: use a single map once Claims is an enum
     provider_claims: Arc<RwLock<HashMap<String, jwt::Claims<jwt::CapabilityProvider>>>>,
     metrics: Arc<HostMetrics>,
     max_execution_time: Duration,
+    /// Whether supplemental config was loaded, or did not need to be
+    supplemental_config_loaded: AtomicBool,
+    /// Result of the last readiness probes, refreshed in the background
+    readiness: RwLock<HostReadiness>,
+    /// Task periodically probing the readiness of the host
+    readiness_probes: AbortHandle,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
@@ -393,6 +459,15 @@ pub struct ComponentSpecification {
     url: String,
     /// All outbound links from this component to other components, used for routing when calling a component `import`
     links: Vec<InterfaceLinkDefinition>,
+    /// Names of the instances imported by the component, known once a host started it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    imports: Option<Vec<String>>,
+    /// Names of the instances exported by the component, known once a host started it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    exports: Option<Vec<String>>,
+    /// JSON schema of the config of the provider, known once a host started it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    config_schema: Option<serde_json::Value>,
     ////
     // Possible additions in the future, left in as comments to facilitate discussion
     ////
@@ -410,6 +485,9 @@ impl ComponentSpecification {
         Self {
             url: url.as_ref().to_string(),
             links: Vec::new(),
+            imports: None,
+            exports: None,
+            config_schema: None,
         }
     }
 }
@@ -444,6 +522,8 @@ impl From<StoredClaims> for Claims {
                     rev,
                     ver,
                     call_alias,
+                    imports: claims.imports,
+                    exports: claims.exports,
                     ..Default::default()
                 };
                 let claims = ClaimsBuilder::new()
@@ -482,16 +562,28 @@ impl From<StoredClaims> for Claims {
 async fn create_bucket(
     jetstream: &async_nats::jetstream::Context,
     bucket: &str,
+    history: i64,
 ) -> anyhow::Result<Store> {
     // Don't create the bucket if it already exists
     if let Ok(store) = jetstream.get_key_value(bucket).await {
         info!(%bucket, "bucket already exists. Skipping creation.");
+        // Buckets created by older hosts may keep fewer revisions than requested
+        let mut stream_config = store.stream.cached_info().config.clone();
+        if (0..history).contains(&stream_config.max_messages_per_subject) {
+            info!(%bucket, history, "increasing bucket history");
+            stream_config.max_messages_per_subject = history;
+            jetstream
+                .update_stream(&stream_config)
+                .await
+                .map_err(|err| anyhow!(err).context("failed to update bucket history"))?;
+        }
         return Ok(store);
     }
 
     match jetstream
         .create_key_value(async_nats::jetstream::kv::Config {
             bucket: bucket.to_string(),
+            history,
             ..Default::default()
         })
         .await
@@ -504,6 +596,57 @@ async fn create_bucket(
     }
 }
 
+#[instrument(level = "debug", skip_all)]
+async fn create_event_stream(
+    jetstream: &async_nats::jetstream::Context,
+    lattice: &str,
+    config: &EventStreamConfig,
+) -> anyhow::Result<()> {
+    let name = host_event_stream_name(lattice);
+    if let Ok(stream) = jetstream.get_stream(&name).await {
+        // Only apply retention that was explicitly configured, so that a stream configured by an
+        // operator or another host is not reset to the defaults
+        if config.max_age.is_none() && config.max_messages.is_none() && config.max_bytes.is_none() {
+            debug!(%name, "using existing event stream");
+            return Ok(());
+        }
+        let mut stream_config = stream.cached_info().config.clone();
+        if let Some(max_age) = config.max_age {
+            stream_config.max_age = max_age;
+        }
+        if let Some(max_messages) = config.max_messages {
+            stream_config.max_messages = max_messages;
+        }
+        if let Some(max_bytes) = config.max_bytes {
+            stream_config.max_bytes = max_bytes;
+        }
+        jetstream
+            .update_stream(stream_config)
+            .await
+            .map_err(|err| anyhow!(err).context(format!("failed to update stream '{name}'")))?;
+        info!(%name, "updated event stream retention");
+    } else {
+        jetstream
+            .create_stream(async_nats::jetstream::stream::Config {
+                name: name.clone(),
+                description: Some(format!("wasmCloud host events for lattice `{lattice}`")),
+                subjects: vec![host_event_stream_subject(lattice, "*")],
+                max_age: config.max_age.unwrap_or(EventStreamConfig::DEFAULT_MAX_AGE),
+                max_messages: config
+                    .max_messages
+                    .unwrap_or(EventStreamConfig::DEFAULT_MAX_MESSAGES),
+                max_bytes: config
+                    .max_bytes
+                    .unwrap_or(EventStreamConfig::DEFAULT_MAX_BYTES),
+                ..Default::default()
+            })
+            .await
+            .map_err(|err| anyhow!(err).context(format!("failed to create stream '{name}'")))?;
+        info!(%name, "created event stream");
+    }
+    Ok(())
+}
+
 /// Given the NATS address, authentication jwt, seed, tls requirement and optional request timeout,
 /// attempt to establish connection.
 ///
@@ -546,6 +689,8 @@ async fn connect_nats(
 #[derive(Debug, Default)]
 struct SupplementalConfig {
     registry_config: Option<HashMap<String, RegistryConfig>>,
+    /// Whether the config service responded with a valid config
+    loaded: bool,
 }
 
 #[instrument(level = "debug", skip_all)]
@@ -580,6 +725,7 @@ async fn load_supplemental_config(
                             })
                             .collect()
                     }),
+                    loaded: true,
                 }),
                 Err(e) => {
                     error!(
@@ -703,6 +849,24 @@ impl Host {
         } else {
             Arc::new(KeyPair::new(KeyPairType::Server))
         };
+        let trust_chain = config
+            .trusted_issuers
+            .as_ref()
+            .map(TrustChain::new)
+            .transpose()
+            .context("failed to validate trusted issuers")?;
+        if let Some(required) = &config.required_signatures {
+            ensure!(
+                required.threshold >= 1,
+                "at least one signature must be required"
+            );
+            ensure!(
+                required.threshold <= required.signers.len(),
+                "{} signatures are required, but only {} trusted signers are configured",
+                required.threshold,
+                required.signers.len()
+            );
+        }
 
         let mut labels = HashMap::from([
             ("hostcore.arch".into(), ARCH.into()),
@@ -728,11 +892,11 @@ impl Host {
             .context("failed to encode host claims")?;
         let host_token = Arc::new(jwt::Token { jwt, claims });
 
-        let start_evt = json!({
-            "friendly_name": friendly_name,
-            "labels": labels,
-            "uptime_seconds": 0,
-            "version": config.version,
+        let start_evt = HostEvent::HostStarted(HostStarted {
+            friendly_name: friendly_name.clone(),
+            labels: labels.clone(),
+            uptime_seconds: 0,
+            version: config.version.clone(),
         });
 
         let ((ctl_nats, queue), rpc_nats) = try_join!(
@@ -805,20 +969,36 @@ impl Host {
             async_nats::jetstream::new(ctl_nats.clone())
         };
         let bucket = format!("LATTICEDATA_{}", config.lattice);
-        let data = create_bucket(&ctl_jetstream, &bucket).await?;
+        let data = create_bucket(&ctl_jetstream, &bucket, 1).await?;
 
         let config_bucket = format!("CONFIGDATA_{}", config.lattice);
-        let config_data = create_bucket(&ctl_jetstream, &config_bucket).await?;
+        let config_data =
+            create_bucket(&ctl_jetstream, &config_bucket, config.config_history.into()).await?;
+
+        let revocation_data =
+            create_bucket(&ctl_jetstream, &revocation_bucket(&config.lattice), 1).await?;
+
+        let event_stream = if let Some(event_stream) = &config.event_stream {
+            create_event_stream(&ctl_jetstream, &config.lattice, event_stream).await?;
+            Some(ctl_jetstream.clone())
+        } else {
+            None
+        };
 
         let (queue_abort, queue_abort_reg) = AbortHandle::new_pair();
         let (heartbeat_abort, heartbeat_abort_reg) = AbortHandle::new_pair();
         let (data_watch_abort, data_watch_abort_reg) = AbortHandle::new_pair();
+        let (readiness_probes_abort, readiness_probes_abort_reg) = AbortHandle::new_pair();
 
         let supplemental_config = if config.config_service_enabled {
             load_supplemental_config(&ctl_nats, &config.lattice, &labels).await?
         } else {
-            SupplementalConfig::default()
+            SupplementalConfig {
+                loaded: true,
+                ..SupplementalConfig::default()
+            }
         };
+        let supplemental_config_loaded = supplemental_config.loaded;
 
         let registry_config = RwLock::new(supplemental_config.registry_config.unwrap_or_default());
         merge_registry_config(&registry_config, config.oci_opts.clone()).await;
@@ -862,7 +1042,22 @@ impl Host {
                 KeyValue::new("host.version", config.version.clone()),
             ]),
         );
-        let metrics = HostMetrics::new(&meter, host_key.public_key(), config.lattice.to_string());
+        let metrics = Arc::new(HostMetrics::new(
+            &meter,
+            host_key.public_key(),
+            config.lattice.to_string(),
+        ));
+        let circuit_breakers = Arc::new(CircuitBreakers::new(
+            config.circuit_breaker,
+            host_key.public_key(),
+            EventPublisher {
+                event_builder: event_builder.clone(),
+                ctl_nats: ctl_nats.clone(),
+                event_stream: event_stream.clone(),
+                lattice: Arc::clone(&config.lattice),
+            },
+            Arc::clone(&metrics),
+        ));
 
         let config_generator = BundleGenerator::new(config_data.clone());
 
@@ -871,6 +1066,7 @@ impl Host {
         let host = Host {
             components: RwLock::default(),
             event_builder,
+            event_stream,
             friendly_name,
             heartbeat: heartbeat_abort.clone(),
             ctl_topic_prefix: config.ctl_topic_prefix.clone(),
@@ -895,10 +1091,18 @@ impl Host {
             stop_tx,
             queue: queue_abort.clone(),
             links: RwLock::default(),
+            target_groups: Arc::default(),
+            circuit_breakers,
+            trust_chain,
+            revocations: RwLock::default(),
+            claims_expiry: Mutex::default(),
             component_claims: Arc::default(),
             provider_claims: Arc::default(),
-            metrics: Arc::new(metrics),
+            metrics,
             max_execution_time: max_execution_time_ms,
+            supplemental_config_loaded: AtomicBool::new(supplemental_config_loaded),
+            readiness: RwLock::default(),
+            readiness_probes: readiness_probes_abort.clone(),
         };
 
         let host = Arc::new(host);
@@ -974,17 +1178,8 @@ impl Host {
                         move |_| {
                             let host = Arc::clone(&host);
                             async move {
-                                let heartbeat = match host.heartbeat().await {
-                                    Ok(heartbeat) => heartbeat,
-                                    Err(e) => {
-                                        error!("failed to generate heartbeat: {e}");
-                                        return;
-                                    }
-                                };
-
-                                if let Err(e) =
-                                    host.publish_event("host_heartbeat", heartbeat).await
-                                {
+                                let heartbeat = host.heartbeat().await;
+                                if let Err(e) = host.publish_event(heartbeat).await {
                                     error!("failed to publish heartbeat: {e}");
                                 }
                             }
@@ -1001,6 +1196,106 @@ impl Host {
             }
         });
 
+        let readiness_probes = spawn({
+            let host = Arc::clone(&host);
+            async move {
+                let mut probes = Abortable::new(
+                    IntervalStream::new(interval_at(Instant::now(), heartbeat_interval)),
+                    readiness_probes_abort_reg,
+                );
+                while probes.next().await.is_some() {
+                    let readiness = host.probe_readiness().await;
+                    *host.readiness.write().await = readiness;
+                }
+            }
+        });
+
+        let admin = if let Some(addr) = host.host_config.http_admin {
+            Some(
+                admin::serve(addr, Arc::clone(&host))
+                    .await
+                    .context("failed to start admin HTTP listener")?,
+            )
+        } else {
+            None
+        };
+
+        let secrets_rotation = if let Some(prefix) = &host.host_config.secrets_topic_prefix {
+            let changes = host
+                .ctl_nats
+                .subscribe(format!(
+                    "{prefix}.{SECRET_API_VERSION}.*.{SECRETS_CHANGED_OPERATION}"
+                ))
+                .await
+                .context("failed to subscribe to secrets changes")?
+                .map(|_| ());
+            let refreshes = if let Some(interval) = host.host_config.secrets_refresh_interval {
+                Either::Left(
+                    IntervalStream::new(interval_at(Instant::now() + interval, interval))
+                        .map(|_| ()),
+                )
+            } else {
+                Either::Right(stream::pending())
+            };
+            let host = Arc::clone(&host);
+            Some(spawn(async move {
+                let mut rotations = stream::select(changes, refreshes);
+                while rotations.next().await.is_some() {
+                    host.rotate_secrets().await;
+                }
+            }))
+        } else {
+            None
+        };
+
+        let claims_expiry = host.host_config.claims_expiry.clone().map(|config| {
+            let host = Arc::clone(&host);
+            spawn(async move {
+                let mut checks =
+                    IntervalStream::new(interval_at(Instant::now(), config.check_interval));
+                while checks.next().await.is_some() {
+                    host.check_claims_expiry(&config).await;
+                }
+            })
+        });
+
+        // Watch revocations before loading existing ones, so that no revocation written meanwhile
+        // is missed. Revocations loaded twice are applied again, which has no effect.
+        let mut revocation_watch = revocation_data
+            .watch_all()
+            .await
+            .context("failed to watch revocation bucket")?;
+        // Load revocations before starting anything
+        revocation_data
+            .keys()
+            .await
+            .context("failed to read keys of revocation bucket")?
+            .map_err(|e| anyhow!(e).context("failed to read revocation stream"))
+            .try_filter_map(|key| async {
+                revocation_data
+                    .entry(key)
+                    .await
+                    .context("failed to get entry in revocation bucket")
+            })
+            .for_each(|entry| async {
+                match entry {
+                    Ok(entry) => host.process_revocation(entry, false).await,
+                    Err(err) => error!(%err, "failed to read entry from revocation bucket"),
+                }
+            })
+            .await;
+        let revocation_watch = {
+            let host = Arc::clone(&host);
+            spawn(async move {
+                while let Some(entry) = revocation_watch.next().await {
+                    match entry {
+                        Ok(entry) => host.process_revocation(entry, true).await,
+                        Err(error) => error!("failed to watch revocation bucket: {error}"),
+                    }
+                }
+            })
+        };
+
         // Process existing data without emitting events
         data.keys()
             .await
@@ -1019,7 +1314,7 @@ impl Host {
             })
             .await;
 
-        host.publish_event("host_started", start_evt)
+        host.publish_event(start_evt)
             .await
             .context("failed to publish start event")?;
         info!(
@@ -1029,16 +1324,25 @@ impl Host {
 
         Ok((Arc::clone(&host), async move {
             heartbeat_abort.abort();
+            readiness_probes_abort.abort();
             queue_abort.abort();
             data_watch_abort.abort();
             host.policy_manager.policy_changes.abort();
-            let _ = try_join!(queue, data_watch, heartbeat).context("failed to await tasks")?;
-            host.publish_event(
-                "host_stopped",
-                json!({
-                    "labels": *host.labels.read().await,
-                }),
-            )
+            if let Some(admin) = admin {
+                admin.abort();
+            }
+            if let Some(secrets_rotation) = secrets_rotation {
+                secrets_rotation.abort();
+            }
+            revocation_watch.abort();
+            if let Some(claims_expiry) = claims_expiry {
+                claims_expiry.abort();
+            }
+            let _ = try_join!(queue, data_watch, heartbeat, readiness_probes)
+                .context("failed to await tasks")?;
+            host.publish_event(HostEvent::HostStopped(HostStopped {
+                labels: host.labels.read().await.clone(),
+            }))
             .await
             .context("failed to publish stop event")?;
             // Before we exit, make sure to flush all messages or we may lose some that we've
@@ -1087,22 +1391,20 @@ impl Host {
         let components = self.components.read().await;
         let components: Vec<_> = stream::iter(components.iter())
             .filter_map(|(id, component)| async move {
-                let name = component
+                let metadata = component
                     .claims()
-                    .and_then(|claims| claims.metadata.as_ref())
-                    .and_then(|metadata| metadata.name.as_ref())
-                    .cloned();
+                    .and_then(|claims| claims.metadata.as_ref());
                 Some(ComponentDescription {
                     id: id.into(),
                     image_ref: component.image_reference.to_string(),
                     annotations: Some(component.annotations.clone().into_iter().collect()),
                     max_instances: component.max_instances.get().try_into().unwrap_or(u32::MAX),
-                    revision: component
-                        .claims()
-                        .and_then(|claims| claims.metadata.as_ref())
+                    revision: metadata
                         .and_then(|jwt::Component { rev, .. }| *rev)
                         .unwrap_or_default(),
-                    name,
+                    name: metadata.and_then(|metadata| metadata.name.clone()),
+                    imports: metadata.and_then(|metadata| metadata.imports.clone()),
+                    exports: metadata.and_then(|metadata| metadata.exports.clone()),
                 })
             })
             .collect()
@@ -1155,20 +1457,99 @@ impl Host {
         }
     }
 
+    /// Returns the result of the last readiness probes
+    async fn readiness(&self) -> HostReadiness {
+        *self.readiness.read().await
+    }
+
+    /// Probes the dependencies the host needs to serve requests, retrying to load supplemental
+    /// config if that previously failed
+    #[instrument(level = "debug", skip_all)]
+    async fn probe_readiness(&self) -> HostReadiness {
+        trace!("probing host readiness");
+        let nats_connected = self.ctl_nats.connection_state()
+            == async_nats::connection::State::Connected
+            && self.rpc_nats.connection_state() == async_nats::connection::State::Connected;
+        let (data, config_data, policy, secrets, supplemental_config_loaded) = join!(
+            self.data.status(),
+            self.config_data.status(),
+            self.policy_manager.probe(),
+            self.secrets_manager.probe(self.host_config.rpc_timeout),
+            self.retry_supplemental_config(),
+        );
+        if let Err(err) = &policy {
+            warn!(?err, "policy readiness probe failed");
+        }
+        if let Err(err) = &secrets {
+            warn!(?err, "secrets readiness probe failed");
+        }
+        HostReadiness {
+            nats_connected,
+            buckets_opened: data.is_ok() && config_data.is_ok(),
+            policy_reachable: policy.is_ok(),
+            secrets_reachable: secrets.is_ok(),
+            supplemental_config_loaded,
+        }
+    }
+
+    /// Requests supplemental config again if it was not loaded at startup, returning whether it
+    /// is loaded now
+    #[instrument(level = "debug", skip_all)]
+    async fn retry_supplemental_config(&self) -> bool {
+        if self.supplemental_config_loaded.load(Ordering::Relaxed) {
+            return true;
+        }
+        let labels = self.labels.read().await.clone();
+        let supplemental_config = match load_supplemental_config(
+            &self.ctl_nats,
+            &self.host_config.lattice,
+            &labels,
+        )
+        .await
+        {
+            Ok(supplemental_config) if supplemental_config.loaded => supplemental_config,
+            Ok(_) => return false,
+            Err(err) => {
+                warn!(?err, "failed to load supplemental config");
+                return false;
+            }
+        };
+        let supplemental_registry_config =
+            RwLock::new(supplemental_config.registry_config.unwrap_or_default());
+        merge_registry_config(
+            &supplemental_registry_config,
+            self.host_config.oci_opts.clone(),
+        )
+        .await;
+        // Registry credentials put since startup take precedence over the supplemental config
+        let mut registry_config = self.registry_config.write().await;
+        for (reg, config) in supplemental_registry_config.into_inner() {
+            registry_config.entry(reg).or_insert(config);
+        }
+        self.supplemental_config_loaded
+            .store(true, Ordering::Relaxed);
+        info!("loaded supplemental config");
+        true
+    }
+
     #[instrument(level = "debug", skip_all)]
-    async fn heartbeat(&self) -> anyhow::Result<serde_json::Value> {
+    async fn heartbeat(&self) -> HostEvent {
         trace!("generating heartbeat");
-        Ok(serde_json::to_value(self.inventory().await)?)
+        let (inventory, readiness) = join!(self.inventory(), self.readiness());
+        HostEvent::HostHeartbeat(HostHeartbeat {
+            inventory,
+            readiness: Some(readiness),
+        })
     }
 
-    #[instrument(level = "debug", skip(self))]
-    async fn publish_event(&self, name: &str, data: serde_json::Value) -> anyhow::Result<()> {
+    #[instrument(level = "debug", skip_all, fields(name = event.name()))]
+    async fn publish_event(&self, event: HostEvent) -> anyhow::Result<()> {
         event::publish(
             &self.event_builder,
             &self.ctl_nats,
+            self.event_stream.as_ref(),
             &self.host_config.lattice,
-            name,
-            data,
+            &event,
         )
         .await
     }
@@ -1259,15 +1640,30 @@ impl Host {
                             while let Some(evt) = events_rx.recv().await {
                                 match evt {
                                     WrpcServeEvent::HttpIncomingHandlerHandleReturned {
-                                        context: (start_at, ref attributes),
+                                        context:
+                                            InvocationContext {
+                                                start_at,
+                                                ref attributes,
+                                                ..
+                                            },
                                         success,
                                     }
                                     | WrpcServeEvent::MessagingHandlerHandleMessageReturned {
-                                        context: (start_at, ref attributes),
+                                        context:
+                                            InvocationContext {
+                                                start_at,
+                                                ref attributes,
+                                                ..
+                                            },
                                         success,
                                     }
                                     | WrpcServeEvent::DynamicExportReturned {
-                                        context: (start_at, ref attributes),
+                                        context:
+                                            InvocationContext {
+                                                start_at,
+                                                ref attributes,
+                                                ..
+                                            },
                                         success,
                                     } => {
                                         metrics.record_component_invocation(
@@ -1305,6 +1701,7 @@ impl Host {
         annotations: &Annotations,
         config: ConfigBundle,
         secrets: HashMap<String, Secret<SecretValue>>,
+        secret_refs: SecretRefs,
     ) -> anyhow::Result<&'a mut Arc<Component>> {
         debug!(?component_ref, ?max_instances, "starting new component");
 
@@ -1314,10 +1711,14 @@ impl Host {
                 .context("failed to store claims")?;
         }
 
-        let component_spec = self
+        let component = wasmcloud_runtime::Component::new(&self.runtime, &wasm)?;
+        let mut component_spec = self
             .get_component_spec(&component_id)
             .await?
             .unwrap_or_else(|| ComponentSpecification::new(&component_ref));
+        // Record the WIT of the component, used to check compatibility of links
+        component_spec.imports = Some(component.imports().to_vec());
+        component_spec.exports = Some(component.exports().to_vec());
         self.store_component_spec(&component_id, &component_spec)
             .await?;
 
@@ -1328,12 +1729,30 @@ impl Host {
             lattice: Arc::clone(&self.host_config.lattice),
             component_id: Arc::clone(&component_id),
             secrets: Arc::new(RwLock::new(secrets)),
+            secret_refs: Arc::new(RwLock::new(secret_refs)),
             targets: Arc::default(),
             trace_ctx: Arc::default(),
+            invocation_deadline: None,
             instance_links: Arc::new(RwLock::new(component_import_links(&component_spec.links))),
+            retry_policies: Arc::new(RwLock::new(component_retry_policies(&component_spec.links))),
             invocation_timeout: Duration::from_secs(10), // TODO: Make this configurable
+            circuit_breakers: Arc::clone(&self.circuit_breakers),
+            metrics: Arc::clone(&self.metrics),
+            target_groups: Arc::clone(&self.target_groups),
+            log_forwarder: self
+                .host_config
+                .log_forwarding
+                .as_ref()
+                .map(|log_forwarding| {
+                    Arc::new(LogForwarder::new(
+                        self.ctl_nats.clone(),
+                        &self.host_config.lattice,
+                        self.host_key.public_key(),
+                        component_id.as_ref(),
+                        log_forwarding.max_records_per_second,
+                    ))
+                }),
         };
-        let component = wasmcloud_runtime::Component::new(&self.runtime, &wasm)?;
         let component = self
             .instantiate_component(
                 annotations,
@@ -1347,17 +1766,14 @@ impl Host {
             .context("failed to instantiate component")?;
 
         info!(?component_ref, "component started");
-        self.publish_event(
-            "component_scaled",
-            event::component_scaled(
-                claims.as_ref(),
-                annotations,
-                self.host_key.public_key(),
-                max_instances,
-                &component_ref,
-                &component_id,
-            ),
-        )
+        self.publish_event(event::component_scaled(
+            claims.as_ref(),
+            annotations,
+            self.host_key.public_key(),
+            max_instances,
+            &component_ref,
+            &component_id,
+        ))
         .await?;
 
         Ok(entry.insert(component))
@@ -1372,6 +1788,46 @@ impl Host {
         Ok(())
     }
 
+    /// Returns whether the labels of this host satisfy all auction constraints
+    async fn auction_constraints_satisfied(
+        &self,
+        constraints: &HashMap<String, String>,
+        label_constraints: &[LabelConstraint],
+    ) -> bool {
+        let host_labels = self.labels.read().await;
+        constraints
+            .iter()
+            .all(|(k, v)| host_labels.get(k).is_some_and(|hv| hv == v))
+            && label_constraints
+                .iter()
+                .all(|constraint| constraint.matches(&host_labels))
+    }
+
+    /// Returns the capacity hints this host reports when bidding in auctions
+    async fn capacity(&self) -> HostCapacity {
+        let running_instances = self
+            .components
+            .read()
+            .await
+            .values()
+            .map(|component| component.max_instances.get())
+            .fold(0usize, usize::saturating_add);
+        let running_instances = u32::try_from(running_instances).unwrap_or(u32::MAX);
+        let free_component_slots = self
+            .host_config
+            .max_components
+            .saturating_sub(running_instances);
+        let running_providers = self.providers.read().await.len();
+        HostCapacity {
+            max_components: self.host_config.max_components,
+            free_component_slots,
+            running_instances,
+            running_providers: u32::try_from(running_providers).unwrap_or(u32::MAX),
+            linear_memory_headroom: u64::from(free_component_slots)
+                .saturating_mul(self.host_config.max_linear_memory),
+        }
+    }
+
     #[instrument(level = "debug", skip_all)]
     async fn handle_auction_component(
         &self,
@@ -1381,6 +1837,7 @@ impl Host {
             component_ref,
             component_id,
             constraints,
+            label_constraints,
         } = serde_json::from_slice(payload.as_ref())
             .context("failed to deserialize component auction command")?;
 
@@ -1388,13 +1845,13 @@ impl Host {
             component_ref,
             component_id,
             ?constraints,
+            ?label_constraints,
             "handling auction for component"
         );
 
-        let host_labels = self.labels.read().await;
-        let constraints_satisfied = constraints
-            .iter()
-            .all(|(k, v)| host_labels.get(k).is_some_and(|hv| hv == v));
+        let constraints_satisfied = self
+            .auction_constraints_satisfied(&constraints, &label_constraints)
+            .await;
         let component_id_running = self.components.read().await.contains_key(&component_id);
 
         // This host can run the component if all constraints are satisfied and the component is not already running
@@ -1403,7 +1860,9 @@ impl Host {
                 component_ref,
                 component_id,
                 constraints,
+                label_constraints,
                 host_id: self.host_key.public_key(),
+                capacity: Some(self.capacity().await),
             })))
         } else {
             Ok(None)
@@ -1419,6 +1878,7 @@ impl Host {
             provider_ref,
             provider_id,
             constraints,
+            label_constraints,
         } = serde_json::from_slice(payload.as_ref())
             .context("failed to deserialize provider auction command")?;
 
@@ -1426,21 +1886,22 @@ impl Host {
             provider_ref,
             provider_id,
             ?constraints,
+            ?label_constraints,
             "handling auction for provider"
         );
 
-        let host_labels = self.labels.read().await;
-        let constraints_satisfied = constraints
-            .iter()
-            .all(|(k, v)| host_labels.get(k).is_some_and(|hv| hv == v));
-        let providers = self.providers.read().await;
-        let provider_running = providers.contains_key(&provider_id);
+        let constraints_satisfied = self
+            .auction_constraints_satisfied(&constraints, &label_constraints)
+            .await;
+        let provider_running = self.providers.read().await.contains_key(&provider_id);
         if constraints_satisfied && !provider_running {
             Ok(Some(CtlResponse::ok(ProviderAuctionAck {
                 provider_ref,
                 provider_id,
                 constraints,
+                label_constraints,
                 host_id: self.host_key.public_key(),
+                capacity: Some(self.capacity().await),
             })))
         } else {
             Ok(None)
@@ -1504,6 +1965,7 @@ impl Host {
         info!(?timeout, "handling stop host");
 
         self.heartbeat.abort();
+        self.readiness_probes.abort();
         self.data_watch.abort();
         self.queue.abort();
         self.policy_manager.policy_changes.abort();
@@ -1593,18 +2055,15 @@ impl Host {
                 Ok((wasm, Ok(claims_token))) => (wasm, claims_token),
                 Err(e) | Ok((_, Err(e))) => {
                     if let Err(e) = self
-                        .publish_event(
-                            "component_scale_failed",
-                            event::component_scale_failed(
-                                None,
-                                &annotations,
-                                host_id,
-                                &component_ref,
-                                &component_id,
-                                max_instances,
-                                &e,
-                            ),
-                        )
+                        .publish_event(event::component_scale_failed(
+                            None,
+                            &annotations,
+                            host_id,
+                            &component_ref,
+                            &component_id,
+                            max_instances,
+                            &e,
+                        ))
                         .await
                     {
                         error!(%component_ref, %component_id, err = ?e, "failed to publish component scale failed event");
@@ -1628,18 +2087,15 @@ impl Host {
             {
                 error!(%component_ref, %component_id, err = ?e, "failed to scale component");
                 if let Err(e) = self
-                    .publish_event(
-                        "component_scale_failed",
-                        event::component_scale_failed(
-                            claims_token.map(|c| c.claims).as_ref(),
-                            &annotations,
-                            host_id,
-                            &component_ref,
-                            &component_id,
-                            max_instances,
-                            &e,
-                        ),
-                    )
+                    .publish_event(event::component_scale_failed(
+                        claims_token.map(|c| c.claims).as_ref(),
+                        &annotations,
+                        host_id,
+                        &component_ref,
+                        &component_id,
+                        max_instances,
+                        &e,
+                    ))
                     .await
                 {
                     error!(%component_ref, %component_id, err = ?e, "failed to publish component scale failed event");
@@ -1687,6 +2143,14 @@ impl Host {
         trace!(?component_ref, max_instances, "scale component task");
 
         let claims = claims_token.map(|c| c.claims.clone());
+        if max_instances > 0 {
+            self.verify_claims(
+                "component",
+                claims_token,
+                &self.component_cosignatures(&wasm)?,
+            )
+            .await?;
+        }
         match self
             .policy_manager
             .evaluate_start_component(
@@ -1730,6 +2194,11 @@ impl Host {
             ),
             // No component is running and we requested to scale to some amount, start with specified max
             (hash_map::Entry::Vacant(entry), Some(max)) => {
+                let secret_refs = SecretRefs::new(
+                    &config,
+                    claims_token.as_ref().map(|c| &c.jwt),
+                    annotations.get("wasmcloud.dev/appspec"),
+                );
                 let (config, secrets) = self
                     .fetch_config_and_secrets(
                         &config,
@@ -1748,6 +2217,7 @@ impl Host {
                     annotations,
                     config,
                     secrets,
+                    secret_refs,
                 )
                 .await?;
 
@@ -1799,6 +2269,11 @@ impl Host {
                     // We must partially clone the handler as we can't be sharing the targets between components
                     let handler = component.handler.copy_for_new();
                     if config_changed {
+                        *handler.secret_refs.write().await = SecretRefs::new(
+                            &config,
+                            claims_token.as_ref().map(|c| &c.jwt),
+                            annotations.get("wasmcloud.dev/appspec"),
+                        );
                         let (config, secrets) = self
                             .fetch_config_and_secrets(
                                 &config,
@@ -1833,7 +2308,7 @@ impl Host {
             }
         };
 
-        self.publish_event("component_scaled", scaled_event).await?;
+        self.publish_event(scaled_event).await?;
 
         Ok(())
     }
@@ -1937,6 +2412,12 @@ impl Host {
             }
 
             let new_component = self.fetch_component(&new_component_ref).await?;
+            self.verify_claims(
+                "component",
+                wasmcloud_runtime::component::claims_token(&new_component)?.as_ref(),
+                &self.component_cosignatures(&new_component)?,
+            )
+            .await?;
             let new_component = wasmcloud_runtime::Component::new(&self.runtime, &new_component)
                 .context("failed to initialize component")?;
             let new_claims = new_component.claims().cloned();
@@ -1962,34 +2443,28 @@ impl Host {
             };
 
             info!(%new_component_ref, "component updated");
-            self.publish_event(
-                "component_scaled",
-                event::component_scaled(
-                    new_claims.as_ref(),
-                    &component.annotations,
-                    host_id,
-                    max,
-                    new_component_ref,
-                    &component_id,
-                ),
-            )
+            self.publish_event(event::component_scaled(
+                new_claims.as_ref(),
+                &component.annotations,
+                host_id,
+                max,
+                new_component_ref,
+                &component_id,
+            ))
             .await?;
 
             // TODO(#1548): If this errors, we need to rollback
             self.stop_component(&component, host_id)
                 .await
                 .context("failed to stop old component")?;
-            self.publish_event(
-                "component_scaled",
-                event::component_scaled(
-                    component.claims(),
-                    &component.annotations,
-                    host_id,
-                    0_usize,
-                    &component.image_reference,
-                    &component.id,
-                ),
-            )
+            self.publish_event(event::component_scaled(
+                component.claims(),
+                &component.annotations,
+                host_id,
+                0_usize,
+                &component.image_reference,
+                &component.id,
+            ))
             .await?;
 
             component
@@ -2023,6 +2498,24 @@ impl Host {
             ));
         }
 
+        // Validate the config before starting the provider in the background, so that the caller
+        // gets the error. The schema is only known here if a host started this provider before,
+        // otherwise the config is validated once the provider is fetched.
+        if let Some(schema) = self
+            .get_component_spec(&provider_id)
+            .await?
+            .filter(|spec| spec.url == provider_ref)
+            .and_then(|spec| spec.config_schema)
+        {
+            if let Err(e) = self
+                .validate_config_schema(&schema, &config)
+                .await
+                .context("invalid provider config")
+            {
+                return Ok(CtlResponse::error(&format!("{e:#}")));
+            }
+        }
+
         info!(provider_ref, provider_id, "handling start provider"); // Log at info since starting providers can take a while
 
         let host_id = host_id.to_string();
@@ -2039,10 +2532,11 @@ impl Host {
             {
                 error!(provider_ref, provider_id, ?err, "failed to start provider");
                 if let Err(err) = self
-                    .publish_event(
-                        "provider_start_failed",
-                        event::provider_start_failed(provider_ref, provider_id, &err),
-                    )
+                    .publish_event(event::provider_start_failed(
+                        provider_ref,
+                        provider_id,
+                        &err,
+                    ))
                     .await
                 {
                     error!(?err, "failed to publish provider_start_failed event");
@@ -2064,7 +2558,7 @@ impl Host {
         trace!(provider_ref, provider_id, "start provider task");
 
         let registry_config = self.registry_config.read().await;
-        let (path, claims_token) = crate::fetch_provider(
+        let (path, claims_token, cosignatures, wit) = crate::fetch_provider(
             provider_ref,
             host_id,
             self.host_config.allow_file_load,
@@ -2072,6 +2566,8 @@ impl Host {
         )
         .await
         .context("failed to fetch provider")?;
+        self.verify_claims("provider", claims_token.as_ref(), &cosignatures)
+            .await?;
         let claims = claims_token.as_ref().map(|t| t.claims.clone());
 
         if let Some(claims) = claims.clone() {
@@ -2095,14 +2591,36 @@ impl Host {
             "policy denied request to start provider `{request_id}`: `{message:?}`",
         );
 
-        let component_specification = self
+        let config_schema = claims
+            .as_ref()
+            .and_then(|claims| claims.metadata.as_ref())
+            .and_then(|metadata| metadata.config_schema.clone());
+
+        let mut component_specification = self
             .get_component_spec(provider_id)
             .await?
             .unwrap_or_else(|| ComponentSpecification::new(provider_ref));
+        // Store the schema for validating the config of links on any host
+        component_specification.config_schema = config_schema.clone();
+        // Record the WIT of the provider, if bundled, used to check compatibility of links
+        if let Some(wit) = wit {
+            match compat::wit_package_instances(&wit) {
+                Ok((imports, exports)) => {
+                    component_specification.imports = Some(imports);
+                    component_specification.exports = Some(exports);
+                }
+                Err(err) => warn!(?err, provider_id, "failed to read WIT of provider"),
+            }
+        }
 
         self.store_component_spec(&provider_id, &component_specification)
             .await?;
 
+        let secret_refs = SecretRefs::new(
+            config,
+            claims_token.as_ref().map(|t| &t.jwt),
+            annotations.get("wasmcloud.dev/appspec"),
+        );
         let (config, secrets) = self
             .fetch_config_and_secrets(
                 config,
@@ -2110,6 +2628,10 @@ impl Host {
                 annotations.get("wasmcloud.dev/appspec"),
             )
             .await?;
+        if let Some(schema) = &config_schema {
+            provider_archive::validate_config(schema, &*config.get_config().await)
+                .map_err(|e| anyhow!(e).context("invalid provider config"))?;
+        }
 
         let mut providers = self.providers.write().await;
         if let hash_map::Entry::Vacant(entry) = providers.entry(provider_id.into()) {
@@ -2139,6 +2661,25 @@ impl Host {
                 protocol: self.host_config.otel_config.protocol,
                 additional_ca_paths: self.host_config.otel_config.additional_ca_paths.clone(),
                 trace_level: self.host_config.otel_config.trace_level.clone(),
+                traces_sampling_ratio: self.host_config.otel_config.traces_sampling_ratio,
+                traces_sampling_parent_based: self
+                    .host_config
+                    .otel_config
+                    .traces_sampling_parent_based,
+                // Providers write to their own file next to the host's, since they
+                // cannot share a rotated file
+                traces_file: self
+                    .host_config
+                    .otel_config
+                    .traces_file
+                    .as_ref()
+                    .map(|path| {
+                        let mut path = path.clone().into_os_string();
+                        path.push(format!(".{provider_id}"));
+                        path.into()
+                    }),
+                traces_file_max_bytes: self.host_config.otel_config.traces_file_max_bytes,
+                traces_file_max_files: self.host_config.otel_config.traces_file_max_files,
             };
 
             let provider_xkey = XKey::new();
@@ -2195,7 +2736,7 @@ impl Host {
                 .collect::<Vec<wasmcloud_core::InterfaceLinkDefinition>>()
                 .await;
 
-            let secrets = {
+            let host_secrets = {
                 // NOTE(brooksmtownsend): This trait import is used here to ensure we're only exposing secret
                 // values when we need them.
                 use secrecy::ExposeSecret;
@@ -2226,7 +2767,7 @@ impl Host {
                 provider_key: provider_id.to_string(),
                 link_definitions,
                 config: config.get_config().await.clone(),
-                secrets,
+                secrets: host_secrets,
                 provider_xkey_private_key,
                 host_xkey_public_key: self.secrets_xkey.public_key(),
                 cluster_issuers: vec![],
@@ -2299,6 +2840,7 @@ impl Host {
             let rpc_nats = self.rpc_nats.clone();
             let ctl_nats = self.ctl_nats.clone();
             let event_builder = self.event_builder.clone();
+            let event_stream = self.event_stream.clone();
             // NOTE: health_ prefix here is to allow us to move the variables into the closure
             let health_lattice = self.host_config.lattice.clone();
             let health_host_id = host_id.to_string();
@@ -2330,12 +2872,12 @@ impl Host {
                                             if let Err(e) = event::publish(
                                                 &event_builder,
                                                 &ctl_nats,
+                                                event_stream.as_ref(),
                                                 &health_lattice,
-                                                "health_check_passed",
-                                                event::provider_health_check(
+                                                &HostEvent::HealthCheckPassed(event::provider_health_check(
                                                     &health_host_id,
                                                     &health_provider_id,
-                                                )
+                                                )),
                                             ).await {
                                                 warn!(
                                                     ?e,
@@ -2350,12 +2892,12 @@ impl Host {
                                             if let Err(e) = event::publish(
                                                 &event_builder,
                                                 &ctl_nats,
+                                                event_stream.as_ref(),
                                                 &health_lattice,
-                                                "health_check_failed",
-                                                event::provider_health_check(
+                                                &HostEvent::HealthCheckFailed(event::provider_health_check(
                                                     &health_host_id,
                                                     &health_provider_id,
-                                                )
+                                                )),
                                             ).await {
                                                 warn!(
                                                     ?e,
@@ -2369,12 +2911,12 @@ impl Host {
                                             if let Err(e) = event::publish(
                                                 &event_builder,
                                                 &ctl_nats,
+                                                event_stream.as_ref(),
                                                 &health_lattice,
-                                                "health_check_status",
-                                                event::provider_health_check(
+                                                &HostEvent::HealthCheckStatus(event::provider_health_check(
                                                     &health_host_id,
                                                     &health_provider_id,
-                                                )
+                                                )),
                                             ).await {
                                                 warn!(
                                                     ?e,
@@ -2403,16 +2945,13 @@ impl Host {
                 }
             });
             info!(provider_ref, provider_id, "provider started");
-            self.publish_event(
-                "provider_started",
-                event::provider_started(
-                    claims.as_ref(),
-                    &annotations,
-                    host_id,
-                    provider_ref,
-                    provider_id,
-                ),
-            )
+            self.publish_event(event::provider_started(
+                claims.as_ref(),
+                &annotations,
+                host_id,
+                provider_ref,
+                provider_id,
+            ))
             .await?;
 
             // Spawn off a task to watch for config bundle updates and forward them to
@@ -2465,6 +3004,8 @@ impl Host {
                 image_ref: provider_ref.to_string(),
                 xkey,
                 config,
+                secrets: Arc::new(RwLock::new(secrets)),
+                secret_refs,
             });
         } else {
             bail!("provider is already running with that ID")
@@ -2484,13 +3025,27 @@ impl Host {
 
         debug!(provider_id, "handling stop provider");
 
-        let mut providers = self.providers.write().await;
-        let hash_map::Entry::Occupied(entry) = providers.entry(provider_id.clone()) else {
+        if !self.stop_provider(&provider_id, host_id, "stop").await? {
             warn!(
                 provider_id,
                 "received request to stop provider that is not running"
             );
             return Ok(CtlResponse::error("provider with that ID is not running"));
+        }
+        Ok(CtlResponse::success())
+    }
+
+    /// Stop the provider `provider_id`, publishing a `provider_stopped` event with `reason`.
+    /// Returns `false` if the provider is not running.
+    async fn stop_provider(
+        &self,
+        provider_id: &str,
+        host_id: &str,
+        reason: &str,
+    ) -> anyhow::Result<bool> {
+        let mut providers = self.providers.write().await;
+        let hash_map::Entry::Occupied(entry) = providers.entry(provider_id.to_string()) else {
+            return Ok(false);
         };
         let Provider {
             ref annotations, ..
@@ -2523,12 +3078,14 @@ impl Host {
             );
         }
         info!(provider_id, "provider stopped");
-        self.publish_event(
-            "provider_stopped",
-            event::provider_stopped(annotations, host_id, provider_id, "stop"),
-        )
+        self.publish_event(event::provider_stopped(
+            annotations,
+            host_id,
+            provider_id,
+            reason,
+        ))
         .await?;
-        Ok(CtlResponse::success())
+        Ok(true)
     }
 
     #[instrument(level = "debug", skip_all)]
@@ -2584,10 +3141,76 @@ impl Host {
         }
     }
 
-    #[instrument(level = "debug", skip_all)]
-    async fn handle_label_put(
-        &self,
-        host_id: &str,
+    #[instrument(level = "trace", skip(self))]
+    async fn handle_target_group_get(&self, group_name: &str) -> anyhow::Result<Vec<u8>> {
+        trace!(%group_name, "handling get target group");
+        if let Some(group) = self.target_groups.get(group_name).await {
+            serde_json::to_vec(&CtlResponse::ok(group)).map_err(anyhow::Error::from)
+        } else {
+            serde_json::to_vec(&CtlResponse::<()> {
+                success: true,
+                response: None,
+                message: "Target group not found".to_string(),
+            })
+            .map_err(anyhow::Error::from)
+        }
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    async fn handle_target_groups(&self) -> anyhow::Result<Vec<u8>> {
+        trace!("handling get target groups");
+        let groups = self.target_groups.list().await;
+        serde_json::to_vec(&CtlResponse::ok(groups)).context("failed to serialize response")
+    }
+
+    #[instrument(level = "debug", skip_all, fields(%group_name))]
+    async fn handle_target_group_put(
+        &self,
+        group_name: &str,
+        payload: impl AsRef<[u8]>,
+    ) -> anyhow::Result<CtlResponse<()>> {
+        debug!("handle target group put");
+        let group: TargetGroup = serde_json::from_slice(payload.as_ref())
+            .context("failed to deserialize target group")?;
+        if group.name != group_name {
+            return Ok(CtlResponse::error(&format!(
+                "target group name `{}` does not match subject `{group_name}`",
+                group.name
+            )));
+        }
+        if let Err(e) = validate_target_group(&group) {
+            return Ok(CtlResponse::error(&e.to_string()));
+        }
+        let bytes = serde_json::to_vec(&group)
+            .context("failed to serialize target group")?
+            .into();
+        self.data
+            .put(format!("TARGETGROUP_{group_name}"), bytes)
+            .await
+            .context("failed to put target group")?;
+        // Update the cache right away, so that subsequent invocations on this host use the group
+        self.target_groups.put(group).await;
+        Ok(CtlResponse::success())
+    }
+
+    #[instrument(level = "debug", skip_all, fields(%group_name))]
+    async fn handle_target_group_delete(
+        &self,
+        group_name: &str,
+    ) -> anyhow::Result<CtlResponse<()>> {
+        debug!("handle target group deletion");
+        self.data
+            .delete(format!("TARGETGROUP_{group_name}"))
+            .await
+            .context("failed to delete target group")?;
+        self.target_groups.remove(group_name).await;
+        Ok(CtlResponse::success())
+    }
+
+    #[instrument(level = "debug", skip_all)]
+    async fn handle_label_put(
+        &self,
+        host_id: &str,
         payload: impl AsRef<[u8]>,
     ) -> anyhow::Result<CtlResponse<()>> {
         let HostLabel { key, value } = serde_json::from_slice(payload.as_ref())
@@ -2604,12 +3227,9 @@ impl Host {
             }
         }
 
-        self.publish_event(
-            "labels_changed",
-            event::labels_changed(host_id, labels.clone()),
-        )
-        .await
-        .context("failed to publish labels_changed event")?;
+        self.publish_event(event::labels_changed(host_id, labels.clone()))
+            .await
+            .context("failed to publish labels_changed event")?;
 
         Ok(CtlResponse::success())
     }
@@ -2631,12 +3251,9 @@ impl Host {
         };
 
         info!(key, "removed label");
-        self.publish_event(
-            "labels_changed",
-            event::labels_changed(host_id, labels.clone()),
-        )
-        .await
-        .context("failed to publish labels_changed event")?;
+        self.publish_event(event::labels_changed(host_id, labels.clone()))
+            .await
+            .context("failed to publish labels_changed event")?;
 
         Ok(CtlResponse::success())
     }
@@ -2647,8 +3264,9 @@ impl Host {
     #[instrument(level = "debug", skip_all)]
     async fn handle_link_put(&self, payload: impl AsRef<[u8]>) -> anyhow::Result<CtlResponse<()>> {
         let payload = payload.as_ref();
-        let interface_link_definition: InterfaceLinkDefinition = serde_json::from_slice(payload)
-            .context("failed to deserialize wrpc link definition")?;
+        let mut interface_link_definition: InterfaceLinkDefinition =
+            serde_json::from_slice(payload)
+                .context("failed to deserialize wrpc link definition")?;
 
         let link_set_result: anyhow::Result<()> = async {
             let InterfaceLinkDefinition {
@@ -2660,6 +3278,8 @@ impl Host {
                 name,
                 source_config: _,
                 target_config: _,
+                status: _,
+                retry_policy: _,
             } = interface_link_definition.clone();
 
             let ns_and_package = format!("{wit_namespace}:{wit_package}");
@@ -2684,6 +3304,44 @@ impl Host {
                 .get_component_spec(&source_id)
                 .await?
                 .unwrap_or_default();
+            let target_spec = if target.starts_with(TARGET_GROUP_PREFIX) {
+                None
+            } else {
+                self.get_component_spec(&target).await?
+            };
+
+            // Check the link config against the config schema of providers on either end
+            if let Some(schema) = &component_spec.config_schema {
+                self.validate_config_schema(schema, &interface_link_definition.source_config)
+                    .await
+                    .context("invalid source config")?;
+            }
+            if let Some(schema) = target_spec
+                .as_ref()
+                .and_then(|spec| spec.config_schema.as_ref())
+            {
+                self.validate_config_schema(schema, &interface_link_definition.target_config)
+                    .await
+                    .context("invalid target config")?;
+            }
+
+            // Check the link against the WIT of both ends, if known
+            let target_exports = target_spec.and_then(|spec| spec.exports);
+            interface_link_definition.status = compat::check_link(
+                &interface_link_definition,
+                component_spec.imports.as_deref(),
+                target_exports.as_deref(),
+            );
+            if let Some(LinkStatus::Unhealthy { reason }) = &interface_link_definition.status {
+                ensure!(
+                    !self.host_config.enforce_link_compatibility,
+                    "link is incompatible with its source or target: {reason}"
+                );
+                warn!(
+                    source_id,
+                    target, ns_and_package, name, reason, "storing incompatible link as unhealthy"
+                );
+            }
 
             // If we can find an existing link with the same source, target, namespace, package, and name, update it.
             // Otherwise, add the new link to the component specification.
@@ -2712,19 +3370,20 @@ impl Host {
         }
         .await;
 
+        for event in link_put_events(&interface_link_definition, &link_set_result) {
+            self.publish_event(event).await?;
+        }
         if let Err(e) = link_set_result {
-            self.publish_event(
-                "linkdef_set_failed",
-                event::linkdef_set_failed(&interface_link_definition, &e),
-            )
-            .await?;
-            Ok(CtlResponse::error(e.to_string().as_ref()))
+            Ok(CtlResponse::error(&format!("{e:#}")))
+        } else if let Some(LinkStatus::Unhealthy { reason }) = &interface_link_definition.status {
+            Ok(CtlResponse {
+                success: true,
+                response: None,
+                message: format!(
+                    "link is incompatible with its source or target: {reason}, stored link as unhealthy"
+                ),
+            })
         } else {
-            self.publish_event(
-                "linkdef_set",
-                event::linkdef_set(&interface_link_definition),
-            )
-            .await?;
             Ok(CtlResponse::success())
         }
     }
@@ -2776,10 +3435,12 @@ impl Host {
         self.store_component_spec(&source_id, &component_spec)
             .await?;
 
-        self.publish_event(
-            "linkdef_deleted",
-            event::linkdef_deleted(&source_id, name, wit_namespace, wit_package),
-        )
+        self.publish_event(event::linkdef_deleted(
+            &source_id,
+            name,
+            wit_namespace,
+            wit_package,
+        ))
         .await?;
 
         self.del_provider_link(&source_id, deleted_link_target, payload.to_owned().into())
@@ -2829,33 +3490,179 @@ impl Host {
         // Validate that the data is of the proper type by deserialing it
         serde_json::from_slice::<HashMap<String, String>>(&data)
             .context("config data should be a map of string -> string")?;
-        self.config_data
-            .put(config_name, data)
-            .await
-            .context("unable to store config data")?;
-        // We don't write it into the cached data and instead let the caching thread handle it as we
-        // won't need it immediately.
-        self.publish_event("config_set", event::config_set(config_name))
-            .await?;
+        self.put_config_revision(config_name, data).await?;
 
         Ok(CtlResponse::success())
     }
 
+    /// Store `data` as a new revision of `config_name` and publish a `config_set` event
+    async fn put_config_revision(&self, config_name: &str, data: Bytes) -> anyhow::Result<()> {
+        // The update only succeeds if no other revision was written since the entry was read, so
+        // that the event references the revision which was actually replaced
+        const ATTEMPTS: usize = 3;
+        let mut attempt = 1;
+        let (revision, previous_revision) = loop {
+            let entry = self
+                .config_data
+                .entry(config_name)
+                .await
+                .context("unable to get current config data")?;
+            let expected_revision = entry.as_ref().map_or(0, |entry| entry.revision);
+            let previous_revision = entry
+                .filter(|entry| matches!(entry.operation, Operation::Put))
+                .map(|entry| entry.revision);
+            match self
+                .config_data
+                .update(config_name, data.clone(), expected_revision)
+                .await
+            {
+                Ok(revision) => break (revision, previous_revision),
+                Err(err) if attempt < ATTEMPTS => {
+                    debug!(
+                        ?err,
+                        attempt, "config data changed while storing it, retrying"
+                    );
+                    attempt += 1;
+                }
+                Err(err) => return Err(anyhow!(err).context("unable to store config data")),
+            }
+        };
+        // We don't write it into the cached data and instead let the caching thread handle it as we
+        // won't need it immediately.
+        self.publish_event(event::config_set(
+            config_name,
+            Some(revision),
+            previous_revision,
+        ))
+        .await
+    }
+
     #[instrument(level = "debug", skip_all, fields(%config_name))]
     async fn handle_config_delete(&self, config_name: &str) -> anyhow::Result<CtlResponse<()>> {
         debug!("handle config entry deletion");
 
+        // Deleting rather than purging keeps the history of the config for rollbacks
         self.config_data
-            .purge(config_name)
+            .delete(config_name)
             .await
             .context("Unable to delete config data")?;
 
-        self.publish_event("config_deleted", event::config_deleted(config_name))
+        self.publish_event(event::config_deleted(config_name))
             .await?;
 
         Ok(CtlResponse::success())
     }
 
+    /// Returns the revisions of `config_name` kept in the config bucket, oldest first
+    async fn config_history(&self, config_name: &str) -> anyhow::Result<Vec<ConfigRevision>> {
+        // The history of a key which never existed never completes, so check for it first
+        if self
+            .config_data
+            .entry(config_name)
+            .await
+            .context("unable to get current config data")?
+            .is_none()
+        {
+            return Ok(Vec::default());
+        }
+        self.config_data
+            .history(config_name)
+            .await
+            .context("unable to get config history")?
+            .map_err(anyhow::Error::from)
+            .and_then(|entry| async move {
+                let deleted = !matches!(entry.operation, Operation::Put);
+                let config = if deleted {
+                    HashMap::default()
+                } else {
+                    serde_json::from_slice(&entry.value)
+                        .context("config data should be a map of string -> string")?
+                };
+                Ok(ConfigRevision {
+                    revision: entry.revision,
+                    created_at: entry
+                        .created
+                        .format(&Rfc3339)
+                        .context("failed to format config revision creation time")?,
+                    deleted,
+                    config,
+                })
+            })
+            .try_collect()
+            .await
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn handle_config_history(&self, config_name: &str) -> anyhow::Result<Vec<u8>> {
+        trace!(%config_name, "handling get config history");
+        let history = self.config_history(config_name).await?;
+        if history.is_empty() {
+            serde_json::to_vec(&CtlResponse::<()> {
+                success: true,
+                response: None,
+                message: "Configuration not found".to_string(),
+            })
+            .map_err(anyhow::Error::from)
+        } else {
+            serde_json::to_vec(&CtlResponse::ok(history)).map_err(anyhow::Error::from)
+        }
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn handle_config_revision_get(
+        &self,
+        config_name: &str,
+        revision: &str,
+    ) -> anyhow::Result<Vec<u8>> {
+        trace!(%config_name, %revision, "handling get config revision");
+        let Ok(revision) = revision.parse::<u64>() else {
+            return serde_json::to_vec(&CtlResponse::<()>::error(&format!(
+                "invalid config revision `{revision}`"
+            )))
+            .map_err(anyhow::Error::from);
+        };
+        let history = self.config_history(config_name).await?;
+        if let Some(revision) = history.into_iter().find(|r| r.revision == revision) {
+            serde_json::to_vec(&CtlResponse::ok(revision)).map_err(anyhow::Error::from)
+        } else {
+            serde_json::to_vec(&CtlResponse::<()> {
+                success: true,
+                response: None,
+                message: "Configuration revision not found".to_string(),
+            })
+            .map_err(anyhow::Error::from)
+        }
+    }
+
+    #[instrument(level = "debug", skip_all, fields(%config_name, %revision))]
+    async fn handle_config_rollback(
+        &self,
+        config_name: &str,
+        revision: &str,
+    ) -> anyhow::Result<CtlResponse<()>> {
+        debug!("handle config rollback");
+        let Ok(revision) = revision.parse::<u64>() else {
+            return Ok(CtlResponse::error(&format!(
+                "invalid config revision `{revision}`"
+            )));
+        };
+        let history = self.config_history(config_name).await?;
+        let Some(target) = history.into_iter().find(|r| r.revision == revision) else {
+            return Ok(CtlResponse::error(&format!(
+                "revision {revision} of config `{config_name}` not found"
+            )));
+        };
+        if target.deleted {
+            return Ok(CtlResponse::error(&format!(
+                "revision {revision} of config `{config_name}` is a deletion, delete the config instead"
+            )));
+        }
+        let data = serde_json::to_vec(&target.config).context("failed to serialize config")?;
+        self.put_config_revision(config_name, data.into()).await?;
+
+        Ok(CtlResponse::success())
+    }
+
     #[instrument(level = "debug", skip_all)]
     async fn handle_ping_hosts(
         &self,
@@ -2971,6 +3778,25 @@ impl Host {
                 .await
                 .map(Some)
                 .map(serialize_ctl_response),
+            // Target group commands
+            (Some("targetgroup"), Some("get"), None, None) => self
+                .handle_target_groups()
+                .await
+                .map(|bytes| Some(Ok(bytes))),
+            (Some("targetgroup"), Some("get"), Some(group_name), None) => self
+                .handle_target_group_get(group_name)
+                .await
+                .map(|bytes| Some(Ok(bytes))),
+            (Some("targetgroup"), Some("put"), Some(group_name), None) => self
+                .handle_target_group_put(group_name, message.payload)
+                .await
+                .map(Some)
+                .map(serialize_ctl_response),
+            (Some("targetgroup"), Some("del"), Some(group_name), None) => self
+                .handle_target_group_delete(group_name)
+                .await
+                .map(Some)
+                .map(serialize_ctl_response),
             // Label commands
             (Some("label"), Some("del"), Some(host_id), None) => self
                 .handle_label_del(host_id, message.payload)
@@ -2993,6 +3819,19 @@ impl Host {
                 .handle_config_get(config_name)
                 .await
                 .map(|bytes| Some(Ok(bytes))),
+            (Some("config"), Some("get"), Some(config_name), Some(revision)) => self
+                .handle_config_revision_get(config_name, revision)
+                .await
+                .map(|bytes| Some(Ok(bytes))),
+            (Some("config"), Some("history"), Some(config_name), None) => self
+                .handle_config_history(config_name)
+                .await
+                .map(|bytes| Some(Ok(bytes))),
+            (Some("config"), Some("rollback"), Some(config_name), Some(revision)) => self
+                .handle_config_rollback(config_name, revision)
+                .await
+                .map(Some)
+                .map(serialize_ctl_response),
             (Some("config"), Some("put"), Some(config_name), None) => self
                 .handle_config_put(config_name, message.payload)
                 .await
@@ -3231,6 +4070,260 @@ impl Host {
         Ok(())
     }
 
+    /// Verify that the entity with the embedded claims `token` may be started, i.e. that its
+    /// issuer is trusted, if the host is configured with trusted issuers, that it is signed by
+    /// enough trusted signers, counting its `cosignatures`, if the host requires signatures, that
+    /// its claims are not revoked and, if the host blocks or stops entities with expired claims,
+    /// not expired
+    async fn verify_claims<T>(
+        &self,
+        kind: &str,
+        token: Option<&jwt::Token<T>>,
+        cosignatures: &[jwt::Token<jwt::CoSignature>],
+    ) -> anyhow::Result<()>
+    where
+        T: Serialize + serde::de::DeserializeOwned + jwt::WascapEntity,
+    {
+        if let Some(chain) = &self.trust_chain {
+            chain.verify(kind, token)?;
+        }
+        if let Some(required) = &self.host_config.required_signatures {
+            verify_signatures(required, kind, token, cosignatures)?;
+        }
+        if let Some(token) = token {
+            if let Some(revoked) = self.revoked(&token.claims).await {
+                bail!(
+                    "{kind} `{}` cannot be started, {revoked}",
+                    token.claims.subject
+                );
+            }
+            if let (
+                Some(ClaimsExpiry {
+                    action: ClaimsExpiryAction::Block | ClaimsExpiryAction::Stop,
+                    ..
+                }),
+                Some(expires),
+            ) = (&self.host_config.claims_expiry, token.claims.expires)
+            {
+                ensure!(
+                    unix_now() < expires,
+                    "{kind} `{}` cannot be started, its claims expired",
+                    token.claims.subject
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the detached co-signatures of the component `wasm` if the host requires signatures,
+    /// which are otherwise not needed
+    fn component_cosignatures(
+        &self,
+        wasm: &[u8],
+    ) -> anyhow::Result<Vec<jwt::Token<jwt::CoSignature>>> {
+        if self.host_config.required_signatures.is_none() {
+            return Ok(Vec::new());
+        }
+        wascap::wasm::extract_cosignatures(wasm)
+            .context("failed to extract component co-signatures")
+    }
+
+    /// Returns a description of the revocation of `claims`, if they are revoked
+    async fn revoked<T>(&self, claims: &jwt::Claims<T>) -> Option<String> {
+        let revocations = self.revocations.read().await;
+        [
+            (RevocationKind::Subject, &claims.subject),
+            (RevocationKind::Issuer, &claims.issuer),
+            (RevocationKind::Jti, &claims.id),
+        ]
+        .into_iter()
+        .find_map(|(kind, id)| {
+            let revocation = revocations.get(&kind.key(id))?;
+            let kind = match kind {
+                RevocationKind::Subject => "subject",
+                RevocationKind::Issuer => "issuer",
+                RevocationKind::Jti => "JWT ID",
+            };
+            Some(match &revocation.reason {
+                Some(reason) => format!("{kind} `{id}` is revoked: {reason}"),
+                None => format!("{kind} `{id}` is revoked"),
+            })
+        })
+    }
+
+    /// Apply a change of the revocation bucket. If `stop` is set and the host is configured to stop
+    /// revoked entities, running components and providers with newly revoked claims are stopped.
+    #[instrument(level = "debug", skip_all)]
+    async fn process_revocation(&self, entry: KvEntry, stop: bool) {
+        let KvEntry {
+            key,
+            value,
+            operation,
+            ..
+        } = entry;
+        if RevocationKind::parse_key(&key).is_none() {
+            warn!(%key, "ignoring invalid key in revocation bucket");
+            return;
+        }
+        match operation {
+            Operation::Put => {
+                let revocation: Revocation = serde_json::from_slice(&value).unwrap_or_else(|err| {
+                    warn!(
+                        %key,
+                        ?err,
+                        "failed to decode revocation, revoking without reason"
+                    );
+                    Revocation::default()
+                });
+                info!(%key, ?revocation, "claims revoked");
+                self.revocations.write().await.insert(key, revocation);
+                if stop && self.host_config.stop_revoked {
+                    self.stop_revoked().await;
+                }
+            }
+            Operation::Delete | Operation::Purge => {
+                info!(%key, "claims revocation lifted");
+                self.revocations.write().await.remove(&key);
+            }
+        }
+    }
+
+    /// Stop all running components and providers whose claims are revoked
+    async fn stop_revoked(&self) {
+        let host_id = self.host_key.public_key();
+        let components: Vec<_> = self.components.read().await.values().cloned().collect();
+        for component in components {
+            let Some(claims) = component.claims() else {
+                continue;
+            };
+            let Some(revoked) = self.revoked(claims).await else {
+                continue;
+            };
+            warn!(component_id = %component.id, %revoked, "stopping revoked component");
+            if let Err(err) = self.remove_component(&component.id, &host_id).await {
+                error!(component_id = %component.id, ?err, "failed to stop revoked component");
+            }
+        }
+
+        let mut revoked_providers = Vec::new();
+        for (provider_id, provider) in self.providers.read().await.iter() {
+            let Some(token) = &provider.claims_token else {
+                continue;
+            };
+            if let Some(revoked) = self.revoked(&token.claims).await {
+                warn!(%provider_id, %revoked, "stopping revoked provider");
+                revoked_providers.push(provider_id.clone());
+            }
+        }
+        for provider_id in revoked_providers {
+            if let Err(err) = self.stop_provider(&provider_id, &host_id, "revoked").await {
+                error!(%provider_id, ?err, "failed to stop revoked provider");
+            }
+        }
+    }
+
+    /// Stop and remove the running component `component_id`, if any, and publish a
+    /// `component_scaled` event for it
+    async fn remove_component(&self, component_id: &str, host_id: &str) -> anyhow::Result<()> {
+        let Some(component) = self.components.write().await.remove(component_id) else {
+            return Ok(());
+        };
+        self.stop_component(&component, host_id).await?;
+        self.publish_event(event::component_scaled(
+            component.claims(),
+            &component.annotations,
+            host_id,
+            0_usize,
+            &component.image_reference,
+            &component.id,
+        ))
+        .await
+    }
+
+    /// Publish `claims_expiring` events for running components and providers whose claims expire
+    /// within one of the configured lead times or expired, and apply the configured action to
+    /// those whose claims expired
+    #[instrument(level = "debug", skip_all)]
+    async fn check_claims_expiry(&self, config: &ClaimsExpiry) {
+        let host_id = self.host_key.public_key();
+        let now = unix_now();
+        let mut components = Vec::new();
+        for component in self.components.read().await.values() {
+            if let Some(jwt::Claims {
+                subject,
+                expires: Some(expires),
+                ..
+            }) = component.claims()
+            {
+                components.push((component.id.to_string(), subject.clone(), *expires));
+            }
+        }
+        let mut providers = Vec::new();
+        for (provider_id, provider) in self.providers.read().await.iter() {
+            if let Some(jwt::Token {
+                claims:
+                    jwt::Claims {
+                        subject,
+                        expires: Some(expires),
+                        ..
+                    },
+                ..
+            }) = &provider.claims_token
+            {
+                providers.push((provider_id.clone(), subject.clone(), *expires));
+            }
+        }
+
+        let due: Vec<_> = {
+            let mut tracker = self.claims_expiry.lock().await;
+            tracker.retain(
+                components
+                    .iter()
+                    .chain(&providers)
+                    .map(|(id, ..)| id.as_str()),
+            );
+            components
+                .into_iter()
+                .map(|entity| (entity, false))
+                .chain(providers.into_iter().map(|entity| (entity, true)))
+                .filter(|((id, _, expires), _)| tracker.due(id, *expires, now, &config.lead_times))
+                .collect()
+        };
+        for ((id, public_key, expires), is_provider) in due {
+            let expired = now >= expires;
+            if expired {
+                warn!(entity_id = %id, %public_key, action = ?config.action, "claims expired");
+            } else {
+                info!(entity_id = %id, %public_key, expires, "claims expiring");
+            }
+            if let Err(err) = self
+                .publish_event(event::claims_expiring(
+                    &host_id,
+                    &id,
+                    &public_key,
+                    expires,
+                    now,
+                ))
+                .await
+            {
+                error!(?err, "failed to publish claims expiring event");
+            }
+            if !expired || config.action != ClaimsExpiryAction::Stop {
+                continue;
+            }
+            let res = if is_provider {
+                self.stop_provider(&id, &host_id, "claims expired")
+                    .await
+                    .map(|_| ())
+            } else {
+                self.remove_component(&id, &host_id).await
+            };
+            if let Err(err) = res {
+                error!(entity_id = %id, ?err, "failed to stop entity with expired claims");
+            }
+        }
+    }
+
     #[instrument(level = "debug", skip_all)]
     async fn store_claims(&self, claims: Claims) -> anyhow::Result<()> {
         match &claims {
@@ -3321,6 +4414,7 @@ impl Host {
         // If the component is already running, update the links
         if let Some(component) = self.components.write().await.get(id) {
             *component.handler.instance_links.write().await = component_import_links(&spec.links);
+            *component.handler.retry_policies.write().await = component_retry_policies(&spec.links);
             // NOTE(brooksmtownsend): We can consider updating the component if the image URL changes
         };
 
@@ -3404,6 +4498,22 @@ impl Host {
         Ok(())
     }
 
+    #[instrument(level = "debug", skip_all)]
+    async fn process_target_group_put(
+        &self,
+        name: impl AsRef<str>,
+        value: impl AsRef<[u8]>,
+    ) -> anyhow::Result<()> {
+        let name = name.as_ref();
+        debug!(name, "process target group put");
+
+        let group: TargetGroup =
+            serde_json::from_slice(value.as_ref()).context("failed to decode target group")?;
+        ensure!(group.name == name, "target group name mismatch");
+        self.target_groups.put(group).await;
+        Ok(())
+    }
+
     #[instrument(level = "trace", skip_all)]
     async fn process_entry(
         &self,
@@ -3437,6 +4547,13 @@ impl Host {
             (Operation::Delete, Some(("CLAIMS", pubkey))) => {
                 self.process_claims_delete(pubkey, value).await
             }
+            (Operation::Put, Some(("TARGETGROUP", name))) => {
+                self.process_target_group_put(name, value).await
+            }
+            (Operation::Delete | Operation::Purge, Some(("TARGETGROUP", name))) => {
+                self.target_groups.remove(name).await;
+                Ok(())
+            }
             (operation, Some(("REFMAP", id))) => {
                 // TODO: process REFMAP entries
                 debug!(?operation, id, "ignoring REFMAP entry");
@@ -3452,6 +4569,166 @@ impl Host {
         }
     }
 
+    /// Re-fetches the secrets of running components and providers, atomically replacing the cached
+    /// secrets of those whose secrets changed. Updated secrets are delivered to providers encrypted
+    /// for their xkey and a `secrets_rotated` event is published for every updated entity. Links
+    /// referencing secrets are delivered to providers again, which apply them if they changed.
+    ///
+    /// Failures to deliver secrets to one entity are logged and do not stop the rotation of others.
+    #[instrument(level = "debug", skip_all)]
+    async fn rotate_secrets(&self) {
+        let host_id = self.host_key.public_key();
+
+        let components: Vec<_> = self.components.read().await.values().cloned().collect();
+        for component in components {
+            let refs = component.handler.secret_refs.read().await.clone();
+            let Some(changed) = self
+                .refetch_secrets(&component.id, &refs, &component.handler.secrets)
+                .await
+            else {
+                continue;
+            };
+            if let Err(err) = self
+                .publish_event(event::secrets_rotated(&host_id, &component.id, changed))
+                .await
+            {
+                error!(
+                    ?err,
+                    component_id = %component.id,
+                    "failed to publish secrets_rotated event"
+                );
+            }
+        }
+
+        let providers = self.providers.read().await;
+        for (provider_id, provider) in providers.iter() {
+            let Some(changed) = self
+                .refetch_secrets(provider_id, &provider.secret_refs, &provider.secrets)
+                .await
+            else {
+                continue;
+            };
+            if let Err(err) = self.put_provider_secrets(provider_id, provider).await {
+                error!(
+                    ?err,
+                    provider_id, "failed to deliver rotated secrets to provider"
+                );
+                continue;
+            }
+            if let Err(err) = self
+                .publish_event(event::secrets_rotated(&host_id, provider_id, changed))
+                .await
+            {
+                error!(?err, provider_id, "failed to publish secrets_rotated event");
+            }
+        }
+
+        let links: Vec<_> = self
+            .links
+            .read()
+            .await
+            .values()
+            .flatten()
+            .cloned()
+            .collect();
+        for link in links {
+            let references_secrets =
+                |config: &[String]| config.iter().any(|name| name.starts_with(SECRET_PREFIX));
+            for (provider_id, config) in [
+                (&link.source_id, &link.source_config),
+                (&link.target, &link.target_config),
+            ] {
+                let Some(provider) = providers.get(provider_id) else {
+                    continue;
+                };
+                if !references_secrets(config) {
+                    continue;
+                }
+                if let Err(err) = self.put_provider_link(provider, &link).await {
+                    error!(
+                        ?err,
+                        provider_id, "failed to deliver link with rotated secrets to provider"
+                    );
+                }
+            }
+        }
+    }
+
+    /// Publishes the current secrets of a provider running on this host, encrypted for its xkey
+    async fn put_provider_secrets(
+        &self,
+        provider_id: &str,
+        provider: &Provider,
+    ) -> anyhow::Result<()> {
+        let update: HashMap<String, wasmcloud_core::secrets::SecretValue> = {
+            use secrecy::ExposeSecret;
+            provider
+                .secrets
+                .read()
+                .await
+                .iter()
+                .map(|(k, v)| match v.expose_secret() {
+                    SecretValue::String(s) => (
+                        k.clone(),
+                        wasmcloud_core::secrets::SecretValue::String(s.to_owned()),
+                    ),
+                    SecretValue::Bytes(b) => (
+                        k.clone(),
+                        wasmcloud_core::secrets::SecretValue::Bytes(b.to_owned()),
+                    ),
+                })
+                .collect()
+        };
+        let update = serde_json::to_vec(&update)
+            .map(|update| self.secrets_xkey.seal(&update, &provider.xkey))
+            .context("failed to serialize and encrypt provider secrets")??;
+        self.rpc_nats
+            .publish(
+                provider_secrets_update_subject(&self.host_config.lattice, provider_id),
+                update.into(),
+            )
+            .await
+            .context("failed to publish provider secrets update")
+    }
+
+    /// Re-fetches the secrets referenced by `refs`, replacing `secrets` if any of them changed.
+    /// Returns the names of the changed secrets, or `None` if nothing changed. Failures to fetch
+    /// are logged and keep the current secrets, so that running entities are not disrupted.
+    async fn refetch_secrets(
+        &self,
+        entity_id: &str,
+        refs: &SecretRefs,
+        secrets: &RwLock<HashMap<String, Secret<SecretValue>>>,
+    ) -> Option<Vec<String>> {
+        if refs.is_empty() {
+            return None;
+        }
+        let fetched = match self
+            .secrets_manager
+            .fetch_secrets(
+                refs.names.clone(),
+                refs.entity_jwt.as_ref(),
+                &self.host_token.jwt,
+                refs.application.as_ref(),
+            )
+            .await
+        {
+            Ok(fetched) => fetched,
+            Err(err) => {
+                warn!(?err, entity_id, "failed to re-fetch secrets");
+                return None;
+            }
+        };
+        let mut secrets = secrets.write().await;
+        let changed = changed_secrets(&secrets, &fetched);
+        if changed.is_empty() {
+            return None;
+        }
+        *secrets = fetched;
+        info!(entity_id, ?changed, "rotated secrets");
+        Some(changed)
+    }
+
     async fn fetch_config_and_secrets(
         &self,
         config_names: &[String],
@@ -3521,6 +4798,32 @@ impl Host {
         Ok(())
     }
 
+    /// Validates the merged named config `config_names` of a provider or link against the config
+    /// `schema` of a provider. Secret references are not validated, since their values are not available.
+    async fn validate_config_schema(
+        &self,
+        schema: &serde_json::Value,
+        config_names: &[String],
+    ) -> anyhow::Result<()> {
+        let mut config = HashMap::new();
+        for config_name in config_names
+            .iter()
+            .filter(|name| !name.starts_with(SECRET_PREFIX))
+        {
+            if let Some(data) = self
+                .config_data
+                .get(config_name)
+                .await
+                .context("unable to get config data")?
+            {
+                let data: HashMap<String, String> = serde_json::from_slice(&data)
+                    .context("config data should be a map of string -> string")?;
+                config.extend(data);
+            }
+        }
+        provider_archive::validate_config(schema, &config).map_err(|e| anyhow!(e))
+    }
+
     /// Transform a [`wasmcloud_control_interface::InterfaceLinkDefinition`] into a [`wasmcloud_core::InterfaceLinkDefinition`]
     /// by fetching the source and target configurations and secrets, and encrypting the secrets.
     async fn resolve_link_config(
@@ -3640,6 +4943,45 @@ fn component_import_links(
     m
 }
 
+/// Given a list of links, returns the retry policies of those which have one
+///
+/// # Returns
+/// - A `HashMap` in the form of `link_name` -> `instance` -> retry policy
+fn component_retry_policies(
+    links: &[InterfaceLinkDefinition],
+) -> HashMap<Box<str>, HashMap<Box<str>, RetryPolicy>> {
+    let mut m: HashMap<Box<str>, HashMap<Box<str>, RetryPolicy>> = HashMap::new();
+    for link in links {
+        let Some(retry_policy) = &link.retry_policy else {
+            continue;
+        };
+        let instances = m.entry(link.name.clone().into_boxed_str()).or_default();
+        for interface in &link.interfaces {
+            instances.insert(
+                format!("{}:{}/{interface}", link.wit_namespace, link.wit_package).into_boxed_str(),
+                retry_policy.clone(),
+            );
+        }
+    }
+    m
+}
+
+/// Returns the events to publish once `link` was put with `result`. Incompatible links stored as
+/// unhealthy are reported as set, followed by a failure carrying the reason they are incompatible.
+fn link_put_events(link: &InterfaceLinkDefinition, result: &anyhow::Result<()>) -> Vec<HostEvent> {
+    match (result, &link.status) {
+        (Err(err), _) => vec![event::linkdef_set_failed(link, err)],
+        (Ok(()), Some(LinkStatus::Unhealthy { reason })) => vec![
+            event::linkdef_set(link),
+            event::linkdef_set_failed(
+                link,
+                &anyhow!("link is incompatible with its source or target: {reason}"),
+            ),
+        ],
+        (Ok(()), _) => vec![event::linkdef_set(link)],
+    }
+}
+
 /// Helper function to serialize `CtlResponse`<T> into a Vec<u8> if the response is Some
 fn serialize_ctl_response<T: Serialize>(
     ctl_response: Option<CtlResponse<T>>,
@@ -3668,6 +5010,10 @@ struct StoredComponentClaims {
     #[serde(deserialize_with = "deserialize_messy_vec")]
     tags: Vec<String>,
     version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    imports: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    exports: Option<Vec<String>>,
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -3701,6 +5047,8 @@ impl TryFrom<Claims> for StoredClaims {
                     rev,
                     ver,
                     call_alias,
+                    imports,
+                    exports,
                     ..
                 } = metadata.context("no metadata found on component claims")?;
                 Ok(StoredClaims::Component(StoredComponentClaims {
@@ -3711,6 +5059,8 @@ impl TryFrom<Claims> for StoredClaims {
                     subject,
                     tags: tags.unwrap_or_default(),
                     version: ver.unwrap_or_default(),
+                    imports,
+                    exports,
                 }))
             }
             Claims::Provider(jwt::Claims {
@@ -3756,6 +5106,8 @@ impl TryFrom<&Claims> for StoredClaims {
                     rev,
                     ver,
                     call_alias,
+                    imports,
+                    exports,
                     ..
                 } = metadata
                     .as_ref()
@@ -3768,6 +5120,8 @@ impl TryFrom<&Claims> for StoredClaims {
                     subject: subject.clone(),
                     tags: tags.clone().unwrap_or_default(),
                     version: ver.clone().unwrap_or_default(),
+                    imports: imports.clone(),
+                    exports: exports.clone(),
                 }))
             }
             Claims::Provider(jwt::Claims {
@@ -3813,6 +5167,14 @@ impl From<StoredClaims> for HashMap<String, String> {
                 ("subject".to_string(), claims.subject),
                 ("tags".to_string(), claims.tags.join(",")),
                 ("version".to_string(), claims.version),
+                (
+                    "imports".to_string(),
+                    claims.imports.unwrap_or_default().join(","),
+                ),
+                (
+                    "exports".to_string(),
+                    claims.exports.unwrap_or_default().join(","),
+                ),
             ]),
             StoredClaims::Provider(claims) => HashMap::from([
                 ("iss".to_string(), claims.issuer.clone()), // TODO: remove in #1093
@@ -3881,6 +5243,14 @@ impl<'de> Deserialize<'de> for MessyVec {
     }
 }
 
+/// The current time in seconds since the epoch, as used in JWTs
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.as_secs())
+        .unwrap_or_default()
+}
+
 fn human_friendly_uptime(uptime: Duration) -> String {
     // strip sub-seconds, then convert to human-friendly format
     humantime::format_duration(
@@ -3919,6 +5289,8 @@ mod test {
                 name: "default".to_string(),
                 source_config: vec![],
                 target_config: vec![],
+                status: None,
+                retry_policy: None,
             },
             InterfaceLinkDefinition {
                 source_id: "source_component".to_string(),
@@ -3929,6 +5301,8 @@ mod test {
                 name: "secret".to_string(),
                 source_config: vec![],
                 target_config: vec!["my-secret".to_string()],
+                status: None,
+                retry_policy: None,
             },
             InterfaceLinkDefinition {
                 source_id: "source_component".to_string(),
@@ -3939,6 +5313,8 @@ mod test {
                 name: "secret".to_string(),
                 source_config: vec![],
                 target_config: vec!["my-secret".to_string()],
+                status: None,
+                retry_policy: None,
             },
             InterfaceLinkDefinition {
                 source_id: "http".to_string(),
@@ -3949,6 +5325,8 @@ mod test {
                 name: "default".to_string(),
                 source_config: vec!["some-port".to_string()],
                 target_config: vec![],
+                status: None,
+                retry_policy: None,
             },
             InterfaceLinkDefinition {
                 source_id: "source_component".to_string(),
@@ -3959,6 +5337,8 @@ mod test {
                 name: "default".to_string(),
                 source_config: vec![],
                 target_config: vec!["some-port".to_string()],
+                status: None,
+                retry_policy: None,
             },
             InterfaceLinkDefinition {
                 source_id: "source_component".to_string(),
@@ -3969,6 +5349,8 @@ mod test {
                 name: "default".to_string(),
                 source_config: vec![],
                 target_config: vec![],
+                status: None,
+                retry_policy: None,
             },
             InterfaceLinkDefinition {
                 source_id: "other_component".to_string(),
@@ -3979,6 +5361,8 @@ mod test {
                 name: "link2".to_string(),
                 source_config: vec![],
                 target_config: vec![],
+                status: None,
+                retry_policy: None,
             },
         ];
 
@@ -4041,4 +5425,186 @@ mod test {
 
         assert_eq!(links_map, expected_result);
     }
+
+    // Ensure that only links with a retry policy are included in the retry policies of a component
+    #[test]
+    fn can_compute_component_retry_policies() {
+        use wasmcloud_control_interface::{InterfaceLinkDefinition, RetryPolicy};
+
+        let links = vec![
+            InterfaceLinkDefinition {
+                source_id: "source_component".to_string(),
+                target: "kv-redis".to_string(),
+                wit_namespace: "wasi".to_string(),
+                wit_package: "keyvalue".to_string(),
+                interfaces: vec!["store".to_string()],
+                name: "default".to_string(),
+                retry_policy: Some(RetryPolicy::default()),
+                ..Default::default()
+            },
+            InterfaceLinkDefinition {
+                source_id: "source_component".to_string(),
+                target: "httpclient".to_string(),
+                wit_namespace: "wasi".to_string(),
+                wit_package: "http".to_string(),
+                interfaces: vec!["outgoing-handler".to_string()],
+                name: "default".to_string(),
+                ..Default::default()
+            },
+        ];
+
+        let policies = super::component_retry_policies(&links);
+        assert_eq!(policies.len(), 1);
+        assert_eq!(policies["default"].len(), 1);
+        assert_eq!(
+            policies["default"]["wasi:keyvalue/store"],
+            RetryPolicy::default()
+        );
+    }
+
+    #[test]
+    fn incompatible_links_publish_failures() {
+        use anyhow::anyhow;
+        use wasmcloud_control_interface::{HostEvent, InterfaceLinkDefinition, LinkStatus};
+
+        let link = InterfaceLinkDefinition {
+            source_id: "source_component".to_string(),
+            target: "kv-redis".to_string(),
+            wit_namespace: "wasi".to_string(),
+            wit_package: "keyvalue".to_string(),
+            interfaces: vec!["store".to_string()],
+            name: "default".to_string(),
+            ..Default::default()
+        };
+        assert!(matches!(
+            super::link_put_events(&link, &Ok(())).as_slice(),
+            [HostEvent::LinkdefSet(_)]
+        ));
+        assert!(matches!(
+            super::link_put_events(&link, &Err(anyhow!("invalid source config"))).as_slice(),
+            [HostEvent::LinkdefSetFailed(failed)] if failed.error == "invalid source config"
+        ));
+
+        let link = InterfaceLinkDefinition {
+            status: Some(LinkStatus::Unhealthy {
+                reason: "target does not export `wasi:keyvalue/store`".to_string(),
+            }),
+            ..link
+        };
+        match super::link_put_events(&link, &Ok(())).as_slice() {
+            [HostEvent::LinkdefSet(set), HostEvent::LinkdefSetFailed(failed)] => {
+                assert_eq!(set.link, link);
+                assert_eq!(failed.link, link);
+                assert_eq!(
+                    failed.error,
+                    "link is incompatible with its source or target: target does not export `wasi:keyvalue/store`"
+                );
+            }
+            events => panic!("unexpected events: {events:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn concurrent_invocations_keep_their_deadline() {
+        use std::collections::HashMap;
+        use std::sync::Arc;
+        use std::time::{Duration, SystemTime};
+
+        use bytes::Bytes;
+        use cloudevents::{EventBuilder as _, EventBuilderV10};
+        use tokio::sync::RwLock;
+        use tokio::time::Instant;
+        use wasmcloud_runtime::component::InvocationContext as _;
+        use wasmcloud_tracing::global;
+        use wrpc_transport::Invoke as _;
+
+        use super::breaker::{CircuitBreakers, EventPublisher};
+        use super::config::ConfigBundle;
+        use super::{Handler, InvocationContext};
+        use crate::metrics::HostMetrics;
+
+        // Invocations are never answered, it does not matter that nothing listens
+        let nats = async_nats::ConnectOptions::new()
+            .retry_on_initial_connect()
+            .connect("127.0.0.1:1")
+            .await
+            .expect("failed to build NATS client");
+        let metrics = Arc::new(HostMetrics::new(
+            &global::meter("test"),
+            "host".to_string(),
+            "default".to_string(),
+        ));
+        let handler = Handler {
+            nats: Arc::new(nats.clone()),
+            config_data: Arc::new(RwLock::new(ConfigBundle::new(Vec::new()).await)),
+            secrets: Arc::default(),
+            secret_refs: Arc::default(),
+            lattice: Arc::from("default"),
+            component_id: Arc::from("component"),
+            targets: Arc::default(),
+            trace_ctx: Arc::default(),
+            invocation_deadline: None,
+            instance_links: Arc::new(RwLock::new(HashMap::from([(
+                "default".into(),
+                HashMap::from([("wasmcloud:example/greet".into(), "target".into())]),
+            )]))),
+            retry_policies: Arc::default(),
+            invocation_timeout: Duration::from_millis(100),
+            circuit_breakers: Arc::new(CircuitBreakers::new(
+                None,
+                "host",
+                EventPublisher {
+                    event_builder: EventBuilderV10::new(),
+                    ctl_nats: nats,
+                    event_stream: None,
+                    lattice: Arc::from("default"),
+                },
+                Arc::clone(&metrics),
+            )),
+            metrics,
+            target_groups: Arc::default(),
+            log_forwarder: None,
+        };
+
+        let now = SystemTime::now();
+        let expired = InvocationContext {
+            start_at: Instant::now(),
+            attributes: Vec::new(),
+            deadline: Some(now - Duration::from_secs(1)),
+        };
+        let pending = InvocationContext {
+            start_at: Instant::now(),
+            attributes: Vec::new(),
+            deadline: Some(now + Duration::from_secs(60)),
+        };
+        // Both instances are created before either of them invokes anything
+        let expired = expired.handler(&handler);
+        let pending = pending.handler(&handler);
+        assert_eq!(
+            expired.invocation_deadline,
+            Some(now - Duration::from_secs(1))
+        );
+        assert_eq!(
+            pending.invocation_deadline,
+            Some(now + Duration::from_secs(60))
+        );
+        assert_eq!(handler.clone().invocation_deadline, None);
+
+        let invoke = |handler: Handler| async move {
+            handler
+                .invoke(
+                    None,
+                    "wasmcloud:example/greet",
+                    "greet",
+                    Bytes::new(),
+                    &[] as &[&[Option<usize>]],
+                )
+                .await
+                .map(|_| ())
+                .map_err(|err| format!("{err:#}"))
+        };
+        let (expired, pending) = tokio::join!(invoke(expired), invoke(pending));
+        assert!(expired.is_err_and(|err| err.contains("invocation deadline exceeded")));
+        assert!(!pending.is_err_and(|err| err.contains("invocation deadline exceeded")));
+    }
 }
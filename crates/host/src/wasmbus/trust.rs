@@ -0,0 +1,292 @@
+//! Verification of the issuers of components and providers against trusted operators and accounts
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use anyhow::{ensure, Context as _};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use wascap::jwt::{
+    self, validate_token, Account, Claims, CoSignature, TokenValidation, WascapEntity,
+};
+
+use super::host_config::{RequiredSignatures, TrustedIssuers};
+
+/// Ensure that a token, whose validation result is `validation`, may be used
+fn ensure_usable(validation: &TokenValidation) -> anyhow::Result<()> {
+    ensure!(validation.signature_valid, "signature is not valid");
+    ensure!(
+        !validation.expired,
+        "token expired {}",
+        validation.expires_human
+    );
+    ensure!(
+        !validation.cannot_use_yet,
+        "token cannot be used until {}",
+        validation.not_before_human
+    );
+    Ok(())
+}
+
+/// The accounts trusted to issue components and providers
+#[derive(Debug)]
+pub struct TrustChain {
+    /// Public keys of trusted accounts, including the signing keys of accounts, with the JWT of
+    /// the account they are trusted by, unless the account is trusted directly
+    accounts: HashMap<String, Option<Arc<str>>>,
+}
+
+impl TrustChain {
+    /// Build the chain of trust from `config`, validating every account JWT and that it was issued
+    /// by a trusted operator. Fails if any account JWT is invalid or untrusted.
+    pub fn new(config: &TrustedIssuers) -> anyhow::Result<Self> {
+        let mut accounts: HashMap<String, Option<Arc<str>>> = config
+            .accounts
+            .iter()
+            .map(|account| (account.clone(), None))
+            .collect();
+        for token in &config.account_jwts {
+            let validation = validate_token::<Account>(token).context("invalid account JWT")?;
+            let claims = Claims::<Account>::decode(token).context("invalid account JWT")?;
+            ensure_usable(&validation)
+                .with_context(|| format!("invalid JWT of account `{}`", claims.subject))?;
+            ensure!(
+                config.operators.contains(&claims.issuer),
+                "account `{}` is issued by untrusted operator `{}`",
+                claims.subject,
+                claims.issuer
+            );
+            let signers = claims
+                .metadata
+                .and_then(|account| account.valid_signers)
+                .unwrap_or_default();
+            let token: Arc<str> = Arc::from(token.as_str());
+            accounts.insert(claims.subject, Some(Arc::clone(&token)));
+            accounts.extend(
+                signers
+                    .into_iter()
+                    .map(|signer| (signer, Some(Arc::clone(&token)))),
+            );
+        }
+        Ok(Self { accounts })
+    }
+
+    /// Verify that the entity with the embedded `token` is signed and issued by a trusted account.
+    /// The JWT of the account is validated again, since it may have expired since the chain was
+    /// built. `kind` names the entity in errors, e.g. `component`.
+    pub fn verify<T>(&self, kind: &str, token: Option<&jwt::Token<T>>) -> anyhow::Result<()>
+    where
+        T: Serialize + DeserializeOwned + WascapEntity,
+    {
+        let token = token.with_context(|| {
+            format!("{kind} is not signed, only {kind}s issued by trusted accounts may be started")
+        })?;
+        let validation =
+            validate_token::<T>(&token.jwt).with_context(|| format!("invalid {kind} JWT"))?;
+        ensure_usable(&validation).with_context(|| format!("invalid {kind} JWT"))?;
+        let account = self.accounts.get(&token.claims.issuer).with_context(|| {
+            format!(
+                "{kind} `{}` is issued by untrusted account `{}`",
+                token.claims.subject, token.claims.issuer
+            )
+        })?;
+        if let Some(account) = account {
+            validate_token::<Account>(account)
+                .map_err(anyhow::Error::from)
+                .and_then(|validation| ensure_usable(&validation))
+                .with_context(|| {
+                    format!(
+                        "{kind} `{}` is issued by account `{}` with an invalid JWT",
+                        token.claims.subject, token.claims.issuer
+                    )
+                })?;
+        }
+        Ok(())
+    }
+}
+
+/// Verify that the entity with the embedded `token` and detached `cosignatures` is signed by at
+/// least `threshold` distinct trusted signers of `config`. Only valid, usable signatures issued for
+/// the subject of `token` are counted. `kind` names the entity in errors, e.g. `component`.
+pub fn verify_signatures<T>(
+    config: &RequiredSignatures,
+    kind: &str,
+    token: Option<&jwt::Token<T>>,
+    cosignatures: &[jwt::Token<CoSignature>],
+) -> anyhow::Result<()>
+where
+    T: Serialize + DeserializeOwned + WascapEntity,
+{
+    let token = token.with_context(|| {
+        format!("{kind} is not signed, only {kind}s signed by trusted signers may be started")
+    })?;
+    let is_valid = |validation: wascap::Result<TokenValidation>| {
+        validation.is_ok_and(|validation| ensure_usable(&validation).is_ok())
+    };
+    let mut signers = HashSet::new();
+    if is_valid(validate_token::<T>(&token.jwt)) {
+        signers.insert(&token.claims.issuer);
+    }
+    for cosignature in cosignatures {
+        if cosignature.claims.subject == token.claims.subject
+            && is_valid(validate_token::<CoSignature>(&cosignature.jwt))
+        {
+            signers.insert(&cosignature.claims.issuer);
+        }
+    }
+    let trusted = signers
+        .into_iter()
+        .filter(|signer| config.signers.contains(*signer))
+        .count();
+    ensure!(
+        trusted >= config.threshold,
+        "{kind} `{}` is signed by {trusted} of the {} required trusted signers",
+        token.claims.subject,
+        config.threshold
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use wascap::jwt::{Claims, ClaimsBuilder, CoSignature, Component, Token};
+    use wascap::prelude::KeyPair;
+
+    use super::{verify_signatures, RequiredSignatures, TrustChain, TrustedIssuers};
+
+    fn component(account: &KeyPair) -> Token<Component> {
+        let claims: Claims<Component> = ClaimsBuilder::new()
+            .issuer(&account.public_key())
+            .subject(&KeyPair::new_module().public_key())
+            .with_metadata(Component::default())
+            .build();
+        Token {
+            jwt: claims.encode(account).expect("failed to encode claims"),
+            claims,
+        }
+    }
+
+    #[test]
+    fn verifies_component_account_operator_chain() -> anyhow::Result<()> {
+        let operator = KeyPair::new_operator();
+        let account = KeyPair::new_account();
+        let signer = KeyPair::new_account();
+        let account_jwt = Claims::<wascap::jwt::Account>::new(
+            "test".into(),
+            operator.public_key(),
+            account.public_key(),
+            vec![signer.public_key()],
+        )
+        .encode(&operator)?;
+
+        let chain = TrustChain::new(&TrustedIssuers {
+            operators: vec![operator.public_key()],
+            account_jwts: vec![account_jwt.clone()],
+            ..Default::default()
+        })?;
+        chain.verify("component", Some(&component(&account)))?;
+        chain.verify("component", Some(&component(&signer)))?;
+        assert!(chain
+            .verify("component", Some(&component(&KeyPair::new_account())))
+            .is_err());
+        assert!(chain.verify::<Component>("component", None).is_err());
+
+        // Accounts must be issued by a trusted operator
+        assert!(TrustChain::new(&TrustedIssuers {
+            operators: vec![KeyPair::new_operator().public_key()],
+            account_jwts: vec![account_jwt],
+            ..Default::default()
+        })
+        .is_err());
+
+        // Accounts can also be trusted directly
+        let other = KeyPair::new_account();
+        let chain = TrustChain::new(&TrustedIssuers {
+            accounts: vec![other.public_key()],
+            ..Default::default()
+        })?;
+        chain.verify("component", Some(&component(&other)))?;
+        assert!(chain
+            .verify("component", Some(&component(&account)))
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn rechecks_account_expiry() -> anyhow::Result<()> {
+        let operator = KeyPair::new_operator();
+        let account = KeyPair::new_account();
+        let mut claims = Claims::<wascap::jwt::Account>::new(
+            "test".into(),
+            operator.public_key(),
+            account.public_key(),
+            vec![],
+        );
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_secs();
+        claims.expires = Some(now + 2);
+        let chain = TrustChain::new(&TrustedIssuers {
+            operators: vec![operator.public_key()],
+            account_jwts: vec![claims.encode(&operator)?],
+            ..Default::default()
+        })?;
+        chain.verify("component", Some(&component(&account)))?;
+
+        // Components of accounts that expired after the chain was built are not trusted
+        std::thread::sleep(std::time::Duration::from_secs(3));
+        assert!(chain
+            .verify("component", Some(&component(&account)))
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn requires_n_of_m_signers() -> anyhow::Result<()> {
+        let build = KeyPair::new_account();
+        let security = KeyPair::new_account();
+        let release = KeyPair::new_account();
+        let config = RequiredSignatures {
+            signers: vec![
+                build.public_key(),
+                security.public_key(),
+                release.public_key(),
+            ],
+            threshold: 2,
+        };
+        let token = component(&build);
+        let cosign = |signer: &KeyPair, subject: &str| -> anyhow::Result<Token<CoSignature>> {
+            let claims = Claims::<CoSignature>::new(
+                signer.public_key(),
+                subject.to_string(),
+                "HASH".to_string(),
+            );
+            Ok(Token {
+                jwt: claims.encode(signer)?,
+                claims,
+            })
+        };
+
+        assert!(verify_signatures(&config, "component", Some(&token), &[]).is_err());
+        verify_signatures(
+            &config,
+            "component",
+            Some(&token),
+            &[cosign(&security, &token.claims.subject)?],
+        )?;
+        // Untrusted co-signers, duplicate signers and co-signatures of other subjects don't count
+        assert!(verify_signatures(
+            &config,
+            "component",
+            Some(&token),
+            &[
+                cosign(&KeyPair::new_account(), &token.claims.subject)?,
+                cosign(&build, &token.claims.subject)?,
+                cosign(&release, &KeyPair::new_module().public_key())?,
+            ],
+        )
+        .is_err());
+        assert!(verify_signatures::<Component>(&config, "component", None, &[]).is_err());
+        Ok(())
+    }
+}
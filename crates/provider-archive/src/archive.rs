@@ -1,6 +1,9 @@
 use crate::Result;
 use async_compression::{
-    tokio::{bufread::GzipDecoder, write::GzipEncoder},
+    tokio::{
+        bufread::{GzipDecoder, ZstdDecoder},
+        write::{GzipEncoder, ZstdEncoder},
+    },
     Level,
 };
 use data_encoding::HEXUPPER;
@@ -17,13 +20,67 @@ use tokio::{
 use tokio_stream::StreamExt;
 use tokio_tar::Archive;
 use wascap::{
-    jwt::{CapabilityProvider, Claims, Token},
+    jwt::{CapabilityProvider, Claims, CoSignature, Token},
     prelude::KeyPair,
 };
 
 const CLAIMS_JWT_FILE: &str = "claims.jwt";
+/// Directory of the archive containing detached co-signatures, one `<issuer>.jwt` file per signer
+const COSIGNATURES_DIR: &str = "cosignatures";
+/// The encoded WIT package describing the interfaces of the provider
+const WIT_FILE: &str = "wit.wasm";
 
 const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// The compression of a Provider Archive (PAR) file
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Compression {
+    /// An uncompressed tar file, usually with a `.par` extension
+    #[default]
+    None,
+    /// A gzip compressed tar file, usually with a `.par.gz` extension
+    Gzip,
+    /// A zstd compressed tar file, usually with a `.par.zst` extension. Zstd decompresses
+    /// considerably faster than gzip, which speeds up loading large multi-target archives.
+    Zstd,
+}
+
+impl Compression {
+    /// Detects the compression of the Provider Archive (PAR) file starting with `input`
+    #[must_use]
+    pub fn detect(input: &[u8]) -> Self {
+        if input.starts_with(&GZIP_MAGIC) {
+            Self::Gzip
+        } else if input.starts_with(&ZSTD_MAGIC) {
+            Self::Zstd
+        } else {
+            Self::None
+        }
+    }
+
+    /// The file extension appended to archives with this compression
+    #[must_use]
+    pub fn extension(self) -> Option<&'static str> {
+        match self {
+            Self::None => None,
+            Self::Gzip => Some("gz"),
+            Self::Zstd => Some("zst"),
+        }
+    }
+}
+
+impl From<bool> for Compression {
+    /// Archives are compressed with gzip if `compress` is set, for compatibility with consumers
+    /// that don't support zstd
+    fn from(compress: bool) -> Self {
+        if compress {
+            Self::Gzip
+        } else {
+            Self::None
+        }
+    }
+}
 
 /// A provider archive is a specialized ZIP file that contains a set of embedded and signed claims
 /// (a .JWT file) as well as a list of binary files, one plugin library for each supported
@@ -36,6 +93,11 @@ pub struct ProviderArchive {
     ver: Option<String>,
     token: Option<Token<CapabilityProvider>>,
     json_schema: Option<serde_json::Value>,
+    tags: Option<Vec<String>>,
+    expires: Option<u64>,
+    not_before: Option<u64>,
+    cosignatures: Vec<Token<CoSignature>>,
+    wit: Option<Vec<u8>>,
 }
 
 impl ProviderArchive {
@@ -50,6 +112,11 @@ impl ProviderArchive {
             ver,
             token: None,
             json_schema: None,
+            tags: None,
+            expires: None,
+            not_before: None,
+            cosignatures: Vec::new(),
+            wit: None,
         }
     }
 
@@ -69,6 +136,54 @@ impl ProviderArchive {
         Ok(())
     }
 
+    /// Sets the tags of this provider. Like the schema, these are injected into the claims written
+    /// to a provider's PAR file
+    pub fn set_tags(&mut self, tags: Vec<String>) {
+        self.tags = Some(tags);
+    }
+
+    /// Obtains the tags if they were either set explicitly on the structure or loaded from claims
+    /// in the PAR
+    #[must_use]
+    pub fn tags(&self) -> Option<&[String]> {
+        self.tags.as_deref()
+    }
+
+    /// Sets the times (in seconds since the epoch) at which the claims written to a provider's PAR
+    /// file expire and become valid. Archives loaded from a PAR keep those of their claims
+    pub fn set_validity(&mut self, expires: Option<u64>, not_before: Option<u64>) {
+        self.expires = expires;
+        self.not_before = not_before;
+    }
+
+    /// Obtains the expiry time, in seconds since the epoch, if it was either set explicitly on the
+    /// structure or loaded from claims in the PAR
+    #[must_use]
+    pub fn expires(&self) -> Option<u64> {
+        self.expires
+    }
+
+    /// Obtains the time, in seconds since the epoch, before which the claims aren't valid, if it
+    /// was either set explicitly on the structure or loaded from claims in the PAR
+    #[must_use]
+    pub fn not_before(&self) -> Option<u64> {
+        self.not_before
+    }
+
+    /// Sets the WIT package describing the interfaces of this provider, encoded as a WebAssembly
+    /// binary (e.g. by `wit_component::encode`). Its hash is injected into the claims written to a
+    /// provider's PAR file
+    pub fn set_wit(&mut self, wit: Vec<u8>) {
+        self.wit = Some(wit);
+    }
+
+    /// Obtains the encoded WIT package if it was either set explicitly on the structure or loaded
+    /// from the PAR. Loaded packages were verified against the hash in the embedded claims
+    #[must_use]
+    pub fn wit(&self) -> Option<&[u8]> {
+        self.wit.as_deref()
+    }
+
     /// Gets the list of architecture/OS targets within the archive
     #[must_use]
     pub fn targets(&self) -> Vec<String> {
@@ -95,6 +210,39 @@ impl ProviderArchive {
         self.token.clone()
     }
 
+    /// Returns the detached co-signatures of this archive. Co-signatures are issued over the target
+    /// hashes of the embedded claims, and were verified against them when the archive was loaded.
+    #[must_use]
+    pub fn cosignatures(&self) -> &[Token<CoSignature>] {
+        &self.cosignatures
+    }
+
+    /// Co-signs the embedded claims of this archive with `issuer`, replacing an existing
+    /// co-signature by the same issuer. The archive must have been written or loaded, and the
+    /// co-signature is only persisted by [`ProviderArchive::write_signed`].
+    pub fn cosign(&mut self, issuer: &KeyPair, expires: Option<u64>) -> Result<()> {
+        let claims = self
+            .token
+            .as_ref()
+            .map(|token| &token.claims)
+            .ok_or("provider archive has no embedded claims to co-sign")?;
+        let cosignature = Claims::<CoSignature>::with_dates(
+            issuer.public_key(),
+            claims.subject.clone(),
+            cosignature_hash(claims),
+            None,
+            expires,
+        );
+        let jwt = cosignature.encode(issuer)?;
+        self.cosignatures
+            .retain(|existing| existing.claims.issuer != cosignature.issuer);
+        self.cosignatures.push(Token {
+            jwt,
+            claims: cosignature,
+        });
+        Ok(())
+    }
+
     /// Obtains the JSON schema if one was either set explicitly on the structure or loaded from
     /// claims in the PAR
     #[must_use]
@@ -102,6 +250,15 @@ impl ProviderArchive {
         self.json_schema.clone()
     }
 
+    /// Validates named config against the JSON schema of this archive, see [`crate::validate_config`].
+    /// Any config is valid if the archive has no schema.
+    pub fn validate_config(&self, config: &HashMap<String, String>) -> Result<()> {
+        match &self.json_schema {
+            Some(schema) => crate::validate_config(schema, config),
+            None => Ok(()),
+        }
+    }
+
     /// Attempts to read a Provider Archive (PAR) file's bytes to analyze and verify its contents.
     ///
     /// The embedded claims in this archive will be validated, and the file hashes contained in
@@ -195,40 +352,30 @@ impl ProviderArchive {
         target: Option<&str>,
     ) -> Result<ProviderArchive> {
         let mut libraries = HashMap::new();
-
-        let mut magic = [0; 2];
-        if let Err(e) = input.read_exact(&mut magic).await {
-            // If we can't fill the buffer, it isn't a valid par file
-            if matches!(e.kind(), std::io::ErrorKind::UnexpectedEof) {
-                return Err("Not enough bytes to be a valid PAR file".into());
-            }
-            return Err(e.into());
-        }
-
-        // Seek back to beginning
-        input.rewind().await?;
-
-        let mut par = Archive::new(if magic == GZIP_MAGIC {
-            Box::new(GzipDecoder::new(BufReader::new(input)))
-                as Box<dyn AsyncRead + Unpin + Sync + Send>
-        } else {
-            Box::new(input) as Box<dyn AsyncRead + Unpin + Sync + Send>
-        });
+        let mut par = open_archive(input).await?;
 
         let mut token: Option<Token<CapabilityProvider>> = None;
+        let mut cosignatures = Vec::new();
+        let mut wit = None;
 
         let mut entries = par.entries()?;
 
         while let Some(res) = entries.next().await {
             let mut entry = res?;
             let mut bytes = Vec::new();
-            let file_target = PathBuf::from(entry.path()?)
-                .file_stem()
-                .unwrap()
-                .to_str()
-                .unwrap()
-                .to_string();
-            if file_target == "claims" {
+            let path = PathBuf::from(entry.path()?);
+            let file_target = path.file_stem().unwrap().to_str().unwrap().to_string();
+            if path.parent() == Some(Path::new(COSIGNATURES_DIR)) {
+                tokio::io::copy(&mut entry, &mut bytes).await?;
+                let jwt = std::str::from_utf8(&bytes)?;
+                cosignatures.push(Token {
+                    jwt: jwt.to_string(),
+                    claims: Claims::<CoSignature>::decode(jwt)?,
+                });
+            } else if path == Path::new(WIT_FILE) {
+                tokio::io::copy(&mut entry, &mut bytes).await?;
+                wit = Some(bytes);
+            } else if file_target == "claims" {
                 tokio::io::copy(&mut entry, &mut bytes).await?;
                 let jwt = std::str::from_utf8(&bytes)?;
                 let claims = Some(Claims::<CapabilityProvider>::decode(jwt)?);
@@ -258,32 +405,153 @@ impl ProviderArchive {
             );
         }
 
-        if let Some(ref claims_token) = token {
-            let cl = &claims_token.claims;
-            let metadata = cl.metadata.as_ref().unwrap();
-            let name = cl.name();
-            let vendor = metadata.vendor.to_string();
-            let rev = metadata.rev;
-            let ver = metadata.ver.clone();
-            let json_schema = metadata.config_schema.clone();
-
-            validate_hashes(&libraries, cl)?;
-
-            Ok(ProviderArchive {
-                libraries,
-                name,
-                vendor,
-                rev,
-                ver,
-                token,
-                json_schema,
-            })
-        } else {
-            Err("No claims found embedded in provider archive.".into())
+        match token {
+            Some(token) => {
+                validate_hashes(&libraries, &token.claims)?;
+                validate_wit(wit.as_deref(), &token.claims)?;
+                let mut archive = Self::from_token(libraries, token, cosignatures)?;
+                archive.wit = wit;
+                Ok(archive)
+            }
+            None => Err("No claims found embedded in provider archive.".into()),
         }
     }
 
+    /// Attempts to read a Provider Archive (PAR) file to verify its contents, streaming _only_ the
+    /// binary of the specified target to `output` without buffering it in memory.
+    ///
+    /// The hash of the target binary is computed incrementally while it is streamed, and only
+    /// verified against the target hash in the embedded claims once the whole archive was read.
+    /// Until this returns successfully, `output` contains unverified bytes, so it should be a
+    /// temporary location, and its contents must be discarded if an error is returned. Only the
+    /// binary of `target` is verified, the binaries of other targets are skipped unchecked. The
+    /// returned `ProviderArchive` contains the claims and WIT, but no binaries.
+    pub async fn extract_target<R, W>(
+        input: &mut R,
+        target: &str,
+        output: &mut W,
+    ) -> Result<ProviderArchive>
+    where
+        R: AsyncRead + AsyncSeek + Unpin + Send + Sync,
+        W: AsyncWrite + Unpin,
+    {
+        let mut par = open_archive(input).await?;
+
+        let mut token: Option<Token<CapabilityProvider>> = None;
+        let mut cosignatures = Vec::new();
+        let mut wit = None;
+        let mut target_hash = None;
+
+        let mut entries = par.entries()?;
+        while let Some(res) = entries.next().await {
+            let mut entry = res?;
+            let path = PathBuf::from(entry.path()?);
+            let file_target = path.file_stem().unwrap().to_str().unwrap().to_string();
+            if path.parent() == Some(Path::new(COSIGNATURES_DIR)) {
+                let mut bytes = Vec::new();
+                tokio::io::copy(&mut entry, &mut bytes).await?;
+                let jwt = std::str::from_utf8(&bytes)?;
+                cosignatures.push(Token {
+                    jwt: jwt.to_string(),
+                    claims: Claims::<CoSignature>::decode(jwt)?,
+                });
+            } else if path == Path::new(WIT_FILE) {
+                let mut bytes = Vec::new();
+                tokio::io::copy(&mut entry, &mut bytes).await?;
+                wit = Some(bytes);
+            } else if file_target == "claims" {
+                let mut bytes = Vec::new();
+                tokio::io::copy(&mut entry, &mut bytes).await?;
+                let jwt = std::str::from_utf8(&bytes)?;
+                token = Some(Token {
+                    jwt: jwt.to_string(),
+                    claims: Claims::<CapabilityProvider>::decode(jwt)?,
+                });
+            } else if file_target == target {
+                let mut context = Context::new(&SHA256);
+                let mut buffer = vec![0; 64 * 1024];
+                loop {
+                    let count = entry.read(&mut buffer).await?;
+                    if count == 0 {
+                        break;
+                    }
+                    context.update(&buffer[..count]);
+                    output.write_all(&buffer[..count]).await?;
+                }
+                target_hash = Some(HEXUPPER.encode(context.finish().as_ref()));
+            }
+        }
+        output.flush().await?;
+
+        let token = token.ok_or("No claims found embedded in provider archive.")?;
+        let target_hash = target_hash
+            .ok_or_else(|| format!("Target '{target}' not found in provider archive"))?;
+        let expected_hash = token
+            .claims
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.target_hashes.get(target))
+            .ok_or_else(|| format!("No file hash found in claims for '{target}'"))?;
+        if *expected_hash != target_hash {
+            return Err(format!("File hash and verify hash do not match for '{target}'").into());
+        }
+        validate_wit(wit.as_deref(), &token.claims)?;
+        let mut archive = Self::from_token(HashMap::new(), token, cosignatures)?;
+        archive.wit = wit;
+        Ok(archive)
+    }
+
+    /// Attempts to read a Provider Archive (PAR) file, streaming _only_ the binary of the specified
+    /// target to `output`. See [`ProviderArchive::extract_target`].
+    pub async fn extract_target_from_file<W: AsyncWrite + Unpin>(
+        path: impl AsRef<Path>,
+        target: &str,
+        output: &mut W,
+    ) -> Result<ProviderArchive> {
+        let mut file = File::open(&path).await.map_err(|e| {
+            std::io::Error::new(
+                e.kind(),
+                format!(
+                    "failed to extract target [{target}] from PAR from file [{}]: {e}",
+                    path.as_ref().display()
+                ),
+            )
+        })?;
+        Self::extract_target(&mut file, target, output).await
+    }
+
+    /// Builds an archive from the embedded claims `token`, whose target hashes were verified
+    /// against `libraries`, and the detached `cosignatures`
+    fn from_token(
+        libraries: HashMap<String, Vec<u8>>,
+        token: Token<CapabilityProvider>,
+        cosignatures: Vec<Token<CoSignature>>,
+    ) -> Result<ProviderArchive> {
+        let cl = &token.claims;
+        let metadata = cl.metadata.as_ref().ok_or("Claims contain no metadata")?;
+        validate_cosignatures(&cosignatures, cl)?;
+
+        Ok(ProviderArchive {
+            libraries,
+            name: cl.name(),
+            vendor: metadata.vendor.to_string(),
+            rev: metadata.rev,
+            ver: metadata.ver.clone(),
+            json_schema: metadata.config_schema.clone(),
+            tags: metadata.tags.clone(),
+            expires: cl.expires,
+            not_before: cl.not_before,
+            token: Some(token),
+            cosignatures,
+            wit: None,
+        })
+    }
+
     /// Generates a Provider Archive (PAR) file with all of the library files and a signed set of claims in an embedded JWT
+    ///
+    /// Co-signatures of the archive are kept if they were issued for the same subject and targets.
+    /// Compressed archives are compressed with gzip, see [`ProviderArchive::write_compressed`] to
+    /// compress them with zstd.
     pub async fn write(
         &mut self,
         destination: impl AsRef<Path>,
@@ -291,28 +559,20 @@ impl ProviderArchive {
         subject: &KeyPair,
         compress_par: bool,
     ) -> Result<()> {
-        let file = File::create(
-            if compress_par && destination.as_ref().extension().unwrap_or_default() != "gz" {
-                let mut file_name = destination
-                    .as_ref()
-                    .file_name()
-                    .ok_or("Destination is not a file")?
-                    .to_owned();
-                file_name.push(".gz");
-                destination.as_ref().with_file_name(file_name)
-            } else {
-                destination.as_ref().to_owned()
-            },
-        )
-        .await?;
-
-        let mut par = tokio_tar::Builder::new(if compress_par {
-            Box::new(GzipEncoder::with_quality(file, Level::Best))
-                as Box<dyn AsyncWrite + Send + Sync + Unpin>
-        } else {
-            Box::new(file) as Box<dyn AsyncWrite + Send + Sync + Unpin>
-        });
+        self.write_compressed(destination, issuer, subject, compress_par.into())
+            .await
+    }
 
+    /// Generates a Provider Archive (PAR) file with all of the library files and a signed set of
+    /// claims in an embedded JWT, compressed with `compression`. The extension of the compression
+    /// is appended to `destination` if it doesn't have it already.
+    pub async fn write_compressed(
+        &mut self,
+        destination: impl AsRef<Path>,
+        issuer: &KeyPair,
+        subject: &KeyPair,
+        compression: Compression,
+    ) -> Result<()> {
         let mut claims = Claims::<CapabilityProvider>::new(
             self.name.to_string(),
             issuer.public_key(),
@@ -325,20 +585,101 @@ impl ProviderArchive {
         if let Some(schema) = self.json_schema.clone() {
             claims.metadata.as_mut().unwrap().config_schema = Some(schema);
         }
+        claims
+            .metadata
+            .as_mut()
+            .unwrap()
+            .tags
+            .clone_from(&self.tags);
+        claims.metadata.as_mut().unwrap().wit_hash = self.wit.as_deref().map(hash_bytes);
+        claims.expires = self.expires;
+        claims.not_before = self.not_before;
+
+        let hash = cosignature_hash(&claims);
+        self.cosignatures.retain(|cosignature| {
+            cosignature.claims.subject == claims.subject
+                && cosignature
+                    .claims
+                    .metadata
+                    .as_ref()
+                    .is_some_and(|meta| meta.hash == hash)
+        });
 
         let claims_jwt = claims.encode(issuer)?;
         self.token = Some(Token {
-            jwt: claims_jwt.clone(),
+            jwt: claims_jwt,
             claims,
         });
+        self.write_signed(destination, compression).await
+    }
+
+    /// Generates a Provider Archive (PAR) file with all of the library files, the existing embedded
+    /// claims and the co-signatures of this archive, e.g. after co-signing a loaded archive. The
+    /// library files must match the target hashes of the claims.
+    pub async fn write_signed(
+        &self,
+        destination: impl AsRef<Path>,
+        compression: Compression,
+    ) -> Result<()> {
+        let token = self
+            .token
+            .as_ref()
+            .ok_or("provider archive has no embedded claims")?;
+        validate_hashes(&self.libraries, &token.claims)?;
+        validate_wit(self.wit.as_deref(), &token.claims)?;
+
+        let file = File::create(match compression.extension() {
+            Some(extension)
+                if destination.as_ref().extension().unwrap_or_default() != extension =>
+            {
+                let mut file_name = destination
+                    .as_ref()
+                    .file_name()
+                    .ok_or("Destination is not a file")?
+                    .to_owned();
+                file_name.push(".");
+                file_name.push(extension);
+                destination.as_ref().with_file_name(file_name)
+            }
+            _ => destination.as_ref().to_owned(),
+        })
+        .await?;
+
+        let mut par = tokio_tar::Builder::new(match compression {
+            Compression::None => Box::new(file) as Box<dyn AsyncWrite + Send + Sync + Unpin>,
+            Compression::Gzip => Box::new(GzipEncoder::with_quality(file, Level::Best))
+                as Box<dyn AsyncWrite + Send + Sync + Unpin>,
+            // NOTE: The highest zstd levels are very slow and memory hungry for little gain
+            Compression::Zstd => Box::new(ZstdEncoder::with_quality(file, Level::Default))
+                as Box<dyn AsyncWrite + Send + Sync + Unpin>,
+        });
 
         let mut header = tokio_tar::Header::new_gnu();
         header.set_path(CLAIMS_JWT_FILE)?;
-        header.set_size(claims_jwt.as_bytes().len() as u64);
+        header.set_size(token.jwt.len() as u64);
         header.set_cksum();
-        par.append_data(&mut header, CLAIMS_JWT_FILE, Cursor::new(claims_jwt))
+        par.append_data(&mut header, CLAIMS_JWT_FILE, Cursor::new(&token.jwt))
             .await?;
 
+        for cosignature in &self.cosignatures {
+            let mut header = tokio_tar::Header::new_gnu();
+            let path = format!("{COSIGNATURES_DIR}/{}.jwt", cosignature.claims.issuer);
+            header.set_path(&path)?;
+            header.set_size(cosignature.jwt.len() as u64);
+            header.set_cksum();
+            par.append_data(&mut header, &path, Cursor::new(&cosignature.jwt))
+                .await?;
+        }
+
+        if let Some(wit) = &self.wit {
+            let mut header = tokio_tar::Header::new_gnu();
+            header.set_path(WIT_FILE)?;
+            header.set_size(wit.len() as u64);
+            header.set_cksum();
+            par.append_data(&mut header, WIT_FILE, Cursor::new(wit))
+                .await?;
+        }
+
         for (tgt, lib) in &self.libraries {
             let mut header = tokio_tar::Header::new_gnu();
             let path = format!("{tgt}.bin");
@@ -359,6 +700,34 @@ impl ProviderArchive {
     }
 }
 
+/// Opens the optionally compressed Provider Archive (PAR) `input`, detecting its compression
+async fn open_archive<R: AsyncRead + AsyncSeek + Unpin + Send + Sync>(
+    input: &mut R,
+) -> Result<Archive<Box<dyn AsyncRead + Unpin + Sync + Send + '_>>> {
+    let mut magic = [0; 4];
+    let mut read = 0;
+    while read < magic.len() {
+        let count = input.read(&mut magic[read..]).await?;
+        if count == 0 {
+            break;
+        }
+        read += count;
+    }
+    // If we can't read the magic bytes of a compression format, it isn't a valid par file
+    if read < 2 {
+        return Err("Not enough bytes to be a valid PAR file".into());
+    }
+
+    // Seek back to beginning
+    input.rewind().await?;
+
+    Ok(Archive::new(match Compression::detect(&magic[..read]) {
+        Compression::None => Box::new(input) as Box<dyn AsyncRead + Unpin + Sync + Send + '_>,
+        Compression::Gzip => Box::new(GzipDecoder::new(BufReader::new(input))),
+        Compression::Zstd => Box::new(ZstdDecoder::new(BufReader::new(input))),
+    }))
+}
+
 fn validate_hashes(
     libraries: &HashMap<String, Vec<u8>>,
     claims: &Claims<CapabilityProvider>,
@@ -366,15 +735,72 @@ fn validate_hashes(
     let file_hashes = claims.metadata.as_ref().unwrap().target_hashes.clone();
 
     for (tgt, library) in libraries {
-        let file_hash = file_hashes.get(tgt).cloned().unwrap();
+        let file_hash = file_hashes
+            .get(tgt)
+            .ok_or_else(|| format!("No file hash found in claims for '{tgt}'"))?;
         let check_hash = hash_bytes(library);
-        if file_hash != check_hash {
+        if *file_hash != check_hash {
             return Err(format!("File hash and verify hash do not match for '{tgt}'").into());
         }
     }
     Ok(())
 }
 
+fn validate_wit(wit: Option<&[u8]>, claims: &Claims<CapabilityProvider>) -> Result<()> {
+    let wit_hash = claims
+        .metadata
+        .as_ref()
+        .and_then(|meta| meta.wit_hash.as_ref());
+    match (wit, wit_hash) {
+        (None, None) => Ok(()),
+        (Some(wit), Some(wit_hash)) if hash_bytes(wit) == *wit_hash => Ok(()),
+        (Some(_), Some(_)) => Err("WIT package hash and verify hash do not match".into()),
+        (Some(_), None) => Err("No WIT package hash found in claims".into()),
+        (None, Some(_)) => Err("WIT package not found in provider archive".into()),
+    }
+}
+
+fn validate_cosignatures(
+    cosignatures: &[Token<CoSignature>],
+    claims: &Claims<CapabilityProvider>,
+) -> Result<()> {
+    let hash = cosignature_hash(claims);
+    for cosignature in cosignatures {
+        if cosignature.claims.metadata.as_ref().map(|meta| &meta.hash) != Some(&hash) {
+            return Err(format!(
+                "Co-signature of '{}' does not match the archive hashes",
+                cosignature.claims.issuer
+            )
+            .into());
+        }
+    }
+    Ok(())
+}
+
+/// Computes the hash co-signatures are issued over, which covers the target hashes and the WIT
+/// package hash of `claims`
+fn cosignature_hash(claims: &Claims<CapabilityProvider>) -> String {
+    let mut target_hashes: Vec<_> = claims
+        .metadata
+        .as_ref()
+        .map(|meta| meta.target_hashes.iter().collect())
+        .unwrap_or_default();
+    target_hashes.sort();
+    let mut targets: String = target_hashes
+        .into_iter()
+        .map(|(target, hash)| format!("{target}={hash}\n"))
+        .collect();
+    // NOTE: Only appended if present, so co-signatures of archives without WIT remain valid
+    if let Some(wit_hash) = claims
+        .metadata
+        .as_ref()
+        .and_then(|meta| meta.wit_hash.as_ref())
+    {
+        targets.push_str(&format!("{WIT_FILE}={wit_hash}\n"));
+    }
+    hash_bytes(targets.as_bytes())
+}
+
 fn generate_hashes(libraries: &HashMap<String, Vec<u8>>) -> HashMap<String, String> {
     let mut hm = HashMap::new();
     for (target, lib) in libraries {
@@ -692,6 +1118,7 @@ mod test {
         arch.add_library("aarch64-linux", b"blahblah")?;
         arch.add_library("x86_64-linux", b"bloobloo")?;
         arch.add_library("x86_64-macos", b"blarblar")?;
+        arch.set_tags(vec!["testing".to_string()]);
 
         let issuer = KeyPair::new_account();
         let subject = KeyPair::new_service();
@@ -732,8 +1159,126 @@ mod test {
         assert_eq!(arch3.claims().unwrap().metadata.unwrap().ver.unwrap(), ver);
         assert_eq!(arch3.claims().unwrap().metadata.unwrap().rev.unwrap(), rev);
         assert_eq!(arch3.claims().unwrap().metadata.unwrap().vendor, vendor);
+        assert_eq!(arch3.tags(), Some(&["testing".to_string()][..]));
         assert_eq!(arch3.targets().len(), 4);
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn cosignatures() -> Result<()> {
+        let mut arch = ProviderArchive::new("Testing", "wasmCloud", None, None);
+        arch.add_library("x86_64-linux", b"bloobloo")?;
+        let issuer = KeyPair::new_account();
+        let subject = KeyPair::new_service();
+        let build = KeyPair::new_account();
+        assert!(
+            arch.cosign(&build, None).is_err(),
+            "Archives without claims cannot be co-signed"
+        );
+
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("cosigned.par.gz");
+        arch.write(&path, &issuer, &subject, true).await?;
+
+        let mut arch2 = ProviderArchive::try_load_file(&path).await?;
+        assert!(arch2.cosignatures().is_empty());
+        arch2.cosign(&build, None)?;
+        arch2.cosign(&KeyPair::new_account(), None)?;
+        arch2.cosign(&build, None)?;
+        arch2.write_signed(&path, Compression::Gzip).await?;
+
+        let arch3 = ProviderArchive::try_load_target_from_file(&path, "x86_64-linux").await?;
+        assert_eq!(arch3.claims_token(), arch2.claims_token());
+        assert_eq!(arch3.cosignatures().len(), 2);
+        assert_eq!(arch3.cosignatures()[1].claims.issuer, build.public_key());
+        assert!(arch3
+            .cosignatures()
+            .iter()
+            .all(|cosignature| cosignature.claims.subject == subject.public_key()));
+
+        // Co-signatures are kept when re-signing the same targets, but not once targets change
+        arch2
+            .write(&path, &KeyPair::new_account(), &subject, true)
+            .await?;
+        assert_eq!(arch2.cosignatures().len(), 2);
+        arch2.add_library("aarch64-linux", b"blahblah")?;
+        arch2.write(&path, &issuer, &subject, true).await?;
+        assert!(arch2.cosignatures().is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn zstd_extract_target() -> Result<()> {
+        let mut arch = ProviderArchive::new("Testing", "wasmCloud", None, None);
+        arch.add_library("aarch64-linux", b"blahblah")?;
+        arch.add_library("x86_64-linux", b"bloobloo")?;
+        let issuer = KeyPair::new_account();
+        let subject = KeyPair::new_service();
+
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("zstd.par");
+        arch.write_compressed(&path, &issuer, &subject, Compression::Zstd)
+            .await?;
+        let path = tempdir.path().join("zstd.par.zst");
+        let bytes = tokio::fs::read(&path).await?;
+        assert_eq!(Compression::detect(&bytes), Compression::Zstd);
+
+        let arch2 = ProviderArchive::try_load(&bytes).await?;
+        assert_eq!(arch2.claims(), arch.claims());
+        assert_eq!(arch2.targets().len(), 2);
+
+        let mut output = Vec::new();
+        let arch3 =
+            ProviderArchive::extract_target_from_file(&path, "x86_64-linux", &mut output).await?;
+        assert_eq!(output, b"bloobloo");
+        assert_eq!(arch3.claims(), arch.claims());
+        assert!(arch3.targets().is_empty());
+
+        assert!(
+            ProviderArchive::extract_target_from_file(&path, "mips-linux", &mut Vec::new())
+                .await
+                .is_err(),
+            "Missing targets should fail to extract"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn wit() -> Result<()> {
+        let mut arch = ProviderArchive::new("Testing", "wasmCloud", None, None);
+        arch.add_library("x86_64-linux", b"bloobloo")?;
+        arch.set_wit(b"\0asmwit".to_vec());
+        let issuer = KeyPair::new_account();
+        let subject = KeyPair::new_service();
+
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("wit.par.gz");
+        arch.write(&path, &issuer, &subject, true).await?;
+        assert!(arch
+            .claims()
+            .and_then(|claims| claims.metadata)
+            .is_some_and(|meta| meta.wit_hash.is_some()));
+
+        let arch2 = ProviderArchive::try_load_file(&path).await?;
+        assert_eq!(arch2.wit(), Some(&b"\0asmwit"[..]));
+        assert_eq!(arch2.targets(), vec!["x86_64-linux".to_string()]);
+
+        // The WIT package is skipped when only extracting a target
+        let mut output = Vec::new();
+        ProviderArchive::extract_target_from_file(&path, "x86_64-linux", &mut output).await?;
+        assert_eq!(output, b"bloobloo");
+
+        // Modifying the WIT package invalidates the archive
+        let mut arch3 = ProviderArchive::try_load_file(&path).await?;
+        arch3.set_wit(b"\0asmtampered".to_vec());
+        assert!(
+            arch3.write_signed(&path, Compression::Gzip).await.is_err(),
+            "Archives with a modified WIT package must be re-signed"
+        );
+
+        Ok(())
+    }
 }
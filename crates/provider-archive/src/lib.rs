@@ -1,4 +1,6 @@
 mod archive;
+mod schema;
 
 pub type Result<T> = ::std::result::Result<T, Box<dyn std::error::Error + Sync + Send>>;
-pub use archive::ProviderArchive;
+pub use archive::{Compression, ProviderArchive};
+pub use schema::validate_config;
@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+use jsonschema::JSONSchema;
+use serde_json::{Map, Value};
+
+use crate::Result;
+
+/// Validates named config, as passed to a provider at start or on links, against the
+/// `config_schema` JSON schema embedded in the provider's claims.
+///
+/// Config values are always strings. Values of properties, which the schema declares with a
+/// non-string `type`, are parsed as JSON before validation, so that e.g. `"8080"` satisfies
+/// `{ "type": "integer" }`. All violations are reported in a single error.
+pub fn validate_config(schema: &Value, config: &HashMap<String, String>) -> Result<()> {
+    let compiled =
+        JSONSchema::compile(schema).map_err(|e| format!("invalid config schema: {e}"))?;
+    let instance = config_instance(schema, config);
+    if let Err(errors) = compiled.validate(&instance) {
+        let errors = errors
+            .map(|e| {
+                let path = e.instance_path.to_string();
+                if path.is_empty() {
+                    e.to_string()
+                } else {
+                    format!("{path}: {e}")
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        return Err(format!("config does not match provider config schema: {errors}").into());
+    }
+    Ok(())
+}
+
+/// Builds the JSON object validated against `schema` from string `config` values
+fn config_instance(schema: &Value, config: &HashMap<String, String>) -> Value {
+    let properties = schema.get("properties").and_then(Value::as_object);
+    let instance = config
+        .iter()
+        .map(|(k, v)| {
+            let declared_string = properties
+                .and_then(|properties| properties.get(k))
+                .and_then(|property| property.get("type"))
+                .is_none_or(|ty| match ty {
+                    Value::String(ty) => ty == "string",
+                    Value::Array(tys) => tys.iter().any(|ty| ty == "string"),
+                    _ => true,
+                });
+            let value = if declared_string {
+                Value::String(v.clone())
+            } else {
+                serde_json::from_str(v).unwrap_or_else(|_| Value::String(v.clone()))
+            };
+            (k.clone(), value)
+        })
+        .collect::<Map<_, _>>();
+    Value::Object(instance)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use super::validate_config;
+
+    #[test]
+    fn validates_config() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "address": { "type": "string" },
+                "port": { "type": "integer", "maximum": 65535 },
+            },
+            "required": ["address"],
+        });
+        let config = HashMap::from([
+            ("address".to_string(), "0.0.0.0".to_string()),
+            ("port".to_string(), "8080".to_string()),
+        ]);
+        validate_config(&schema, &config).expect("config should be valid");
+
+        let config = HashMap::from([("port".to_string(), "70000".to_string())]);
+        let err = validate_config(&schema, &config)
+            .expect_err("config should be invalid")
+            .to_string();
+        assert!(err.contains("address"), "{err}");
+        assert!(err.contains("/port"), "{err}");
+    }
+}
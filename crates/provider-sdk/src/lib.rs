@@ -195,6 +195,18 @@ impl ProviderConfigUpdate for &HashMap<String, String> {
     }
 }
 
+/// Objects that can act as provider secrets updates
+pub trait ProviderSecretsUpdate: Send + Sync {
+    /// Get the full set of secrets of the provider after the update
+    fn get_secrets(&self) -> &HashMap<String, SecretValue>;
+}
+
+impl ProviderSecretsUpdate for &HashMap<String, SecretValue> {
+    fn get_secrets(&self) -> &HashMap<String, SecretValue> {
+        self
+    }
+}
+
 /// Present information related to a link delete, normally used as part of the [`Provider`] interface,
 /// for providers that must process a link deletion in some way.
 pub trait LinkDeleteInfo: Send + Sync {
@@ -271,6 +283,26 @@ pub trait Provider<E = anyhow::Error>: Sync {
         async { Ok(()) }
     }
 
+    /// Process a secrets update for the provider
+    ///
+    /// When secrets referenced by the provider are rotated in the secrets backend, the host
+    /// re-fetches them and delivers the full, updated set of secrets of the provider.
+    ///
+    /// Secrets of links are not part of this update. Links referencing rotated secrets are delivered
+    /// again through [`Provider::receive_link_config_as_source`] or
+    /// [`Provider::receive_link_config_as_target`] with the updated secrets.
+    ///
+    /// # Arguments
+    ///
+    /// * `update` - The updated secrets of the provider
+    fn on_secrets_update(
+        &self,
+        update: impl ProviderSecretsUpdate,
+    ) -> impl Future<Output = Result<(), E>> + Send {
+        let _ = update;
+        async { Ok(()) }
+    }
+
     /// Receive and handle a link that has been established on the lattice where this provider is the source.
     ///
     /// Implement this when your provider needs to call other components.
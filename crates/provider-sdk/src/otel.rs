@@ -33,6 +33,9 @@ macro_rules! propagate_trace_for_ctx {
 /// This macro loads host data and uses the provider-sdk to build a [`tracing_core::Dispatch`] and
 /// relevant guards/internal structures to configure it with information relevant to the host
 ///
+/// Trace sampling and local trace file export follow the [`OtelConfig`](crate::core::OtelConfig)
+/// supplied by the host.
+///
 /// This macro introduces the following variables into scope:
 /// - `__observability__guard`
 ///
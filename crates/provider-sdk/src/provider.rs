@@ -25,8 +25,8 @@ use wasmcloud_core::nats::convert_header_map_to_hashmap;
 use wasmcloud_core::rpc::{health_subject, link_del_subject, link_put_subject, shutdown_subject};
 use wasmcloud_core::secrets::SecretValue;
 use wasmcloud_core::{
-    provider_config_update_subject, HealthCheckRequest, HealthCheckResponse, HostData,
-    InterfaceLinkDefinition, LatticeTarget,
+    provider_config_update_subject, provider_secrets_update_subject, HealthCheckRequest,
+    HealthCheckResponse, HostData, InterfaceLinkDefinition, LatticeTarget,
 };
 
 #[cfg(feature = "otel")]
@@ -367,12 +367,46 @@ async fn subscribe_config_update(
     Ok(config_update_rx)
 }
 
+/// Subscribe to secrets updates that are passed by the host.
+///
+/// We expect the hosts to send the full set of secrets of the provider over NATS when they are
+/// rotated, serialized and encrypted for the provider xkey like the secrets of links.
+async fn subscribe_secrets_update(
+    nats: Arc<async_nats::Client>,
+    mut quit: broadcast::Receiver<()>,
+    lattice: &str,
+    provider_key: &str,
+) -> ProviderInitResult<mpsc::Receiver<(Bytes, oneshot::Sender<()>)>> {
+    let (secrets_update_tx, secrets_update_rx) = mpsc::channel(1);
+    let mut sub = nats
+        .subscribe(provider_secrets_update_subject(lattice, provider_key).to_subject())
+        .await?;
+    spawn({
+        async move {
+            process_until_quit!(sub, quit, msg, {
+                let (tx, rx) = oneshot::channel();
+                if let Err(err) = secrets_update_tx.send((msg.payload, tx)).await {
+                    error!(%err, "failed to send secrets update");
+                    continue;
+                }
+                if let Err(err) = rx.await.as_ref() {
+                    error!(%err, "failed to receive secrets update response");
+                }
+            });
+        }
+        .instrument(tracing::debug_span!("subscribe_secrets_update"))
+    });
+
+    Ok(secrets_update_rx)
+}
+
 pub(crate) struct ProviderCommandReceivers {
     pub health: mpsc::Receiver<(HealthCheckRequest, oneshot::Sender<HealthCheckResponse>)>,
     pub shutdown: mpsc::Receiver<oneshot::Sender<()>>,
     pub link_put: mpsc::Receiver<(InterfaceLinkDefinition, oneshot::Sender<()>)>,
     pub link_del: mpsc::Receiver<(InterfaceLinkDefinition, oneshot::Sender<()>)>,
     pub config_update: mpsc::Receiver<(HashMap<String, String>, oneshot::Sender<()>)>,
+    pub secrets_update: mpsc::Receiver<(Bytes, oneshot::Sender<()>)>,
 }
 
 /// State of provider initialization
@@ -485,7 +519,7 @@ async fn init_provider(name: &str) -> ProviderInitResult<ProviderInitState> {
     let nats = Arc::new(nats);
 
     // Listen and process various provider events/functionality
-    let (health, shutdown, link_put, link_del, config_update) = try_join!(
+    let (health, shutdown, link_put, link_del, config_update, secrets_update) = try_join!(
         subscribe_health(
             Arc::clone(&nats),
             quit_tx.subscribe(),
@@ -517,6 +551,12 @@ async fn init_provider(name: &str) -> ProviderInitResult<ProviderInitState> {
             lattice_rpc_prefix,
             provider_key,
         ),
+        subscribe_secrets_update(
+            Arc::clone(&nats),
+            quit_tx.subscribe(),
+            lattice_rpc_prefix,
+            provider_key,
+        ),
     )?;
     Ok(ProviderInitState {
         nats,
@@ -536,6 +576,7 @@ async fn init_provider(name: &str) -> ProviderInitResult<ProviderInitState> {
             link_put,
             link_del,
             config_update,
+            secrets_update,
         },
     })
 }
@@ -611,6 +652,51 @@ fn decrypt_link_secret(
         .unwrap_or(Ok(HashMap::with_capacity(0)))
 }
 
+/// Returns `true` if the secrets of `ld` for this provider differ from those of the same link it
+/// already received, which is the case when the host delivers a link again after its secrets were
+/// rotated.
+async fn link_secrets_changed(
+    connection: &ProviderConnection,
+    ld: &InterfaceLinkDefinition,
+) -> bool {
+    let (stored, secrets) = if ld.source_id == *connection.provider_id {
+        (
+            connection
+                .source_links
+                .read()
+                .await
+                .get(&ld.target)
+                .map(|stored| stored.source_secrets.clone()),
+            ld.source_secrets.as_deref(),
+        )
+    } else {
+        (
+            connection
+                .target_links
+                .read()
+                .await
+                .get(&ld.source_id)
+                .map(|stored| stored.target_secrets.clone()),
+            ld.target_secrets.as_deref(),
+        )
+    };
+    let Some(stored) = stored else {
+        return false;
+    };
+    // Secrets are sealed with a new nonce every time, so compare them decrypted
+    let decrypt = |secrets: Option<&[u8]>| {
+        decrypt_link_secret(secrets, &connection.provider_xkey, &connection.host_xkey)
+            .and_then(|secrets| serde_json::to_value(secrets).context("failed to compare secrets"))
+    };
+    match (decrypt(stored.as_deref()), decrypt(secrets)) {
+        (Ok(stored), Ok(secrets)) => stored != secrets,
+        (_, Err(e)) | (Err(e), _) => {
+            warn!(error = %e, "failed to decrypt link secrets");
+            false
+        }
+    }
+}
+
 async fn delete_link_for_provider<P>(
     provider: &P,
     connection: &ProviderConnection,
@@ -644,6 +730,7 @@ async fn handle_provider_commands(
         mut link_put,
         mut link_del,
         mut config_update,
+        mut secrets_update,
     }: ProviderCommandReceivers,
 ) {
     loop {
@@ -698,13 +785,20 @@ async fn handle_provider_commands(
             }
             req = link_put.recv() => {
                 if let Some((ld, tx)) = req {
-                    // If the link has already been put, return early
+                    // If the link has already been put, return early, unless its secrets were rotated
                     if connection.is_linked(&ld.source_id, &ld.target).await {
-                        warn!(
-                            source = &ld.source_id,
-                            target = &ld.target,
-                            "Ignoring duplicate link put"
-                        );
+                        if link_secrets_changed(connection, &ld).await {
+                            info!("Updating secrets of link");
+                            if let Err(e) = receive_link_for_provider(&provider, connection, ld).await {
+                                error!(error = %e, "failed to receive link for provider");
+                            }
+                        } else {
+                            warn!(
+                                source = &ld.source_id,
+                                target = &ld.target,
+                                "Ignoring duplicate link put"
+                            );
+                        }
                     } else {
                         info!("Linking component with provider");
                         if let Err(e) = receive_link_for_provider(&provider, connection, ld).await {
@@ -767,6 +861,36 @@ async fn handle_provider_commands(
                     return
                 };
             }
+            req = secrets_update.recv() => {
+                if let Some((secrets, tx)) = req {
+                    // Notify the provider that its secrets have been rotated
+                    match decrypt_link_secret(
+                        Some(&secrets),
+                        &connection.provider_xkey,
+                        &connection.host_xkey,
+                    ) {
+                        Ok(secrets) => {
+                            if let Err(e) = provider.on_secrets_update(&secrets).await {
+                                error!(error = %e, "failed to pass through secrets update for provider");
+                            }
+                        }
+                        Err(e) => error!(error = %e, "failed to decrypt secrets update"),
+                    }
+
+                    if tx.send(()).is_err() {
+                        error!("failed to send secrets update response");
+                    }
+                } else {
+                    error!("failed to handle secrets update, shutdown");
+                    if let Err(e) = provider.shutdown().await {
+                        error!(error = %e, "failed to shutdown provider");
+                    }
+                    if quit_tx.send(()).is_err() {
+                        error!("failed to send quit");
+                    };
+                    return
+                };
+            }
         }
     }
 }
@@ -1107,3 +1231,128 @@ impl ProviderConnection {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ProviderSecretsUpdate;
+
+    const PROVIDER_ID: &str = "provider";
+
+    /// Provider reporting the secrets it receives on updates and links
+    struct SecretsProvider(mpsc::UnboundedSender<(&'static str, HashMap<String, SecretValue>)>);
+
+    impl Provider for SecretsProvider {
+        async fn on_secrets_update(&self, update: impl ProviderSecretsUpdate) -> Result<()> {
+            self.0.send(("update", update.get_secrets().clone()))?;
+            Ok(())
+        }
+
+        async fn receive_link_config_as_target(&self, config: LinkConfig<'_>) -> Result<()> {
+            self.0.send(("link", config.secrets.clone()))?;
+            Ok(())
+        }
+    }
+
+    fn seal(host_xkey: &XKey, provider_xkey: &XKey, secret: &str) -> Vec<u8> {
+        let secrets =
+            HashMap::from([("token".to_string(), SecretValue::String(secret.to_string()))]);
+        let secrets = serde_json::to_vec(&secrets).expect("failed to encode secrets");
+        host_xkey
+            .seal(&secrets, provider_xkey)
+            .expect("failed to encrypt secrets")
+    }
+
+    fn link(target_secrets: Vec<u8>) -> InterfaceLinkDefinition {
+        InterfaceLinkDefinition {
+            source_id: "component".to_string(),
+            target: PROVIDER_ID.to_string(),
+            name: "default".to_string(),
+            wit_namespace: "wasi".to_string(),
+            wit_package: "keyvalue".to_string(),
+            interfaces: vec!["store".to_string()],
+            target_secrets: Some(target_secrets),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn delivers_rotated_provider_and_link_secrets() -> Result<()> {
+        let host_xkey = XKey::new();
+        let provider_xkey = XKey::new();
+        let provider_public_xkey = XKey::from_public_key(&provider_xkey.public_key())?;
+        let nats = async_nats::ConnectOptions::new()
+            .retry_on_initial_connect()
+            .connect("127.0.0.1:1")
+            .await?;
+        let connection = ProviderConnection::new(
+            Arc::new(nats),
+            PROVIDER_ID.into(),
+            "test".to_string(),
+            "host".to_string(),
+            HashMap::default(),
+            provider_xkey,
+            XKey::from_public_key(&host_xkey.public_key())?,
+        )?;
+
+        let (_health_tx, health) = mpsc::channel(1);
+        let (_shutdown_tx, shutdown) = mpsc::channel(1);
+        let (link_put_tx, link_put) = mpsc::channel(1);
+        let (_link_del_tx, link_del) = mpsc::channel(1);
+        let (_config_update_tx, config_update) = mpsc::channel(1);
+        let (secrets_update_tx, secrets_update) = mpsc::channel(1);
+        let (quit_tx, quit_rx) = broadcast::channel(1);
+        let (received_tx, mut received) = mpsc::unbounded_channel();
+        let commands = spawn(async move {
+            handle_provider_commands(
+                SecretsProvider(received_tx),
+                &connection,
+                quit_rx,
+                quit_tx,
+                ProviderCommandReceivers {
+                    health,
+                    shutdown,
+                    link_put,
+                    link_del,
+                    config_update,
+                    secrets_update,
+                },
+            )
+            .await;
+        });
+
+        // Rotated secrets of the provider are passed to `on_secrets_update`
+        let (tx, rx) = oneshot::channel();
+        secrets_update_tx
+            .send((
+                seal(&host_xkey, &provider_public_xkey, "rotated").into(),
+                tx,
+            ))
+            .await?;
+        rx.await?;
+        let (kind, secrets) = received.try_recv()?;
+        assert_eq!(kind, "update");
+        assert_eq!(secrets["token"].as_string(), Some("rotated"));
+
+        // A link is received once, and again only if its secrets changed
+        for (secret, delivered) in [("first", true), ("first", false), ("second", true)] {
+            let (tx, rx) = oneshot::channel();
+            link_put_tx
+                .send((link(seal(&host_xkey, &provider_public_xkey, secret)), tx))
+                .await?;
+            rx.await?;
+            if delivered {
+                let (kind, secrets) = received.try_recv()?;
+                assert_eq!(kind, "link");
+                assert_eq!(secrets["token"].as_string(), Some(secret));
+            } else {
+                assert!(received.try_recv().is_err());
+            }
+        }
+
+        // Quitting flushes the NATS client, which never connected
+        commands.abort();
+        Ok(())
+    }
+}
@@ -1,4 +1,6 @@
-use super::{new_store, Ctx, Handler, Instance, ReplacedInstanceTarget, WrpcServeEvent};
+use super::{
+    new_store, Ctx, Handler, Instance, InvocationContext, ReplacedInstanceTarget, WrpcServeEvent,
+};
 
 use crate::capability::http::types;
 
@@ -117,7 +119,7 @@ where
 impl<H, C> ServeIncomingHandlerWasmtime<C> for Instance<H, C>
 where
     H: Handler,
-    C: Send,
+    C: InvocationContext<H>,
 {
     #[instrument(level = "debug", skip_all)]
     async fn handle(
@@ -134,7 +136,11 @@ where
         let scheme = wrpc_interface_http::bindings::wrpc::http::types::Scheme::from(scheme).into();
 
         let (tx, rx) = oneshot::channel();
-        let mut store = new_store(&self.engine, self.handler.clone(), self.max_execution_time);
+        let mut store = new_store(
+            &self.engine,
+            cx.handler(&self.handler),
+            self.max_execution_time,
+        );
         let pre = incoming_http_bindings::IncomingHttpPre::new(self.pre.clone())
             .context("failed to pre-instantiate `wasi:http/incoming-handler`")?;
         let bindings = pre
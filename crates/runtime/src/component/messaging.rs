@@ -1,4 +1,4 @@
-use super::{new_store, Ctx, Handler, Instance, WrpcServeEvent};
+use super::{new_store, Ctx, Handler, Instance, InvocationContext, WrpcServeEvent};
 
 use crate::capability::messaging::{consumer, types};
 use crate::capability::wrpc;
@@ -90,7 +90,7 @@ impl<H, C> wrpc_handler_bindings::exports::wasmcloud::messaging::handler::Handle
     for Instance<H, C>
 where
     H: Handler,
-    C: Send,
+    C: InvocationContext<H>,
 {
     #[instrument(level = "debug", skip_all)]
     async fn handle_message(
@@ -102,7 +102,11 @@ where
             reply_to,
         }: wrpc_handler_bindings::wasmcloud::messaging::types::BrokerMessage,
     ) -> anyhow::Result<Result<(), String>> {
-        let mut store = new_store(&self.engine, self.handler.clone(), self.max_execution_time);
+        let mut store = new_store(
+            &self.engine,
+            cx.handler(&self.handler),
+            self.max_execution_time,
+        );
         let pre = wasmtime_handler_bindings::MessagingHandlerPre::new(self.pre.clone())
             .context("failed to pre-instantiate `wasmcloud:messaging/handler`")?;
         let bindings = pre.instantiate_async(&mut store).await?;
@@ -6,6 +6,8 @@ use core::future::Future;
 use core::pin::Pin;
 use core::time::Duration;
 
+use std::sync::Arc;
+
 use anyhow::{ensure, Context as _};
 use futures::{Stream, TryStreamExt as _};
 use tokio::io::{AsyncRead, AsyncReadExt as _};
@@ -20,7 +22,7 @@ use wasmtime::component::{types, Linker, ResourceTable, ResourceTableError};
 use wasmtime_wasi::{WasiCtx, WasiCtxBuilder, WasiView};
 use wasmtime_wasi_http::WasiHttpCtx;
 use wrpc_runtime_wasmtime::{
-    collect_component_resources, link_item, ServeExt as _, SharedResourceTable, WrpcView,
+    call, collect_component_resources, link_item, SharedResourceTable, WrpcView,
 };
 
 pub use bus::Bus;
@@ -151,6 +153,13 @@ impl<
 {
 }
 
+/// Context of an invocation served by [`Component::serve_wrpc`]
+pub trait InvocationContext<H>: Send + Sync + 'static {
+    /// Returns the [`Handler`] of the component instance serving the invocation, derived from the
+    /// [`Handler`] the component is served with
+    fn handler(&self, handler: &H) -> H;
+}
+
 /// Component instance configuration
 #[derive(Clone, Debug, Default)]
 pub struct ComponentConfig {
@@ -196,6 +205,8 @@ where
     claims: Option<jwt::Claims<jwt::Component>>,
     instance_pre: wasmtime::component::InstancePre<Ctx<H>>,
     max_execution_time: Duration,
+    imports: Arc<[String]>,
+    exports: Arc<[String]>,
 }
 
 impl<H> Debug for Component<H>
@@ -207,6 +218,8 @@ where
             .field("claims", &self.claims)
             .field("runtime", &"wasmtime")
             .field("max_execution_time", &self.max_execution_time)
+            .field("imports", &self.imports)
+            .field("exports", &self.exports)
             .finish_non_exhaustive()
     }
 }
@@ -347,11 +360,23 @@ where
                 .context("failed to link item")?;
         }
         let instance_pre = linker.instantiate_pre(&component)?;
+        let instance_names = |items: &mut dyn Iterator<Item = (&str, types::ComponentItem)>| {
+            items
+                .filter_map(|(name, ty)| {
+                    matches!(ty, types::ComponentItem::ComponentInstance(..))
+                        .then(|| name.to_string())
+                })
+                .collect()
+        };
+        let imports = instance_names(&mut ty.imports(&engine));
+        let exports = instance_names(&mut ty.exports(&engine));
         Ok(Self {
             engine,
             claims,
             instance_pre,
             max_execution_time: rt.max_execution_time,
+            imports,
+            exports,
         })
     }
 
@@ -395,6 +420,18 @@ where
         self.claims.as_ref()
     }
 
+    /// Names of the instances imported by this [Component], e.g. `wasi:keyvalue/store@0.2.0-draft`
+    #[must_use]
+    pub fn imports(&self) -> &[String] {
+        &self.imports
+    }
+
+    /// Names of the instances exported by this [Component], e.g. `wasi:http/incoming-handler@0.2.0`
+    #[must_use]
+    pub fn exports(&self) -> &[String] {
+        &self.exports
+    }
+
     /// Serve all exports of this [Component] using supplied [`wrpc_transport::Serve`]
     ///
     /// The returned [Vec] contains an [InvocationStream] per each function exported by the component.
@@ -410,6 +447,7 @@ where
     ) -> anyhow::Result<Vec<InvocationStream>>
     where
         S: wrpc_transport::Serve,
+        S::Context: InvocationContext<H>,
     {
         let span = Span::current();
         let max_execution_time = self.max_execution_time;
@@ -455,20 +493,19 @@ where
                     invocations.push(handle_message);
                 }
                 (name, types::ComponentItem::ComponentFunc(ty)) => {
-                    let engine = self.engine.clone();
-                    let handler = handler.clone();
-                    let pre = self.instance_pre.clone();
                     debug!(?name, "serving root function");
-                    let func = srv
-                        .serve_function(
-                            move || new_store(&engine, handler.clone(), max_execution_time),
-                            pre,
-                            ty,
-                            "",
-                            name,
-                        )
-                        .await
-                        .context("failed to serve root function")?;
+                    let func = serve_function(
+                        srv,
+                        self.engine.clone(),
+                        self.instance_pre.clone(),
+                        handler.clone(),
+                        max_execution_time,
+                        ty,
+                        "",
+                        name,
+                    )
+                    .await
+                    .context("failed to serve root function")?;
                     let events = events.clone();
                     let span = span.clone();
                     invocations.push(Box::pin(func.map_ok(move |(cx, res)| {
@@ -508,22 +545,19 @@ where
                     for (name, ty) in ty.exports(&self.engine) {
                         match ty {
                             types::ComponentItem::ComponentFunc(ty) => {
-                                let engine = self.engine.clone();
-                                let handler = handler.clone();
-                                let pre = self.instance_pre.clone();
                                 debug!(?instance_name, ?name, "serving instance function");
-                                let func = srv
-                                    .serve_function(
-                                        move || {
-                                            new_store(&engine, handler.clone(), max_execution_time)
-                                        },
-                                        pre,
-                                        ty,
-                                        instance_name,
-                                        name,
-                                    )
-                                    .await
-                                    .context("failed to serve instance function")?;
+                                let func = serve_function(
+                                    srv,
+                                    self.engine.clone(),
+                                    self.instance_pre.clone(),
+                                    handler.clone(),
+                                    max_execution_time,
+                                    ty,
+                                    instance_name,
+                                    name,
+                                )
+                                .await
+                                .context("failed to serve instance function")?;
                                 let events = events.clone();
                                 let span = span.clone();
                                 invocations.push(Box::pin(func.map_ok(move |(cx, res)| {
@@ -587,6 +621,89 @@ where
     }
 }
 
+/// Serve function `name` of instance `instance_name` exported by a component, instantiating it on
+/// each invocation with the [`Handler`] returned by the [`InvocationContext`] of the invocation
+#[allow(clippy::too_many_arguments)]
+async fn serve_function<S, H>(
+    srv: &S,
+    engine: wasmtime::Engine,
+    pre: wasmtime::component::InstancePre<Ctx<H>>,
+    handler: H,
+    max_execution_time: Duration,
+    ty: types::ComponentFunc,
+    instance_name: &str,
+    name: &str,
+) -> anyhow::Result<
+    Pin<
+        Box<
+            dyn Stream<
+                    Item = anyhow::Result<(
+                        S::Context,
+                        Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'static>>,
+                    )>,
+                > + Send
+                + 'static,
+        >,
+    >,
+>
+where
+    S: wrpc_transport::Serve,
+    S::Context: InvocationContext<H>,
+    H: Handler,
+{
+    let component_ty = pre.component();
+    let idx = if instance_name.is_empty() {
+        None
+    } else {
+        let (_, idx) = component_ty
+            .export_index(None, instance_name)
+            .with_context(|| format!("export `{instance_name}` not found"))?;
+        Some(idx)
+    };
+    let (_, idx) = component_ty
+        .export_index(idx.as_ref(), name)
+        .with_context(|| format!("export `{name}` not found"))?;
+
+    // Resource functions are invoked by their name without the kind prefix
+    let func = ["[constructor]", "[static]", "[method]"]
+        .iter()
+        .find_map(|prefix| name.strip_prefix(prefix))
+        .unwrap_or(name);
+    let invocations = srv.serve(instance_name, func, []).await?;
+    let name = Arc::<str>::from(name);
+    let params_ty: Arc<[_]> = ty.params().collect();
+    let results_ty: Arc<[_]> = ty.results().collect();
+    Ok(Box::pin(invocations.map_ok(move |(cx, tx, rx)| {
+        let mut store = new_store(&engine, cx.handler(&handler), max_execution_time);
+        let pre = pre.clone();
+        let name = Arc::clone(&name);
+        let params_ty = Arc::clone(&params_ty);
+        let results_ty = Arc::clone(&results_ty);
+        (
+            cx,
+            Box::pin(async move {
+                let instance = pre
+                    .instantiate_async(&mut store)
+                    .await
+                    .context("failed to instantiate component")?;
+                let func = instance
+                    .get_func(&mut store, idx)
+                    .with_context(|| format!("function export `{name}` not found"))?;
+                call(
+                    &mut store,
+                    rx,
+                    tx,
+                    params_ty.iter(),
+                    results_ty.iter(),
+                    func,
+                    &[],
+                )
+                .await
+            }) as Pin<Box<dyn Future<Output = _> + Send + 'static>>,
+        )
+    })))
+}
+
 impl<H> From<Component<H>> for Option<jwt::Claims<jwt::Component>>
 where
     H: Handler,
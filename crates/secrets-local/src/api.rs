@@ -0,0 +1,247 @@
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use async_nats::{Message, Subject};
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::StreamExt;
+use nkeys::XKey;
+use tracing::{info, warn};
+use wascap::jwt::{CapabilityProvider, Host};
+use wascap::prelude::{validate_token, Claims, Component};
+use wasmcloud_secrets_types::*;
+
+use crate::store::{Store, StoredSecret};
+
+const OPERATION_INDEX: usize = 3;
+
+/// How often the secrets file is checked for changes, to notify hosts that secrets changed
+const FILE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Returns the modification time of the file at `path`, or `None` if it does not exist
+async fn modified(path: &Path) -> Option<SystemTime> {
+    tokio::fs::metadata(path)
+        .await
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// The `Api` struct implements the functionality of this secrets backend.
+pub struct Api {
+    /// The server's transit XKey, used to decrypt requests sent to the server.
+    server_transit_xkey: XKey,
+    /// The sources secrets are served from.
+    store: Store,
+    /// The NATS client used to communicate with wasmCloud hosts.
+    pub client: async_nats::Client,
+    /// The base subject for all secrets operations. Should default to `wasmcloud.secrets`.
+    subject_base: String,
+    /// The name of this backend, which secret references use to refer to it.
+    pub name: String,
+    /// The version of the secrets API that this backend implements.
+    api_version: String,
+}
+
+impl Api {
+    pub fn new(
+        server_xkey: XKey,
+        store: Store,
+        client: async_nats::Client,
+        subject_base: String,
+        name: String,
+        api_version: String,
+    ) -> Self {
+        Self {
+            server_transit_xkey: server_xkey,
+            store,
+            client,
+            subject_base,
+            name,
+            api_version,
+        }
+    }
+
+    pub fn subject(&self) -> String {
+        format!("{}.{}.{}", self.subject_base, self.api_version, self.name)
+    }
+
+    async fn reply_error(&self, reply: Subject, error: GetSecretError) {
+        let _ = self
+            .client
+            .publish(reply, SecretResponse::from(error).into())
+            .await;
+    }
+
+    async fn handle_get_secret(&self, msg: &Message, reply: Subject) {
+        if msg.payload.is_empty() {
+            return self
+                .reply_error(reply, GetSecretError::InvalidPayload)
+                .await;
+        }
+        let Some(headers) = &msg.headers else {
+            return self
+                .reply_error(reply, GetSecretError::InvalidHeaders)
+                .await;
+        };
+        let Some(host_key) = headers
+            .get(WASMCLOUD_HOST_XKEY)
+            .and_then(|key| XKey::from_public_key(key.as_str()).ok())
+        else {
+            return self.reply_error(reply, GetSecretError::InvalidXKey).await;
+        };
+        let Ok(payload) = self.server_transit_xkey.open(&msg.payload, &host_key) else {
+            return self
+                .reply_error(reply, GetSecretError::DecryptionError)
+                .await;
+        };
+        let Ok(request) = serde_json::from_slice::<SecretRequest>(&payload) else {
+            return self
+                .reply_error(reply, GetSecretError::InvalidRequest)
+                .await;
+        };
+
+        match self.get(request).await {
+            Ok(resp) => {
+                let encoded: Bytes = resp.into();
+                let encryption_key = XKey::new();
+                let Ok(encrypted) = encryption_key.seal(&encoded, &host_key) else {
+                    return self
+                        .reply_error(reply, GetSecretError::EncryptionError)
+                        .await;
+                };
+                let mut headers = async_nats::HeaderMap::new();
+                headers.insert(RESPONSE_XKEY, encryption_key.public_key().as_str());
+                let _ = self
+                    .client
+                    .publish_with_headers(reply, headers, encrypted.into())
+                    .await;
+            }
+            Err(e) => self.reply_error(reply, e).await,
+        }
+    }
+
+    /// Notifies hosts that secrets changed, so that they re-fetch the secrets of running
+    /// components and providers.
+    async fn notify_changed(&self) {
+        let subject = format!("{}.{SECRETS_CHANGED_OPERATION}", self.subject());
+        if let Err(e) = self.client.publish(subject, Bytes::new()).await {
+            warn!(error = %e, "failed to notify hosts of changed secrets");
+        }
+    }
+
+    /// Run the secrets backend. This function will block until the NATS connection is closed.
+    ///
+    /// While running, the secrets file is checked for changes, e.g. secrets put or deleted by the
+    /// CLI, and hosts are notified when it changed.
+    pub async fn run(&self) -> anyhow::Result<()> {
+        let subject = format!("{}.>", self.subject());
+        info!(subject, "Starting listener");
+        let mut sub = self.client.subscribe(subject).await?;
+
+        let file = self.store.file_path();
+        let mut file_modified = modified(file).await;
+        let mut file_polls = tokio::time::interval(FILE_POLL_INTERVAL);
+        loop {
+            let msg = tokio::select! {
+                msg = sub.next() => match msg {
+                    Some(msg) => msg,
+                    None => break,
+                },
+                _ = file_polls.tick() => {
+                    let current = modified(file).await;
+                    if current != file_modified {
+                        file_modified = current;
+                        self.notify_changed().await;
+                    }
+                    continue;
+                }
+            };
+            let Some(reply) = msg.reply.clone() else {
+                continue;
+            };
+
+            let parts: Vec<&str> = msg
+                .subject
+                .trim_start_matches(&self.subject_base)
+                .split('.')
+                .collect();
+            match parts.get(OPERATION_INDEX) {
+                Some(&"server_xkey") => {
+                    let _ = self
+                        .client
+                        .publish(reply, self.server_xkey().public_key().into())
+                        .await;
+                }
+                Some(&"get") => self.handle_get_secret(&msg, reply).await,
+                Some(o) => {
+                    let _ = self
+                        .client
+                        .publish(reply, format!("unknown operation {o}").into())
+                        .await;
+                }
+                None => {
+                    let _ = self.client.publish(reply, "invalid subject".into()).await;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SecretsServer for Api {
+    async fn get(&self, request: SecretRequest) -> Result<SecretResponse, GetSecretError> {
+        // First validate the entity JWT
+        if let Err(e) = request.context.valid_claims() {
+            return Err(GetSecretError::InvalidEntityJWT(e.to_string()));
+        }
+
+        // Next, validate the host JWT
+        let host_claims: Claims<Host> = Claims::decode(&request.context.host_jwt)
+            .map_err(|e| GetSecretError::InvalidHostJWT(e.to_string()))?;
+        if let Err(e) = validate_token::<Host>(&request.context.host_jwt) {
+            return Err(GetSecretError::InvalidHostJWT(e.to_string()));
+        };
+        if host_claims.issuer.starts_with('N') {
+            warn!("Host JWT issued by a non-account key");
+        }
+
+        let component_claims: wascap::Result<Claims<Component>> =
+            Claims::decode(&request.context.entity_jwt);
+        let provider_claims: wascap::Result<Claims<CapabilityProvider>> =
+            Claims::decode(&request.context.entity_jwt);
+        let subject = match (component_claims, provider_claims) {
+            (Ok(c), _) => c.subject,
+            (_, Ok(p)) => p.subject,
+            (Err(e), _) => return Err(GetSecretError::InvalidEntityJWT(e.to_string())),
+        };
+
+        // Local secrets are not versioned
+        if request.version.is_some() {
+            return Err(GetSecretError::SecretNotFound);
+        }
+
+        let mut secret = Secret {
+            version: "0".to_string(),
+            ..Default::default()
+        };
+        match self
+            .store
+            .get(&subject, &request.key, request.field.as_deref())
+            .await?
+        {
+            StoredSecret::String(s) => secret.string_secret = Some(s),
+            StoredSecret::Binary(b) => secret.binary_secret = Some(b),
+        }
+        Ok(SecretResponse {
+            secret: Some(secret),
+            ..Default::default()
+        })
+    }
+
+    fn server_xkey(&self) -> XKey {
+        XKey::from_public_key(self.server_transit_xkey.public_key().as_str())
+            .expect("public key of a valid XKey is valid")
+    }
+}
@@ -0,0 +1,5 @@
+pub mod api;
+pub use api::*;
+
+pub mod store;
+pub use store::*;
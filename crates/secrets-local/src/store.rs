@@ -0,0 +1,233 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use nkeys::XKey;
+use serde::{Deserialize, Serialize};
+use wasmcloud_secrets_types::GetSecretError;
+
+/// A secret value stored in a [`SecretsFile`]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StoredSecret {
+    String(String),
+    Binary(Vec<u8>),
+}
+
+/// The decrypted contents of a secrets file.
+///
+/// On disk, the JSON encoding of this struct is encrypted with the encryption XKey of the backend.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SecretsFile {
+    /// The secrets in the file, by name
+    #[serde(default)]
+    pub secrets: BTreeMap<String, StoredSecret>,
+    /// The names of the secrets that entities, identified by the subject of their claims, are
+    /// allowed to access. Entities without a mapping can't access any secret.
+    #[serde(default)]
+    pub mappings: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl SecretsFile {
+    /// Read and decrypt the secrets file at `path`. Returns an empty [`SecretsFile`] if the file
+    /// does not exist yet.
+    pub async fn load(path: impl AsRef<Path>, encryption_xkey: &XKey) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let encrypted = match tokio::fs::read(path).await {
+            Ok(encrypted) => encrypted,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("failed to read secrets file '{}'", path.display()))
+            }
+        };
+        let decrypted = encryption_xkey
+            .open(&encrypted, encryption_xkey)
+            .with_context(|| format!("failed to decrypt secrets file '{}'", path.display()))?;
+        serde_json::from_slice(&decrypted)
+            .with_context(|| format!("failed to parse secrets file '{}'", path.display()))
+    }
+
+    /// Encrypt and write the secrets file to `path`. The file is replaced atomically, so that a
+    /// running backend never reads a partially written file.
+    pub async fn save(&self, path: impl AsRef<Path>, encryption_xkey: &XKey) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let encoded = serde_json::to_vec(self).context("failed to encode secrets file")?;
+        let encrypted = encryption_xkey
+            .seal(&encoded, encryption_xkey)
+            .context("failed to encrypt secrets file")?;
+        let tmp = path.with_extension("tmp");
+        tokio::fs::write(&tmp, encrypted)
+            .await
+            .with_context(|| format!("failed to write secrets file '{}'", tmp.display()))?;
+        tokio::fs::rename(&tmp, path)
+            .await
+            .with_context(|| format!("failed to replace secrets file '{}'", path.display()))
+    }
+}
+
+/// The sources the local secrets backend serves secrets from
+pub struct Store {
+    /// Path of the encrypted secrets file
+    file: PathBuf,
+    /// The XKey the secrets file is encrypted with
+    xkey: XKey,
+    /// Prefix of environment variables secrets are read from
+    env_prefix: Option<String>,
+}
+
+impl Store {
+    /// Create a new store serving secrets from the file at `file`, encrypted with
+    /// `encryption_xkey`, and, if `env_prefix` is provided, from environment variables starting
+    /// with it. Secrets in the file take precedence.
+    ///
+    /// The file is required even if all secrets are read from environment variables, since it
+    /// holds the mappings that grant entities access to secrets. It is read on every request, so
+    /// changes to it are picked up without a restart.
+    pub fn new(file: PathBuf, encryption_xkey: XKey, env_prefix: Option<String>) -> Self {
+        Self {
+            file,
+            xkey: encryption_xkey,
+            env_prefix,
+        }
+    }
+
+    /// The path of the secrets file served from
+    pub fn file_path(&self) -> &Path {
+        &self.file
+    }
+
+    /// The name of the environment variable the secret `key` is read from, which is the key,
+    /// uppercased and with all characters other than ASCII alphanumerics replaced by `_`, appended
+    /// to the configured prefix. E.g. `db-password` with prefix `WASMCLOUD_SECRET_` is read from
+    /// `WASMCLOUD_SECRET_DB_PASSWORD`.
+    pub fn env_var_name(prefix: &str, key: &str) -> String {
+        let key: String = key
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_uppercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        format!("{prefix}{key}")
+    }
+
+    /// Get the secret `key` on behalf of `entity`, the subject of the requesting entity's claims.
+    ///
+    /// If `field` is provided, the secret must be a JSON object, and the string value of `field`
+    /// in that object is returned.
+    pub async fn get(
+        &self,
+        entity: &str,
+        key: &str,
+        field: Option<&str>,
+    ) -> Result<StoredSecret, GetSecretError> {
+        let file = SecretsFile::load(&self.file, &self.xkey)
+            .await
+            .map_err(|e| GetSecretError::UpstreamError(format!("{e:#}")))?;
+        if !file
+            .mappings
+            .get(entity)
+            .is_some_and(|secrets| secrets.contains(key))
+        {
+            return Err(GetSecretError::Unauthorized);
+        }
+
+        let secret = file
+            .secrets
+            .get(key)
+            .cloned()
+            .or_else(|| {
+                let prefix = self.env_prefix.as_ref()?;
+                std::env::var(Self::env_var_name(prefix, key))
+                    .ok()
+                    .map(StoredSecret::String)
+            })
+            .ok_or(GetSecretError::SecretNotFound)?;
+
+        let Some(field) = field else {
+            return Ok(secret);
+        };
+        let StoredSecret::String(secret) = secret else {
+            return Err(GetSecretError::InvalidRequest);
+        };
+        match serde_json::from_str::<serde_json::Value>(&secret)
+            .map_err(|_| GetSecretError::InvalidRequest)?
+            .get(field)
+        {
+            Some(serde_json::Value::String(value)) => Ok(StoredSecret::String(value.clone())),
+            Some(value) => Ok(StoredSecret::String(value.to_string())),
+            None => Err(GetSecretError::SecretNotFound),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn file_round_trip_and_lookup() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("secrets");
+        let xkey = XKey::new();
+
+        let mut file = SecretsFile::load(&path, &xkey).await?;
+        assert_eq!(file, SecretsFile::default());
+        file.secrets.insert(
+            "db".into(),
+            StoredSecret::String(r#"{"user":"admin","password":"hunter2"}"#.into()),
+        );
+        file.secrets
+            .insert("cert".into(), StoredSecret::Binary(vec![1, 2, 3]));
+        file.save(&path, &xkey).await?;
+        assert!(SecretsFile::load(&path, &XKey::new()).await.is_err());
+
+        let store = Store::new(path.clone(), XKey::from_seed(&xkey.seed()?)?, None);
+        // Access is denied without mappings
+        assert!(matches!(
+            store.get("MENTITY", "cert", None).await,
+            Err(GetSecretError::Unauthorized)
+        ));
+
+        file.mappings.insert(
+            "MENTITY".into(),
+            BTreeSet::from(["cert".into(), "db".into(), "missing".into()]),
+        );
+        file.save(&path, &xkey).await?;
+        assert_eq!(
+            store.get("MENTITY", "cert", None).await?,
+            StoredSecret::Binary(vec![1, 2, 3])
+        );
+        assert_eq!(
+            store.get("MENTITY", "db", Some("password")).await?,
+            StoredSecret::String("hunter2".into())
+        );
+        assert!(matches!(
+            store.get("MENTITY", "missing", None).await,
+            Err(GetSecretError::SecretNotFound)
+        ));
+
+        file.mappings
+            .insert("MOTHER".into(), BTreeSet::from(["cert".into()]));
+        file.save(&path, &xkey).await?;
+        assert!(matches!(
+            store.get("MOTHER", "db", None).await,
+            Err(GetSecretError::Unauthorized)
+        ));
+        assert!(store.get("MOTHER", "cert", None).await.is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn env_var_names() {
+        assert_eq!(
+            Store::env_var_name("WASMCLOUD_SECRET_", "db-password.v1"),
+            "WASMCLOUD_SECRET_DB_PASSWORD_V1"
+        );
+    }
+}
@@ -1,8 +1,10 @@
+use anyhow::{anyhow, bail, ensure, Context as _};
 use async_nats::{
     jetstream::{
         self,
+        consumer::{pull::Config as PullConfig, AckPolicy},
         context::KeyValueError,
-        kv::{Config, Entry, History, Store},
+        kv::{Config, Entry, History, Operation, Store},
         publish::PublishAck,
         response::Response,
         stream::{Config as StreamConfig, DiscardPolicy, StorageType},
@@ -11,10 +13,21 @@ use async_nats::{
 };
 use async_trait::async_trait;
 use backoff::{future::retry, Error as BackoffError, ExponentialBackoffBuilder};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
 use bytes::Bytes;
-use futures::StreamExt;
+use futures::{stream::FuturesUnordered, StreamExt, TryStreamExt};
 use nkeys::XKey;
-use std::{collections::HashSet, time::Duration};
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashSet, VecDeque},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, RwLock,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use time::format_description::well_known::Rfc3339;
+use tokio::task::JoinHandle;
 use tracing::{error, info, warn};
 use wascap::jwt::{CapabilityProvider, Host};
 use wascap::prelude::{validate_token, Claims, Component};
@@ -24,14 +37,23 @@ use crate::types::*;
 
 const OPERATION_INDEX: usize = 3;
 
+/// How long locks are held at most before they expire, unless they are renewed.
+const LOCK_MAX_AGE: Duration = Duration::from_secs(3);
+/// How often locks held by long-running operations are renewed.
+const LOCK_RENEW_INTERVAL: Duration = Duration::from_secs(1);
+
 /// The `Api` struct implements the functionality of this secrets backend.
 pub struct Api {
     /// The server's public XKey, used to decrypt secrets sent to the server.
     server_transit_xkey: XKey,
     /// The encryption key used to encrypt secrets in NATS KV.
     /// This _must_ always be the same value after the first time a secret is written otherwise you
-    /// will *not* able to decrypt it!
-    encryption_xkey: XKey,
+    /// will *not* able to decrypt it! Use the `reencrypt` operation to change it.
+    encryption_xkey: RwLock<Arc<XKey>>,
+    /// The other key of an ongoing re-encryption. While re-encrypting, the bucket holds versions
+    /// encrypted with both keys, so versions that can't be decrypted with the current key are
+    /// decrypted with this one.
+    reencryption_xkey: RwLock<Option<Arc<XKey>>>,
     /// The NATS client used to communicate with wasmCloud hosts and the KV backend.
     pub client: async_nats::Client,
     /// The base subject for all secrets operations. Should default to `wasmcloud.secrets`.
@@ -42,6 +64,11 @@ pub struct Api {
     pub bucket: String,
     /// The maximum number of revisions to keep for each secret.
     max_secret_history: usize,
+    /// How long audit records are kept, zero keeps them forever.
+    audit_max_age: Duration,
+    /// The maximum size of the audit stream in bytes, older records are discarded once it is
+    /// reached. Zero or less is unlimited.
+    audit_max_bytes: i64,
     /// The prefix to use for the name of the queue subscription group that this backend belongs
     /// to.
     queue_base: String,
@@ -85,7 +112,7 @@ impl Api {
             discard_new_per_subject: true,
             storage: StorageType::Memory,
             max_messages_per_subject: 1,
-            max_age: Duration::from_secs(3),
+            max_age: LOCK_MAX_AGE,
             subjects: vec![format!("{}.*", self.lock_stream_name())],
             ..Default::default()
         })
@@ -93,6 +120,228 @@ impl Api {
         Ok(())
     }
 
+    /// The name of the stream recording every request to read a secret.
+    pub fn audit_stream_name(&self) -> String {
+        format!("SECRETS_{}_audit", self.name)
+    }
+
+    /// Retrieve the append-only stream used to record access to secrets, applying the configured
+    /// retention if it already exists. Records are published on `{stream}.{entity}.{secret}`, see
+    /// [`audit_subject_token`], so they can be filtered by the requesting entity and the secret.
+    async fn ensure_audit_stream(&self) -> anyhow::Result<()> {
+        let name = self.audit_stream_name();
+        let js = jetstream::new(self.client.clone());
+        let config = StreamConfig {
+            name: name.clone(),
+            description: Some("Audit trail of access to secrets".to_string()),
+            storage: StorageType::File,
+            deny_delete: true,
+            deny_purge: true,
+            max_age: self.audit_max_age,
+            max_bytes: self.audit_max_bytes,
+            subjects: vec![format!("{name}.*.*")],
+            ..Default::default()
+        };
+        js.get_or_create_stream(config.clone()).await?;
+        js.update_stream(config)
+            .await
+            .context("failed to apply retention of audit stream")?;
+        Ok(())
+    }
+
+    /// The subject records of `entity` requesting `secret` are published on in the audit stream.
+    fn audit_subject(&self, entity: &str, secret: &str) -> String {
+        format!(
+            "{}.{entity}.{}",
+            self.audit_stream_name(),
+            audit_subject_token(secret)
+        )
+    }
+
+    /// Record the outcome of a request to read a secret in the audit stream.
+    async fn record_access(
+        &self,
+        mut record: AuditRecord,
+        response: &Result<SecretResponse, GetSecretError>,
+    ) -> anyhow::Result<()> {
+        match response {
+            Ok(SecretResponse {
+                secret: Some(secret),
+                ..
+            }) => {
+                record.version = Some(secret.version.clone());
+                record.outcome = AuditOutcome::Allowed;
+            }
+            Ok(_) => {}
+            Err(e) => record.reason = Some(e.to_string()),
+        }
+        // NOTE: Public keys are valid subject tokens, anything else is recorded as unknown
+        let token = if is_public_key_token(&record.entity) {
+            record.entity.as_str()
+        } else {
+            "unknown"
+        };
+        let js = jetstream::new(self.client.clone());
+        js.publish(
+            self.audit_subject(token, &record.secret),
+            serde_json::to_vec(&record)?.into(),
+        )
+        .await?
+        .await?;
+        Ok(())
+    }
+
+    /// Handle a query of the audit stream. Only holders of the transit xkey seed may query it, so
+    /// the query must be sealed with the transit xkey for itself. The response is sealed the same
+    /// way.
+    async fn handle_query_audit(&self, msg: &Message, reply: Subject) {
+        let server_xkey = self.server_xkey();
+        let result = async {
+            let payload = self
+                .server_transit_xkey
+                .open(&msg.payload, &server_xkey)
+                .context(
+                    "failed to decrypt request, audit queries must be sealed with the transit xkey",
+                )?;
+            let query = serde_json::from_slice(&payload).context("invalid request")?;
+            self.query_audit(query).await
+        }
+        .await;
+        let payload = match result.and_then(|v| Ok(serde_json::to_vec(&v)?)) {
+            Ok(payload) => payload,
+            Err(e) => e.to_string().into_bytes(),
+        };
+        match self.server_transit_xkey.seal(&payload, &server_xkey) {
+            Ok(sealed) => {
+                let _ = self.client.publish(reply, sealed.into()).await;
+            }
+            Err(e) => error!(error = %e, "failed to seal audit query response"),
+        }
+    }
+
+    /// Query the audit stream for records of requests to read secrets, oldest first.
+    pub async fn query_audit(&self, query: AuditQuery) -> anyhow::Result<Vec<AuditRecord>> {
+        let entity = match query.entity.as_deref() {
+            Some(entity) => {
+                ensure!(
+                    is_public_key_token(entity),
+                    "invalid entity public key {entity}"
+                );
+                entity
+            }
+            None => "*",
+        };
+        let filter_subject = match query.secret.as_deref() {
+            Some(secret) => self.audit_subject(entity, secret),
+            None => format!("{}.{entity}.*", self.audit_stream_name()),
+        };
+        let js = jetstream::new(self.client.clone());
+        let stream = js.get_stream(self.audit_stream_name()).await?;
+        let consumer = stream
+            .create_consumer(PullConfig {
+                description: Some("secrets audit query".to_string()),
+                filter_subject,
+                ack_policy: AckPolicy::None,
+                inactive_threshold: Duration::from_secs(30),
+                ..Default::default()
+            })
+            .await?;
+
+        let mut records = VecDeque::new();
+        loop {
+            let mut batch = consumer.fetch().max_messages(256).messages().await?;
+            let mut received = 0;
+            while let Some(msg) = batch.next().await {
+                let msg = msg.map_err(|e| anyhow::anyhow!("failed to read audit record: {e}"))?;
+                received += 1;
+                let record: AuditRecord = match serde_json::from_slice(&msg.payload) {
+                    Ok(record) => record,
+                    Err(e) => {
+                        warn!(error = %e, "skipping invalid audit record");
+                        continue;
+                    }
+                };
+                records.push_back(record);
+                if query.limit.is_some_and(|limit| records.len() > limit) {
+                    records.pop_front();
+                }
+            }
+            if received == 0 {
+                break;
+            }
+        }
+        Ok(records.into())
+    }
+
+    /// The subject of the lock held while writing to the secrets bucket.
+    fn secrets_lock_subject(&self) -> String {
+        // NOTE: Locks on the state bucket use the entity public key, which is never lowercase
+        format!("{}.secrets", self.lock_stream_name())
+    }
+
+    /// Release a lock acquired with [`Self::get_lock`].
+    async fn release_lock(&self, sequence: u64) -> anyhow::Result<()> {
+        let js = jetstream::new(self.client.clone());
+        js.get_stream(&self.lock_stream_name())
+            .await?
+            .delete_message(sequence)
+            .await?;
+        Ok(())
+    }
+
+    /// Ensure that the lock with `sequence` is still held, i.e. it neither expired nor was lost
+    /// while renewing it.
+    async fn ensure_lock_held(&self, sequence: u64) -> anyhow::Result<()> {
+        let js = jetstream::new(self.client.clone());
+        js.get_stream(&self.lock_stream_name())
+            .await?
+            .get_raw_message(sequence)
+            .await
+            .map_err(|e| anyhow!(e).context("lock is no longer held"))?;
+        Ok(())
+    }
+
+    /// The current encryption key used to encrypt secrets in NATS KV.
+    fn encryption_xkey(&self) -> Arc<XKey> {
+        Arc::clone(
+            &self
+                .encryption_xkey
+                .read()
+                .expect("encryption xkey lock poisoned"),
+        )
+    }
+
+    /// Decrypt a version of a secret stored in NATS KV, using the other key of an ongoing
+    /// re-encryption if the version can't be decrypted with the current key.
+    fn decrypt(&self, value: &[u8]) -> Option<Vec<u8>> {
+        let current = self.encryption_xkey();
+        current.open(value, &current).ok().or_else(|| {
+            let other = self
+                .reencryption_xkey
+                .read()
+                .expect("re-encryption xkey lock poisoned")
+                .clone()?;
+            other.open(value, &other).ok()
+        })
+    }
+
+    /// Sets the other key of an ongoing re-encryption, see [`Self::decrypt`].
+    fn set_reencryption_xkey(&self, xkey: Option<Arc<XKey>>) {
+        *self
+            .reencryption_xkey
+            .write()
+            .expect("re-encryption xkey lock poisoned") = xkey;
+    }
+
+    /// Reply with the JSON encoding of `result`, or the error message if it failed.
+    async fn reply_json<T: Serialize>(&self, reply: Subject, result: anyhow::Result<T>) {
+        let payload = match result.and_then(|v| Ok(serde_json::to_vec(&v)?)) {
+            Ok(payload) => payload.into(),
+            Err(e) => e.to_string().into(),
+        };
+        let _ = self.client.publish(reply, payload).await;
+    }
+
     async fn handle_put_secret(&self, msg: &Message, reply: Subject) {
         let js = jetstream::new(self.client.clone());
         let payload = &msg.payload;
@@ -165,14 +414,27 @@ impl Api {
             }
         };
 
+        let lock = match self.get_lock(self.secrets_lock_subject()).await {
+            Ok(ack) => ack.sequence,
+            Err(e) => {
+                let _ = self.client.publish(reply, e.to_string().into()).await;
+                return;
+            }
+        };
+        self.put_secret_locked(&store, secret, reply).await;
+        if let Err(e) = self.release_lock(lock).await {
+            warn!(error = %e, "failed to release secrets lock");
+        }
+    }
+
+    async fn put_secret_locked(&self, store: &Store, secret: PutSecretRequest, reply: Subject) {
+        let encryption_xkey = self.encryption_xkey();
         let encrypted_value = if let Some(s) = secret.string_secret {
-            self.encryption_xkey
-                .seal(s.as_bytes(), &self.encryption_xkey)
+            encryption_xkey
+                .seal(s.as_bytes(), &encryption_xkey)
                 .unwrap()
         } else if let Some(b) = secret.binary_secret {
-            self.encryption_xkey
-                .seal(&b, &self.encryption_xkey)
-                .unwrap()
+            encryption_xkey.seal(&b, &encryption_xkey).unwrap()
         } else {
             let _ = self
                 .client
@@ -191,6 +453,7 @@ impl Api {
                     .client
                     .publish(reply, serde_json::to_string(&resp).unwrap().into())
                     .await;
+                self.notify_changed().await;
             }
             Err(e) => {
                 let _ = self.client.publish(reply, e.to_string().into()).await;
@@ -265,7 +528,21 @@ impl Api {
             }
         };
 
+        let record = audit_record(&secret_req);
         let response = self.get(secret_req).await;
+        // NOTE: Secrets are only returned if access to them was recorded
+        let response = match self.record_access(record, &response).await {
+            Ok(()) => response,
+            Err(e) => {
+                error!(error = %e, "failed to record access to secret");
+                match response {
+                    Ok(_) => Err(GetSecretError::UpstreamError(
+                        "failed to record access to secret".to_string(),
+                    )),
+                    Err(e) => Err(e),
+                }
+            }
+        };
         match response {
             Ok(resp) => {
                 let encoded: Bytes = resp.into();
@@ -301,6 +578,282 @@ impl Api {
         }
     }
 
+    /// Decrypt the payload of a request sealed for the transit xkey with the xkey in the
+    /// [`WASMCLOUD_HOST_XKEY`] header.
+    fn open_request(&self, msg: &Message) -> anyhow::Result<Vec<u8>> {
+        let host_key = msg
+            .headers
+            .as_ref()
+            .and_then(|headers| headers.get(WASMCLOUD_HOST_XKEY))
+            .context("missing request xkey header")?;
+        let host_key = XKey::from_public_key(host_key.as_str())?;
+        self.server_transit_xkey
+            .open(&msg.payload, &host_key)
+            .context("failed to decrypt request")
+    }
+
+    /// Decrypt the name of a secret from a sealed request.
+    fn open_key_request(&self, msg: &Message) -> anyhow::Result<String> {
+        let payload = self.open_request(msg)?;
+        String::from_utf8(payload).context("invalid secret name")
+    }
+
+    async fn handle_reencrypt(&self, msg: Message, reply: Subject) {
+        let result = async {
+            let payload = self.open_request(&msg)?;
+            let request: ReencryptRequest =
+                serde_json::from_slice(&payload).context("invalid request")?;
+            let current = XKey::from_seed(&request.current_encryption_xkey_seed)
+                .context("invalid current encryption xkey seed")?;
+            ensure!(
+                current.public_key() == self.encryption_xkey().public_key(),
+                "current encryption xkey does not match the key of the backend"
+            );
+            let new = XKey::from_seed(&request.new_encryption_xkey_seed)
+                .context("invalid new encryption xkey seed")?;
+            self.reencrypt(new).await
+        }
+        .await;
+        self.reply_json(reply, result).await;
+    }
+
+    /// List the names of all secrets, excluding deleted secrets.
+    pub async fn list_secrets(&self) -> anyhow::Result<Vec<String>> {
+        let js = jetstream::new(self.client.clone());
+        let store = js.get_key_value(&self.bucket).await?;
+        let mut keys: Vec<String> = store.keys().await?.try_collect().await?;
+        keys.sort_unstable();
+        Ok(keys)
+    }
+
+    /// Delete a secret. Previous versions are kept and can still be retrieved by version.
+    pub async fn delete_secret(&self, key: &str) -> anyhow::Result<()> {
+        let js = jetstream::new(self.client.clone());
+        let store = js.get_key_value(&self.bucket).await?;
+        let lock = self.get_lock(self.secrets_lock_subject()).await?.sequence;
+        let result = async {
+            match store.entry(key).await? {
+                Some(Entry {
+                    operation: Operation::Put,
+                    ..
+                }) => Ok(store.delete(key).await?),
+                _ => bail!("secret {key} not found"),
+            }
+        }
+        .await;
+        if let Err(e) = self.release_lock(lock).await {
+            warn!(error = %e, "failed to release secrets lock");
+        }
+        if result.is_ok() {
+            self.notify_changed().await;
+        }
+        result
+    }
+
+    /// Notifies hosts that secrets changed, so that they re-fetch the secrets of running
+    /// components and providers.
+    async fn notify_changed(&self) {
+        let subject = format!("{}.{SECRETS_CHANGED_OPERATION}", self.subject());
+        if let Err(e) = self.client.publish(subject, Bytes::new()).await {
+            warn!(error = %e, "failed to notify hosts of changed secrets");
+        }
+    }
+
+    /// Returns the version history of a secret, oldest first. Values are never included.
+    pub async fn secret_history(&self, key: &str) -> anyhow::Result<Vec<SecretVersion>> {
+        let js = jetstream::new(self.client.clone());
+        let store = js.get_key_value(&self.bucket).await?;
+        // NOTE: The history of a key that was never written never completes
+        ensure!(store.entry(key).await?.is_some(), "secret {key} not found");
+        store
+            .history(key)
+            .await?
+            .map_err(anyhow::Error::from)
+            .and_then(|entry| async move {
+                Ok(SecretVersion {
+                    version: entry.revision.to_string(),
+                    created_at: entry.created.format(&Rfc3339)?,
+                    deleted: !matches!(entry.operation, Operation::Put),
+                })
+            })
+            .try_collect()
+            .await
+    }
+
+    /// Re-encrypt every version of every secret with `new_key` and use it for all further
+    /// operations.
+    ///
+    /// All versions are decrypted before anything is written, and writes are rolled back if any of
+    /// them fails, so the bucket is either fully re-encrypted or left unchanged. Writers are
+    /// excluded using the secrets lock, which is renewed for the duration of the operation. If the
+    /// lock is lost before all versions are written, re-encryption is rolled back. Versions are
+    /// decrypted with either key until the operation completes.
+    ///
+    /// Since revisions are sequence numbers of the bucket stream, every version gets a new
+    /// revision and previous revisions can no longer be requested. The new revision of every
+    /// previous one is returned.
+    pub async fn reencrypt(&self, new_key: XKey) -> anyhow::Result<ReencryptResponse> {
+        let subject = self.secrets_lock_subject();
+        let lock = Arc::new(AtomicU64::new(
+            self.get_lock(subject.clone()).await?.sequence,
+        ));
+        let renewal = self.spawn_lock_renewal(subject, Arc::clone(&lock));
+        let result = self.reencrypt_locked(Arc::new(new_key), &lock).await;
+        renewal.abort();
+        self.set_reencryption_xkey(None);
+        if let Err(e) = self.release_lock(lock.load(Ordering::SeqCst)).await {
+            warn!(error = %e, "failed to release secrets lock");
+        }
+        result
+    }
+
+    /// Keep renewing the lock with the sequence stored in `lock` on `subject` until the returned
+    /// task is aborted, storing the sequence of every renewed lock in `lock`.
+    fn spawn_lock_renewal(&self, subject: String, lock: Arc<AtomicU64>) -> JoinHandle<()> {
+        let js = jetstream::new(self.client.clone());
+        let stream = self.lock_stream_name();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(LOCK_RENEW_INTERVAL);
+            // The first tick completes immediately
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                match renew_lock(&js, &stream, subject.clone(), lock.load(Ordering::SeqCst)).await {
+                    Ok(sequence) => lock.store(sequence, Ordering::SeqCst),
+                    Err(e) => {
+                        error!(error = %e, subject, "failed to renew lock");
+                        return;
+                    }
+                }
+            }
+        })
+    }
+
+    async fn reencrypt_locked(
+        &self,
+        new_key: Arc<XKey>,
+        lock: &AtomicU64,
+    ) -> anyhow::Result<ReencryptResponse> {
+        let js = jetstream::new(self.client.clone());
+        let store = js.get_key_value(&self.bucket).await?;
+        let current_key = self.encryption_xkey();
+
+        // Collect and decrypt every version of every secret, including deleted ones
+        let mut versions = Vec::new();
+        if store.status().await?.values() > 0 {
+            let mut latest = store.watch_with_history(">").await?;
+            let mut keys = Vec::new();
+            while let Some(entry) = latest.next().await {
+                let entry = entry?;
+                let done = entry.delta == 0;
+                keys.push(entry.key);
+                if done {
+                    break;
+                }
+            }
+            for key in keys {
+                let mut history = store.history(&key).await?;
+                while let Some(entry) = history.next().await {
+                    let entry = entry?;
+                    let value = match entry.operation {
+                        Operation::Put => {
+                            Some(current_key.open(&entry.value, &current_key).with_context(
+                                || {
+                                    format!(
+                                        "failed to decrypt version {} of secret {key}",
+                                        entry.revision
+                                    )
+                                },
+                            )?)
+                        }
+                        Operation::Delete | Operation::Purge => None,
+                    };
+                    versions.push((entry.revision, key.clone(), value));
+                }
+            }
+        }
+        versions.sort_unstable_by_key(|(revision, ..)| *revision);
+
+        // Allow the bucket to temporarily hold both the old and new versions of every secret, so
+        // that no old version is discarded before all new versions are written
+        let mut stream = js.get_stream(format!("KV_{}", self.bucket)).await?;
+        let config = stream.info().await?.config.clone();
+        js.update_stream(StreamConfig {
+            max_messages_per_subject: config.max_messages_per_subject.saturating_mul(2),
+            ..config.clone()
+        })
+        .await
+        .context("failed to extend history of secrets bucket")?;
+
+        self.set_reencryption_xkey(Some(Arc::clone(&new_key)));
+        let mut written: Vec<(u64, u64, String)> = Vec::with_capacity(versions.len());
+        let mut result = Ok(());
+        for (revision, key, value) in &versions {
+            let write = async {
+                match value {
+                    Some(value) => {
+                        let encrypted = new_key.seal(value, &new_key)?;
+                        Ok(store.put(key, encrypted.into()).await?)
+                    }
+                    // Delete markers don't return a revision, so look it up
+                    None => {
+                        store.delete(key).await?;
+                        store
+                            .entry(key)
+                            .await?
+                            .map(|entry| entry.revision)
+                            .context("delete marker not found after writing it")
+                    }
+                }
+            };
+            match write.await {
+                Ok(new_revision) => written.push((*revision, new_revision, key.clone())),
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            }
+        }
+        // Writers may have used the current key if the lock was lost, so only switch to the new
+        // key if the lock is still held
+        if result.is_ok() {
+            result = self.ensure_lock_held(lock.load(Ordering::SeqCst)).await;
+        }
+        if result.is_ok() {
+            *self
+                .encryption_xkey
+                .write()
+                .expect("encryption xkey lock poisoned") = new_key;
+            self.set_reencryption_xkey(Some(current_key));
+        }
+
+        // Discard either the new versions to roll back, or the old versions on success
+        let discard: Vec<u64> = if result.is_ok() {
+            written.iter().map(|(old, ..)| *old).collect()
+        } else {
+            written.iter().map(|(_, new, _)| *new).collect()
+        };
+        for sequence in discard {
+            if let Err(e) = stream.delete_message(sequence).await {
+                error!(error = %e, sequence, "failed to discard version during re-encryption");
+            }
+        }
+        if let Err(e) = js.update_stream(config).await {
+            error!(error = %e, "failed to restore history of secrets bucket");
+        }
+        result.context("failed to write re-encrypted secrets, re-encryption was rolled back")?;
+        info!(versions = written.len(), "re-encrypted secrets");
+
+        let mut revisions: BTreeMap<String, Vec<RevisionChange>> = BTreeMap::new();
+        for (previous, current, key) in written {
+            revisions
+                .entry(key)
+                .or_default()
+                .push(RevisionChange { previous, current });
+        }
+        Ok(ReencryptResponse { revisions })
+    }
+
     /// Run the secrets backend. This function will block until the NATS connection is closed.
     pub async fn run(&self) -> anyhow::Result<()> {
         let queue_name = self.queue_name();
@@ -348,8 +901,18 @@ impl Api {
         };
 
         self.ensure_state_lock_stream().await?;
+        self.ensure_audit_stream().await?;
 
-        while let Some(msg) = sub.next().await {
+        // Re-encryption runs alongside other requests, so that secrets can be read meanwhile
+        let mut reencryption = FuturesUnordered::new();
+        loop {
+            let msg = tokio::select! {
+                msg = sub.next() => match msg {
+                    Some(msg) => msg,
+                    None => break,
+                },
+                Some(()) = reencryption.next(), if !reencryption.is_empty() => continue,
+            };
             let reply = match &msg.reply {
                 Some(reply) => reply.clone(),
                 None => continue,
@@ -445,6 +1008,42 @@ impl Api {
                 "put_secret" => {
                     self.handle_put_secret(&msg, reply).await;
                 }
+                "list_secrets" => {
+                    let result = match self.open_request(&msg) {
+                        Ok(_) => self.list_secrets().await,
+                        Err(e) => Err(e),
+                    };
+                    self.reply_json(reply, result).await;
+                }
+                "delete_secret" => {
+                    let result = match self.open_key_request(&msg) {
+                        Ok(key) => self.delete_secret(&key).await,
+                        Err(e) => Err(e),
+                    };
+                    let payload = match result {
+                        Ok(()) => "ok".to_string(),
+                        Err(e) => e.to_string(),
+                    };
+                    let _ = self.client.publish(reply, payload.into()).await;
+                }
+                "secret_history" => {
+                    let result = match self.open_key_request(&msg) {
+                        Ok(key) => self.secret_history(&key).await,
+                        Err(e) => Err(e),
+                    };
+                    self.reply_json(reply, result).await;
+                }
+                "reencrypt" => {
+                    if reencryption.is_empty() {
+                        reencryption.push(self.handle_reencrypt(msg, reply));
+                    } else {
+                        self.reply_json::<()>(reply, Err(anyhow!("re-encryption is in progress")))
+                            .await;
+                    }
+                }
+                "query_audit" => {
+                    self.handle_query_audit(&msg, reply).await;
+                }
                 o => {
                     let _ = self
                         .client
@@ -556,17 +1155,22 @@ impl Api {
         name: String,
         bucket: String,
         max_secret_history: usize,
+        audit_max_age: Duration,
+        audit_max_bytes: i64,
         queue_base: String,
         api_version: String,
     ) -> Self {
         Self {
             server_transit_xkey: server_xkey,
-            encryption_xkey,
+            encryption_xkey: RwLock::new(Arc::new(encryption_xkey)),
+            reencryption_xkey: RwLock::new(None),
             client,
             subject_base,
             name,
             bucket,
             max_secret_history,
+            audit_max_age,
+            audit_max_bytes,
             queue_base,
             api_version,
         }
@@ -648,11 +1252,10 @@ impl SecretsServer for Api {
                 .map_err(|e| GetSecretError::UpstreamError(e.to_string()))?,
         };
 
-        if entry.is_none() {
+        // Deleted secrets and delete markers in the history are not found
+        let Some(entry) = entry.filter(|entry| matches!(entry.operation, Operation::Put)) else {
             return Err(GetSecretError::SecretNotFound);
-        }
-        // SAFETY: entry is not None, we just verified that
-        let entry = entry.unwrap();
+        };
 
         let mut secret = Secret {
             version: entry.revision.to_string(),
@@ -660,9 +1263,8 @@ impl SecretsServer for Api {
         };
 
         let decrypted = self
-            .encryption_xkey
-            .open(&entry.value, &self.encryption_xkey)
-            .map_err(|_| GetSecretError::DecryptionError)?;
+            .decrypt(&entry.value)
+            .ok_or(GetSecretError::DecryptionError)?;
 
         match String::from_utf8(decrypted) {
             Ok(s) => {
@@ -686,6 +1288,74 @@ impl SecretsServer for Api {
     }
 }
 
+/// Create an audit record of a request to read a secret. The outcome is denied until the request
+/// is handled.
+fn audit_record(request: &SecretRequest) -> AuditRecord {
+    // NOTE: Claims are only decoded here, they are validated when handling the request
+    let entity = Claims::<Component>::decode(&request.context.entity_jwt)
+        .map(|claims| claims.subject)
+        .or_else(|_| {
+            Claims::<CapabilityProvider>::decode(&request.context.entity_jwt)
+                .map(|claims| claims.subject)
+        })
+        .unwrap_or_default();
+    let host = Claims::<Host>::decode(&request.context.host_jwt)
+        .map(|claims| claims.subject)
+        .unwrap_or_default();
+    AuditRecord {
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+        secret: request.key.clone(),
+        version: request.version.clone(),
+        entity,
+        host,
+        application: request.context.application.name.clone(),
+        outcome: AuditOutcome::Denied,
+        reason: None,
+    }
+}
+
+/// Encode `secret` as a token of a NATS subject. Secret names may contain `.`, so they are encoded
+/// as URL-safe base64, which only uses characters valid in subject tokens.
+fn audit_subject_token(secret: &str) -> String {
+    if secret.is_empty() {
+        // NOTE: Base64 never encodes anything as a single character
+        return "_".to_string();
+    }
+    URL_SAFE_NO_PAD.encode(secret)
+}
+
+/// Whether `key` looks like a public key and can be used as a token of a NATS subject
+fn is_public_key_token(key: &str) -> bool {
+    !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+/// Renew the lock with `sequence` on `subject` in the lock `stream`, see [`Api::spawn_lock_renewal`].
+/// The lock is released and acquired again, so this fails if another writer acquired it in between
+/// or it already expired.
+async fn renew_lock(
+    js: &jetstream::Context,
+    stream: &str,
+    subject: String,
+    sequence: u64,
+) -> anyhow::Result<u64> {
+    ensure!(
+        js.get_stream(stream)
+            .await?
+            .delete_message(sequence)
+            .await?,
+        "lock expired"
+    );
+    let ack = js
+        .publish(subject, "lock".into())
+        .await?
+        .await
+        .context("lock was acquired by another writer")?;
+    Ok(ack.sequence)
+}
+
 async fn find_key_rev(h: &mut History, revision: u64) -> Option<Entry> {
     while let Some(entry) = h.next().await {
         if let Ok(entry) = entry {
@@ -4,7 +4,10 @@ use anyhow::{ensure, Context};
 
 pub const SECRETS_API_VERSION: &str = "v1alpha1";
 
-use crate::{PutSecretError, PutSecretRequest, PutSecretResponse};
+use crate::{
+    AuditQuery, AuditRecord, PutSecretError, PutSecretRequest, PutSecretResponse, ReencryptRequest,
+    ReencryptResponse, SecretVersion,
+};
 
 /// Helper function wrapper around [`put_secret`] that allows putting multiple secrets in the secret store.
 /// See the documentation for [`put_secret`] for more information.
@@ -128,3 +131,180 @@ pub async fn remove_mapping(
 
     Ok(())
 }
+
+/// Parse a JSON response of the secret store, which replies with the error message as text if the
+/// operation failed
+fn parse_response<T: serde::de::DeserializeOwned>(payload: &[u8]) -> anyhow::Result<T> {
+    serde_json::from_slice(payload)
+        .map_err(|_| anyhow::anyhow!("{}", String::from_utf8_lossy(payload)))
+}
+
+/// Send a request to the secret store with the payload sealed for the transit key, using a newly
+/// generated request xkey that is passed in the [`wasmcloud_secrets_types::WASMCLOUD_HOST_XKEY`]
+/// header
+async fn sealed_request(
+    nats_client: &async_nats::Client,
+    subject: String,
+    transit_xkey: &nkeys::XKey,
+    payload: &[u8],
+) -> anyhow::Result<async_nats::Message> {
+    let request_xkey = nkeys::XKey::new();
+    let mut headers = async_nats::HeaderMap::new();
+    headers.insert(
+        wasmcloud_secrets_types::WASMCLOUD_HOST_XKEY,
+        request_xkey
+            .public_key()
+            .parse::<async_nats::HeaderValue>()
+            .context("could not parse request xkey public key as header value")?,
+    );
+
+    let v = request_xkey
+        .seal(payload, transit_xkey)
+        .expect("should be able to encrypt the request");
+    Ok(nats_client
+        .request_with_headers(subject, headers, v.into())
+        .await?)
+}
+
+/// List the names of all secrets in the secret store. Deleted secrets are not included.
+///
+/// # Arguments
+/// - `nats_client` - the NATS client connected to a server that the secret store is listening on
+/// - `subject_base` - the base subject to use for requests to the secret store
+/// - `transit_xkey` - the transit key to use to encrypt the request. Can be constructed from a seed or public key
+pub async fn list_secrets(
+    nats_client: &async_nats::Client,
+    subject_base: &str,
+    transit_xkey: &nkeys::XKey,
+) -> anyhow::Result<Vec<String>> {
+    ensure!(!subject_base.is_empty(), "subject base cannot be empty");
+
+    let response = sealed_request(
+        nats_client,
+        format!("{subject_base}.{SECRETS_API_VERSION}.nats-kv.list_secrets"),
+        transit_xkey,
+        &[],
+    )
+    .await?;
+    parse_response(&response.payload)
+}
+
+/// Delete a secret from the secret store. Previous versions of the secret can still be retrieved
+/// by version until they are removed from the history of the store.
+///
+/// # Arguments
+/// - `nats_client` - the NATS client connected to a server that the secret store is listening on
+/// - `subject_base` - the base subject to use for requests to the secret store
+/// - `transit_xkey` - the transit key to use to encrypt the request. Can be constructed from a seed or public key
+/// - `key` - the name of the secret to delete
+pub async fn delete_secret(
+    nats_client: &async_nats::Client,
+    subject_base: &str,
+    transit_xkey: &nkeys::XKey,
+    key: &str,
+) -> anyhow::Result<()> {
+    ensure!(!subject_base.is_empty(), "subject base cannot be empty");
+    ensure!(!key.is_empty(), "key cannot be empty");
+
+    let response = sealed_request(
+        nats_client,
+        format!("{subject_base}.{SECRETS_API_VERSION}.nats-kv.delete_secret"),
+        transit_xkey,
+        key.as_bytes(),
+    )
+    .await?;
+    ensure!(
+        response.payload.as_ref() == b"ok",
+        "{}",
+        String::from_utf8_lossy(&response.payload)
+    );
+    Ok(())
+}
+
+/// Get the version history of a secret, oldest first. Values of the secret are not included.
+///
+/// # Arguments
+/// - `nats_client` - the NATS client connected to a server that the secret store is listening on
+/// - `subject_base` - the base subject to use for requests to the secret store
+/// - `transit_xkey` - the transit key to use to encrypt the request. Can be constructed from a seed or public key
+/// - `key` - the name of the secret
+pub async fn secret_history(
+    nats_client: &async_nats::Client,
+    subject_base: &str,
+    transit_xkey: &nkeys::XKey,
+    key: &str,
+) -> anyhow::Result<Vec<SecretVersion>> {
+    ensure!(!subject_base.is_empty(), "subject base cannot be empty");
+    ensure!(!key.is_empty(), "key cannot be empty");
+
+    let response = sealed_request(
+        nats_client,
+        format!("{subject_base}.{SECRETS_API_VERSION}.nats-kv.secret_history"),
+        transit_xkey,
+        key.as_bytes(),
+    )
+    .await?;
+    parse_response(&response.payload)
+}
+
+/// Re-encrypt all secrets in the secret store with a new encryption key. Either all versions of
+/// all secrets are re-encrypted, or none are.
+///
+/// Every version of every secret is written as a new version, so the returned response maps the
+/// previous revision numbers to the new ones. Other instances of the backend using the same bucket
+/// must be restarted with the new encryption key.
+///
+/// # Arguments
+/// - `nats_client` - the NATS client connected to a server that the secret store is listening on
+/// - `subject_base` - the base subject to use for requests to the secret store
+/// - `transit_xkey` - the transit key to use to encrypt the request. Can be constructed from a seed or public key
+/// - `request` - the seeds of the current and new encryption keys
+pub async fn reencrypt(
+    nats_client: &async_nats::Client,
+    subject_base: &str,
+    transit_xkey: &nkeys::XKey,
+    request: ReencryptRequest,
+) -> anyhow::Result<ReencryptResponse> {
+    ensure!(!subject_base.is_empty(), "subject base cannot be empty");
+
+    let value = serde_json::to_vec(&request).context("failed to serialize request")?;
+    let response = sealed_request(
+        nats_client,
+        format!("{subject_base}.{SECRETS_API_VERSION}.nats-kv.reencrypt"),
+        transit_xkey,
+        &value,
+    )
+    .await?;
+    parse_response(&response.payload)
+}
+
+/// Query the audit trail of requests to read secrets, oldest first.
+///
+/// # Arguments
+/// - `nats_client` - the NATS client connected to a server that the secret store is listening on
+/// - `subject_base` - the base subject to use for requests to the secret store
+/// - `transit_xkey` - the transit key of the secret store. Must be constructed from its seed, since only holders of the seed may query the audit trail
+/// - `query` - filters for the secret name and requesting entity, and the maximum number of records
+pub async fn query_audit(
+    nats_client: &async_nats::Client,
+    subject_base: &str,
+    transit_xkey: &nkeys::XKey,
+    query: AuditQuery,
+) -> anyhow::Result<Vec<AuditRecord>> {
+    ensure!(!subject_base.is_empty(), "subject base cannot be empty");
+
+    let value = serde_json::to_vec(&query).context("failed to serialize audit query")?;
+    let v = transit_xkey
+        .seal(&value, transit_xkey)
+        .context("failed to encrypt audit query, the transit xkey seed is required")?;
+    let response = nats_client
+        .request(
+            format!("{subject_base}.{SECRETS_API_VERSION}.nats-kv.query_audit"),
+            v.into(),
+        )
+        .await?;
+    let payload = transit_xkey
+        .open(&response.payload, transit_xkey)
+        .context("failed to decrypt audit query response")?;
+    parse_response(&payload)
+}
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 
 use anyhow::{ensure, Context};
 use clap::{Parser, Subcommand};
@@ -7,7 +7,7 @@ use secrets_nats_kv::client::SECRETS_API_VERSION;
 use secrets_nats_kv::Api;
 
 use secrets_nats_kv::client;
-use secrets_nats_kv::PutSecretRequest;
+use secrets_nats_kv::{AuditOutcome, AuditQuery, PutSecretRequest, ReencryptRequest};
 
 #[derive(Parser)]
 #[command(about, version, name = "secrets-nats-kv")]
@@ -24,6 +24,16 @@ enum Command {
     Run(RunCommand),
     /// Put a secret into the NATS KV secrets backend
     Put(PutCommand),
+    /// List the names of the secrets in the NATS KV secrets backend
+    List(ListCommand),
+    /// Delete a secret from the NATS KV secrets backend
+    Delete(SecretCommand),
+    /// Show the version history of a secret in the NATS KV secrets backend
+    History(SecretCommand),
+    /// Re-encrypt all secrets in the NATS KV secrets backend with a new encryption key
+    Reencrypt(ReencryptCommand),
+    /// Query the audit trail of requests to read secrets from the NATS KV secrets backend
+    Audit(AuditCommand),
     /// Add a secret mapping to the NATS KV secrets backend
     AddMapping(AddSecretMappingCommand),
     /// Remove a secret mapping from the NATS KV secrets backend
@@ -50,6 +60,12 @@ struct RunCommand {
     /// The maximum number of versions to keep for each secret
     #[clap(long, default_value = "64")]
     max_secret_history: usize,
+    /// How long to keep records of requests to read secrets in the audit stream, in seconds. Zero keeps them forever
+    #[clap(long, default_value = "0")]
+    audit_max_age_seconds: u64,
+    /// The maximum size of the audit stream in bytes, the oldest records are discarded once it is reached. Zero is unlimited
+    #[clap(long, default_value = "0")]
+    audit_max_bytes: u64,
     /// The NATS queue group to use for running multiple instances of the secrets backend
     #[clap(long, default_value = "wasmcloud_secrets")]
     nats_queue_base: String,
@@ -95,6 +111,75 @@ struct PutCommand {
     binary: Option<PathBuf>,
 }
 
+#[derive(Parser, Debug, Clone)]
+struct ListCommand {
+    /// The server's transit XKey, used to decrypt requests sent to the server.
+    #[clap(short, long, env = "TRANSIT_XKEY_SEED")]
+    transit_xkey_seed: String,
+    /// The NATS address to connect to where the backend is running
+    #[clap(long, default_value = "127.0.0.1:4222")]
+    nats_address: String,
+    /// The subject prefix to use for all requests to the secrets backend, defaults to `wasmcloud.secrets`
+    #[clap(short, long, default_value = "wasmcloud.secrets")]
+    subject_base: String,
+}
+
+#[derive(Parser, Debug, Clone)]
+struct SecretCommand {
+    /// The server's transit XKey, used to decrypt requests sent to the server.
+    #[clap(short, long, env = "TRANSIT_XKEY_SEED")]
+    transit_xkey_seed: String,
+    /// The NATS address to connect to where the backend is running
+    #[clap(long, default_value = "127.0.0.1:4222")]
+    nats_address: String,
+    /// The subject prefix to use for all requests to the secrets backend, defaults to `wasmcloud.secrets`
+    #[clap(short, long, default_value = "wasmcloud.secrets")]
+    subject_base: String,
+    /// The name of the secret
+    name: String,
+}
+
+#[derive(Parser, Debug, Clone)]
+struct ReencryptCommand {
+    /// The server's transit XKey, used to decrypt requests sent to the server.
+    #[clap(short, long, env = "TRANSIT_XKEY_SEED")]
+    transit_xkey_seed: String,
+    /// The encryption XKey secrets are currently encrypted with
+    #[clap(short, long, env = "ENCRYPTION_XKEY_SEED")]
+    encryption_xkey_seed: String,
+    /// The encryption XKey to re-encrypt all secrets with
+    #[clap(long, env = "NEW_ENCRYPTION_XKEY_SEED")]
+    new_encryption_xkey_seed: String,
+    /// The subject prefix to use for all requests to the secrets backend, defaults to `wasmcloud.secrets`
+    #[clap(short, long, default_value = "wasmcloud.secrets")]
+    subject_base: String,
+    /// The NATS address to connect to where the backend is running
+    #[clap(long, default_value = "127.0.0.1:4222")]
+    nats_address: String,
+}
+
+#[derive(Parser, Debug, Clone)]
+struct AuditCommand {
+    /// The server's transit XKey. Only holders of its seed may query the audit trail.
+    #[clap(short, long, env = "TRANSIT_XKEY_SEED")]
+    transit_xkey_seed: String,
+    /// The NATS address to connect to where the backend is running
+    #[clap(long, default_value = "127.0.0.1:4222")]
+    nats_address: String,
+    /// The subject prefix to use for all requests to the secrets backend, defaults to `wasmcloud.secrets`
+    #[clap(short, long, default_value = "wasmcloud.secrets")]
+    subject_base: String,
+    /// Only show requests for this secret
+    #[clap(long)]
+    secret: Option<String>,
+    /// Only show requests made by the entity with this public key
+    #[clap(long)]
+    entity: Option<String>,
+    /// The maximum number of most recent records to show
+    #[clap(long, default_value = "100")]
+    limit: usize,
+}
+
 #[derive(Parser, Debug, Clone)]
 struct AddSecretMappingCommand {
     /// The NATS address to connect to where the backend is running
@@ -134,6 +219,11 @@ async fn main() -> anyhow::Result<()> {
     match args.command {
         Command::Run(args) => run(args).await,
         Command::Put(args) => put(args).await,
+        Command::List(args) => list(args).await,
+        Command::Delete(args) => delete(args).await,
+        Command::History(args) => history(args).await,
+        Command::Reencrypt(args) => reencrypt(args).await,
+        Command::Audit(args) => audit(args).await,
         Command::AddMapping(args) => add_mapping(args).await,
         Command::RemoveMapping(args) => remove_mapping(args).await,
     }
@@ -157,6 +247,10 @@ async fn run(args: RunCommand) -> anyhow::Result<()> {
         args.name.clone(),
         args.secrets_bucket,
         args.max_secret_history,
+        Duration::from_secs(args.audit_max_age_seconds),
+        args.audit_max_bytes
+            .try_into()
+            .context("audit stream size is too large")?,
         args.nats_queue_base,
         args.secrets_api_version,
     );
@@ -198,6 +292,123 @@ async fn put(args: PutCommand) -> anyhow::Result<()> {
     Ok(())
 }
 
+async fn list(args: ListCommand) -> anyhow::Result<()> {
+    let server_xkey = XKey::from_seed(&args.transit_xkey_seed)
+        .context("failed to create server key from seed")?;
+    let nats_client = async_nats::connect(&args.nats_address)
+        .await
+        .with_context(|| format!("failed to connect to NATS at {}", args.nats_address))?;
+
+    for name in client::list_secrets(&nats_client, &args.subject_base, &server_xkey).await? {
+        println!("{name}");
+    }
+    Ok(())
+}
+
+async fn delete(args: SecretCommand) -> anyhow::Result<()> {
+    let server_xkey = XKey::from_seed(&args.transit_xkey_seed)
+        .context("failed to create server key from seed")?;
+    let nats_client = async_nats::connect(&args.nats_address)
+        .await
+        .with_context(|| format!("failed to connect to NATS at {}", args.nats_address))?;
+
+    client::delete_secret(&nats_client, &args.subject_base, &server_xkey, &args.name).await?;
+    println!("Secret '{}' deleted successfully", args.name);
+    Ok(())
+}
+
+async fn history(args: SecretCommand) -> anyhow::Result<()> {
+    let server_xkey = XKey::from_seed(&args.transit_xkey_seed)
+        .context("failed to create server key from seed")?;
+    let nats_client = async_nats::connect(&args.nats_address)
+        .await
+        .with_context(|| format!("failed to connect to NATS at {}", args.nats_address))?;
+
+    let versions =
+        client::secret_history(&nats_client, &args.subject_base, &server_xkey, &args.name).await?;
+    println!("{:<12}{:<40}STATE", "VERSION", "CREATED");
+    for version in versions {
+        let state = if version.deleted { "deleted" } else { "" };
+        println!("{:<12}{:<40}{state}", version.version, version.created_at);
+    }
+    Ok(())
+}
+
+async fn reencrypt(args: ReencryptCommand) -> anyhow::Result<()> {
+    let server_xkey = XKey::from_seed(&args.transit_xkey_seed)
+        .context("failed to create server key from seed")?;
+    XKey::from_seed(&args.new_encryption_xkey_seed)
+        .context("failed to create new encryption key from seed")?;
+    let nats_client = async_nats::connect(&args.nats_address)
+        .await
+        .with_context(|| format!("failed to connect to NATS at {}", args.nats_address))?;
+
+    let response = client::reencrypt(
+        &nats_client,
+        &args.subject_base,
+        &server_xkey,
+        ReencryptRequest {
+            current_encryption_xkey_seed: args.encryption_xkey_seed,
+            new_encryption_xkey_seed: args.new_encryption_xkey_seed,
+        },
+    )
+    .await?;
+
+    for (name, revisions) in &response.revisions {
+        for revision in revisions {
+            println!(
+                "Secret '{name}' version {} is now version {}",
+                revision.previous, revision.current
+            );
+        }
+    }
+    println!(
+        "Re-encrypted {} secrets. Restart other instances of the backend with the new encryption key",
+        response.revisions.len()
+    );
+    Ok(())
+}
+
+async fn audit(args: AuditCommand) -> anyhow::Result<()> {
+    let transit_xkey = XKey::from_seed(&args.transit_xkey_seed)
+        .context("failed to create transit key from seed")?;
+    let nats_client = async_nats::connect(&args.nats_address)
+        .await
+        .with_context(|| format!("failed to connect to NATS at {}", args.nats_address))?;
+
+    let records = client::query_audit(
+        &nats_client,
+        &args.subject_base,
+        &transit_xkey,
+        AuditQuery {
+            secret: args.secret,
+            entity: args.entity,
+            limit: Some(args.limit),
+        },
+    )
+    .await?;
+    for record in records {
+        let outcome = match record.outcome {
+            AuditOutcome::Allowed => "allowed",
+            AuditOutcome::Denied => "denied",
+        };
+        println!(
+            "{} secret={} version={} entity={} host={} application={} outcome={outcome}{}",
+            record.timestamp,
+            record.secret,
+            record.version.as_deref().unwrap_or("latest"),
+            record.entity,
+            record.host,
+            record.application.as_deref().unwrap_or("-"),
+            record
+                .reason
+                .map(|reason| format!(" reason=\"{reason}\""))
+                .unwrap_or_default(),
+        );
+    }
+    Ok(())
+}
+
 async fn add_mapping(args: AddSecretMappingCommand) -> anyhow::Result<()> {
     ensure!(
         !args.secrets.is_empty(),
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use bytes::Bytes;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -56,3 +58,83 @@ impl From<u64> for PutSecretResponse {
         }
     }
 }
+
+/// A version of a secret, as returned by the `secret_history` operation.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SecretVersion {
+    /// The version of the secret, which can be used to request this version of it
+    pub version: String,
+    /// When this version was written, formatted as RFC 3339
+    pub created_at: String,
+    /// Whether this version marks the deletion of the secret
+    pub deleted: bool,
+}
+
+/// The request of a `reencrypt` operation, encrypted with the server's transit XKey.
+#[derive(Serialize, Deserialize, Default)]
+pub struct ReencryptRequest {
+    /// The seed of the encryption XKey secrets are currently encrypted with. Required to prove
+    /// that the requester is allowed to decrypt the secrets.
+    pub current_encryption_xkey_seed: String,
+    /// The seed of the encryption XKey to re-encrypt all secrets with
+    pub new_encryption_xkey_seed: String,
+}
+
+/// The previous and current version of a secret that was re-encrypted.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RevisionChange {
+    pub previous: u64,
+    pub current: u64,
+}
+
+/// The response to a `reencrypt` operation.
+/// Since re-encrypted secrets are written as new versions, this maps every secret to the new
+/// revision numbers of each of its versions. The previous revisions can no longer be requested and
+/// this mapping is not stored, so it is the only way to update references pinned to a version.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct ReencryptResponse {
+    pub revisions: BTreeMap<String, Vec<RevisionChange>>,
+}
+
+/// Whether access to a secret was granted, as recorded in the audit stream.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOutcome {
+    Allowed,
+    Denied,
+}
+
+/// A record of a request to read a secret, written to the audit stream for every `get`
+/// operation. Secret values are never recorded.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AuditRecord {
+    /// When the request was handled, in seconds since the Unix epoch
+    pub timestamp: u64,
+    /// The name of the requested secret
+    pub secret: String,
+    /// The version of the secret that was returned, or the requested version if access was
+    /// denied
+    pub version: Option<String>,
+    /// The public key of the requesting entity, as claimed by its JWT. Empty if the JWT could not
+    /// be decoded.
+    pub entity: String,
+    /// The public key of the host the request was made from, as claimed by its JWT. Empty if the
+    /// JWT could not be decoded.
+    pub host: String,
+    /// The name of the application the requesting entity belongs to
+    pub application: Option<String>,
+    pub outcome: AuditOutcome,
+    /// The reason access was denied
+    pub reason: Option<String>,
+}
+
+/// The request of a `query_audit` operation. All filters are optional.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct AuditQuery {
+    /// Only return records of requests for this secret
+    pub secret: Option<String>,
+    /// Only return records of requests made by the entity with this public key
+    pub entity: Option<String>,
+    /// Only return the most recent `limit` matching records
+    pub limit: Option<usize>,
+}
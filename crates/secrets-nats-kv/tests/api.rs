@@ -3,8 +3,11 @@ use std::collections::HashSet;
 use async_nats::{jetstream, Client};
 use nkeys::{KeyPair, XKey};
 use rand::{distributions::Alphanumeric, thread_rng, Rng};
-use secrets_nats_kv::{Api, PutSecretRequest, PutSecretResponse};
-use std::collections::HashMap;
+use secrets_nats_kv::{
+    Api, AuditOutcome, AuditQuery, AuditRecord, PutSecretRequest, PutSecretResponse,
+    ReencryptRequest, ReencryptResponse, SecretVersion,
+};
+use std::{collections::HashMap, time::Duration};
 use wascap::jwt::{Claims, ClaimsBuilder, Component, Host};
 use wasmcloud_secrets_types::{Application, Context, SecretRequest, WASMCLOUD_HOST_XKEY};
 
@@ -31,6 +34,9 @@ impl Drop for Suite {
                 js.delete_stream(format!("SECRETS_{}_state_lock", name.clone()))
                     .await
                     .unwrap();
+                js.delete_stream(format!("SECRETS_{}_audit", name.clone()))
+                    .await
+                    .unwrap();
             });
         })
         .join()
@@ -281,6 +287,339 @@ async fn integration_test_kvstore_version() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn integration_test_kvstore_manage_and_reencrypt() -> anyhow::Result<()> {
+    let client = async_nats::connect("127.0.0.1:4222").await?;
+
+    let encryption_xkey = XKey::new();
+    let server_xkey = XKey::new();
+    let request_key = XKey::new();
+
+    let (api, name) = setup_api(
+        client.clone(),
+        encryption_xkey.seed().unwrap(),
+        server_xkey.seed().unwrap(),
+    );
+
+    let base_sub = api.subject();
+    let _suite = Suite { name: name.clone() };
+    tokio::spawn(async move {
+        api.run().await.unwrap();
+    });
+    // Give the server some time to start
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let mut headers = async_nats::HeaderMap::new();
+    headers.insert(WASMCLOUD_HOST_XKEY, request_key.public_key().as_str());
+    for (key, value) in [("a", "one"), ("b", "two"), ("a", "three")] {
+        let value = serde_json::to_string(&PutSecretRequest {
+            key: key.to_string(),
+            string_secret: Some(value.to_string()),
+            ..Default::default()
+        })?;
+        let v = request_key.seal(value.as_bytes(), &server_xkey).unwrap();
+        client
+            .request_with_headers(format!("{base_sub}.put_secret"), headers.clone(), v.into())
+            .await?;
+    }
+
+    let v = request_key.seal(&[], &server_xkey).unwrap();
+    let resp = client
+        .request_with_headers(
+            format!("{base_sub}.list_secrets"),
+            headers.clone(),
+            v.into(),
+        )
+        .await?;
+    let keys: Vec<String> = serde_json::from_slice(&resp.payload)?;
+    assert_eq!(keys, vec!["a", "b"]);
+
+    let v = request_key.seal(b"b", &server_xkey).unwrap();
+    let resp = client
+        .request_with_headers(
+            format!("{base_sub}.delete_secret"),
+            headers.clone(),
+            v.into(),
+        )
+        .await?;
+    assert_eq!(resp.payload.to_vec(), b"ok");
+    let v = request_key.seal(b"missing", &server_xkey).unwrap();
+    let resp = client
+        .request_with_headers(
+            format!("{base_sub}.delete_secret"),
+            headers.clone(),
+            v.into(),
+        )
+        .await?;
+    assert_ne!(resp.payload.to_vec(), b"ok");
+
+    let v = request_key.seal(&[], &server_xkey).unwrap();
+    let resp = client
+        .request_with_headers(
+            format!("{base_sub}.list_secrets"),
+            headers.clone(),
+            v.into(),
+        )
+        .await?;
+    let keys: Vec<String> = serde_json::from_slice(&resp.payload)?;
+    assert_eq!(keys, vec!["a"]);
+
+    let v = request_key.seal(b"b", &server_xkey).unwrap();
+    let resp = client
+        .request_with_headers(
+            format!("{base_sub}.secret_history"),
+            headers.clone(),
+            v.into(),
+        )
+        .await?;
+    let history: Vec<SecretVersion> = serde_json::from_slice(&resp.payload)?;
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].version, "2");
+    // Times are formatted as RFC 3339, e.g. `2024-06-03T12:00:00.123456Z`
+    assert!(history[0].created_at.contains('T') && history[0].created_at.ends_with('Z'));
+    assert!(!history[0].deleted);
+    assert!(history[1].deleted);
+
+    // Managing secrets requires requests sealed for the transit key
+    for (op, payload) in [
+        ("list_secrets", ""),
+        ("delete_secret", "a"),
+        ("secret_history", "a"),
+    ] {
+        let resp = client
+            .request(format!("{base_sub}.{op}"), payload.into())
+            .await?;
+        assert_ne!(resp.payload.to_vec(), b"ok");
+        assert!(serde_json::from_slice::<serde_json::Value>(&resp.payload).is_err());
+
+        let v = request_key.seal(payload.as_bytes(), &XKey::new()).unwrap();
+        let resp = client
+            .request_with_headers(format!("{base_sub}.{op}"), headers.clone(), v.into())
+            .await?;
+        assert_ne!(resp.payload.to_vec(), b"ok");
+        assert!(serde_json::from_slice::<serde_json::Value>(&resp.payload).is_err());
+    }
+    let v = request_key.seal(&[], &server_xkey).unwrap();
+    let resp = client
+        .request_with_headers(
+            format!("{base_sub}.list_secrets"),
+            headers.clone(),
+            v.into(),
+        )
+        .await?;
+    let keys: Vec<String> = serde_json::from_slice(&resp.payload)?;
+    assert_eq!(keys, vec!["a"]);
+
+    // Re-encryption requires the current encryption key
+    let new_xkey = XKey::new();
+    let request = serde_json::to_vec(&ReencryptRequest {
+        current_encryption_xkey_seed: XKey::new().seed().unwrap(),
+        new_encryption_xkey_seed: new_xkey.seed().unwrap(),
+    })?;
+    let v = request_key.seal(&request, &server_xkey).unwrap();
+    let resp = client
+        .request_with_headers(format!("{base_sub}.reencrypt"), headers.clone(), v.into())
+        .await?;
+    assert!(serde_json::from_slice::<ReencryptResponse>(&resp.payload).is_err());
+
+    let request = serde_json::to_vec(&ReencryptRequest {
+        current_encryption_xkey_seed: encryption_xkey.seed().unwrap(),
+        new_encryption_xkey_seed: new_xkey.seed().unwrap(),
+    })?;
+    let v = request_key.seal(&request, &server_xkey).unwrap();
+    let resp = client
+        .request_with_headers(format!("{base_sub}.reencrypt"), headers.clone(), v.into())
+        .await?;
+    let reencrypted: ReencryptResponse = serde_json::from_slice(&resp.payload)?;
+    assert_eq!(reencrypted.revisions["a"].len(), 2);
+    assert_eq!(reencrypted.revisions["b"].len(), 2);
+
+    // Every version is stored encrypted with the new key and no old version is left
+    let js = jetstream::new(client.clone());
+    let store = js.get_key_value(&name).await?;
+    let entry = store.entry("a").await?.expect("secret a should exist");
+    assert_eq!(new_xkey.open(&entry.value, &new_xkey).unwrap(), b"three");
+    assert!(encryption_xkey
+        .open(&entry.value, &encryption_xkey)
+        .is_err());
+    let v = request_key.seal(b"a", &server_xkey).unwrap();
+    let resp = client
+        .request_with_headers(
+            format!("{base_sub}.secret_history"),
+            headers.clone(),
+            v.into(),
+        )
+        .await?;
+    let history: Vec<SecretVersion> = serde_json::from_slice(&resp.payload)?;
+    let versions: Vec<String> = history.into_iter().map(|v| v.version).collect();
+    let expected: Vec<String> = reencrypted.revisions["a"]
+        .iter()
+        .map(|r| r.current.to_string())
+        .collect();
+    assert_eq!(versions, expected);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn integration_test_kvstore_audit() -> anyhow::Result<()> {
+    let client = async_nats::connect("127.0.0.1:4222").await?;
+
+    let encryption_xkey = XKey::new();
+    let server_xkey = XKey::new();
+    let request_key = XKey::new();
+
+    let (api, name) = setup_api(
+        client.clone(),
+        encryption_xkey.seed().unwrap(),
+        server_xkey.seed().unwrap(),
+    );
+
+    let base_sub = api.subject();
+    let _suite = Suite { name: name.clone() };
+    tokio::spawn(async move {
+        api.run().await.unwrap();
+    });
+    // Give the server some time to start
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let mut headers = async_nats::HeaderMap::new();
+    headers.insert(WASMCLOUD_HOST_XKEY, request_key.public_key().as_str());
+    for key in ["allowed", "denied"] {
+        let value = serde_json::to_string(&PutSecretRequest {
+            key: key.to_string(),
+            string_secret: Some("value".to_string()),
+            ..Default::default()
+        })?;
+        let v = request_key.seal(value.as_bytes(), &server_xkey).unwrap();
+        client
+            .request_with_headers(format!("{base_sub}.put_secret"), headers.clone(), v.into())
+            .await?;
+    }
+
+    let account = wascap::prelude::KeyPair::new_account();
+    let component_key = KeyPair::new_module();
+    let claims: Claims<Component> = ClaimsBuilder::new()
+        .issuer(account.public_key().as_str())
+        .subject(component_key.public_key().as_str())
+        .build();
+    let entity_jwt = claims.encode(&account)?;
+    let response = client
+        .request(
+            format!("{base_sub}.add_mapping.{}", component_key.public_key()),
+            serde_json::to_vec(&HashSet::from(["allowed".to_string()]))?.into(),
+        )
+        .await?;
+    assert_eq!(response.payload.to_vec(), b"ok");
+
+    let host_key = KeyPair::new_server();
+    let claims: Claims<Host> = ClaimsBuilder::new()
+        .issuer(account.public_key().as_str())
+        .subject(host_key.public_key().as_str())
+        .with_metadata(Host::new("test".to_string(), HashMap::new()))
+        .build();
+    let host_jwt = claims.encode(&account)?;
+
+    let secrets_client = wasmcloud_secrets_client::Client::new_with_version(
+        &name,
+        SUBJECT_BASE,
+        client.clone(),
+        Some(TEST_API_VERSION),
+    )
+    .await?;
+    for key in ["allowed", "denied"] {
+        let request = SecretRequest {
+            key: key.to_string(),
+            field: None,
+            context: Context {
+                entity_jwt: entity_jwt.clone(),
+                host_jwt: host_jwt.clone(),
+                application: Application {
+                    name: Some("test".to_string()),
+                    policy: "".to_string(),
+                },
+            },
+            version: None,
+        };
+        let _ = secrets_client
+            .get(request, XKey::from_seed(&request_key.seed().unwrap())?)
+            .await;
+    }
+
+    // Queries must be sealed with the transit xkey itself, and so are responses
+    let transit_seed = server_xkey.seed().unwrap();
+    let query = |query: AuditQuery| {
+        let client = client.clone();
+        let base_sub = base_sub.clone();
+        let transit_xkey = XKey::from_seed(&transit_seed).unwrap();
+        async move {
+            let request = serde_json::to_vec(&query).unwrap();
+            let resp = client
+                .request(
+                    format!("{base_sub}.query_audit"),
+                    transit_xkey.seal(&request, &transit_xkey).unwrap().into(),
+                )
+                .await
+                .unwrap();
+            let payload = transit_xkey.open(&resp.payload, &transit_xkey).unwrap();
+            serde_json::from_slice::<Vec<AuditRecord>>(&payload).unwrap()
+        }
+    };
+
+    let request = serde_json::to_vec(&AuditQuery::default())?;
+    let resp = client
+        .request(
+            format!("{base_sub}.query_audit"),
+            request_key.seal(&request, &server_xkey).unwrap().into(),
+        )
+        .await?;
+    assert!(server_xkey
+        .open(&resp.payload, &server_xkey)
+        .is_ok_and(|payload| serde_json::from_slice::<Vec<AuditRecord>>(&payload).is_err()));
+
+    let records = query(AuditQuery {
+        entity: Some(component_key.public_key()),
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].secret, "allowed");
+    assert_eq!(records[0].outcome, AuditOutcome::Allowed);
+    assert_eq!(records[0].version.as_deref(), Some("1"));
+    assert_eq!(records[0].host, host_key.public_key());
+    assert_eq!(records[0].application.as_deref(), Some("test"));
+    assert_eq!(records[1].secret, "denied");
+    assert_eq!(records[1].outcome, AuditOutcome::Denied);
+    assert!(records[1].reason.is_some());
+
+    let records = query(AuditQuery {
+        secret: Some("denied".to_string()),
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].entity, component_key.public_key());
+
+    let records = query(AuditQuery {
+        entity: Some(KeyPair::new_module().public_key()),
+        ..Default::default()
+    })
+    .await;
+    assert!(records.is_empty());
+
+    // Values are never recorded
+    let raw = serde_json::to_string(
+        &query(AuditQuery {
+            limit: Some(1),
+            ..Default::default()
+        })
+        .await,
+    )?;
+    assert!(!raw.contains("value"));
+
+    Ok(())
+}
+
 fn setup_api(client: Client, enc_seed: String, server_seed: String) -> (Api, String) {
     let server_xkey = XKey::from_seed(&server_seed).unwrap();
     let encryption_key = XKey::from_seed(&enc_seed).unwrap();
@@ -301,6 +640,8 @@ fn setup_api(client: Client, enc_seed: String, server_seed: String) -> (Api, Str
             name.clone(),
             name.clone(),
             64,
+            Duration::ZERO,
+            0,
             "wasmcloud_secrets_test".to_string(),
             TEST_API_VERSION.to_string(),
         ),
@@ -28,6 +28,11 @@ pub const SECRET_POLICY_PROPERTIES_TYPE: &str = "properties.secret.wasmcloud.dev
 /// The prefix for all secret keys in the config store
 pub const SECRET_PREFIX: &str = "SECRET";
 
+/// The operation, on which secrets backends notify hosts that secrets changed, e.g.
+/// `wasmcloud.secrets.v1alpha1.nats-kv.changed`. Hosts re-fetch the secrets of running components
+/// and providers when a message is published on this subject, the payload is ignored.
+pub const SECRETS_CHANGED_OPERATION: &str = "changed";
+
 /// The request context for retrieving a secret
 #[derive(Serialize, Deserialize, Default)]
 pub struct Context {
@@ -31,6 +31,8 @@ pub async fn assert_advertise_link(
             interfaces,
             source_config,
             target_config,
+            status: None,
+            retry_policy: None,
         })
         .await
         .map_err(|e| anyhow!(e).context("failed to advertise link"))?;
@@ -0,0 +1,149 @@
+//! A span exporter writing OTLP JSON lines to a size-rotated local file, which allows capturing
+//! traces without an OpenTelemetry collector
+
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write as _};
+use std::path::{Path, PathBuf};
+
+use futures::future::{self, BoxFuture};
+use opentelemetry::trace::TraceError;
+use opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceRequest;
+use opentelemetry_sdk::export::trace::{ExportResult, SpanData, SpanExporter};
+
+/// The size in bytes at which the traces file is rotated, if not configured
+pub(crate) const DEFAULT_MAX_BYTES: u64 = 100 * 1024 * 1024;
+
+/// The number of rotated traces files to keep, if not configured
+pub(crate) const DEFAULT_MAX_FILES: usize = 5;
+
+/// A file, which is renamed to `<path>.1` once it would exceed `max_bytes`. Previously rotated
+/// files are shifted to `<path>.<n + 1>`, keeping at most `max_files` of them.
+#[derive(Debug)]
+struct RotatingFile {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    file: File,
+    size: u64,
+}
+
+impl RotatingFile {
+    fn open(path: PathBuf, max_bytes: u64, max_files: usize) -> io::Result<Self> {
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path,
+            max_bytes,
+            max_files,
+            file,
+            size,
+        })
+    }
+
+    /// Returns the path of the `n`th rotated file
+    fn rotated_path(path: &Path, n: usize) -> PathBuf {
+        let mut path = OsString::from(path);
+        path.push(format!(".{n}"));
+        path.into()
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        if self.max_files == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            for n in (1..self.max_files).rev() {
+                match fs::rename(
+                    Self::rotated_path(&self.path, n),
+                    Self::rotated_path(&self.path, n + 1),
+                ) {
+                    Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+                    _ => {}
+                }
+            }
+            fs::rename(&self.path, Self::rotated_path(&self.path, 1))?;
+        }
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+
+    fn write_line(&mut self, line: &[u8]) -> io::Result<()> {
+        let len = u64::try_from(line.len())
+            .unwrap_or(u64::MAX)
+            .saturating_add(1);
+        if self.size > 0 && self.size.saturating_add(len) > self.max_bytes {
+            self.rotate()?;
+        }
+        self.file.write_all(line)?;
+        self.file.write_all(b"\n")?;
+        self.file.flush()?;
+        self.size = self.size.saturating_add(len);
+        Ok(())
+    }
+}
+
+/// [`SpanExporter`] writing each exported batch as a single OTLP JSON encoded
+/// `ExportTraceServiceRequest` line
+#[derive(Debug)]
+pub(crate) struct FileSpanExporter {
+    file: RotatingFile,
+}
+
+impl FileSpanExporter {
+    pub(crate) fn new(path: PathBuf, max_bytes: u64, max_files: usize) -> io::Result<Self> {
+        RotatingFile::open(path, max_bytes, max_files).map(|file| Self { file })
+    }
+
+    fn write_batch(&mut self, batch: Vec<SpanData>) -> ExportResult {
+        let request = ExportTraceServiceRequest {
+            resource_spans: batch.into_iter().map(Into::into).collect(),
+        };
+        let line = serde_json::to_vec(&request)
+            .map_err(|err| TraceError::from(format!("failed to encode spans: {err}")))?;
+        self.file
+            .write_line(&line)
+            .map_err(|err| TraceError::from(format!("failed to write spans to file: {err}")))
+    }
+}
+
+impl SpanExporter for FileSpanExporter {
+    fn export(&mut self, batch: Vec<SpanData>) -> BoxFuture<'static, ExportResult> {
+        Box::pin(future::ready(self.write_batch(batch)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RotatingFile;
+
+    #[test]
+    fn rotates_files() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("traces.jsonl");
+        let mut file = RotatingFile::open(path.clone(), 8, 2)?;
+        for line in ["first", "second", "third", "fourth"] {
+            file.write_line(line.as_bytes())?;
+        }
+        assert_eq!(std::fs::read_to_string(&path)?, "fourth\n");
+        assert_eq!(
+            std::fs::read_to_string(RotatingFile::rotated_path(&path, 1))?,
+            "third\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(RotatingFile::rotated_path(&path, 2))?,
+            "second\n"
+        );
+        assert!(!RotatingFile::rotated_path(&path, 3).exists());
+        Ok(())
+    }
+}
@@ -16,6 +16,9 @@ use wasmcloud_core::OtelConfig;
 #[cfg(feature = "otel")]
 pub mod context;
 
+#[cfg(feature = "otel")]
+mod file;
+
 mod traces;
 
 #[cfg(feature = "otel")]
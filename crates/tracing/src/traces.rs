@@ -207,8 +207,39 @@ where
     S: Subscriber,
     S: for<'a> tracing_subscriber::registry::LookupSpan<'a>,
 {
+    use opentelemetry::trace::TracerProvider as _;
     use tracing_opentelemetry::OpenTelemetryLayer;
 
+    let trace_config = opentelemetry_sdk::trace::config()
+        .with_sampler(get_sampler(otel_config))
+        .with_id_generator(opentelemetry_sdk::trace::RandomIdGenerator::default())
+        .with_max_events_per_span(64)
+        .with_max_attributes_per_span(16)
+        .with_max_events_per_span(16)
+        .with_resource(opentelemetry_sdk::Resource::new(vec![
+            opentelemetry::KeyValue::new("service.name", service_name),
+        ]));
+
+    if let Some(path) = &otel_config.traces_file {
+        let exporter = crate::file::FileSpanExporter::new(
+            path.clone(),
+            otel_config
+                .traces_file_max_bytes
+                .unwrap_or(crate::file::DEFAULT_MAX_BYTES),
+            otel_config
+                .traces_file_max_files
+                .unwrap_or(crate::file::DEFAULT_MAX_FILES),
+        )
+        .with_context(|| format!("failed to open traces file `{}`", path.display()))?;
+        let provider = opentelemetry_sdk::trace::TracerProvider::builder()
+            .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
+            .with_config(trace_config)
+            .build();
+        let tracer = provider.tracer("wasmcloud-tracing");
+        opentelemetry::global::set_tracer_provider(provider);
+        return Ok(OpenTelemetryLayer::new(tracer).with_filter(trace_level_filter));
+    }
+
     let builder: SpanExporterBuilder = match otel_config.protocol {
         OtelProtocol::Http => {
             let client = crate::get_http_client(otel_config)
@@ -232,23 +263,29 @@ where
     let tracer = opentelemetry_otlp::new_pipeline()
         .tracing()
         .with_exporter(builder)
-        .with_trace_config(
-            opentelemetry_sdk::trace::config()
-                .with_sampler(opentelemetry_sdk::trace::Sampler::AlwaysOn)
-                .with_id_generator(opentelemetry_sdk::trace::RandomIdGenerator::default())
-                .with_max_events_per_span(64)
-                .with_max_attributes_per_span(16)
-                .with_max_events_per_span(16)
-                .with_resource(opentelemetry_sdk::Resource::new(vec![
-                    opentelemetry::KeyValue::new("service.name", service_name),
-                ])),
-        )
+        .with_trace_config(trace_config)
         .install_batch(opentelemetry_sdk::runtime::Tokio)
         .context("failed to create OTEL tracer")?;
 
     Ok(OpenTelemetryLayer::new(tracer).with_filter(trace_level_filter))
 }
 
+/// Returns the sampler configured by `traces_sampling_ratio` and `traces_sampling_parent_based`,
+/// sampling all traces by default
+#[cfg(feature = "otel")]
+fn get_sampler(otel_config: &OtelConfig) -> opentelemetry_sdk::trace::Sampler {
+    use opentelemetry_sdk::trace::Sampler;
+
+    let sampler = otel_config
+        .traces_sampling_ratio
+        .map_or(Sampler::AlwaysOn, Sampler::TraceIdRatioBased);
+    if otel_config.traces_sampling_parent_based {
+        Sampler::ParentBased(Box::new(sampler))
+    } else {
+        sampler
+    }
+}
+
 #[cfg(feature = "otel")]
 fn get_otel_logging_layer<S>(
     service_name: Arc<str>,
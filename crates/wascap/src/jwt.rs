@@ -75,6 +75,16 @@ pub struct Component {
     /// Indicates whether this module is a capability provider
     #[serde(rename = "prov", default = "default_as_false")]
     pub provider: bool,
+
+    /// The interfaces imported by the component, e.g. `wasi:keyvalue/store@0.2.0-draft`. Recorded
+    /// from the component when the claims are embedded, absent for core modules.
+    #[serde(rename = "imports", default, skip_serializing_if = "Option::is_none")]
+    pub imports: Option<Vec<String>>,
+
+    /// The interfaces exported by the component, e.g. `wasi:http/incoming-handler@0.2.0`. Recorded
+    /// from the component when the claims are embedded, absent for core modules.
+    #[serde(rename = "exports", default, skip_serializing_if = "Option::is_none")]
+    pub exports: Option<Vec<String>>,
 }
 
 /// The claims metadata corresponding to a capability provider
@@ -95,6 +105,24 @@ pub struct CapabilityProvider {
     /// If the provider chooses, it can supply a JSON schma that describes its expected link configuration
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub config_schema: Option<serde_json::Value>,
+    /// Optional list of tags, e.g. recording the previous issuers of the provider
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    /// The hash of the encoded WIT package describing the provider's interfaces, if the provider
+    /// archive contains one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wit_hash: Option<String>,
+}
+
+/// The claims metadata corresponding to a detached co-signature of a component or provider. A
+/// co-signature is issued by an additional signer, e.g. a security team, over the same hash as the
+/// primary claims of the entity, without replacing them.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct CoSignature {
+    /// The hash of the co-signed entity. For components, this is the module hash of the primary
+    /// claims. For provider archives, this is a hash of the target hashes of the primary claims.
+    #[serde(rename = "hash")]
+    pub hash: String,
 }
 
 /// The claims metadata corresponding to an account
@@ -263,6 +291,12 @@ impl WascapEntity for CapabilityProvider {
     }
 }
 
+impl WascapEntity for CoSignature {
+    fn name(&self) -> String {
+        "Co-signature".to_string()
+    }
+}
+
 impl WascapEntity for Account {
     fn name(&self) -> String {
         self.name
@@ -401,6 +435,8 @@ impl Claims<CapabilityProvider> {
                 target_hashes: hashes,
                 vendor,
                 config_schema: None,
+                tags: None,
+                wit_hash: None,
             }),
             expires,
             id: nuid::next(),
@@ -613,6 +649,34 @@ impl Claims<Host> {
     }
 }
 
+impl Claims<CoSignature> {
+    /// Creates a new non-expiring Claims wrapper for a co-signature of the entity `subject` with
+    /// the hash `hash`
+    #[must_use]
+    pub fn new(issuer: String, subject: String, hash: String) -> Self {
+        Self::with_dates(issuer, subject, hash, None, None)
+    }
+
+    pub fn with_dates(
+        issuer: String,
+        subject: String,
+        hash: String,
+        not_before: Option<u64>,
+        expires: Option<u64>,
+    ) -> Claims<CoSignature> {
+        Claims {
+            metadata: Some(CoSignature { hash }),
+            expires,
+            id: nuid::next(),
+            issued_at: since_the_epoch().as_secs(),
+            issuer,
+            subject,
+            not_before,
+            wascap_revision: Some(WASCAP_INTERNAL_REVISION),
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct ClaimsBuilder<T> {
     claims: Claims<T>,
@@ -840,6 +904,8 @@ impl Component {
             rev,
             ver,
             call_alias: normalize_call_alias(call_alias),
+            imports: None,
+            exports: None,
         }
     }
 }
@@ -860,6 +926,8 @@ impl CapabilityProvider {
             rev,
             ver,
             config_schema: None,
+            tags: None,
+            wit_hash: None,
         }
     }
 }
@@ -1184,6 +1252,8 @@ mod test {
                 ver: Some("v0.0.1".to_string()),
                 target_hashes: hashes,
                 config_schema: Some(schema),
+                tags: None,
+                wit_hash: None,
             })
             .build();
 
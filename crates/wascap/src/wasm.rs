@@ -2,13 +2,14 @@
 
 use crate::{
     errors::{self, ErrorKind},
-    jwt::{Claims, Component, Token, MIN_WASCAP_INTERNAL_REVISION},
+    jwt::{Claims, CoSignature, Component, Token, MIN_WASCAP_INTERNAL_REVISION},
     Result,
 };
 use data_encoding::HEXUPPER;
 use nkeys::KeyPair;
 use ring::digest::{Context, Digest, SHA256};
 use std::{
+    collections::BTreeSet,
     io::Read,
     mem,
     time::{SystemTime, UNIX_EPOCH},
@@ -20,6 +21,7 @@ use wasmparser::Parser;
 const SECS_PER_DAY: u64 = 86400;
 const SECTION_JWT: &str = "jwt"; // Versions of wascap prior to 0.9 used this section
 const SECTION_WC_JWT: &str = "wasmcloud_jwt";
+const SECTION_WC_COSIGNATURE: &str = "wasmcloud_cosignature";
 
 /// Extracts a set of claims from the raw bytes of a WebAssembly module. In the case where no
 /// JWT is discovered in the module, this function returns `None`.
@@ -61,25 +63,104 @@ pub fn extract_claims(contents: impl AsRef<[u8]>) -> Result<Option<Token<Compone
     Ok(None)
 }
 
+/// Computes the hash of the raw bytes of a WebAssembly module without its embedded claims and
+/// co-signatures, which is the module hash recorded in the claims when the module is signed.
+///
+/// # Errors
+/// Will return an error if the module can't be parsed
+pub fn module_hash(contents: impl AsRef<[u8]>) -> Result<String> {
+    compute_hash(&strip_custom_section(contents.as_ref())?)
+}
+
+/// Extracts the detached co-signatures from the raw bytes of a WebAssembly module. In the case
+/// where no co-signatures are discovered in the module, this function returns an empty list.
+///
+/// # Errors
+/// Will return an error if a co-signature can't be decoded or was not issued over the hash of
+/// the module
+pub fn extract_cosignatures(contents: impl AsRef<[u8]>) -> Result<Vec<Token<CoSignature>>> {
+    let target_hash = compute_hash(&strip_custom_section(contents.as_ref())?)?;
+    top_level_custom_sections(contents.as_ref(), SECTION_WC_COSIGNATURE)?
+        .into_iter()
+        .map(|jwt| {
+            let claims: Claims<CoSignature> = Claims::decode(&jwt)?;
+            if claims.metadata.as_ref().map(|meta| meta.hash.as_str()) != Some(&target_hash) {
+                return Err(errors::new(ErrorKind::InvalidModuleHash));
+            }
+            Ok(Token { jwt, claims })
+        })
+        .collect()
+}
+
+/// This function will embed a detached co-signature, issued by the provided `KeyPair`, inside the
+/// bytecode of a WebAssembly module with embedded claims. The co-signature is issued over the module
+/// hash for the subject of the embedded claims, which are left untouched. An existing co-signature
+/// by the same issuer is replaced. Returns a vector of bytes representing the new WebAssembly module
+/// which can be saved to a `.wasm` file
+///
+/// # Errors
+/// Will return an error if the module has no embedded claims or its existing signatures are invalid
+pub fn embed_cosignature(
+    orig_bytecode: &[u8],
+    kp: &KeyPair,
+    expires: Option<u64>,
+) -> Result<Vec<u8>> {
+    let token = extract_claims(orig_bytecode)?.ok_or_else(|| {
+        errors::new(ErrorKind::Token(
+            "module has no embedded claims to co-sign".into(),
+        ))
+    })?;
+    let issuer = kp.public_key();
+    let mut cosignatures: Vec<String> = extract_cosignatures(orig_bytecode)?
+        .into_iter()
+        .filter(|cosignature| cosignature.claims.issuer != issuer)
+        .map(|cosignature| cosignature.jwt)
+        .collect();
+
+    let mut bytes = strip_custom_section(orig_bytecode)?;
+    let hash = compute_hash(&bytes)?;
+    let claims =
+        Claims::<CoSignature>::with_dates(issuer, token.claims.subject, hash, None, expires);
+    cosignatures.push(claims.encode(kp)?);
+
+    wasm_gen::write_custom_section(&mut bytes, SECTION_WC_JWT, token.jwt.as_bytes());
+    for jwt in cosignatures {
+        wasm_gen::write_custom_section(&mut bytes, SECTION_WC_COSIGNATURE, jwt.as_bytes());
+    }
+    Ok(bytes)
+}
+
 /// This function will embed a set of claims inside the bytecode of a WebAssembly module. The claims
 /// are converted into a JWT and signed using the provided `KeyPair`.
 /// According to the WebAssembly [custom section](https://webassembly.github.io/spec/core/appendix/custom.html)
 /// specification, arbitary sets of bytes can be stored in a WebAssembly module without impacting
 /// parsers or interpreters. Returns a vector of bytes representing the new WebAssembly module which can
 /// be saved to a `.wasm` file
+///
+/// Co-signatures of the module are kept if they were issued for the same subject, since the module
+/// hash they were issued over does not change when the module is re-signed.
 #[allow(clippy::missing_errors_doc)] // TODO: document errors
 pub fn embed_claims(
     orig_bytecode: &[u8],
     claims: &Claims<Component>,
     kp: &KeyPair,
 ) -> Result<Vec<u8>> {
+    let cosignatures = top_level_custom_sections(orig_bytecode, SECTION_WC_COSIGNATURE)?;
     let mut bytes = orig_bytecode.to_vec();
     bytes = strip_custom_section(&bytes)?;
 
     let hash = compute_hash(&bytes)?;
+    // Components encoded before the component model binary format settled can't be parsed, so
+    // they are signed without their interfaces
+    let (imports, exports) = match component_interfaces(&bytes).ok().flatten() {
+        Some((imports, exports)) => (Some(imports), Some(exports)),
+        None => (None, None),
+    };
     let mut claims = (*claims).clone();
     let meta = claims.metadata.map(|md| Component {
-        module_hash: hash,
+        module_hash: hash.clone(),
+        imports,
+        exports,
         ..md
     });
     claims.metadata = meta;
@@ -88,6 +169,17 @@ pub fn embed_claims(
     let encvec = encoded.as_bytes().to_vec();
     wasm_gen::write_custom_section(&mut bytes, SECTION_WC_JWT, &encvec);
 
+    for jwt in cosignatures {
+        let Ok(cosignature) = Claims::<CoSignature>::decode(&jwt) else {
+            continue;
+        };
+        if cosignature.subject == claims.subject
+            && cosignature.metadata.is_some_and(|meta| meta.hash == hash)
+        {
+            wasm_gen::write_custom_section(&mut bytes, SECTION_WC_COSIGNATURE, jwt.as_bytes());
+        }
+    }
+
     Ok(bytes)
 }
 
@@ -123,6 +215,76 @@ pub fn sign_buffer_with_claims(
     embed_claims(buf.as_ref(), &claims, acct_kp)
 }
 
+/// Returns the names of the interfaces imported and exported by the WebAssembly component in
+/// `buf`, e.g. `wasi:http/incoming-handler@0.2.0`, sorted and deduplicated. Returns `None` if `buf`
+/// contains a core module.
+///
+/// # Errors
+/// Will return an error if `buf` can't be parsed
+pub fn component_interfaces(buf: &[u8]) -> Result<Option<(Vec<String>, Vec<String>)>> {
+    use wasmparser::Payload::{
+        ComponentExportSection, ComponentImportSection, ComponentSection, End, ModuleSection,
+        Version,
+    };
+
+    // Only named instances are interfaces, functions and other items are skipped
+    let is_interface = |name: &str| name.contains(':') && name.contains('/');
+    let mut imports = BTreeSet::new();
+    let mut exports = BTreeSet::new();
+    let mut depth = 0;
+    for payload in Parser::new(0).parse_all(buf) {
+        match payload? {
+            Version { encoding, .. } if depth == 0 => {
+                if matches!(encoding, wasmparser::Encoding::Module) {
+                    return Ok(None);
+                }
+            }
+            ModuleSection { .. } | ComponentSection { .. } => depth += 1,
+            End { .. } => depth -= 1,
+            ComponentImportSection(reader) if depth == 0 => {
+                for import in reader {
+                    let name = import?.name.0;
+                    if is_interface(name) {
+                        imports.insert(name.to_string());
+                    }
+                }
+            }
+            ComponentExportSection(reader) if depth == 0 => {
+                for export in reader {
+                    let name = export?.name.0;
+                    if is_interface(name) {
+                        exports.insert(name.to_string());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(Some((
+        imports.into_iter().collect(),
+        exports.into_iter().collect(),
+    )))
+}
+
+/// Returns the contents of the custom sections named `name` of the outermost module or component
+fn top_level_custom_sections(buf: &[u8], name: &str) -> Result<Vec<String>> {
+    use wasmparser::Payload::{ComponentSection, CustomSection, End, ModuleSection};
+
+    let mut sections = Vec::new();
+    let mut depth = 0;
+    for payload in Parser::new(0).parse_all(buf) {
+        match payload? {
+            ModuleSection { .. } | ComponentSection { .. } => depth += 1,
+            End { .. } => depth -= 1,
+            CustomSection(c) if c.name() == name && depth == 0 => {
+                sections.push(String::from_utf8(c.data().to_vec())?);
+            }
+            _ => {}
+        }
+    }
+    Ok(sections)
+}
+
 pub(crate) fn strip_custom_section(buf: &[u8]) -> Result<Vec<u8>> {
     use wasmparser::Payload::{ComponentSection, CustomSection, End, ModuleSection, Version};
 
@@ -156,7 +318,11 @@ pub(crate) fn strip_custom_section(buf: &[u8]) -> Result<Vec<u8>> {
         }
 
         match payload {
-            CustomSection(c) if (c.name() == SECTION_JWT) || (c.name() == SECTION_WC_JWT) => {
+            CustomSection(c)
+                if (c.name() == SECTION_JWT)
+                    || (c.name() == SECTION_WC_JWT)
+                    || (c.name() == SECTION_WC_COSIGNATURE) =>
+            {
                 // skip
             }
             _ => {
@@ -330,6 +496,8 @@ mod test {
 
         if let Some(token) = extract_claims(modified_bytecode).unwrap() {
             assert_eq!(claims.issuer, token.claims.issuer);
+            // Core modules have no interfaces
+            assert_eq!(token.claims.metadata.unwrap().imports, None);
         } else {
             unreachable!()
         }
@@ -414,4 +582,108 @@ mod test {
             unreachable!()
         }
     }
+
+    #[test]
+    fn embed_component_interfaces() {
+        use wasm_encoder::{
+            ComponentExportKind, ComponentExportSection, ComponentImportSection, ComponentTypeRef,
+            ComponentTypeSection, InstanceType,
+        };
+
+        let mut types = ComponentTypeSection::new();
+        types.instance(&InstanceType::new());
+        let mut imports = ComponentImportSection::new();
+        imports
+            .import("wasi:logging/logging", ComponentTypeRef::Instance(0))
+            .import("wasi:http/types@0.2.0", ComponentTypeRef::Instance(0))
+            .import("run", ComponentTypeRef::Instance(0));
+        let mut exports = ComponentExportSection::new();
+        exports.export(
+            "wasi:http/incoming-handler@0.2.0",
+            ComponentExportKind::Instance,
+            0,
+            None,
+        );
+        let mut component = wasm_encoder::Component::new();
+        component
+            .section(&types)
+            .section(&imports)
+            .section(&exports);
+        let component = component.finish();
+
+        let kp = KeyPair::new_account();
+        let claims = Claims::<Component>::new(
+            "testing".to_string(),
+            kp.public_key(),
+            KeyPair::new_module().public_key(),
+            None,
+            false,
+            None,
+            None,
+            None,
+        );
+        let signed = embed_claims(&component, &claims, &kp).unwrap();
+        let metadata = extract_claims(&signed)
+            .unwrap()
+            .unwrap()
+            .claims
+            .metadata
+            .unwrap();
+        assert_eq!(
+            metadata.imports.unwrap(),
+            vec!["wasi:http/types@0.2.0", "wasi:logging/logging"]
+        );
+        assert_eq!(
+            metadata.exports.unwrap(),
+            vec!["wasi:http/incoming-handler@0.2.0"]
+        );
+    }
+
+    #[test]
+    fn cosignatures_roundtrip() {
+        let dec_module = BASE64.decode(WASM_BASE64.as_bytes()).unwrap();
+
+        let kp = KeyPair::new_account();
+        let claims = Claims::<Component>::new(
+            "testing".to_string(),
+            kp.public_key(),
+            KeyPair::new_module().public_key(),
+            None,
+            false,
+            None,
+            None,
+            None,
+        );
+        let signed = embed_claims(&dec_module, &claims, &kp).unwrap();
+        assert!(extract_cosignatures(&signed).unwrap().is_empty());
+        assert!(
+            embed_cosignature(&dec_module, &kp, None).is_err(),
+            "Modules without claims cannot be co-signed"
+        );
+
+        let build = KeyPair::new_account();
+        let security = KeyPair::new_account();
+        let cosigned = embed_cosignature(&signed, &build, None).unwrap();
+        let cosigned = embed_cosignature(&cosigned, &security, None).unwrap();
+        // Co-signing again replaces the existing co-signature of the issuer
+        let cosigned = embed_cosignature(&cosigned, &security, None).unwrap();
+
+        let token = extract_claims(&cosigned).unwrap().unwrap();
+        assert_eq!(token.jwt, extract_claims(&signed).unwrap().unwrap().jwt);
+        let cosignatures = extract_cosignatures(&cosigned).unwrap();
+        let issuers: Vec<_> = cosignatures
+            .iter()
+            .map(|cosignature| cosignature.claims.issuer.clone())
+            .collect();
+        assert_eq!(issuers, vec![build.public_key(), security.public_key()]);
+        assert!(cosignatures.iter().all(|cosignature| {
+            cosignature.claims.subject == claims.subject
+                && cosignature.claims.metadata.as_ref().unwrap().hash
+                    == token.claims.metadata.as_ref().unwrap().module_hash
+        }));
+
+        // Re-signing the module keeps the co-signatures
+        let resigned = embed_claims(&cosigned, &claims, &KeyPair::new_account()).unwrap();
+        assert_eq!(extract_cosignatures(&resigned).unwrap().len(), 2);
+    }
 }
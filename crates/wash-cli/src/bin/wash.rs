@@ -11,12 +11,14 @@ use wash_lib::cli::get::GetCommand;
 use wash_lib::cli::inspect::InspectCliCommand;
 use wash_lib::cli::label::LabelHostCommand;
 use wash_lib::cli::link::LinkCommand;
+use wash_lib::cli::logs::LogsCommand;
 use wash_lib::cli::registry::{RegistryPullCommand, RegistryPushCommand};
 use wash_lib::cli::scale::ScaleCommand;
 use wash_lib::cli::spy::SpyCommand;
 use wash_lib::cli::start::StartCommand;
 use wash_lib::cli::stop::StopCommand;
 use wash_lib::cli::update::UpdateCommand;
+use wash_lib::cli::verify::VerifyCommand;
 use wash_lib::cli::{CommandOutput, OutputKind};
 use wash_lib::drain::Drain as DrainSelection;
 use wash_lib::plugin::subcommand::{DirMapping, SubcommandRunner};
@@ -67,6 +69,7 @@ Run:
   down         Tear down a local wasmCloud environment (launched with wash up)
   app          Manage declarative applications and deployments (wadm)
   spy          Spy on all invocations a component sends and receives
+  logs         Tail logs of components running in a lattice
   ui           Serve a web UI for wasmCloud
 
 Iterate:
@@ -84,6 +87,7 @@ Iterate:
 Publish:
   pull         Pull an artifact from an OCI compliant registry
   push         Push an artifact to an OCI compliant registry
+  verify       Verify the hashes, signature and issuer of a signed component or capability provider
 
 Configure:
   completions  Generate shell completions for wash
@@ -195,6 +199,9 @@ enum CliCommand {
     /// Link one component to another on a set of interfaces
     #[clap(name = "link", alias = "links", subcommand)]
     Link(LinkCommand),
+    /// Tail logs of components running in a lattice
+    #[clap(name = "logs", alias = "log")]
+    Logs(LogsCommand),
     /// Create a new project from a template
     #[clap(name = "new", subcommand)]
     New(NewCliCommand),
@@ -210,7 +217,7 @@ enum CliCommand {
     /// Pull an artifact from an OCI compliant registry
     #[clap(name = "pull")]
     RegPull(RegistryPullCommand),
-    /// Manage secret references
+    /// Manage secret references and run a local secrets backend
     #[clap(name = "secrets", alias = "secret", subcommand)]
     Secrets(SecretsCliCommand),
     /// Spy on all invocations a component sends and receives
@@ -234,6 +241,9 @@ enum CliCommand {
     /// Bootstrap a wasmCloud environment
     #[clap(name = "up")]
     Up(UpCommand),
+    /// Verify the hashes, signatures and issuer of a signed component or capability provider, including its detached co-signatures
+    #[clap(name = "verify")]
+    Verify(VerifyCommand),
     /// Serve a web UI for wasmCloud
     #[clap(name = "ui")]
     Ui(UiCommand),
@@ -419,8 +429,11 @@ async fn main() {
         CliCommand::Inspect(inspect_cli) => {
             wash_lib::cli::inspect::handle_command(inspect_cli, output_kind).await
         }
-        CliCommand::Keys(keys_cli) => keys::handle_command(keys_cli),
+        CliCommand::Keys(keys_cli) => keys::handle_command(keys_cli).await,
         CliCommand::Link(link_cli) => common::link_cmd::handle_command(link_cli, output_kind).await,
+        CliCommand::Logs(logs_cli) => {
+            wash_lib::cli::logs::handle_command(logs_cli, output_kind).await
+        }
         CliCommand::New(new_cli) => generate::handle_command(new_cli).await,
         CliCommand::Par(par_cli) => par::handle_command(par_cli, output_kind).await,
         CliCommand::Plugin(plugin_cli) => plugin::handle_command(plugin_cli, output_kind).await,
@@ -453,6 +466,7 @@ async fn main() {
         }
         CliCommand::Up(up_cli) => up::handle_command(up_cli, output_kind).await,
         CliCommand::Ui(ui_cli) => ui::handle_command(ui_cli, output_kind).await,
+        CliCommand::Verify(verify_cli) => wash_lib::cli::verify::handle_command(verify_cli).await,
     };
 
     std::process::exit(match res {
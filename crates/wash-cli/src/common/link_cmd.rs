@@ -3,13 +3,16 @@ use std::collections::HashMap;
 use anyhow::{bail, Result};
 use serde_json::json;
 use wash_lib::cli::link::{
-    delete_link, get_links, put_link, LinkCommand, LinkDelCommand, LinkPutCommand, LinkQueryCommand,
+    add_target_group_member, delete_link, delete_target_group, get_links, get_target_groups,
+    put_link, put_target_group, remove_target_group_member, LinkCommand, LinkDelCommand,
+    LinkGroupAddMemberCommand, LinkGroupCommand, LinkGroupDelCommand, LinkGroupGetCommand,
+    LinkGroupRemoveMemberCommand, LinkPutCommand, LinkQueryCommand,
 };
 use wash_lib::cli::{CommandOutput, OutputKind};
-use wasmcloud_control_interface::InterfaceLinkDefinition;
+use wasmcloud_control_interface::{CtlResponse, InterfaceLinkDefinition, RetryPolicy};
 
 use crate::appearance::spinner::Spinner;
-use crate::ctl::{link_del_output, links_table};
+use crate::ctl::{link_del_output, links_table, target_groups_table};
 
 /// Generate output for link put command
 pub fn link_put_output(
@@ -40,6 +43,55 @@ pub fn link_query_output(list: Vec<InterfaceLinkDefinition>) -> CommandOutput {
     CommandOutput::new(links_table(list), map)
 }
 
+/// Generate output for target group commands that modify a group
+fn target_group_output(name: &str, action: &str, res: CtlResponse<()>) -> Result<CommandOutput> {
+    if !res.success {
+        bail!("Error updating target group {name}: {}", res.message);
+    }
+    let mut map = HashMap::new();
+    map.insert("name".to_string(), json!(name));
+    Ok(CommandOutput::new(
+        format!("{action} target group {name} successfully"),
+        map,
+    ))
+}
+
+async fn handle_group_command(command: LinkGroupCommand, sp: &Spinner) -> Result<CommandOutput> {
+    match command {
+        LinkGroupCommand::Put(cmd) => {
+            let group = cmd.target_group();
+            sp.update_spinner_message(format!("Putting target group {} ... ", group.name));
+            let res = put_target_group(cmd.opts.try_into()?, &group).await?;
+            target_group_output(&group.name, "Put", res)
+        }
+        LinkGroupCommand::Get(LinkGroupGetCommand { opts, name }) => {
+            sp.update_spinner_message("Querying target groups ... ".to_string());
+            let groups = get_target_groups(opts.try_into()?, name.as_deref()).await?;
+            let mut map = HashMap::new();
+            map.insert("target_groups".to_string(), json!(groups));
+            Ok(CommandOutput::new(target_groups_table(groups), map))
+        }
+        LinkGroupCommand::Del(LinkGroupDelCommand { opts, name }) => {
+            sp.update_spinner_message(format!("Deleting target group {name} ... "));
+            let res = delete_target_group(opts.try_into()?, &name).await?;
+            target_group_output(&name, "Deleted", res)
+        }
+        LinkGroupCommand::AddMember(LinkGroupAddMemberCommand { opts, name, member }) => {
+            sp.update_spinner_message(format!(
+                "Adding {} to target group {name} ... ",
+                member.target
+            ));
+            let res = add_target_group_member(opts.try_into()?, &name, member).await?;
+            target_group_output(&name, "Updated", res)
+        }
+        LinkGroupCommand::RemoveMember(LinkGroupRemoveMemberCommand { opts, name, target }) => {
+            sp.update_spinner_message(format!("Removing {target} from target group {name} ... "));
+            let res = remove_target_group_member(opts.try_into()?, &name, &target).await?;
+            target_group_output(&name, "Updated", res)
+        }
+    }
+}
+
 pub async fn handle_command(
     command: LinkCommand,
     output_kind: OutputKind,
@@ -81,6 +133,9 @@ pub async fn handle_command(
             interfaces,
             source_config,
             target_config,
+            retry_max_attempts,
+            retry_initial_backoff_ms,
+            retry_max_backoff_ms,
         }) => {
             sp.update_spinner_message(format!("Defining link {source_id} -> {target} ... ",));
 
@@ -97,6 +152,12 @@ pub async fn handle_command(
                     interfaces,
                     source_config,
                     target_config,
+                    status: None,
+                    retry_policy: retry_max_attempts.map(|max_attempts| RetryPolicy {
+                        max_attempts,
+                        initial_backoff_ms: retry_initial_backoff_ms,
+                        max_backoff_ms: retry_max_backoff_ms,
+                    }),
                 },
             )
             .await
@@ -109,6 +170,7 @@ pub async fn handle_command(
             let result = get_links(opts.try_into()?).await?;
             link_query_output(result)
         }
+        LinkCommand::Group(command) => handle_group_command(command, &sp).await?,
     };
 
     Ok(out)
@@ -116,6 +116,8 @@ mod test {
             "2002",
             "--constraint",
             "arch=x86_64",
+            "--label-constraint",
+            "zone!=us-east-1a",
             "--host-id",
             HOST_ID,
             "--link-name",
@@ -132,6 +134,7 @@ mod test {
                 provider_id,
                 link_name,
                 constraints,
+                label_constraints,
                 auction_timeout_ms,
                 config,
                 skip_wait,
@@ -143,6 +146,12 @@ mod test {
                 assert_eq!(auction_timeout_ms, 2002);
                 assert_eq!(link_name, "default".to_string());
                 assert_eq!(constraints.unwrap(), vec!["arch=x86_64".to_string()]);
+                assert_eq!(
+                    label_constraints,
+                    vec!["zone!=us-east-1a"
+                        .parse()
+                        .expect("failed to parse constraint")]
+                );
                 assert_eq!(host_id.unwrap(), HOST_ID.to_string());
                 assert_eq!(provider_ref, "wasmcloud.azurecr.io/provider:v1".to_string());
                 assert_eq!(provider_id, "providerv1".to_string());
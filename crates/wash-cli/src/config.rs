@@ -1,12 +1,19 @@
-use std::{collections::HashMap, error::Error};
+use std::{
+    collections::HashMap,
+    error::Error,
+    path::{Path, PathBuf},
+};
 
+use anyhow::Context as _;
 use clap::Subcommand;
+use provider_archive::ProviderArchive;
 use serde_json::json;
 use tracing::error;
 use wash_lib::{
     cli::{input_vec_to_hashmap, CliConnectionOpts, CommandOutput, OutputKind},
     config::WashConnectionOptions,
 };
+use wasmcloud_control_interface::ConfigRevision;
 use wasmcloud_secrets_types::SECRET_PREFIX;
 
 use crate::appearance::spinner::Spinner;
@@ -34,6 +41,40 @@ pub enum ConfigCliCommand {
         /// The name of the configuration to get
         #[clap(name = "name")]
         name: String,
+        /// Get this revision of the configuration instead of the latest one
+        #[clap(long = "revision")]
+        revision: Option<u64>,
+    },
+    /// List the revision history of a named configuration
+    #[clap(name = "history")]
+    HistoryCommand {
+        #[clap(flatten)]
+        opts: CliConnectionOpts,
+        /// The name of the configuration to list the history of
+        #[clap(name = "name")]
+        name: String,
+    },
+    /// Roll a named configuration back to a previous revision
+    #[clap(name = "rollback")]
+    RollbackCommand {
+        #[clap(flatten)]
+        opts: CliConnectionOpts,
+        /// The name of the configuration to roll back
+        #[clap(name = "name")]
+        name: String,
+        /// The revision to roll back to, as listed by `wash config history`
+        #[clap(name = "revision")]
+        revision: u64,
+    },
+    /// Validate a local configuration file against the config schema of a provider archive
+    #[clap(name = "validate")]
+    ValidateCommand {
+        /// Path to the provider archive (PAR) containing the config schema
+        #[clap(long = "par")]
+        par: PathBuf,
+        /// Path to a JSON or YAML file containing a map of configuration keys to values
+        #[clap(name = "file")]
+        file: PathBuf,
     },
     /// Delete a named configuration
     #[clap(name = "del", alias = "delete")]
@@ -66,10 +107,35 @@ pub async fn handle_command(
             )
             .await
         }
-        ConfigCliCommand::GetCommand { opts, name } => {
+        ConfigCliCommand::GetCommand {
+            opts,
+            name,
+            revision: None,
+        } => {
             ensure_not_secret(&name)?;
             get_config(opts, &name, output_kind).await
         }
+        ConfigCliCommand::GetCommand {
+            opts,
+            name,
+            revision: Some(revision),
+        } => {
+            ensure_not_secret(&name)?;
+            get_config_revision(opts, &name, revision, output_kind).await
+        }
+        ConfigCliCommand::HistoryCommand { opts, name } => {
+            ensure_not_secret(&name)?;
+            get_config_history(opts, &name, output_kind).await
+        }
+        ConfigCliCommand::RollbackCommand {
+            opts,
+            name,
+            revision,
+        } => {
+            ensure_not_secret(&name)?;
+            rollback_config(opts, &name, revision, output_kind).await
+        }
+        ConfigCliCommand::ValidateCommand { par, file } => validate_config(&par, &file).await,
         ConfigCliCommand::DelCommand { opts, name } => {
             ensure_not_secret(&name)?;
             delete_config(opts, &name, output_kind).await
@@ -204,6 +270,177 @@ pub(crate) async fn delete_config(
     Ok(output)
 }
 
+pub(crate) async fn get_config_revision(
+    opts: CliConnectionOpts,
+    name: &str,
+    revision: u64,
+    output_kind: OutputKind,
+) -> anyhow::Result<CommandOutput> {
+    let sp: Spinner = Spinner::new(&output_kind)?;
+    sp.update_spinner_message(format!("Getting revision {revision} of configuration..."));
+
+    let wco: WashConnectionOptions = opts.try_into()?;
+    let ctl_client = wco.into_ctl_client(None).await?;
+
+    let config_response = ctl_client
+        .get_config_revision(name, revision)
+        .await
+        .map_err(suggest_run_host_error)?;
+
+    sp.finish_and_clear();
+
+    if !config_response.success {
+        anyhow::bail!(
+            "Error getting revision {revision} of configuration: {}",
+            config_response.message
+        );
+    }
+    match config_response.response {
+        Some(ConfigRevision { deleted: true, .. }) => Err(anyhow::anyhow!(
+            "Revision {revision} of configuration '{name}' deleted the configuration"
+        )),
+        Some(ConfigRevision { config, .. }) => Ok(CommandOutput::new(
+            format!("{:?}", config),
+            config.into_iter().map(|(k, v)| (k, json!(v))).collect(),
+        )),
+        None => Err(anyhow::anyhow!(
+            "No revision {revision} found for configuration: {name}"
+        )),
+    }
+}
+
+pub(crate) async fn get_config_history(
+    opts: CliConnectionOpts,
+    name: &str,
+    output_kind: OutputKind,
+) -> anyhow::Result<CommandOutput> {
+    let sp: Spinner = Spinner::new(&output_kind)?;
+    sp.update_spinner_message("Getting configuration history...".to_string());
+
+    let wco: WashConnectionOptions = opts.try_into()?;
+    let ctl_client = wco.into_ctl_client(None).await?;
+
+    let config_response = ctl_client
+        .get_config_history(name)
+        .await
+        .map_err(suggest_run_host_error)?;
+
+    sp.finish_and_clear();
+
+    if !config_response.success {
+        anyhow::bail!(
+            "Error getting configuration history: {}",
+            config_response.message
+        );
+    }
+    let Some(history) = config_response.response else {
+        anyhow::bail!("No configuration found for name: {name}");
+    };
+    let text = history
+        .iter()
+        .map(
+            |ConfigRevision {
+                 revision,
+                 created_at,
+                 deleted,
+                 config,
+             }| {
+                if *deleted {
+                    format!("{revision}\t{created_at}\t<deleted>")
+                } else {
+                    format!("{revision}\t{created_at}\t{config:?}")
+                }
+            },
+        )
+        .collect::<Vec<_>>()
+        .join("\n");
+    Ok(CommandOutput::new(
+        text,
+        HashMap::from_iter([
+            ("success".to_string(), json!(true)),
+            ("history".to_string(), json!(history)),
+        ]),
+    ))
+}
+
+pub(crate) async fn rollback_config(
+    opts: CliConnectionOpts,
+    name: &str,
+    revision: u64,
+    output_kind: OutputKind,
+) -> anyhow::Result<CommandOutput> {
+    let sp: Spinner = Spinner::new(&output_kind)?;
+    sp.update_spinner_message(format!(
+        "Rolling back configuration to revision {revision} ..."
+    ));
+
+    let wco: WashConnectionOptions = opts.try_into()?;
+    let ctl_client = wco.into_ctl_client(None).await?;
+
+    let config_response = ctl_client
+        .rollback_config(name, revision)
+        .await
+        .map_err(suggest_run_host_error)?;
+
+    sp.finish_and_clear();
+
+    let message = if config_response.message.is_empty() && config_response.success {
+        format!("Configuration '{name}' rolled back to revision {revision} successfully.")
+    } else {
+        config_response.message
+    };
+    let json_out = HashMap::from_iter([
+        ("success".to_string(), json!(config_response.success)),
+        ("message".to_string(), json!(message)),
+    ]);
+    Ok(CommandOutput::new(message, json_out))
+}
+
+pub(crate) async fn validate_config(par: &Path, file: &Path) -> anyhow::Result<CommandOutput> {
+    let archive = ProviderArchive::try_load_file(par)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .with_context(|| format!("failed to load provider archive {}", par.display()))?;
+    let contents = tokio::fs::read_to_string(file)
+        .await
+        .with_context(|| format!("failed to read {}", file.display()))?;
+    // YAML is a superset of JSON, so this parses both
+    let values: HashMap<String, serde_json::Value> = serde_yaml::from_str(&contents)
+        .with_context(|| format!("{} should contain a map of configuration", file.display()))?;
+    let config = values
+        .into_iter()
+        .map(|(k, v)| match v {
+            serde_json::Value::String(v) => (k, v),
+            v => (k, v.to_string()),
+        })
+        .collect();
+
+    if archive.schema().is_none() {
+        let message = format!(
+            "Provider archive {} has no config schema, any configuration is valid",
+            par.display()
+        );
+        return Ok(CommandOutput::new(
+            message.clone(),
+            HashMap::from_iter([
+                ("success".to_string(), json!(true)),
+                ("message".to_string(), json!(message)),
+            ]),
+        ));
+    }
+    archive
+        .validate_config(&config)
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+    let message = format!("Configuration in {} is valid", file.display());
+    Ok(CommandOutput::new(
+        message.clone(),
+        HashMap::from_iter([
+            ("success".to_string(), json!(true)),
+            ("message".to_string(), json!(message)),
+        ]),
+    ))
+}
+
 /// Simple helper function to suggest running a host if no responders are found
 fn suggest_run_host_error(e: Box<dyn Error + std::marker::Send + Sync>) -> anyhow::Error {
     let err_str = e.to_string();
@@ -286,6 +286,8 @@ mod test {
                 source_config,
                 target_config,
                 link_name,
+                retry_max_attempts,
+                ..
             })) => {
                 assert_eq!(&opts.ctl_host.unwrap(), CTL_HOST);
                 assert_eq!(&opts.ctl_port.unwrap(), CTL_PORT);
@@ -299,9 +301,43 @@ mod test {
                 assert_eq!(interfaces.as_slice(), &["foo".to_string()]);
                 assert!(source_config.is_empty());
                 assert!(target_config.is_empty());
+                assert_eq!(retry_max_attempts, None);
             }
             cmd => panic!("ctl link put constructed incorrect command {cmd:?}"),
         }
+        let link_group: Cmd = Parser::try_parse_from([
+            "ctl",
+            "link",
+            "group",
+            "put",
+            "echo",
+            "--strategy",
+            "consistent-hash",
+            "--hash-key",
+            "user",
+            "--member",
+            "echo-v1=9",
+            "--member",
+            "echo-v2",
+        ])?;
+        use wash_lib::cli::link::LinkGroupCommand;
+        match link_group.command {
+            CtlCliCommand::Link(LinkCommand::Group(LinkGroupCommand::Put(cmd))) => {
+                let group = cmd.target_group();
+                assert_eq!(group.name, "echo");
+                assert_eq!(
+                    group.selection,
+                    wasmcloud_control_interface::TargetSelection::ConsistentHash {
+                        key: "user".to_string()
+                    }
+                );
+                assert_eq!(group.members.len(), 2);
+                assert_eq!(group.members[0].target, "echo-v1");
+                assert_eq!(group.members[0].weight, 9);
+                assert_eq!(group.members[1].weight, 1);
+            }
+            cmd => panic!("ctl link group put constructed incorrect command {cmd:?}"),
+        }
         let update_all: Cmd = Parser::try_parse_from([
             "ctl",
             "update",
@@ -8,7 +8,9 @@ use term_table::{
     Table,
 };
 use wash_lib::{cli::CommandOutput, plugin::subcommand::Metadata};
-use wasmcloud_control_interface::{Host, HostInventory, InterfaceLinkDefinition};
+use wasmcloud_control_interface::{
+    Host, HostInventory, InterfaceLinkDefinition, LinkStatus, TargetGroup, TargetSelection,
+};
 
 use crate::util::format_optional;
 
@@ -60,16 +62,22 @@ pub fn links_table(mut list: Vec<InterfaceLinkDefinition>) -> String {
     list.sort_by(|a, b| a.source_id.cmp(&b.source_id));
 
     let mut table = Table::new();
-    crate::util::configure_table_style(&mut table, 4);
+    crate::util::configure_table_style(&mut table, 5);
 
     table.add_row(Row::new(vec![
         TableCell::new_with_alignment("Source ID", 1, Alignment::Left),
         TableCell::new_with_alignment("Target", 1, Alignment::Left),
         TableCell::new_with_alignment("WIT", 1, Alignment::Left),
         TableCell::new_with_alignment("Interfaces", 1, Alignment::Left),
+        TableCell::new_with_alignment("Status", 1, Alignment::Left),
     ]));
 
     list.iter().for_each(|l| {
+        let status = match &l.status {
+            Some(LinkStatus::Healthy) => "healthy".to_string(),
+            Some(LinkStatus::Unhealthy { reason }) => format!("unhealthy: {reason}"),
+            None => "N/A".to_string(),
+        };
         table.add_row(Row::new(vec![
             TableCell::new_with_alignment(l.source_id.clone(), 1, Alignment::Left),
             TableCell::new_with_alignment(l.target.clone(), 1, Alignment::Left),
@@ -79,6 +87,42 @@ pub fn links_table(mut list: Vec<InterfaceLinkDefinition>) -> String {
                 Alignment::Left,
             ),
             TableCell::new_with_alignment(l.interfaces.join(","), 1, Alignment::Left),
+            TableCell::new_with_alignment(status, 1, Alignment::Left),
+        ]))
+    });
+
+    table.render()
+}
+
+/// Helper function to transform a TargetGroup list into a table string for printing
+pub fn target_groups_table(mut list: Vec<TargetGroup>) -> String {
+    list.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut table = Table::new();
+    crate::util::configure_table_style(&mut table, 3);
+
+    table.add_row(Row::new(vec![
+        TableCell::new_with_alignment("Name", 1, Alignment::Left),
+        TableCell::new_with_alignment("Strategy", 1, Alignment::Left),
+        TableCell::new_with_alignment("Members", 1, Alignment::Left),
+    ]));
+
+    list.iter().for_each(|g| {
+        let strategy = match &g.selection {
+            TargetSelection::RoundRobin => "round-robin".to_string(),
+            TargetSelection::Random => "random".to_string(),
+            TargetSelection::ConsistentHash { key } => format!("consistent-hash ({key})"),
+        };
+        let members = g
+            .members
+            .iter()
+            .map(|m| format!("{}={}", m.target, m.weight))
+            .collect::<Vec<_>>()
+            .join(",");
+        table.add_row(Row::new(vec![
+            TableCell::new_with_alignment(g.name.clone(), 1, Alignment::Left),
+            TableCell::new_with_alignment(strategy, 1, Alignment::Left),
+            TableCell::new_with_alignment(members, 1, Alignment::Left),
         ]))
     });
 
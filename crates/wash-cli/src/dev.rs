@@ -23,6 +23,7 @@ use wash_lib::parser::{get_config, ProjectConfig};
 use wasmcloud_control_interface::Host;
 
 use crate::down::{handle_down, DownCommand};
+use crate::secrets::LocalSecretsOpts;
 use crate::up::{handle_up, NatsOpts, UpCommand, WadmOpts, WasmcloudOpts};
 
 #[derive(Debug, Clone, Parser)]
@@ -36,6 +37,9 @@ pub struct DevCommand {
     #[clap(flatten)]
     pub wadm_opts: WadmOpts,
 
+    #[clap(flatten)]
+    pub secrets_local_opts: LocalSecretsOpts,
+
     /// ID of the host to use for `wash dev`
     /// if one is not selected, `wash dev` will attempt to use the single host in the lattice
     #[clap(long = "host-id", name = "host-id", value_parser)]
@@ -284,6 +288,7 @@ pub async fn handle_command(
             );
             let nats_opts = cmd.nats_opts.clone();
             let wadm_opts = cmd.wadm_opts.clone();
+            let secrets_local_opts = cmd.secrets_local_opts.clone();
             host_subprocess = Some(HostSubprocess(Some(tokio::spawn(async move {
                 let _ = handle_up(
                     UpCommand {
@@ -291,6 +296,7 @@ pub async fn handle_command(
                         nats_opts,
                         wasmcloud_opts,
                         wadm_opts,
+                        secrets_local_opts,
                     },
                     output_kind,
                 )
@@ -314,6 +320,7 @@ pub async fn handle_command(
                     nats_opts: cmd.nats_opts,
                     wasmcloud_opts,
                     wadm_opts: cmd.wadm_opts,
+                    secrets_local_opts: cmd.secrets_local_opts,
                 },
                 output_kind,
             )
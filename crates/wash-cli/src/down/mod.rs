@@ -18,8 +18,9 @@ use wash_lib::start::{nats_pid_path, NATS_SERVER_BINARY, WADM_PID};
 
 use crate::appearance::spinner::Spinner;
 use crate::up::{
-    DEFAULT_LATTICE, WASMCLOUD_CTL_CREDSFILE, WASMCLOUD_CTL_HOST, WASMCLOUD_CTL_JWT,
-    WASMCLOUD_CTL_PORT, WASMCLOUD_CTL_SEED, WASMCLOUD_CTL_TLS_CA_FILE, WASMCLOUD_LATTICE,
+    DEFAULT_LATTICE, SECRETS_LOCAL_PID, WASMCLOUD_CTL_CREDSFILE, WASMCLOUD_CTL_HOST,
+    WASMCLOUD_CTL_JWT, WASMCLOUD_CTL_PORT, WASMCLOUD_CTL_SEED, WASMCLOUD_CTL_TLS_CA_FILE,
+    WASMCLOUD_LATTICE,
 };
 
 #[derive(Parser, Debug, Clone, Default, clap::ValueEnum, Eq, PartialEq)]
@@ -172,6 +173,24 @@ pub async fn handle_down(cmd: DownCommand, output_kind: OutputKind) -> Result<Co
         }
     }
 
+    // The local secrets backend only runs in the background if `wash up` was detached
+    let secrets_local_pid_path = install_dir.join(SECRETS_LOCAL_PID);
+    if let Ok(pid) = tokio::fs::read_to_string(&secrets_local_pid_path).await {
+        match Command::new("kill").arg(pid.trim()).output().await {
+            Ok(_) => {
+                out_json.insert("secrets_local_stopped".to_string(), json!(true));
+                out_text.push_str("✅ local secrets backend stopped successfully\n");
+            }
+            Err(e) => {
+                out_json.insert("secrets_local_stopped".to_string(), json!(false));
+                out_text.push_str(&format!("❌ Could not stop local secrets backend: {e:?}\n"));
+            }
+        }
+        if let Err(e) = tokio::fs::remove_file(&secrets_local_pid_path).await {
+            warn!("failed to remove local secrets backend pid file: {e}");
+        }
+    }
+
     if nats_client
         .as_ref()
         .is_ok_and(|_| cmd.purge != PurgeJetstream::None)
@@ -1,11 +1,12 @@
 use std::{collections::HashMap, path::PathBuf};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Subcommand;
 use nkeys::{KeyPair, KeyPairType};
 use serde_json::json;
 use wash_lib::cli::CommandOutput;
 use wash_lib::config::cfg_dir;
+use wash_lib::keys::rotate::{resign_component, resign_provider_archive};
 use wash_lib::keys::{fs::KeyDir, KeyManager};
 
 const NKEYS_EXTENSION: &str = ".nk";
@@ -42,9 +43,85 @@ pub enum KeysCliCommand {
         )]
         directory: Option<PathBuf>,
     },
+    #[clap(
+        name = "encrypt",
+        about = "Encrypts keypairs at rest with a passphrase. Encrypted keypairs are decrypted on use with the passphrase in $WASH_KEYS_PASSPHRASE"
+    )]
+    EncryptCommand {
+        #[clap(
+            help = "The names of the keys to encrypt. Defaults to all plaintext keys in the directory"
+        )]
+        keynames: Vec<String>,
+        #[clap(
+            short = 'd',
+            long = "directory",
+            env = "WASH_KEYS",
+            hide_env_values = true,
+            help = "Absolute path to where keypairs are stored. Defaults to `$HOME/.wash/keys`"
+        )]
+        directory: Option<PathBuf>,
+        #[clap(
+            long = "passphrase",
+            env = "WASH_KEYS_PASSPHRASE",
+            hide_env_values = true,
+            help = "The passphrase to encrypt keypairs with"
+        )]
+        passphrase: String,
+    },
+    #[clap(
+        name = "decrypt",
+        about = "Decrypts passphrase-encrypted keypairs back to plaintext seeds"
+    )]
+    DecryptCommand {
+        #[clap(
+            help = "The names of the keys to decrypt. Defaults to all encrypted keys in the directory"
+        )]
+        keynames: Vec<String>,
+        #[clap(
+            short = 'd',
+            long = "directory",
+            env = "WASH_KEYS",
+            hide_env_values = true,
+            help = "Absolute path to where keypairs are stored. Defaults to `$HOME/.wash/keys`"
+        )]
+        directory: Option<PathBuf>,
+        #[clap(
+            long = "passphrase",
+            env = "WASH_KEYS_PASSPHRASE",
+            hide_env_values = true,
+            help = "The passphrase the keypairs are encrypted with"
+        )]
+        passphrase: String,
+    },
+    #[clap(
+        name = "rotate",
+        about = "Generates a new issuer keypair and re-signs components and provider archives with it"
+    )]
+    RotateCommand {
+        #[clap(help = "The name of the issuer key to rotate")]
+        keyname: String,
+        #[clap(
+            short = 'd',
+            long = "directory",
+            env = "WASH_KEYS",
+            hide_env_values = true,
+            help = "Absolute path to where keypairs are stored. Defaults to `$HOME/.wash/keys`"
+        )]
+        directory: Option<PathBuf>,
+        #[clap(
+            long = "component",
+            help = "Path to a component issued by the key to re-sign in place. Can be specified multiple times"
+        )]
+        components: Vec<PathBuf>,
+        #[clap(
+            long = "provider",
+            help = "Path to a provider archive issued by the key to re-sign in place. Can be specified multiple times"
+        )]
+        providers: Vec<PathBuf>,
+    },
 }
 
-pub fn handle_command(command: KeysCliCommand) -> Result<CommandOutput> {
+pub async fn handle_command(command: KeysCliCommand) -> Result<CommandOutput> {
     match command {
         KeysCliCommand::GenCommand { keytype } => {
             let kt = keytype_parser(&keytype)?;
@@ -52,6 +129,22 @@ pub fn handle_command(command: KeysCliCommand) -> Result<CommandOutput> {
         }
         KeysCliCommand::GetCommand { keyname, directory } => get(&keyname, directory),
         KeysCliCommand::ListCommand { directory } => list(directory),
+        KeysCliCommand::EncryptCommand {
+            keynames,
+            directory,
+            passphrase,
+        } => convert(&keynames, directory, passphrase, true),
+        KeysCliCommand::DecryptCommand {
+            keynames,
+            directory,
+            passphrase,
+        } => convert(&keynames, directory, passphrase, false),
+        KeysCliCommand::RotateCommand {
+            keyname,
+            directory,
+            components,
+            providers,
+        } => rotate(&keyname, directory, &components, &providers).await,
     }
 }
 
@@ -117,6 +210,116 @@ pub fn list(directory: Option<PathBuf>) -> Result<CommandOutput> {
     ))
 }
 
+/// Encrypts the named keypairs with `passphrase`, or decrypts them if `encrypt` is false, in a
+/// specified directory or $WASH_KEYS ($HOME/.wash/keys). All keys are converted if none are named.
+pub fn convert(
+    keynames: &[String],
+    directory: Option<PathBuf>,
+    passphrase: String,
+    encrypt: bool,
+) -> Result<CommandOutput> {
+    let directory = determine_directory(directory)?;
+    let reader = KeyDir::new(&directory)?.with_passphrase(Some(passphrase.clone()));
+    let writer = KeyDir::new(&directory)?.with_passphrase(encrypt.then_some(passphrase));
+    let keynames = if keynames.is_empty() {
+        reader.list_names()?
+    } else {
+        keynames
+            .iter()
+            .map(|keyname| keyname.trim_end_matches(NKEYS_EXTENSION).to_string())
+            .collect()
+    };
+
+    let mut converted = Vec::new();
+    for keyname in keynames {
+        let encrypted = reader
+            .is_encrypted(&keyname)?
+            .ok_or_else(|| anyhow::anyhow!("Key {} doesn't exist", keyname))?;
+        if encrypted == encrypt {
+            continue;
+        }
+        let key = reader
+            .get(&keyname)?
+            .ok_or_else(|| anyhow::anyhow!("Key {} doesn't exist", keyname))?;
+        writer.save(&keyname, &key)?;
+        converted.push(keyname);
+    }
+
+    let (key, action) = if encrypt {
+        ("encrypted", "Encrypted")
+    } else {
+        ("decrypted", "Decrypted")
+    };
+    let mut map = HashMap::new();
+    map.insert(key.to_string(), json!(converted));
+    Ok(CommandOutput::new(
+        format!(
+            "====== {action} keys in {} ======\n{}",
+            reader.display(),
+            converted.join("\n")
+        ),
+        map,
+    ))
+}
+
+/// Rotates the named issuer keypair in a specified directory or $WASH_KEYS ($HOME/.wash/keys),
+/// re-signing the given components and provider archives with the new keypair. The previous
+/// keypair is kept as `<keyname>_<previous public key>`.
+pub async fn rotate(
+    keyname: &str,
+    directory: Option<PathBuf>,
+    components: &[PathBuf],
+    providers: &[PathBuf],
+) -> Result<CommandOutput> {
+    let key_dir = KeyDir::new(determine_directory(directory)?)?;
+    let keyname = keyname.trim_end_matches(NKEYS_EXTENSION);
+    let previous = key_dir
+        .get(keyname)?
+        .ok_or_else(|| anyhow::anyhow!("Key {} doesn't exist", keyname))?;
+    let issuer = KeyPair::new(previous.key_pair_type());
+
+    // Save both keys before re-signing anything, so that neither is lost if re-signing fails
+    let previous_keyname = format!("{keyname}_{}", previous.public_key());
+    key_dir.save(&previous_keyname, &previous)?;
+    key_dir.save(keyname, &issuer)?;
+
+    for path in components {
+        let wasm = tokio::fs::read(path)
+            .await
+            .with_context(|| format!("failed to read component `{}`", path.display()))?;
+        let resigned = resign_component(&wasm, &previous, &issuer)
+            .with_context(|| format!("failed to re-sign component `{}`", path.display()))?;
+        tokio::fs::write(path, resigned)
+            .await
+            .with_context(|| format!("failed to write component `{}`", path.display()))?;
+    }
+    for path in providers {
+        resign_provider_archive(path, &previous, &issuer)
+            .await
+            .with_context(|| format!("failed to re-sign provider archive `{}`", path.display()))?;
+    }
+
+    let mut map = HashMap::new();
+    map.insert("public_key".to_string(), json!(issuer.public_key()));
+    map.insert(
+        "previous_public_key".to_string(),
+        json!(previous.public_key()),
+    );
+    map.insert("previous_key".to_string(), json!(previous_keyname));
+    map.insert("components".to_string(), json!(components));
+    map.insert("providers".to_string(), json!(providers));
+    Ok(CommandOutput::new(
+        format!(
+            "Rotated key {keyname} from {} to {}, keeping the previous key as {previous_keyname}.\nRe-signed {} component(s) and {} provider archive(s).",
+            previous.public_key(),
+            issuer.public_key(),
+            components.len(),
+            providers.len(),
+        ),
+        map,
+    ))
+}
+
 fn determine_directory(directory: Option<PathBuf>) -> Result<PathBuf> {
     if let Some(d) = directory {
         Ok(d)
@@ -363,6 +566,44 @@ mod tests {
         }
     }
 
+    #[test]
+    /// Enumerates multiple options of the `rotate` command to ensure API doesn't
+    /// change between versions. This test will fail if `wash keys rotate`
+    /// changes syntax, ordering of required elements, or flags.
+    fn test_rotate_comprehensive() {
+        let rotate: Cmd = clap::Parser::try_parse_from([
+            "keys",
+            "rotate",
+            "user_account",
+            "-d",
+            "./",
+            "--component",
+            "a.wasm",
+            "--component",
+            "b.wasm",
+            "--provider",
+            "c.par.gz",
+        ])
+        .unwrap();
+        match rotate.keys {
+            KeysCliCommand::RotateCommand {
+                keyname,
+                directory,
+                components,
+                providers,
+            } => {
+                assert_eq!(keyname, "user_account");
+                assert_eq!(directory, Some(PathBuf::from("./")));
+                assert_eq!(
+                    components,
+                    vec![PathBuf::from("a.wasm"), PathBuf::from("b.wasm")]
+                );
+                assert_eq!(providers, vec![PathBuf::from("c.par.gz")]);
+            }
+            other_cmd => panic!("keys rotate generated other command {other_cmd:?}"),
+        }
+    }
+
     #[test]
     /// Enumerates multiple options of the `list` command to ensure API doesn't
     /// change between versions. This test will fail if `wash keys list`
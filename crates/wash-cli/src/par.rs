@@ -5,7 +5,7 @@ use std::{collections::HashMap, path::PathBuf};
 use anyhow::{anyhow, bail, Context, Result};
 use clap::{Parser, Subcommand};
 use nkeys::KeyPairType;
-use provider_archive::ProviderArchive;
+use provider_archive::{Compression, ProviderArchive};
 use serde_json::json;
 use tracing::warn;
 use wash_lib::cli::par::{
@@ -13,8 +13,6 @@ use wash_lib::cli::par::{
 };
 use wash_lib::cli::{extract_keypair, inspect, par, CommandOutput, OutputKind};
 
-const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
-
 #[derive(Debug, Clone, Subcommand)]
 pub enum ParCliCommand {
     /// Build a provider archive file
@@ -51,6 +49,10 @@ pub struct CreateCommand {
     )]
     schema: Option<PathBuf>,
 
+    /// Optional path to the directory of the WIT package describing the interfaces of this provider, which is bundled in the archive. Archives bundling their WIT can't be loaded by hosts and tools using `provider-archive` older than 0.13.0.
+    #[clap(long = "wit")]
+    wit: Option<PathBuf>,
+
     /// Location of key files for signing. Defaults to $WASH_KEYS ($HOME/.wash/keys)
     #[clap(
         short = 'd',
@@ -98,6 +100,10 @@ pub struct CreateCommand {
     #[clap(long = "compress")]
     compress: bool,
 
+    /// Compress the provider archive with zstd instead of gzip, which is faster to load. Implies `--compress`
+    #[clap(long = "zstd")]
+    zstd: bool,
+
     /// Disables autogeneration of signing keys
     #[clap(long = "disable-keygen")]
     disable_keygen: bool,
@@ -197,9 +203,10 @@ pub struct InsertCommand {
 impl From<InspectCommand> for inspect::InspectCliCommand {
     fn from(cmd: InspectCommand) -> Self {
         inspect::InspectCliCommand {
-            target: cmd.archive,
+            target: Some(cmd.archive),
             jwt_only: false,
             wit: false,
+            command: None,
             digest: cmd.digest,
             allow_latest: cmd.allow_latest,
             user: cmd.user,
@@ -220,6 +227,7 @@ impl From<CreateCommand> for par::ParCreateArgs {
             schema: cmd.schema,
             name: cmd.name,
             arch: cmd.arch,
+            wit: cmd.wit,
         }
     }
 }
@@ -262,7 +270,15 @@ pub async fn handle_create(cmd: CreateCommand, output_kind: OutputKind) -> Resul
         output_kind,
     )?;
 
-    let extension = if cmd.compress { ".par.gz" } else { ".par" };
+    let compression = if cmd.zstd {
+        Compression::Zstd
+    } else {
+        cmd.compress.into()
+    };
+    let extension = match compression.extension() {
+        Some(extension) => format!(".par.{extension}"),
+        None => ".par".to_string(),
+    };
     let outfile = match cmd.destination.clone() {
         Some(path) => path,
         None => format!(
@@ -276,10 +292,9 @@ pub async fn handle_create(cmd: CreateCommand, output_kind: OutputKind) -> Resul
         ),
     };
 
-    let compress = cmd.compress;
     let mut par = create_provider_archive(cmd.into(), &lib)
         .context("failed to create provider archive with built provider")?;
-    par.write(&outfile, &issuer, &subject, compress)
+    par.write_compressed(&outfile, &issuer, &subject, compression)
         .await
         .map_err(|e| anyhow!("{e}"))
         .with_context(|| {
@@ -331,7 +346,7 @@ pub async fn handle_insert(cmd: InsertCommand, output_kind: OutputKind) -> Resul
         .map_err(convert_error)?;
 
     par = insert_provider_binary(cmd.arch, &lib, par).await?;
-    par.write(&cmd.archive, &issuer, &subject, is_compressed(&buf)?)
+    par.write_compressed(&cmd.archive, &issuer, &subject, detect_compression(&buf)?)
         .await
         .map_err(convert_error)?;
 
@@ -346,12 +361,12 @@ pub async fn handle_insert(cmd: InsertCommand, output_kind: OutputKind) -> Resul
     ))
 }
 
-/// Inspects the byte slice for a GZIP header, and returns true if the file is compressed
-fn is_compressed(input: &[u8]) -> Result<bool> {
+/// Inspects the byte slice for a GZIP or zstd header, and returns the compression of the file
+fn detect_compression(input: &[u8]) -> Result<Compression> {
     if input.len() < 2 {
         bail!("Not enough bytes to be a valid PAR file");
     }
-    Ok(input[0..2] == GZIP_MAGIC)
+    Ok(Compression::detect(input))
 }
 
 #[cfg(test)]
@@ -395,6 +410,9 @@ mod test {
             SUBJECT,
             "--disable-keygen",
             "--compress",
+            "--zstd",
+            "--wit",
+            "./wit",
         ])
         .unwrap();
         match create_long.par {
@@ -403,6 +421,7 @@ mod test {
                 revision,
                 version,
                 schema,
+                wit,
                 directory,
                 issuer,
                 subject,
@@ -411,6 +430,7 @@ mod test {
                 binary,
                 destination,
                 compress,
+                zstd,
                 disable_keygen,
             }) => {
                 assert_eq!(arch, "x86_64-testrunner");
@@ -424,8 +444,10 @@ mod test {
                 assert_eq!(revision.unwrap(), 1);
                 assert_eq!(version.unwrap(), "1.11.111");
                 assert_eq!(schema, None);
+                assert_eq!(wit.unwrap(), PathBuf::from("./wit"));
                 assert!(disable_keygen);
                 assert!(compress);
+                assert!(zstd);
             }
             cmd => panic!("par insert constructed incorrect command {cmd:?}"),
         }
@@ -460,6 +482,7 @@ mod test {
                 revision,
                 version,
                 schema,
+                wit,
                 directory,
                 issuer,
                 subject,
@@ -468,6 +491,7 @@ mod test {
                 binary,
                 destination,
                 compress,
+                zstd,
                 disable_keygen,
             }) => {
                 assert_eq!(arch, "x86_64-testrunner");
@@ -481,8 +505,10 @@ mod test {
                 assert_eq!(revision.unwrap(), 1);
                 assert_eq!(version.unwrap(), "1.11.111");
                 assert_eq!(schema, None);
+                assert_eq!(wit, None);
                 assert!(!disable_keygen);
                 assert!(!compress);
+                assert!(!zstd);
             }
             cmd => panic!("par insert constructed incorrect command {cmd:?}"),
         }
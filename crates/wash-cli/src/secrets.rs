@@ -1,8 +1,14 @@
-use clap::Subcommand;
+use anyhow::Context as _;
+use clap::{Parser, Subcommand};
+use nkeys::XKey;
+use secrets_local::{
+    Api as LocalSecretsApi, SecretsFile, Store as LocalSecretsStore, StoredSecret,
+};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use tracing::trace;
 use wash_lib::cli::{input_vec_to_hashmap, CliConnectionOpts, CommandOutput, OutputKind};
-use wasmcloud_secrets_types::{SecretConfig, SECRET_PREFIX};
+use wasmcloud_secrets_types::{SecretConfig, SECRET_API_VERSION, SECRET_PREFIX};
 
 use crate::config::{delete_config, get_config, is_secret, put_config};
 
@@ -49,6 +55,210 @@ pub enum SecretsCliCommand {
         #[clap(name = "name")]
         name: String,
     },
+
+    /// Run a local secrets backend or manage its encrypted secrets file
+    #[clap(name = "local", subcommand)]
+    LocalCommand(LocalSecretsCommand),
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum LocalSecretsCommand {
+    /// Run a local secrets backend, serving secrets from an encrypted file or environment variables
+    #[clap(name = "serve")]
+    Serve {
+        #[clap(flatten)]
+        local_opts: LocalSecretsOpts,
+        /// NATS server host to connect to
+        #[clap(long = "nats-host", default_value = "127.0.0.1")]
+        nats_host: String,
+        /// NATS server port to connect to
+        #[clap(long = "nats-port", default_value = "4222")]
+        nats_port: u16,
+        /// The topic prefix the secrets backend listens on, which hosts must be configured with
+        #[clap(long = "secrets-topic", default_value = DEFAULT_SECRETS_TOPIC)]
+        secrets_topic: String,
+    },
+
+    /// Put a secret into the secrets file
+    #[clap(name = "put")]
+    Put {
+        #[clap(flatten)]
+        file_opts: LocalSecretsFileOpts,
+        /// The name of the secret to put in the file
+        #[clap(name = "name")]
+        name: String,
+        /// The string value of the secret
+        #[clap(
+            long = "string",
+            env = "SECRET_STRING_VALUE",
+            hide_env_values = true,
+            required_unless_present = "binary",
+            conflicts_with = "binary"
+        )]
+        string: Option<String>,
+        /// The path to a file to read the binary value of the secret from
+        #[clap(long = "binary", required_unless_present = "string")]
+        binary: Option<PathBuf>,
+    },
+
+    /// Delete a secret from the secrets file
+    #[clap(name = "del", alias = "delete")]
+    Delete {
+        #[clap(flatten)]
+        file_opts: LocalSecretsFileOpts,
+        /// The name of the secret to delete from the file
+        #[clap(name = "name")]
+        name: String,
+    },
+
+    /// Allow a component or provider to access secrets in the secrets file
+    #[clap(name = "add-mapping")]
+    AddMapping {
+        #[clap(flatten)]
+        file_opts: LocalSecretsFileOpts,
+        /// The public key of the component or provider
+        #[clap(name = "public-key")]
+        public_key: String,
+        /// The name of a secret to allow access to. Can be specified multiple times.
+        #[clap(long = "secret", required = true)]
+        secrets: Vec<String>,
+    },
+
+    /// Revoke the access of a component or provider to secrets in the secrets file
+    #[clap(name = "remove-mapping")]
+    RemoveMapping {
+        #[clap(flatten)]
+        file_opts: LocalSecretsFileOpts,
+        /// The public key of the component or provider
+        #[clap(name = "public-key")]
+        public_key: String,
+        /// The name of a secret to revoke access to. Can be specified multiple times.
+        #[clap(long = "secret", required = true)]
+        secrets: Vec<String>,
+    },
+}
+
+/// Options for managing the encrypted secrets file of a local secrets backend
+#[derive(Parser, Debug, Clone)]
+pub struct LocalSecretsFileOpts {
+    /// Path to the encrypted secrets file, created if it does not exist
+    #[clap(long = "secrets-local-file", env = "WASMCLOUD_SECRETS_LOCAL_FILE")]
+    pub secrets_local_file: PathBuf,
+
+    /// The xkey seed the secrets file is encrypted with
+    #[clap(
+        long = "secrets-local-xkey-seed",
+        env = WASMCLOUD_SECRETS_LOCAL_XKEY_SEED,
+        hide_env_values = true
+    )]
+    pub secrets_local_xkey_seed: String,
+}
+
+impl LocalSecretsFileOpts {
+    async fn load(&self) -> anyhow::Result<(SecretsFile, XKey)> {
+        let xkey = XKey::from_seed(&self.secrets_local_xkey_seed)
+            .context("failed to create xkey from seed")?;
+        let file = SecretsFile::load(&self.secrets_local_file, &xkey).await?;
+        Ok((file, xkey))
+    }
+
+    async fn save(&self, file: &SecretsFile, xkey: &XKey) -> anyhow::Result<()> {
+        file.save(&self.secrets_local_file, xkey).await
+    }
+}
+
+/// The topic prefix secrets backends listen on by default
+pub const DEFAULT_SECRETS_TOPIC: &str = "wasmcloud.secrets";
+
+/// Options for running a local secrets backend, see `wash secrets local` for managing the
+/// encrypted secrets file
+#[derive(Parser, Debug, Clone, Default)]
+pub struct LocalSecretsOpts {
+    /// Path to an encrypted secrets file to serve secrets from with a local secrets backend
+    #[clap(
+        long = "secrets-local-file",
+        env = "WASMCLOUD_SECRETS_LOCAL_FILE",
+        requires = "secrets_local_xkey_seed"
+    )]
+    pub secrets_local_file: Option<PathBuf>,
+
+    /// The xkey seed the local secrets file is encrypted with
+    #[clap(
+        long = "secrets-local-xkey-seed",
+        env = WASMCLOUD_SECRETS_LOCAL_XKEY_SEED,
+        hide_env_values = true
+    )]
+    pub secrets_local_xkey_seed: Option<String>,
+
+    /// Prefix of environment variables to serve secrets from with a local secrets backend, e.g. `WASMCLOUD_SECRET_`.
+    /// Access to these secrets is granted by the mappings in the local secrets file.
+    #[clap(
+        long = "secrets-local-env-prefix",
+        env = "WASMCLOUD_SECRETS_LOCAL_ENV_PREFIX",
+        requires = "secrets_local_file"
+    )]
+    pub secrets_local_env_prefix: Option<String>,
+
+    /// Name of the local secrets backend, to be used as the backend of secret references
+    #[clap(long = "secrets-local-name", default_value = "local")]
+    pub secrets_local_name: String,
+}
+
+/// The environment variable the xkey seed of the local secrets file is read from
+pub const WASMCLOUD_SECRETS_LOCAL_XKEY_SEED: &str = "WASMCLOUD_SECRETS_LOCAL_XKEY_SEED";
+
+impl LocalSecretsOpts {
+    /// Returns `true` if a local secrets backend should be run
+    pub fn enabled(&self) -> bool {
+        self.secrets_local_file.is_some()
+    }
+
+    /// Arguments for running a local secrets backend with these options using `wash secrets local serve`.
+    /// The xkey seed is not included, it must be passed in [`WASMCLOUD_SECRETS_LOCAL_XKEY_SEED`].
+    pub fn serve_args(&self) -> Vec<String> {
+        let mut args = vec![
+            "--secrets-local-name".to_string(),
+            self.secrets_local_name.clone(),
+        ];
+        if let Some(file) = &self.secrets_local_file {
+            args.push("--secrets-local-file".to_string());
+            args.push(file.to_string_lossy().to_string());
+        }
+        if let Some(prefix) = &self.secrets_local_env_prefix {
+            args.push("--secrets-local-env-prefix".to_string());
+            args.push(prefix.clone());
+        }
+        args
+    }
+}
+
+/// Run a local secrets backend on the given NATS server until the connection is closed
+pub async fn serve_local_secrets(
+    opts: LocalSecretsOpts,
+    nats_address: &str,
+    secrets_topic: String,
+) -> anyhow::Result<()> {
+    let file = opts
+        .secrets_local_file
+        .context("a secrets file holding the mappings of entities to secrets must be provided")?;
+    let seed = opts
+        .secrets_local_xkey_seed
+        .context("an encryption XKey is required to read the secrets file")?;
+    let encryption_xkey = XKey::from_seed(&seed).context("failed to create xkey from seed")?;
+    let store = LocalSecretsStore::new(file, encryption_xkey, opts.secrets_local_env_prefix);
+    let client = async_nats::connect(nats_address)
+        .await
+        .with_context(|| format!("failed to connect to NATS at {nats_address}"))?;
+    LocalSecretsApi::new(
+        XKey::new(),
+        store,
+        client,
+        secrets_topic,
+        opts.secrets_local_name,
+        SECRET_API_VERSION.to_string(),
+    )
+    .run()
+    .await
 }
 
 pub async fn handle_command(
@@ -89,6 +299,110 @@ pub async fn handle_command(
         SecretsCliCommand::DelCommand { opts, name } => {
             delete_config(opts, &secret_configdata_key(&name), output_kind).await
         }
+        SecretsCliCommand::LocalCommand(command) => handle_local_command(command).await,
+    }
+}
+
+async fn handle_local_command(command: LocalSecretsCommand) -> anyhow::Result<CommandOutput> {
+    match command {
+        LocalSecretsCommand::Serve {
+            local_opts,
+            nats_host,
+            nats_port,
+            secrets_topic,
+        } => {
+            anyhow::ensure!(
+                local_opts.enabled(),
+                "a secrets file must be provided, an environment variable prefix is optional"
+            );
+            let name = local_opts.secrets_local_name.clone();
+            eprintln!("🔐 Serving secrets as backend '{name}' on topic '{secrets_topic}'");
+            serve_local_secrets(
+                local_opts,
+                &format!("{nats_host}:{nats_port}"),
+                secrets_topic,
+            )
+            .await?;
+            Ok(CommandOutput::from_key_and_text(
+                "backend",
+                format!("Local secrets backend '{name}' stopped"),
+            ))
+        }
+        LocalSecretsCommand::Put {
+            file_opts,
+            name,
+            string,
+            binary,
+        } => {
+            let (mut file, xkey) = file_opts.load().await?;
+            // NOTE: The clap parser ensures that exactly one of these is present
+            let secret = match (string, binary) {
+                (Some(string), _) => StoredSecret::String(string),
+                (None, Some(path)) => {
+                    StoredSecret::Binary(tokio::fs::read(&path).await.with_context(|| {
+                        format!(
+                            "failed to read binary secret from file '{}'",
+                            path.display()
+                        )
+                    })?)
+                }
+                (None, None) => {
+                    anyhow::bail!("either a string or a binary secret must be provided")
+                }
+            };
+            file.secrets.insert(name.clone(), secret);
+            file_opts.save(&file, &xkey).await?;
+            Ok(CommandOutput::from_key_and_text(
+                "name",
+                format!("Secret '{name}' put successfully"),
+            ))
+        }
+        LocalSecretsCommand::Delete { file_opts, name } => {
+            let (mut file, xkey) = file_opts.load().await?;
+            anyhow::ensure!(
+                file.secrets.remove(&name).is_some(),
+                "secret '{name}' not found"
+            );
+            file_opts.save(&file, &xkey).await?;
+            Ok(CommandOutput::from_key_and_text(
+                "name",
+                format!("Secret '{name}' deleted successfully"),
+            ))
+        }
+        LocalSecretsCommand::AddMapping {
+            file_opts,
+            public_key,
+            secrets,
+        } => {
+            let (mut file, xkey) = file_opts.load().await?;
+            file.mappings
+                .entry(public_key.clone())
+                .or_default()
+                .extend(secrets.iter().cloned());
+            file_opts.save(&file, &xkey).await?;
+            Ok(CommandOutput::from_key_and_text(
+                "public_key",
+                format!("Public key '{public_key}' can now access secrets: {secrets:?}"),
+            ))
+        }
+        LocalSecretsCommand::RemoveMapping {
+            file_opts,
+            public_key,
+            secrets,
+        } => {
+            let (mut file, xkey) = file_opts.load().await?;
+            if let Some(mapped) = file.mappings.get_mut(&public_key) {
+                mapped.retain(|secret| !secrets.contains(secret));
+                if mapped.is_empty() {
+                    file.mappings.remove(&public_key);
+                }
+            }
+            file_opts.save(&file, &xkey).await?;
+            Ok(CommandOutput::from_key_and_text(
+                "public_key",
+                format!("Public key '{public_key}' no longer has access to secrets: {secrets:?}"),
+            ))
+        }
     }
 }
 
@@ -34,6 +34,9 @@ use wasmcloud_control_interface::{Client as CtlClient, ClientBuilder as CtlClien
 use crate::app::deploy_model_from_manifest;
 use crate::appearance::spinner::Spinner;
 use crate::down::stop_nats;
+use crate::secrets::{
+    serve_local_secrets, LocalSecretsOpts, DEFAULT_SECRETS_TOPIC, WASMCLOUD_SECRETS_LOCAL_XKEY_SEED,
+};
 
 mod config;
 mod credsfile;
@@ -53,6 +56,9 @@ pub struct UpCommand {
 
     #[clap(flatten)]
     pub wadm_opts: WadmOpts,
+
+    #[clap(flatten)]
+    pub secrets_local_opts: LocalSecretsOpts,
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -382,7 +388,12 @@ pub async fn handle_up(cmd: UpCommand, output_kind: OutputKind) -> Result<Comman
         rpc_seed: cmd.wasmcloud_opts.rpc_seed.or(ctx.rpc_seed),
         rpc_credsfile: cmd.wasmcloud_opts.rpc_credsfile.or(ctx.rpc_credsfile),
         max_execution_time: cmd.wasmcloud_opts.max_execution_time,
-        secrets_topic: cmd.wasmcloud_opts.secrets_topic,
+        // The local secrets backend listens on the default topic, unless one is provided
+        secrets_topic: cmd.wasmcloud_opts.secrets_topic.or_else(|| {
+            cmd.secrets_local_opts
+                .enabled()
+                .then(|| DEFAULT_SECRETS_TOPIC.to_string())
+        }),
         policy_topic: cmd.wasmcloud_opts.policy_topic,
         cluster_seed: cmd
             .wasmcloud_opts
@@ -543,6 +554,37 @@ pub async fn handle_up(cmd: UpCommand, output_kind: OutputKind) -> Result<Comman
         None
     };
 
+    let secrets_local = if cmd.secrets_local_opts.enabled() {
+        spinner.update_spinner_message(" Starting local secrets backend ...".to_string());
+        let secrets_topic = wasmcloud_opts
+            .secrets_topic
+            .clone()
+            .context("missing secrets topic")?;
+        match start_local_secrets(
+            cmd.secrets_local_opts.clone(),
+            &nats_host,
+            nats_port,
+            secrets_topic,
+            cmd.detached,
+            &install_dir,
+        )
+        .await
+        {
+            Ok(secrets_local) => Some(secrets_local),
+            Err(e) => {
+                if let Some(child) = wadm_process {
+                    stop_wadm(child, &install_dir).await?;
+                }
+                if nats_bin.is_some() {
+                    stop_nats(install_dir).await?;
+                }
+                return Err(e.context("failed to start local secrets backend"));
+            }
+        }
+    } else {
+        None
+    };
+
     // Download wasmCloud if not already installed
     let wasmcloud_executable = if !wasmcloud_opts.start_only {
         spinner.update_spinner_message(" Downloading wasmCloud ...".to_string());
@@ -670,6 +712,9 @@ pub async fn handle_up(cmd: UpCommand, output_kind: OutputKind) -> Result<Comman
         // remove wadm pidfile, the process is stopped automatically by CTRL+c
         remove_wadm_pidfile(&install_dir).await?;
     }
+    if let Some(LocalSecrets::Task(task)) = secrets_local {
+        task.abort();
+    }
 
     spinner.finish_and_clear();
     Ok(CommandOutput::new(out_text, out_json))
@@ -946,6 +991,63 @@ where
     remove_wadm_pidfile(install_dir).await
 }
 
+/// The name of the pid file of a local secrets backend running in the background
+pub const SECRETS_LOCAL_PID: &str = "secrets-local.pid";
+
+/// A running local secrets backend
+enum LocalSecrets {
+    /// Backend running in this process, in interactive mode
+    Task(tokio::task::JoinHandle<()>),
+    /// Backend running as a `wash secrets local serve` background process, in detached mode
+    Process,
+}
+
+/// Start a local secrets backend. In detached mode, the backend is started as a background process
+/// of `wash secrets local serve`, which is stopped by `wash down`.
+async fn start_local_secrets(
+    opts: LocalSecretsOpts,
+    nats_host: &str,
+    nats_port: u16,
+    secrets_topic: String,
+    detached: bool,
+    install_dir: &Path,
+) -> Result<LocalSecrets> {
+    if !detached {
+        let nats_address = format!("{nats_host}:{nats_port}");
+        return Ok(LocalSecrets::Task(tokio::spawn(async move {
+            if let Err(e) = serve_local_secrets(opts, &nats_address, secrets_topic).await {
+                eprintln!("🟨 Local secrets backend stopped: {e:#}");
+            }
+        })));
+    }
+
+    let log_file = tokio::fs::File::create(install_dir.join("secrets-local.log"))
+        .await?
+        .into_std()
+        .await;
+    let mut cmd = tokio::process::Command::new(
+        std::env::current_exe().context("failed to find wash executable")?,
+    );
+    cmd.args(["secrets", "local", "serve"])
+        .args(opts.serve_args())
+        .args(["--nats-host", nats_host])
+        .args(["--nats-port", &nats_port.to_string()])
+        .args(["--secrets-topic", &secrets_topic])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(log_file);
+    if let Some(seed) = opts.secrets_local_xkey_seed {
+        cmd.env(WASMCLOUD_SECRETS_LOCAL_XKEY_SEED, seed);
+    }
+    let child = cmd
+        .spawn()
+        .context("failed to spawn local secrets backend")?;
+    if let Some(pid) = child.id() {
+        tokio::fs::write(install_dir.join(SECRETS_LOCAL_PID), pid.to_string()).await?;
+    }
+    Ok(LocalSecrets::Process)
+}
+
 async fn remove_wadm_pidfile<P>(install_dir: P) -> Result<()>
 where
     P: AsRef<Path>,
@@ -6,6 +6,7 @@ use std::collections::HashMap;
 
 use wash_cli::config::ConfigCliCommand;
 use wash_lib::cli::{CliConnectionOpts, OutputKind};
+use wasmcloud_control_interface::ConfigRevision;
 
 #[tokio::test]
 async fn test_config_put_and_get() -> anyhow::Result<()> {
@@ -33,6 +34,7 @@ async fn test_config_put_and_get() -> anyhow::Result<()> {
                 ..Default::default()
             },
             name: "foobar".to_string(),
+            revision: None,
         },
         OutputKind::Json,
     )
@@ -46,6 +48,64 @@ async fn test_config_put_and_get() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_config_history_and_rollback() -> anyhow::Result<()> {
+    let wash_instance = TestWashInstance::create().await?;
+    let opts = CliConnectionOpts {
+        ctl_port: Some(wash_instance.nats_port.to_string()),
+        ..Default::default()
+    };
+    for value in ["key=first", "key=second"] {
+        wash_cli::config::handle_command(
+            ConfigCliCommand::PutCommand {
+                opts: opts.clone(),
+                name: "versioned".to_string(),
+                config_values: vec![value.to_string()],
+            },
+            OutputKind::Json,
+        )
+        .await?;
+    }
+
+    let history = wash_cli::config::handle_command(
+        ConfigCliCommand::HistoryCommand {
+            opts: opts.clone(),
+            name: "versioned".to_string(),
+        },
+        OutputKind::Json,
+    )
+    .await?
+    .map;
+    let history: Vec<ConfigRevision> = serde_json::from_value(history["history"].clone())?;
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].config.get("key").unwrap(), "first");
+    assert_eq!(history[1].config.get("key").unwrap(), "second");
+
+    wash_cli::config::handle_command(
+        ConfigCliCommand::RollbackCommand {
+            opts: opts.clone(),
+            name: "versioned".to_string(),
+            revision: history[0].revision,
+        },
+        OutputKind::Json,
+    )
+    .await?;
+
+    let retrieved_config = wash_cli::config::handle_command(
+        ConfigCliCommand::GetCommand {
+            opts,
+            name: "versioned".to_string(),
+            revision: None,
+        },
+        OutputKind::Json,
+    )
+    .await?
+    .map;
+    assert_eq!(retrieved_config.get("key").unwrap(), "first");
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_config_secret_name_error() -> anyhow::Result<()> {
     // Attempt to create a config with a secret name
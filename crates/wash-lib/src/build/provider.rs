@@ -48,6 +48,9 @@ pub(crate) async fn build_provider(
             schema: None,
             name: common_config.name.to_string(),
             arch: detect_arch(),
+            wit: provider_config
+                .bundle_wit
+                .then(|| common_config.path.join("wit")),
         },
         &provider_bytes,
     )
@@ -1,6 +1,7 @@
 use anyhow::{bail, Context, Result};
 use clap::{Args, Parser, Subcommand};
 use nkeys::{KeyPair, KeyPairType};
+use provider_archive::{Compression, ProviderArchive};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::{
@@ -8,16 +9,18 @@ use std::{
     fs::{self, File},
     io::Read,
     path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
 };
 use tracing::warn;
 use wascap::{
-    jwt::{Account, CapabilityProvider, Claims, Component, Operator},
-    wasm::{days_from_now_to_jwt_time, sign_buffer_with_claims},
+    jwt::{Account, CapabilityProvider, Claims, Component, Operator, TokenValidation},
+    wasm::{days_from_now_to_jwt_time, embed_cosignature, sign_buffer_with_claims},
 };
+use wasmcloud_core::revocation::{revocation_bucket, Revocation, RevocationKind};
 
-use super::{extract_keypair, get::GetClaimsCommand, CommandOutput, OutputKind};
+use super::{extract_keypair, get::GetClaimsCommand, CliConnectionOpts, CommandOutput, OutputKind};
 use crate::{
-    cli::inspect,
+    cli::{inspect, verify},
     common::boxed_err_to_anyhow,
     config::WashConnectionOptions,
     parser::{get_config, ComponentConfig, ProjectConfig, ProviderConfig, TypeConfig},
@@ -35,6 +38,81 @@ pub enum ClaimsCliCommand {
     /// Generate a signed JWT by supplying basic token information, a signing seed key, and metadata
     #[clap(name = "token", subcommand)]
     Token(TokenCommand),
+    /// Revoke claims across a lattice, so that hosts refuse to start the components and providers
+    /// they belong to
+    #[clap(name = "revoke")]
+    Revoke(RevokeCommand),
+    /// Add a detached co-signature to a signed component or provider archive, without replacing
+    /// its claims
+    #[clap(name = "cosign")]
+    Cosign(CosignCommand),
+    /// Verify the claims and co-signatures of a signed component or provider archive, optionally
+    /// requiring a number of trusted signers. Equivalent to `wash verify` with `--signer` and
+    /// `--threshold`
+    #[clap(name = "verify")]
+    Verify(VerifyCommand),
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct CosignCommand {
+    /// Path to the signed component or provider archive to co-sign in place. Co-signed provider archives can't be loaded by hosts and tools using `provider-archive` older than 0.13.0
+    pub source: String,
+
+    /// Path to the co-signer's account seed key or the seed itself. Defaults to the account key of the current user in the key directory
+    #[clap(
+        short = 's',
+        long = "signer",
+        env = "WASH_COSIGNER_KEY",
+        hide_env_values = true
+    )]
+    pub signer: Option<String>,
+
+    /// Location of key files for signing. Defaults to $WASH_KEYS ($HOME/.wash/keys)
+    #[clap(long = "directory", env = "WASH_KEYS", hide_env_values = true)]
+    pub directory: Option<PathBuf>,
+
+    /// Indicates the co-signature expires in the given amount of days. If this option is left off, the co-signature will never expire
+    #[clap(short = 'x', long = "expires")]
+    pub expires_in_days: Option<u64>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct VerifyCommand {
+    /// Path to the signed component or provider archive to verify
+    pub source: String,
+
+    /// Public key of a trusted signer, can be specified multiple times. If no trusted signers are provided, all valid signatures count
+    #[clap(long = "signer", value_delimiter = ',')]
+    pub signers: Vec<String>,
+
+    /// The number of distinct signers whose valid signatures are required
+    #[clap(long = "threshold", default_value_t = 1, value_parser = verify::parse_threshold)]
+    pub threshold: usize,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct RevokeCommand {
+    /// The subject public key to revoke claims of, or the issuer public key or JWT ID with `--issuer` or `--jti`
+    pub id: String,
+
+    /// Revoke all claims issued by the public key `id`, e.g. an account
+    #[clap(long = "issuer", conflicts_with = "jti")]
+    pub issuer: bool,
+
+    /// Revoke the single JWT with the ID `id`
+    #[clap(long = "jti", conflicts_with = "issuer")]
+    pub jti: bool,
+
+    /// The reason the claims are revoked
+    #[clap(long = "reason")]
+    pub reason: Option<String>,
+
+    /// Lift a previous revocation of `id` instead of revoking it
+    #[clap(long = "lift", conflicts_with = "reason")]
+    pub lift: bool,
+
+    #[clap(flatten)]
+    pub opts: CliConnectionOpts,
 }
 
 #[derive(Args, Debug, Clone)]
@@ -332,9 +410,10 @@ impl ComponentMetadata {
 impl From<InspectCommand> for inspect::InspectCliCommand {
     fn from(cmd: InspectCommand) -> Self {
         inspect::InspectCliCommand {
-            target: cmd.component,
+            target: Some(cmd.component),
             jwt_only: cmd.jwt_only,
             wit: cmd.wit,
+            command: None,
             digest: cmd.digest,
             allow_latest: cmd.allow_latest,
             user: cmd.user,
@@ -346,6 +425,25 @@ impl From<InspectCommand> for inspect::InspectCliCommand {
     }
 }
 
+impl From<VerifyCommand> for verify::VerifyCommand {
+    fn from(cmd: VerifyCommand) -> Self {
+        verify::VerifyCommand {
+            target: cmd.source,
+            trusted_operators: Vec::new(),
+            trusted_accounts: Vec::new(),
+            trusted_account_jwts: Vec::new(),
+            signers: cmd.signers,
+            threshold: cmd.threshold,
+            digest: None,
+            allow_latest: false,
+            user: None,
+            password: None,
+            insecure: false,
+            insecure_skip_tls_verify: false,
+        }
+    }
+}
+
 pub async fn handle_command(
     command: ClaimsCliCommand,
     output_kind: OutputKind,
@@ -384,9 +482,150 @@ pub async fn handle_command(
         ClaimsCliCommand::Token(gencmd) => {
             generate_token(gencmd, output_kind, project_config.as_ref())
         }
+        ClaimsCliCommand::Revoke(revokecmd) => revoke(revokecmd).await,
+        ClaimsCliCommand::Cosign(cosigncmd) => cosign(cosigncmd, output_kind).await,
+        ClaimsCliCommand::Verify(verifycmd) => verify::handle_command(verifycmd.into()).await,
     }
 }
 
+const WASM_MAGIC: &[u8] = b"\0asm";
+
+/// Add a detached co-signature to a signed component or provider archive in place
+pub async fn cosign(cmd: CosignCommand, output_kind: OutputKind) -> Result<CommandOutput> {
+    let signer = extract_keypair(
+        cmd.signer.as_deref(),
+        Some(&cmd.source),
+        cmd.directory,
+        KeyPairType::Account,
+        true,
+        output_kind,
+    )?;
+    let expires = days_from_now_to_jwt_time(cmd.expires_in_days);
+    let buf = fs::read(&cmd.source)
+        .with_context(|| format!("failed to read file for co-signing '{}'", cmd.source))?;
+    if buf.starts_with(WASM_MAGIC) {
+        let cosigned = embed_cosignature(&buf, &signer, expires)
+            .with_context(|| format!("failed to co-sign component '{}'", cmd.source))?;
+        fs::write(&cmd.source, cosigned)?;
+    } else {
+        let mut par = ProviderArchive::try_load(&buf)
+            .await
+            .map_err(boxed_err_to_anyhow)
+            .with_context(|| format!("failed to load provider archive '{}'", cmd.source))?;
+        par.cosign(&signer, expires)
+            .map_err(boxed_err_to_anyhow)
+            .with_context(|| format!("failed to co-sign provider archive '{}'", cmd.source))?;
+        par.write_signed(&cmd.source, Compression::detect(&buf))
+            .await
+            .map_err(boxed_err_to_anyhow)
+            .with_context(|| format!("failed to write provider archive '{}'", cmd.source))?;
+    }
+
+    let mut map = HashMap::new();
+    map.insert("source".to_string(), json!(cmd.source));
+    map.insert("signer".to_string(), json!(signer.public_key()));
+    Ok(CommandOutput::new(
+        format!(
+            "Successfully co-signed {} with {}",
+            cmd.source,
+            signer.public_key()
+        ),
+        map,
+    ))
+}
+
+/// Returns why a token, whose validation result is `validation`, is not valid
+pub(crate) fn validation_error(validation: wascap::Result<TokenValidation>) -> Option<String> {
+    match validation {
+        Err(e) => Some(e.to_string()),
+        Ok(validation) if !validation.signature_valid => Some("signature is not valid".to_string()),
+        Ok(validation) if validation.expired => {
+            Some(format!("expired {}", validation.expires_human))
+        }
+        Ok(validation) if validation.cannot_use_yet => Some(format!(
+            "cannot be used until {}",
+            validation.not_before_human
+        )),
+        Ok(_) => None,
+    }
+}
+
+/// Publish a revocation to the revocation list of the lattice, or lift it
+pub async fn revoke(cmd: RevokeCommand) -> Result<CommandOutput> {
+    if cmd.id.is_empty()
+        || cmd
+            .id
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '.' | '*' | '>'))
+    {
+        bail!("invalid ID to revoke: `{}`", cmd.id);
+    }
+    let kind = match (cmd.issuer, cmd.jti) {
+        (true, _) => RevocationKind::Issuer,
+        (_, true) => RevocationKind::Jti,
+        _ => RevocationKind::Subject,
+    };
+
+    let wco: WashConnectionOptions = cmd.opts.try_into()?;
+    let lattice = wco.get_lattice();
+    let nats_client = wco.clone().into_nats_client().await?;
+    let js_context = if let Some(domain) = wco.js_domain {
+        async_nats::jetstream::with_domain(nats_client, domain)
+    } else {
+        async_nats::jetstream::new(nats_client)
+    };
+    let bucket = revocation_bucket(&lattice);
+    // NOTE: Hosts create the bucket on start, but revocations may be published before any host
+    // in the lattice was started
+    let store = match js_context.get_key_value(&bucket).await {
+        Ok(store) => store,
+        Err(_) => js_context
+            .create_key_value(async_nats::jetstream::kv::Config {
+                bucket: bucket.clone(),
+                history: 1,
+                ..Default::default()
+            })
+            .await
+            .map_err(|e| anyhow::anyhow!(e))
+            .with_context(|| format!("failed to create revocation bucket `{bucket}`"))?,
+    };
+
+    let key = kind.key(&cmd.id);
+    if cmd.lift {
+        store
+            .delete(&key)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))
+            .context("failed to lift revocation")?;
+        let mut map = HashMap::new();
+        map.insert("lifted".to_string(), json!(key));
+        return Ok(CommandOutput::new(
+            format!("Lifted revocation `{key}` in lattice `{lattice}`"),
+            map,
+        ));
+    }
+
+    let revocation = Revocation {
+        reason: cmd.reason,
+        revoked_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+    };
+    store
+        .put(&key, serde_json::to_vec(&revocation)?.into())
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .context("failed to publish revocation")?;
+    let mut map = HashMap::new();
+    map.insert("revoked".to_string(), json!(key));
+    map.insert("revocation".to_string(), json!(revocation));
+    Ok(CommandOutput::new(
+        format!("Revoked `{key}` in lattice `{lattice}`"),
+        map,
+    ))
+}
+
 fn generate_token(
     cmd: TokenCommand,
     output_kind: OutputKind,
@@ -774,6 +1013,39 @@ mod test {
         assert!(sanitize_alias(None).unwrap().is_none());
     }
 
+    #[test]
+    /// Enumerates all options and flags of the `claims verify` command
+    /// to ensure command line arguments do not change between versions
+    fn test_claims_verify_comprehensive() {
+        const SIGNER: &str = "ACOJJN6WUP4ODD75XEBKKTCCUJJCY5ZKQ56XVKYK4BEJWGVAOOQHZMCW";
+        const OTHER_SIGNER: &str = "AAJJH5YRLR5BDRDCI7RCL2UVR3CFS4TTSWRCQY3BGAR6SGGPUYXRKMB3";
+        let cmd: Cmd = Parser::try_parse_from([
+            "claims",
+            "verify",
+            "./tests/fixtures/echo_s.wasm",
+            "--signer",
+            SIGNER,
+            "--signer",
+            OTHER_SIGNER,
+            "--threshold",
+            "2",
+        ])
+        .unwrap();
+
+        match cmd.claims {
+            ClaimsCliCommand::Verify(VerifyCommand {
+                source,
+                signers,
+                threshold,
+            }) => {
+                assert_eq!(source, "./tests/fixtures/echo_s.wasm");
+                assert_eq!(signers, vec![SIGNER, OTHER_SIGNER]);
+                assert_eq!(threshold, 2);
+            }
+            cmd => panic!("claims constructed incorrect command: {cmd:?}"),
+        }
+    }
+
     #[test]
     /// Enumerates all options and flags of the `claims inspect` command
     /// to ensure command line arguments do not change between versions
@@ -1370,6 +1642,7 @@ mod test {
                 wit_world: Some("wasmcloud:httpserver".to_string()),
                 rust_target: None,
                 bin_name: None,
+                bundle_wit: false,
             })
         );
 
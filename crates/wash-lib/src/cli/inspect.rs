@@ -1,29 +1,44 @@
 use super::{cached_oci_file, CommandOutput, OutputKind};
 use crate::registry::{get_oci_artifact, OciPullOptions};
 use anyhow::{anyhow, bail, Context, Result};
-use clap::Parser;
+use clap::{Args, Parser, Subcommand};
 use provider_archive::ProviderArchive;
-use serde::de::DeserializeOwned;
+use serde::{de::DeserializeOwned, Serialize};
 use serde_json::json;
-use std::{collections::HashMap, fs::File, io::Read, path::PathBuf};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs::File,
+    io::Read,
+    path::PathBuf,
+};
 use term_table::{
     row::Row,
     table_cell::{Alignment, TableCell},
     Table,
 };
 use wascap::jwt::{Claims, Component, Token, TokenValidation, WascapEntity};
+use wit_component::DecodedWasm;
+use wit_parser::{
+    Function, Handle, Resolve, Results, Type, TypeDefKind, TypeId, WorldId, WorldItem,
+};
 
 #[derive(Debug, Parser, Clone)]
+#[clap(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 pub struct InspectCliCommand {
     /// Path or OCI URL to signed component or provider archive
-    pub target: String,
+    #[clap(required = true)]
+    pub target: Option<String>,
+
+    #[clap(subcommand)]
+    pub command: Option<InspectSubcommand>,
 
     /// Extract the raw JWT from the file and print to stdout
     #[clap(name = "jwt_only", long = "jwt-only", conflicts_with = "wit")]
     pub jwt_only: bool,
 
-    /// Extract the WIT world from a component and print to stdout instead of the claims.
-    /// When inspecting a provider archive, this flag will be ignored.
+    /// Extract the WIT world from a component, or the WIT package bundled in a provider archive,
+    /// and print to stdout instead of the claims.
     #[clap(
         name = "wit",
         long = "wit",
@@ -36,8 +51,8 @@ pub struct InspectCliCommand {
     #[clap(short = 'd', long = "digest")]
     pub digest: Option<String>,
 
-    /// Allow latest artifact tags (if OCI URL is provided for `<target>`)
-    #[clap(long = "allow-latest")]
+    /// Allow latest artifact tags (if OCI URLs are provided)
+    #[clap(long = "allow-latest", global = true)]
     pub allow_latest: bool,
 
     /// OCI username, if omitted anonymous authentication will be used
@@ -45,7 +60,8 @@ pub struct InspectCliCommand {
         short = 'u',
         long = "user",
         env = "WASH_REG_USER",
-        hide_env_values = true
+        hide_env_values = true,
+        global = true
     )]
     pub user: Option<String>,
 
@@ -54,54 +70,63 @@ pub struct InspectCliCommand {
         short = 'p',
         long = "password",
         env = "WASH_REG_PASSWORD",
-        hide_env_values = true
+        hide_env_values = true,
+        global = true
     )]
     pub password: Option<String>,
 
     /// Allow insecure (HTTP) registry connections
-    #[clap(long = "insecure")]
+    #[clap(long = "insecure", global = true)]
     pub insecure: bool,
 
     /// Skip checking OCI registry's certificate for validity
-    #[clap(long = "insecure-skip-tls-verify")]
+    #[clap(long = "insecure-skip-tls-verify", global = true)]
     pub insecure_skip_tls_verify: bool,
 
     /// skip the local OCI cache and pull the artifact from the registry to inspect
-    #[clap(long = "no-cache")]
+    #[clap(long = "no-cache", global = true)]
     pub no_cache: bool,
 }
 
+#[derive(Debug, Clone, Subcommand)]
+pub enum InspectSubcommand {
+    /// Compare two versions of a signed component or provider archive, e.g. the deployed and a
+    /// candidate version. Reports changes of the claims, WIT world, size and hashes, and whether
+    /// the WIT changes are breaking.
+    #[clap(name = "diff")]
+    Diff(InspectDiffCommand),
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct InspectDiffCommand {
+    /// Path or OCI URL to the old version of the artifact, e.g. the deployed one
+    pub old: String,
+
+    /// Path or OCI URL to the new version of the artifact, e.g. a candidate for deployment
+    pub new: String,
+}
+
 /// Attempts to inspect a provider archive or component
 pub async fn handle_command(
     command: impl Into<InspectCliCommand>,
     _output_kind: OutputKind,
 ) -> Result<CommandOutput> {
     let command = command.into();
-    let mut buf = Vec::new();
-    if PathBuf::from(command.target.clone()).as_path().is_dir() {
-        let mut f = File::open(&command.target).map_err(|e| {
-            std::io::Error::new(
-                e.kind(),
-                format!("failed to target file [{}]: {e}", &command.target),
-            )
-        })?;
-        f.read_to_end(&mut buf)?;
-    } else {
-        let cache_file = (!command.no_cache).then(|| cached_oci_file(&command.target.clone()));
-        buf = get_oci_artifact(
-            command.target.clone(),
-            cache_file,
-            OciPullOptions {
-                digest: command.digest.clone(),
-                allow_latest: command.allow_latest,
-                user: command.user.clone(),
-                password: command.password.clone(),
-                insecure: command.insecure,
-                insecure_skip_tls_verify: command.insecure_skip_tls_verify,
-            },
-        )
-        .await?;
+    if let Some(InspectSubcommand::Diff(InspectDiffCommand { old, new })) = &command.command {
+        let old_buf = read_artifact(&command, old, None).await?;
+        let new_buf = read_artifact(&command, new, None).await?;
+        return diff_artifacts(
+            old,
+            summarize_artifact(&old_buf).await?,
+            new,
+            summarize_artifact(&new_buf).await?,
+        );
     }
+    let target = command
+        .target
+        .clone()
+        .context("a component or provider archive to inspect must be provided")?;
+    let buf = read_artifact(&command, &target, command.digest.clone()).await?;
 
     let wit_parsed = wasmparser::Parser::new(0).parse_all(&buf).next();
 
@@ -110,18 +135,10 @@ pub async fn handle_command(
         Some(Ok(wasmparser::Payload::Version {
             encoding: wasmparser::Encoding::Component,
             ..
-        })) if command.wit => {
-            let witty = wit_component::decode(&buf).expect("Failed to decode WIT");
-            let resolve = witty.resolve();
-            let main = witty.package();
-            let mut printer = wit_component::WitPrinter::default();
-            CommandOutput::from_key_and_text(
-                "wit",
-                printer
-                    .print(resolve, main, &[])
-                    .context("should be able to print WIT world from a component")?,
-            )
-        }
+        })) if command.wit => CommandOutput::from_key_and_text(
+            "wit",
+            print_wit(&buf).context("should be able to print WIT world from a component")?,
+        ),
         // Catch trying to inspect a WIT from a WASI Preview 1 module
         Some(Ok(wasmparser::Payload::Version {
             encoding: wasmparser::Encoding::Module,
@@ -130,16 +147,29 @@ pub async fn handle_command(
             bail!("No WIT present in Wasm, this looks like a WASI Preview 1 module")
         }
         // Fail to inspect wit from a non-wasm file
-        _ if command.wit => bail!("Invalid Wasm, could not parse WIT"),
+        Some(Ok(_)) if command.wit => bail!("Invalid Wasm, could not parse WIT"),
+        // Inspect the WIT package bundled in a provider archive
+        _ if command.wit => {
+            let artifact = ProviderArchive::try_load(&buf)
+                .await
+                .map_err(|e| anyhow!("{}", e))
+                .context("Invalid Wasm or provider archive, could not parse WIT")?;
+            let wit = artifact
+                .wit()
+                .context("No WIT package present in provider archive")?;
+            CommandOutput::from_key_and_text(
+                "wit",
+                print_wit(wit)
+                    .context("should be able to print WIT package from a provider archive")?,
+            )
+        }
         // Inspect claims inside of Wasm
         Some(Ok(_)) => {
-            let module_name = command.target.clone();
-            let jwt_only = command.jwt_only;
-            let caps = get_caps(command.clone(), &buf).await?;
-            let token =
-                caps.with_context(|| format!("No capabilities discovered in : {module_name}"))?;
+            // Extract will return an error if it encounters an invalid hash in the claims
+            let token = wascap::wasm::extract_claims(&buf)?
+                .with_context(|| format!("No capabilities discovered in : {target}"))?;
 
-            if jwt_only {
+            if command.jwt_only {
                 CommandOutput::from_key_and_text("token", token.jwt)
             } else {
                 let validation = wascap::jwt::validate_token::<Component>(&token.jwt)?;
@@ -154,19 +184,47 @@ pub async fn handle_command(
             }
         }
         //  Fallback to inspecting a provider archive
-        _ => render_provider_claims(command.clone(), &buf).await?,
+        _ => render_provider_claims(&buf).await?,
     };
     Ok(output)
 }
 
-/// Extracts claims for a given OCI artifact
-async fn get_caps(
-    cmd: InspectCliCommand,
-    artifact_bytes: &[u8],
-) -> Result<Option<Token<Component>>> {
-    let _cache_path = (!cmd.no_cache).then(|| cached_oci_file(&cmd.target));
-    // Extract will return an error if it encounters an invalid hash in the claims
-    Ok(wascap::wasm::extract_claims(artifact_bytes)?)
+/// Reads the bytes of the artifact at `target`, a local path or OCI URL
+async fn read_artifact(
+    command: &InspectCliCommand,
+    target: &str,
+    digest: Option<String>,
+) -> Result<Vec<u8>> {
+    if PathBuf::from(target).as_path().is_dir() {
+        let mut buf = Vec::new();
+        let mut f = File::open(target).map_err(|e| {
+            std::io::Error::new(e.kind(), format!("failed to target file [{target}]: {e}"))
+        })?;
+        f.read_to_end(&mut buf)?;
+        Ok(buf)
+    } else {
+        let cache_file = (!command.no_cache).then(|| cached_oci_file(target));
+        get_oci_artifact(
+            target.to_string(),
+            cache_file,
+            OciPullOptions {
+                digest,
+                allow_latest: command.allow_latest,
+                user: command.user.clone(),
+                password: command.password.clone(),
+                insecure: command.insecure,
+                insecure_skip_tls_verify: command.insecure_skip_tls_verify,
+            },
+        )
+        .await
+    }
+}
+
+/// Decodes a Wasm component or encoded WIT package and prints its WIT
+fn print_wit(wasm: &[u8]) -> Result<String> {
+    let decoded = wit_component::decode(wasm).context("failed to decode WIT")?;
+    let mut printer = wit_component::WitPrinter::default();
+    printer.print(decoded.resolve(), decoded.package(), &[])
 }
 
 /// Renders component claims into provided output format
@@ -207,6 +265,12 @@ pub fn render_component_claims(
     map.insert("revision".to_string(), json!(friendly_rev));
     map.insert("tags".to_string(), json!(tags));
     map.insert("name".to_string(), json!(name));
+    if let Some(imports) = &md.imports {
+        map.insert("imports".to_string(), json!(imports));
+    }
+    if let Some(exports) = &md.exports {
+        map.insert("exports".to_string(), json!(exports));
+    }
 
     let mut table = render_core(&claims, validation);
 
@@ -232,6 +296,27 @@ pub fn render_component_claims(
         Alignment::Left,
     )]));
 
+    for (label, interfaces) in [("Imports", &md.imports), ("Exports", &md.exports)] {
+        let Some(interfaces) = interfaces else {
+            continue;
+        };
+        table.add_row(Row::new(vec![TableCell::new_with_alignment(
+            label,
+            2,
+            Alignment::Center,
+        )]));
+        let interfaces = if interfaces.is_empty() {
+            "None".to_string()
+        } else {
+            interfaces.join("\n")
+        };
+        table.add_row(Row::new(vec![TableCell::new_with_alignment(
+            interfaces,
+            2,
+            Alignment::Left,
+        )]));
+    }
+
     CommandOutput::new(table.render(), map)
 }
 
@@ -285,11 +370,7 @@ where
 }
 
 /// Inspects a provider archive
-pub(crate) async fn render_provider_claims(
-    cmd: InspectCliCommand,
-    artifact_bytes: &[u8],
-) -> Result<CommandOutput> {
-    let _cache_file = (!cmd.no_cache).then(|| cached_oci_file(&cmd.target));
+pub(crate) async fn render_provider_claims(artifact_bytes: &[u8]) -> Result<CommandOutput> {
     let artifact = ProviderArchive::try_load(artifact_bytes)
         .await
         .map_err(|e| anyhow!("{}", e))?;
@@ -382,6 +463,441 @@ pub(crate) async fn render_provider_claims(
     Ok(CommandOutput::new(text_table, map))
 }
 
+/// The direction of an item of a WIT world
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum WitDirection {
+    Import,
+    Export,
+}
+
+/// How an item of a WIT world, or one of its members, changed between two artifacts
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum WitChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl WitDirection {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Import => "import",
+            Self::Export => "export",
+        }
+    }
+}
+
+impl WitChangeKind {
+    /// Whether the change breaks consumers of the artifact. New imports must be satisfied by the
+    /// host or linked providers, and removed exports may still be called by other components,
+    /// while changed signatures break both.
+    fn is_breaking(self, direction: WitDirection) -> bool {
+        matches!(
+            (direction, self),
+            (_, Self::Changed)
+                | (WitDirection::Import, Self::Added)
+                | (WitDirection::Export, Self::Removed)
+        )
+    }
+
+    fn symbol(self) -> char {
+        match self {
+            Self::Added => '+',
+            Self::Removed => '-',
+            Self::Changed => '~',
+        }
+    }
+}
+
+/// The members of the items of the WIT worlds of an artifact, keyed by world, direction and
+/// item name. Members are the rendered signatures of the functions and types of an item.
+type WitItems = BTreeMap<(String, WitDirection, String), BTreeMap<String, String>>;
+
+/// The parts of a component or provider archive compared by `wash inspect diff`
+#[derive(Debug, Default)]
+struct ArtifactSummary {
+    kind: &'static str,
+    size: usize,
+    hash: String,
+    issuer: Option<String>,
+    subject: Option<String>,
+    name: Option<String>,
+    tags: Vec<String>,
+    rev: Option<i32>,
+    ver: Option<String>,
+    expires: Option<u64>,
+    /// The module hash of components, or the target hashes of provider archives
+    hashes: BTreeMap<String, String>,
+    wit: WitItems,
+}
+
+/// Loads the claims, hashes and WIT of the component or provider archive in `buf`
+async fn summarize_artifact(buf: &[u8]) -> Result<ArtifactSummary> {
+    let mut summary = ArtifactSummary {
+        size: buf.len(),
+        hash: format!("sha256:{:x}", Sha256::digest(buf)),
+        ..Default::default()
+    };
+    match wasmparser::Parser::new(0).parse_all(buf).next() {
+        Some(Ok(wasmparser::Payload::Version { encoding, .. })) => {
+            let is_component = matches!(encoding, wasmparser::Encoding::Component);
+            summary.kind = if is_component { "component" } else { "module" };
+            if let Some(Token { claims, .. }) = wascap::wasm::extract_claims(buf)? {
+                summary.issuer = Some(claims.issuer);
+                summary.subject = Some(claims.subject);
+                summary.expires = claims.expires;
+                if let Some(metadata) = claims.metadata {
+                    summary.name = metadata.name;
+                    summary.tags = metadata.tags.unwrap_or_default();
+                    summary.rev = metadata.rev;
+                    summary.ver = metadata.ver;
+                    summary
+                        .hashes
+                        .insert("module".to_string(), metadata.module_hash);
+                }
+            }
+            if is_component {
+                summary.wit = wit_items(buf).context("failed to decode WIT of component")?;
+            }
+        }
+        _ => {
+            let artifact = ProviderArchive::try_load(buf)
+                .await
+                .map_err(|e| anyhow!("{}", e))?;
+            let claims = artifact
+                .claims()
+                .ok_or_else(|| anyhow!("No claims found in artifact"))?;
+            let metadata = claims
+                .metadata
+                .ok_or_else(|| anyhow!("No metadata found"))?;
+            summary.kind = "provider";
+            summary.issuer = Some(claims.issuer);
+            summary.subject = Some(claims.subject);
+            summary.expires = claims.expires;
+            summary.name = metadata.name;
+            summary.tags = metadata.tags.unwrap_or_default();
+            summary.rev = metadata.rev;
+            summary.ver = metadata.ver;
+            summary.hashes = metadata.target_hashes.into_iter().collect();
+            if let Some(wit) = artifact.wit() {
+                summary.wit = wit_items(wit).context("failed to decode WIT of provider archive")?;
+            }
+        }
+    }
+    Ok(summary)
+}
+
+/// Collects the items of the world of a Wasm component, or of all worlds of an encoded WIT package
+fn wit_items(wasm: &[u8]) -> Result<WitItems> {
+    let decoded = wit_component::decode(wasm)?;
+    let resolve = decoded.resolve();
+    let worlds: Vec<WorldId> = match &decoded {
+        DecodedWasm::Component(_, world) => vec![*world],
+        _ => resolve.packages[decoded.package()]
+            .worlds
+            .values()
+            .copied()
+            .collect(),
+    };
+
+    let mut items = WitItems::new();
+    for world_id in worlds {
+        let world = &resolve.worlds[world_id];
+        for (direction, world_items) in [
+            (WitDirection::Import, &world.imports),
+            (WitDirection::Export, &world.exports),
+        ] {
+            for (key, item) in world_items {
+                let members = match item {
+                    WorldItem::Interface { id, .. } => {
+                        let interface = &resolve.interfaces[*id];
+                        let types = interface.types.iter().map(|(name, ty)| {
+                            (format!("type {name}"), render_type_def(resolve, *ty))
+                        });
+                        let functions = interface.functions.iter().map(|(name, func)| {
+                            (format!("func {name}"), render_function(resolve, func))
+                        });
+                        types.chain(functions).collect()
+                    }
+                    WorldItem::Function(func) => {
+                        BTreeMap::from([("func".to_string(), render_function(resolve, func))])
+                    }
+                    WorldItem::Type(ty) => {
+                        BTreeMap::from([("type".to_string(), render_type_def(resolve, *ty))])
+                    }
+                };
+                items.insert(
+                    (world.name.clone(), direction, resolve.name_world_key(key)),
+                    members,
+                );
+            }
+        }
+    }
+    Ok(items)
+}
+
+/// Renders the signature of a WIT function, referring to named types by name
+fn render_function(resolve: &Resolve, func: &Function) -> String {
+    let params = render_list(
+        func.params
+            .iter()
+            .map(|(name, ty)| format!("{name}: {}", render_type(resolve, ty))),
+    );
+    match &func.results {
+        Results::Named(results) if results.is_empty() => format!("func({params})"),
+        Results::Named(results) => format!(
+            "func({params}) -> ({})",
+            render_list(
+                results
+                    .iter()
+                    .map(|(name, ty)| format!("{name}: {}", render_type(resolve, ty)))
+            )
+        ),
+        Results::Anon(ty) => format!("func({params}) -> {}", render_type(resolve, ty)),
+    }
+}
+
+/// Renders a reference to a WIT type, which is either a primitive, named or anonymous type
+fn render_type(resolve: &Resolve, ty: &Type) -> String {
+    match ty {
+        Type::Id(id) => match &resolve.types[*id].name {
+            Some(name) => name.clone(),
+            None => render_type_def(resolve, *id),
+        },
+        ty => format!("{ty:?}").to_lowercase(),
+    }
+}
+
+/// Renders the definition of a WIT type
+fn render_type_def(resolve: &Resolve, id: TypeId) -> String {
+    let render_optional = |ty: &Option<Type>| {
+        ty.as_ref()
+            .map_or_else(|| "_".to_string(), |ty| render_type(resolve, ty))
+    };
+    match &resolve.types[id].kind {
+        TypeDefKind::Record(record) => format!(
+            "record {{ {} }}",
+            render_list(record.fields.iter().map(|field| format!(
+                "{}: {}",
+                field.name,
+                render_type(resolve, &field.ty)
+            )))
+        ),
+        TypeDefKind::Flags(flags) => format!(
+            "flags {{ {} }}",
+            render_list(flags.flags.iter().map(|flag| flag.name.clone()))
+        ),
+        TypeDefKind::Enum(enum_) => format!(
+            "enum {{ {} }}",
+            render_list(enum_.cases.iter().map(|case| case.name.clone()))
+        ),
+        TypeDefKind::Variant(variant) => format!(
+            "variant {{ {} }}",
+            render_list(variant.cases.iter().map(|case| match &case.ty {
+                Some(ty) => format!("{}({})", case.name, render_type(resolve, ty)),
+                None => case.name.clone(),
+            }))
+        ),
+        TypeDefKind::Tuple(tuple) => format!(
+            "tuple<{}>",
+            render_list(tuple.types.iter().map(|ty| render_type(resolve, ty)))
+        ),
+        TypeDefKind::Option(ty) => format!("option<{}>", render_type(resolve, ty)),
+        TypeDefKind::Result(result) => format!(
+            "result<{}, {}>",
+            render_optional(&result.ok),
+            render_optional(&result.err)
+        ),
+        TypeDefKind::List(ty) => format!("list<{}>", render_type(resolve, ty)),
+        TypeDefKind::Handle(Handle::Own(id)) => {
+            format!("own<{}>", render_type(resolve, &Type::Id(*id)))
+        }
+        TypeDefKind::Handle(Handle::Borrow(id)) => {
+            format!("borrow<{}>", render_type(resolve, &Type::Id(*id)))
+        }
+        TypeDefKind::Type(ty) => render_type(resolve, ty),
+        kind => kind.as_str().to_string(),
+    }
+}
+
+fn render_list(items: impl Iterator<Item = String>) -> String {
+    items.collect::<Vec<_>>().join(", ")
+}
+
+/// Compares the WIT items of two artifacts, returning a JSON change per added, removed or changed
+/// item and whether any of the changes is breaking
+fn diff_wit(old: &WitItems, new: &WitItems) -> (Vec<serde_json::Value>, Vec<String>, bool) {
+    let mut changes = Vec::new();
+    let mut lines = Vec::new();
+    let mut breaking = false;
+    let keys: BTreeSet<_> = old.keys().chain(new.keys()).collect();
+    for key in keys {
+        let (world, direction, name) = key;
+        let (kind, members) = match (old.get(key), new.get(key)) {
+            (Some(_), None) => (WitChangeKind::Removed, Vec::new()),
+            (None, Some(_)) => (WitChangeKind::Added, Vec::new()),
+            (Some(old), Some(new)) if old != new => {
+                let members: BTreeSet<_> = old.keys().chain(new.keys()).collect();
+                let members = members
+                    .into_iter()
+                    .filter_map(|member| match (old.get(member), new.get(member)) {
+                        (Some(_), None) => Some((WitChangeKind::Removed, member)),
+                        (None, Some(_)) => Some((WitChangeKind::Added, member)),
+                        (Some(old), Some(new)) if old != new => {
+                            Some((WitChangeKind::Changed, member))
+                        }
+                        _ => None,
+                    })
+                    .collect();
+                (WitChangeKind::Changed, members)
+            }
+            _ => continue,
+        };
+        let is_breaking = if members.is_empty() {
+            kind.is_breaking(*direction)
+        } else {
+            members
+                .iter()
+                .any(|(member_kind, _)| member_kind.is_breaking(*direction))
+        };
+        breaking |= is_breaking;
+
+        lines.push(format!(
+            "{} {} {name} ({world}){}",
+            kind.symbol(),
+            direction.as_str(),
+            if is_breaking { " [breaking]" } else { "" }
+        ));
+        lines.extend(
+            members
+                .iter()
+                .map(|(member_kind, member)| format!("    {} {member}", member_kind.symbol())),
+        );
+        changes.push(json!({
+            "world": world,
+            "direction": direction,
+            "name": name,
+            "change": kind,
+            "breaking": is_breaking,
+            "members": members
+                .iter()
+                .map(|(member_kind, member)| json!({ "name": member, "change": member_kind }))
+                .collect::<Vec<_>>(),
+        }));
+    }
+    (changes, lines, breaking)
+}
+
+/// Compares two versions of an artifact, e.g. the deployed and a candidate version
+fn diff_artifacts(
+    old_target: &str,
+    old: ArtifactSummary,
+    new_target: &str,
+    new: ArtifactSummary,
+) -> Result<CommandOutput> {
+    let fields = [
+        ("kind", json!(old.kind), json!(new.kind)),
+        ("name", json!(old.name), json!(new.name)),
+        ("issuer", json!(old.issuer), json!(new.issuer)),
+        ("subject", json!(old.subject), json!(new.subject)),
+        ("version", json!(old.ver), json!(new.ver)),
+        ("revision", json!(old.rev), json!(new.rev)),
+        ("tags", json!(old.tags), json!(new.tags)),
+        ("expires", json!(old.expires), json!(new.expires)),
+        (
+            "targets",
+            json!(old.hashes.keys().collect::<Vec<_>>()),
+            json!(new.hashes.keys().collect::<Vec<_>>()),
+        ),
+    ];
+    let claims: Vec<_> = fields
+        .into_iter()
+        .filter(|(_, old, new)| old != new)
+        .collect();
+
+    let hashes: BTreeSet<_> = old.hashes.keys().chain(new.hashes.keys()).collect();
+    let hashes: Vec<_> = hashes
+        .into_iter()
+        .filter_map(|name| {
+            let (old_hash, new_hash) = (old.hashes.get(name), new.hashes.get(name));
+            (old_hash != new_hash).then_some((name, old_hash, new_hash))
+        })
+        .collect();
+
+    let (wit, wit_lines, breaking) = diff_wit(&old.wit, &new.wit);
+    let changed =
+        !claims.is_empty() || !hashes.is_empty() || !wit.is_empty() || old.hash != new.hash;
+
+    let mut text = vec![format!("Comparing {old_target} with {new_target}")];
+    text.push("\nClaims".to_string());
+    if claims.is_empty() {
+        text.push("  No changes".to_string());
+    }
+    text.extend(
+        claims
+            .iter()
+            .map(|(field, old, new)| format!("  {field}: {old} -> {new}")),
+    );
+    text.push("\nWIT".to_string());
+    if wit_lines.is_empty() {
+        text.push("  No changes".to_string());
+    }
+    text.extend(wit_lines.iter().map(|line| format!("  {line}")));
+    text.push("\nContents".to_string());
+    text.push(format!(
+        "  size: {} -> {} bytes ({:+})",
+        old.size,
+        new.size,
+        new.size as i64 - old.size as i64
+    ));
+    text.push(format!("  hash: {} -> {}", old.hash, new.hash));
+    text.extend(hashes.iter().map(|(name, old, new)| {
+        format!(
+            "  {name} hash: {} -> {}",
+            old.map_or("None", String::as_str),
+            new.map_or("None", String::as_str)
+        )
+    }));
+    text.push(format!(
+        "\n{}",
+        match (changed, breaking) {
+            (false, _) => "Artifacts are identical",
+            (true, false) => "No breaking changes",
+            (true, true) => "Breaking changes found",
+        }
+    ));
+
+    let mut map = HashMap::new();
+    map.insert("changed".to_string(), json!(changed));
+    map.insert("breaking".to_string(), json!(breaking));
+    map.insert(
+        "claims".to_string(),
+        json!(claims
+            .iter()
+            .map(|(field, old, new)| json!({ "field": field, "old": old, "new": new }))
+            .collect::<Vec<_>>()),
+    );
+    map.insert("wit".to_string(), json!(wit));
+    map.insert(
+        "size".to_string(),
+        json!({ "old": old.size, "new": new.size, "delta": new.size as i64 - old.size as i64 }),
+    );
+    map.insert(
+        "hash".to_string(),
+        json!({
+            "old": old.hash,
+            "new": new.hash,
+            "contents": hashes
+                .iter()
+                .map(|(name, old, new)| json!({ "name": name, "old": old, "new": new }))
+                .collect::<Vec<_>>(),
+        }),
+    );
+    Ok(CommandOutput::new(text.join("\n"), map))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -425,8 +941,9 @@ mod test {
             insecure_skip_tls_verify,
             no_cache,
             wit,
+            command,
         } = inspect_long.command;
-        assert_eq!(target, LOCAL);
+        assert_eq!(target.unwrap(), LOCAL);
         assert_eq!(digest.unwrap(), "sha256:blah");
         assert!(!allow_latest);
         assert!(!insecure);
@@ -436,6 +953,7 @@ mod test {
         assert!(jwt_only);
         assert!(no_cache);
         assert!(!wit);
+        assert!(command.is_none());
 
         let inspect_short: Cmd = Parser::try_parse_from([
             "inspect",
@@ -463,8 +981,9 @@ mod test {
             insecure_skip_tls_verify,
             no_cache,
             wit,
+            command,
         } = inspect_short.command;
-        assert_eq!(target, REMOTE);
+        assert_eq!(target.unwrap(), REMOTE);
         assert_eq!(digest.unwrap(), "sha256:blah");
         assert!(allow_latest);
         assert!(insecure);
@@ -474,6 +993,7 @@ mod test {
         assert!(jwt_only);
         assert!(no_cache);
         assert!(!wit);
+        assert!(command.is_none());
 
         let cmd: Cmd = Parser::try_parse_from([
             "inspect",
@@ -502,8 +1022,9 @@ mod test {
             insecure_skip_tls_verify,
             no_cache,
             wit,
+            command,
         } = cmd.command;
-        assert_eq!(target, SUBSCRIBER_OCI);
+        assert_eq!(target.unwrap(), SUBSCRIBER_OCI);
         assert_eq!(
             digest.unwrap(),
             "sha256:5790f650cff526fcbc1271107a05111a6647002098b74a9a5e2e26e3c0a116b8"
@@ -516,6 +1037,7 @@ mod test {
         assert!(jwt_only);
         assert!(no_cache);
         assert!(!wit);
+        assert!(command.is_none());
 
         let short_cmd: Cmd = Parser::try_parse_from([
             "inspect",
@@ -545,8 +1067,9 @@ mod test {
             insecure_skip_tls_verify,
             no_cache,
             wit,
+            command,
         } = short_cmd.command;
-        assert_eq!(target, SUBSCRIBER_OCI);
+        assert_eq!(target.unwrap(), SUBSCRIBER_OCI);
         assert_eq!(
             digest.unwrap(),
             "sha256:5790f650cff526fcbc1271107a05111a6647002098b74a9a5e2e26e3c0a116b8"
@@ -559,5 +1082,232 @@ mod test {
         assert!(!jwt_only);
         assert!(no_cache);
         assert!(wit);
+        assert!(command.is_none());
+
+        let diff_cmd: Cmd = Parser::try_parse_from([
+            "inspect",
+            "diff",
+            SUBSCRIBER_OCI,
+            LOCAL,
+            "--allow-latest",
+            "--no-cache",
+        ])
+        .unwrap();
+        assert_eq!(diff_cmd.command.target, None);
+        assert!(diff_cmd.command.allow_latest);
+        assert!(diff_cmd.command.no_cache);
+        let Some(InspectSubcommand::Diff(InspectDiffCommand { old, new })) =
+            diff_cmd.command.command
+        else {
+            panic!("diff subcommand should be parsed");
+        };
+        assert_eq!(old, SUBSCRIBER_OCI);
+        assert_eq!(new, LOCAL);
+        assert!(
+            Cmd::try_parse_from(["inspect", "diff", SUBSCRIBER_OCI, LOCAL, "--wit"]).is_err(),
+            "diff conflicts with printing the WIT"
+        );
+        assert!(
+            Cmd::try_parse_from(["inspect", "diff", SUBSCRIBER_OCI]).is_err(),
+            "diff requires two artifacts"
+        );
+        assert!(
+            Cmd::try_parse_from(["inspect"]).is_err(),
+            "inspect requires a target"
+        );
+    }
+
+    #[test]
+    fn diff_wit_breaking_changes() {
+        let item = |direction, name: &str, members: &[(&str, &str)]| {
+            (
+                ("root".to_string(), direction, name.to_string()),
+                members
+                    .iter()
+                    .map(|(member, signature)| (member.to_string(), signature.to_string()))
+                    .collect::<BTreeMap<_, _>>(),
+            )
+        };
+        let old = WitItems::from([
+            item(
+                WitDirection::Import,
+                "wasi:logging/logging",
+                &[("func log", "func()")],
+            ),
+            item(
+                WitDirection::Export,
+                "wasi:http/incoming-handler@0.2.0",
+                &[("func handle", "func(request: incoming-request)")],
+            ),
+        ]);
+
+        // Removing imports and adding exports is compatible
+        let new = WitItems::from([
+            item(
+                WitDirection::Export,
+                "wasi:http/incoming-handler@0.2.0",
+                &[("func handle", "func(request: incoming-request)")],
+            ),
+            item(WitDirection::Export, "wasmcloud:example/greet", &[]),
+        ]);
+        let (changes, _, breaking) = diff_wit(&old, &new);
+        assert_eq!(changes.len(), 2);
+        assert!(!breaking);
+
+        // Changing the signature of an export is breaking
+        let new = WitItems::from([
+            item(
+                WitDirection::Import,
+                "wasi:logging/logging",
+                &[("func log", "func()")],
+            ),
+            item(
+                WitDirection::Export,
+                "wasi:http/incoming-handler@0.2.0",
+                &[("func handle", "func(request: u32)")],
+            ),
+        ]);
+        let (changes, _, breaking) = diff_wit(&old, &new);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0]["change"], "changed");
+        assert_eq!(changes[0]["members"][0]["name"], "func handle");
+        assert!(breaking);
+
+        // Adding imports is breaking
+        let (_, _, breaking) = diff_wit(&WitItems::new(), &old);
+        assert!(breaking);
+    }
+
+    /// Encodes a component with a dummy implementation of the `greeter` world in `wit`
+    fn component(wit: &str) -> Vec<u8> {
+        let mut resolve = Resolve::default();
+        let package = resolve
+            .push_str("greeter.wit", wit)
+            .expect("Should be able to parse WIT");
+        let world = resolve
+            .select_world(package, Some("greeter"))
+            .expect("Should be able to select world");
+        let mut module = wit_component::dummy_module(&resolve, world);
+        wit_component::embed_component_metadata(
+            &mut module,
+            &resolve,
+            world,
+            wit_component::StringEncoding::UTF8,
+        )
+        .expect("Should be able to embed component metadata");
+        wit_component::ComponentEncoder::default()
+            .module(&module)
+            .expect("Should be able to set module")
+            .validate(true)
+            .encode()
+            .expect("Should be able to encode component")
+    }
+
+    #[tokio::test]
+    async fn diff_components() {
+        let old = component(
+            r#"
+            package wasmcloud:example;
+
+            interface greet {
+                record greeting { name: string, loud: bool }
+                greet: func(greeting: greeting) -> string;
+            }
+
+            world greeter {
+                import log: func(message: string);
+                export greet;
+            }
+            "#,
+        );
+        let new = component(
+            r#"
+            package wasmcloud:example;
+
+            interface greet {
+                record greeting { name: string, loud: bool, times: u32 }
+                greet: func(greeting: greeting) -> result<string, string>;
+            }
+
+            world greeter {
+                import log: func(message: string);
+                export greet;
+            }
+            "#,
+        );
+
+        let items = wit_items(&old).expect("Should be able to collect WIT items");
+        let (world, _, _) = items
+            .keys()
+            .next()
+            .expect("Component should have WIT items");
+        let world = world.clone();
+        assert_eq!(
+            items[&(world.clone(), WitDirection::Import, "log".to_string())]["func"],
+            "func(message: string)"
+        );
+        let greet = &items[&(
+            world.clone(),
+            WitDirection::Export,
+            "wasmcloud:example/greet".to_string(),
+        )];
+        assert_eq!(
+            greet["type greeting"],
+            "record { name: string, loud: bool }"
+        );
+        assert_eq!(greet["func greet"], "func(greeting: greeting) -> string");
+
+        let dir = tempfile::tempdir().expect("Should be able to create temp dir");
+        let old_path = dir.path().join("old.wasm");
+        let new_path = dir.path().join("new.wasm");
+        std::fs::write(&old_path, &old).expect("Should be able to write component");
+        std::fs::write(&new_path, &new).expect("Should be able to write component");
+        let cmd = Cmd::try_parse_from([
+            "inspect",
+            "diff",
+            old_path.to_str().unwrap(),
+            new_path.to_str().unwrap(),
+            "--no-cache",
+        ])
+        .expect("Should be able to parse diff command");
+        let output = handle_command(cmd.command, OutputKind::Json)
+            .await
+            .expect("Should be able to diff components");
+        assert_eq!(output.map["changed"], true);
+        assert_eq!(output.map["breaking"], true);
+        assert_eq!(output.map["claims"], json!([]));
+        let wit = output.map["wit"]
+            .as_array()
+            .expect("WIT changes should be listed");
+        assert_eq!(wit.len(), 1, "only the exported interface changed");
+        assert_eq!(wit[0]["name"], "wasmcloud:example/greet");
+        assert_eq!(wit[0]["direction"], "export");
+        assert_eq!(
+            wit[0]["members"],
+            json!([
+                { "name": "func greet", "change": "changed" },
+                { "name": "type greeting", "change": "changed" },
+            ])
+        );
+        assert!(output.text.contains(&format!(
+            "~ export wasmcloud:example/greet ({world}) [breaking]"
+        )));
+
+        let output = handle_command(
+            Cmd::try_parse_from([
+                "inspect",
+                "diff",
+                old_path.to_str().unwrap(),
+                old_path.to_str().unwrap(),
+                "--no-cache",
+            ])
+            .expect("Should be able to parse diff command")
+            .command,
+            OutputKind::Json,
+        )
+        .await
+        .expect("Should be able to diff components");
+        assert_eq!(output.map["changed"], false);
+        assert_eq!(output.map["breaking"], false);
     }
 }
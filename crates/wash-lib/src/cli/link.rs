@@ -1,6 +1,10 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use clap::builder::PossibleValuesParser;
 use clap::Parser;
-use wasmcloud_control_interface::{CtlResponse, InterfaceLinkDefinition};
+use wasmcloud_control_interface::{
+    CtlResponse, InterfaceLinkDefinition, TargetGroup, TargetGroupMember, TargetSelection,
+    TARGET_GROUP_PREFIX,
+};
 
 use crate::{cli::CliConnectionOpts, common::boxed_err_to_anyhow, config::WashConnectionOptions};
 
@@ -37,8 +41,8 @@ pub struct LinkPutCommand {
     #[clap(name = "source-id", value_parser = validate_component_id)]
     pub source_id: String,
 
-    /// The ID of the component to link to
-    #[clap(name = "target", value_parser = validate_component_id)]
+    /// The ID of the component to link to, or `group:<name>` to link to a target group
+    #[clap(name = "target", value_parser = validate_link_target)]
     pub target: String,
 
     /// The WIT namespace of the link, e.g. "wasi" in "wasi:http/incoming-handler"
@@ -66,6 +70,27 @@ pub struct LinkPutCommand {
     /// WIT namespace, package, and interface.
     #[clap(short = 'l', long = "link-name")]
     pub link_name: Option<String>,
+
+    /// Retry invocations over the link, which fail to reach the target, up to this many attempts in
+    /// total. Only use this for idempotent interfaces.
+    #[clap(long = "retry-max-attempts")]
+    pub retry_max_attempts: Option<u32>,
+
+    /// Delay before the first retry in milliseconds, doubled for every further retry
+    #[clap(
+        long = "retry-initial-backoff-ms",
+        default_value_t = 100,
+        requires = "retry_max_attempts"
+    )]
+    pub retry_initial_backoff_ms: u64,
+
+    /// Maximum delay between retries in milliseconds
+    #[clap(
+        long = "retry-max-backoff-ms",
+        default_value_t = 2000,
+        requires = "retry_max_attempts"
+    )]
+    pub retry_max_backoff_ms: u64,
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -87,6 +112,156 @@ pub enum LinkCommand {
     /// Delete a link
     #[clap(name = "del", alias = "delete")]
     Del(LinkDelCommand),
+
+    /// Manage target groups, which links can target as `group:<name>` to spread invocations
+    /// across multiple components
+    #[clap(name = "group", subcommand)]
+    Group(LinkGroupCommand),
+}
+
+#[derive(Debug, Clone, Parser)]
+pub enum LinkGroupCommand {
+    /// Put (create or replace) a target group
+    #[clap(name = "put")]
+    Put(LinkGroupPutCommand),
+
+    /// Get a target group, or all target groups if no name is given
+    #[clap(name = "get", alias = "query")]
+    Get(LinkGroupGetCommand),
+
+    /// Delete a target group
+    #[clap(name = "del", alias = "delete")]
+    Del(LinkGroupDelCommand),
+
+    /// Add a member to a target group, creating the group if it does not exist
+    #[clap(name = "add-member")]
+    AddMember(LinkGroupAddMemberCommand),
+
+    /// Remove a member from a target group
+    #[clap(name = "remove-member")]
+    RemoveMember(LinkGroupRemoveMemberCommand),
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct LinkGroupPutCommand {
+    #[clap(flatten)]
+    pub opts: CliConnectionOpts,
+
+    /// Name of the target group
+    #[clap(name = "name", value_parser = validate_component_id)]
+    pub name: String,
+
+    /// Strategy used to select a member for each invocation
+    #[clap(
+        long = "strategy",
+        default_value = "round-robin",
+        value_parser = PossibleValuesParser::new(["round-robin", "random", "consistent-hash"]),
+    )]
+    pub strategy: String,
+
+    /// Name of the W3C baggage entry to hash for the consistent-hash strategy.
+    /// Invocations without the entry are hashed by their trace ID.
+    #[clap(long = "hash-key", required_if_eq("strategy", "consistent-hash"))]
+    pub hash_key: Option<String>,
+
+    /// Member of the group as `<target>[=<weight>]`, e.g. `echo-v1=9`. Weight defaults to 1
+    #[clap(long = "member", value_parser = parse_target_group_member)]
+    pub members: Vec<TargetGroupMember>,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct LinkGroupGetCommand {
+    #[clap(flatten)]
+    pub opts: CliConnectionOpts,
+
+    /// Name of the target group
+    #[clap(name = "name")]
+    pub name: Option<String>,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct LinkGroupDelCommand {
+    #[clap(flatten)]
+    pub opts: CliConnectionOpts,
+
+    /// Name of the target group
+    #[clap(name = "name")]
+    pub name: String,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct LinkGroupAddMemberCommand {
+    #[clap(flatten)]
+    pub opts: CliConnectionOpts,
+
+    /// Name of the target group
+    #[clap(name = "name", value_parser = validate_component_id)]
+    pub name: String,
+
+    /// Member to add as `<target>[=<weight>]`, replacing the weight of an existing member
+    #[clap(name = "member", value_parser = parse_target_group_member)]
+    pub member: TargetGroupMember,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct LinkGroupRemoveMemberCommand {
+    #[clap(flatten)]
+    pub opts: CliConnectionOpts,
+
+    /// Name of the target group
+    #[clap(name = "name")]
+    pub name: String,
+
+    /// Target of the member to remove
+    #[clap(name = "target")]
+    pub target: String,
+}
+
+/// Validate a link target, which is either a component ID or a target group as `group:<name>`
+fn validate_link_target(target: &str) -> Result<String> {
+    match target.strip_prefix(TARGET_GROUP_PREFIX) {
+        Some(group) => {
+            validate_component_id(group).context("invalid target group name")?;
+            Ok(target.to_string())
+        }
+        None => validate_component_id(target),
+    }
+}
+
+/// Parse a target group member from `<target>[=<weight>]`
+fn parse_target_group_member(member: &str) -> Result<TargetGroupMember> {
+    let (target, weight) = match member.split_once('=') {
+        Some((target, weight)) => (
+            target,
+            weight
+                .parse()
+                .with_context(|| format!("invalid weight `{weight}`"))?,
+        ),
+        None => (member, 1),
+    };
+    if weight == 0 {
+        bail!("weight of member `{target}` must be positive");
+    }
+    Ok(TargetGroupMember {
+        target: validate_component_id(target)?,
+        weight,
+    })
+}
+
+impl LinkGroupPutCommand {
+    /// Build the [`TargetGroup`] described by the command
+    pub fn target_group(&self) -> TargetGroup {
+        let selection = match (self.strategy.as_str(), &self.hash_key) {
+            ("random", _) => TargetSelection::Random,
+            ("consistent-hash", Some(key)) => TargetSelection::ConsistentHash { key: key.clone() },
+            _ => TargetSelection::RoundRobin,
+        };
+        TargetGroup {
+            name: self.name.clone(),
+            selection,
+            members: self.members.clone(),
+        }
+    }
 }
 
 /// Query links for a given Wash instance
@@ -197,3 +372,76 @@ pub async fn put_link(
             )
         })
 }
+
+/// Put a target group, replacing an existing group with the same name
+pub async fn put_target_group(
+    wco: WashConnectionOptions,
+    group: &TargetGroup,
+) -> Result<CtlResponse<()>> {
+    wco.into_ctl_client(None)
+        .await?
+        .put_target_group(group)
+        .await
+        .map_err(boxed_err_to_anyhow)
+        .with_context(|| format!("Failed to put target group {}", group.name))
+}
+
+/// Get target groups, either the group `name` or all groups in the lattice
+pub async fn get_target_groups(
+    wco: WashConnectionOptions,
+    name: Option<&str>,
+) -> Result<Vec<TargetGroup>> {
+    let ctl_client = wco.into_ctl_client(None).await?;
+    match name {
+        Some(name) => ctl_client
+            .get_target_group(name)
+            .await
+            .map(|ctl| ctl.response.into_iter().collect()),
+        None => ctl_client
+            .get_target_groups()
+            .await
+            .map(|ctl| ctl.response.unwrap_or_default()),
+    }
+    .map_err(boxed_err_to_anyhow)
+}
+
+/// Delete a target group
+pub async fn delete_target_group(
+    wco: WashConnectionOptions,
+    name: &str,
+) -> Result<CtlResponse<()>> {
+    wco.into_ctl_client(None)
+        .await?
+        .delete_target_group(name)
+        .await
+        .map_err(boxed_err_to_anyhow)
+        .with_context(|| format!("Failed to delete target group {name}"))
+}
+
+/// Add a member to a target group
+pub async fn add_target_group_member(
+    wco: WashConnectionOptions,
+    name: &str,
+    member: TargetGroupMember,
+) -> Result<CtlResponse<()>> {
+    wco.into_ctl_client(None)
+        .await?
+        .add_target_group_member(name, member)
+        .await
+        .map_err(boxed_err_to_anyhow)
+        .with_context(|| format!("Failed to add member to target group {name}"))
+}
+
+/// Remove a member from a target group
+pub async fn remove_target_group_member(
+    wco: WashConnectionOptions,
+    name: &str,
+    target: &str,
+) -> Result<CtlResponse<()>> {
+    wco.into_ctl_client(None)
+        .await?
+        .remove_target_group_member(name, target)
+        .await
+        .map_err(boxed_err_to_anyhow)
+        .with_context(|| format!("Failed to remove {target} from target group {name}"))
+}
@@ -0,0 +1,90 @@
+use anyhow::Result;
+use clap::Parser;
+use wasmcloud_control_interface::{ComponentLogLevel, ComponentLogRecord};
+
+use super::{validate_component_id, CliConnectionOpts, CommandOutput, OutputKind};
+use crate::config::WashConnectionOptions;
+
+#[derive(Debug, Parser, Clone)]
+pub struct LogsCommand {
+    /// Component ID to tail logs of. If not provided, logs of all components in the lattice are shown.
+    /// Hosts must be started with component log forwarding enabled.
+    #[clap(name = "component_id", value_parser = validate_component_id)]
+    pub component_id: Option<String>,
+
+    /// Minimum level of log records to show (trace, debug, info, warn, error, critical)
+    #[clap(long = "level", default_value = "trace")]
+    pub level: ComponentLogLevel,
+
+    /// Only show log records emitted on the host with this ID
+    #[clap(long = "host-id")]
+    pub host_id: Option<String>,
+
+    /// Only show log records with this log context. Not to be confused with `--context`, which
+    /// selects the wash context to connect with
+    #[clap(name = "log_context", long = "log-context")]
+    pub log_context: Option<String>,
+
+    #[clap(flatten)]
+    pub opts: CliConnectionOpts,
+}
+
+impl LogsCommand {
+    fn matches(&self, record: &ComponentLogRecord) -> bool {
+        record.level >= self.level
+            && self
+                .host_id
+                .iter()
+                .all(|host_id| *host_id == record.host_id)
+            && self
+                .log_context
+                .iter()
+                .all(|context| *context == record.context)
+    }
+}
+
+/// Handles the logs command, printing matching log records to stdout until the command is interrupted
+pub async fn handle_command(cmd: LogsCommand, output_kind: OutputKind) -> Result<CommandOutput> {
+    let wco: WashConnectionOptions = cmd.opts.clone().try_into()?;
+    let ctl_client = wco.into_ctl_client(None).await?;
+
+    let mut receiver = ctl_client
+        .component_logs_receiver(cmd.component_id.as_deref())
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    if output_kind == OutputKind::Text {
+        match &cmd.component_id {
+            Some(component_id) => println!("Tailing logs of component {component_id}\n"),
+            None => println!("Tailing logs of all components\n"),
+        }
+    }
+
+    while let Some(record) = receiver.recv().await {
+        if !cmd.matches(&record) {
+            continue;
+        }
+        match output_kind {
+            OutputKind::Json => println!("{}", serde_json::to_string(&record)?),
+            OutputKind::Text => {
+                let trace = record
+                    .trace_id
+                    .as_ref()
+                    .map(|trace_id| format!(" trace={trace_id}"))
+                    .unwrap_or_default();
+                println!(
+                    "{:<8} {} [{}]{trace} {}: {}",
+                    record.level.to_string().to_uppercase(),
+                    record.component_id,
+                    record.host_id,
+                    record.context,
+                    record.message
+                );
+            }
+        }
+    }
+
+    println!("Log subscription closed");
+
+    Ok(CommandOutput::default())
+}
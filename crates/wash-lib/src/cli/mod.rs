@@ -39,6 +39,7 @@ pub mod get;
 pub mod inspect;
 pub mod label;
 pub mod link;
+pub mod logs;
 pub mod output;
 pub mod par;
 pub mod registry;
@@ -47,6 +48,7 @@ pub mod spy;
 pub mod start;
 pub mod stop;
 pub mod update;
+pub mod verify;
 
 /// Used for displaying human-readable output vs JSON format
 #[derive(Debug, Copy, Clone, Eq, Serialize, Deserialize, PartialEq)]
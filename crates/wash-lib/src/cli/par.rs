@@ -1,6 +1,6 @@
 use anyhow::{anyhow, Context, Result};
 use provider_archive::ProviderArchive;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Helper function for detecting the arch used by the current machine
 #[must_use]
@@ -15,6 +15,9 @@ pub struct ParCreateArgs {
     pub schema: Option<PathBuf>,
     pub name: String,
     pub arch: String,
+    /// Path to the directory of the WIT package describing the provider's interfaces, which is
+    /// bundled in the archive
+    pub wit: Option<PathBuf>,
 }
 
 pub fn create_provider_archive(
@@ -25,6 +28,7 @@ pub fn create_provider_archive(
         schema,
         name,
         arch,
+        wit,
     }: ParCreateArgs,
     binary_bytes: &[u8],
 ) -> Result<ProviderArchive> {
@@ -43,9 +47,28 @@ pub fn create_provider_archive(
         .with_context(|| format!("Error parsing JSON schema from file '{schema:?}'"))?;
     }
 
+    if let Some(ref wit) = wit {
+        par.set_wit(encode_wit_dir(wit)?);
+    }
+
     Ok(par)
 }
 
+/// Encodes the WIT package in the provided directory as a WebAssembly binary, which can be
+/// bundled in a provider archive
+pub fn encode_wit_dir(dir: impl AsRef<Path>) -> Result<Vec<u8>> {
+    let mut resolve = wit_parser::Resolve::default();
+    let (package_id, _paths) = resolve
+        .push_dir(dir.as_ref())
+        .with_context(|| format!("failed to add WIT directory @ [{}]", dir.as_ref().display()))?;
+    wit_component::encode(None, &resolve, package_id).with_context(|| {
+        format!(
+            "failed to encode WIT package @ [{}]",
+            dir.as_ref().display()
+        )
+    })
+}
+
 pub async fn insert_provider_binary(
     arch: String,
     binary_bytes: &[u8],
@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use anyhow::{bail, Context, Result};
 use clap::Parser;
 use tokio::time::Duration;
+use wasmcloud_control_interface::LabelConstraint;
 
 use crate::{
     cli::{input_vec_to_hashmap, CliConnectionOpts, CommandOutput},
@@ -63,6 +64,11 @@ pub struct StartComponentCommand {
     #[clap(short = 'c', long = "constraint", name = "constraints")]
     pub constraints: Option<Vec<String>>,
 
+    /// Label constraints for component auction, in the form of "label" (exists), "label=value", "label!=value",
+    /// "label=a|b" (one of) or "label~=>=1.2, <2" (semantic version range). If host-id is supplied, this list is ignored
+    #[clap(long = "label-constraint", name = "label_constraints")]
+    pub label_constraints: Vec<LabelConstraint>,
+
     /// Timeout to await an auction response, defaults to 2000 milliseconds
     #[clap(long = "auction-timeout-ms", default_value_t = default_timeout_ms())]
     pub auction_timeout_ms: u64,
@@ -130,10 +136,11 @@ pub async fn handle_start_component(cmd: StartComponentCommand) -> Result<Comman
         Some(host) => find_host_id(&host, &client).await?.0,
         None => {
             let suitable_hosts = client
-                .perform_component_auction(
+                .perform_component_auction_with_label_constraints(
                     &component_ref,
                     &cmd.component_id,
                     input_vec_to_hashmap(cmd.constraints.unwrap_or_default())?,
+                    cmd.label_constraints,
                 )
                 .await
                 .map_err(boxed_err_to_anyhow)
@@ -150,7 +157,12 @@ pub async fn handle_start_component(cmd: StartComponentCommand) -> Result<Comman
                     .into_iter()
                     .filter_map(|h| h.response)
                     .collect::<Vec<_>>();
-                let ack = acks.first().context("No suitable hosts found")?;
+                // Prefer the host with the most free component slots, falling back to the first responder
+                let ack = acks
+                    .iter()
+                    .rev()
+                    .max_by_key(|ack| ack.capacity.map(|c| c.free_component_slots))
+                    .context("No suitable hosts found")?;
                 ack.host_id
                     .parse()
                     .with_context(|| format!("Failed to parse host id: {}", ack.host_id))?
@@ -221,6 +233,11 @@ pub struct StartProviderCommand {
     #[clap(short = 'c', long = "constraint", name = "constraints")]
     pub constraints: Option<Vec<String>>,
 
+    /// Label constraints for provider auction, in the form of "label" (exists), "label=value", "label!=value",
+    /// "label=a|b" (one of) or "label~=>=1.2, <2" (semantic version range). If host-id is supplied, this list is ignored
+    #[clap(long = "label-constraint", name = "label_constraints")]
+    pub label_constraints: Vec<LabelConstraint>,
+
     /// Timeout to await an auction response, defaults to 2000 milliseconds
     #[clap(long = "auction-timeout-ms", default_value_t = default_timeout_ms())]
     pub auction_timeout_ms: u64,
@@ -254,10 +271,11 @@ pub async fn handle_start_provider(cmd: StartProviderCommand) -> Result<CommandO
         Some(host) => find_host_id(&host, &client).await?.0,
         None => {
             let suitable_hosts = client
-                .perform_provider_auction(
+                .perform_provider_auction_with_label_constraints(
                     &provider_ref,
                     &cmd.link_name,
                     input_vec_to_hashmap(cmd.constraints.unwrap_or_default())?,
+                    cmd.label_constraints,
                 )
                 .await
                 .map_err(boxed_err_to_anyhow)
@@ -274,7 +292,11 @@ pub async fn handle_start_provider(cmd: StartProviderCommand) -> Result<CommandO
                     .into_iter()
                     .filter_map(|h| h.response)
                     .collect::<Vec<_>>();
-                let ack = acks.first().context("No suitable hosts found")?;
+                // Prefer the host running the fewest providers, falling back to the first responder
+                let ack = acks
+                    .iter()
+                    .min_by_key(|ack| ack.capacity.map_or(u32::MAX, |c| c.running_providers))
+                    .context("No suitable hosts found")?;
                 ack.host_id
                     .parse()
                     .with_context(|| format!("Failed to parse host id: {}", ack.host_id))?
@@ -0,0 +1,681 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use provider_archive::ProviderArchive;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::json;
+use wascap::{
+    jwt::{
+        validate_token, Account, CapabilityProvider, Claims, CoSignature, Component, Token,
+        WascapEntity,
+    },
+    wasm::{extract_claims, extract_cosignatures, module_hash},
+};
+
+use super::{claims::validation_error, CommandOutput};
+use crate::{
+    common::boxed_err_to_anyhow,
+    registry::{get_oci_artifact, OciPullOptions},
+};
+
+const WASM_MAGIC: &[u8] = b"\0asm";
+
+#[derive(Debug, Clone, Parser)]
+pub struct VerifyCommand {
+    /// Path or OCI URL to signed component or provider archive
+    pub target: String,
+
+    /// A comma-separated list of operator public keys trusted to issue the accounts in `--trusted-account-jwts`
+    #[clap(long = "trusted-operators", value_delimiter = ',')]
+    pub trusted_operators: Vec<String>,
+
+    /// A comma-separated list of account public keys trusted to issue components and providers. If any trusted operators or accounts are provided, the artifact must be issued by a trusted account
+    #[clap(long = "trusted-accounts", value_delimiter = ',')]
+    pub trusted_accounts: Vec<String>,
+
+    /// A comma-separated list of account JWTs, which are trusted along with their signing keys if issued by one of `--trusted-operators`
+    #[clap(
+        long = "trusted-account-jwts",
+        value_delimiter = ',',
+        requires = "trusted_operators"
+    )]
+    pub trusted_account_jwts: Vec<String>,
+
+    /// Public key of a trusted signer, can be specified multiple times. If no trusted signers are provided, all valid signatures count
+    #[clap(long = "signer", value_delimiter = ',')]
+    pub signers: Vec<String>,
+
+    /// The number of distinct signers whose valid signatures are required, counting both the issuer of the claims and the issuers of detached co-signatures
+    #[clap(long = "threshold", default_value_t = 1, value_parser = parse_threshold)]
+    pub threshold: usize,
+
+    /// Digest to verify artifact against (if OCI URL is provided for `<target>`)
+    #[clap(short = 'd', long = "digest")]
+    pub digest: Option<String>,
+
+    /// Allow latest artifact tags (if OCI URL is provided for `<target>`)
+    #[clap(long = "allow-latest")]
+    pub allow_latest: bool,
+
+    /// OCI username, if omitted anonymous authentication will be used
+    #[clap(
+        short = 'u',
+        long = "user",
+        env = "WASH_REG_USER",
+        hide_env_values = true
+    )]
+    pub user: Option<String>,
+
+    /// OCI password, if omitted anonymous authentication will be used
+    #[clap(
+        short = 'p',
+        long = "password",
+        env = "WASH_REG_PASSWORD",
+        hide_env_values = true
+    )]
+    pub password: Option<String>,
+
+    /// Allow insecure (HTTP) registry connections
+    #[clap(long = "insecure")]
+    pub insecure: bool,
+
+    /// Skip checking OCI registry's certificate for validity
+    #[clap(long = "insecure-skip-tls-verify")]
+    pub insecure_skip_tls_verify: bool,
+}
+
+/// Parses the number of required signatures, which must be at least 1
+pub(crate) fn parse_threshold(arg: &str) -> Result<usize> {
+    let threshold: usize = arg.parse()?;
+    anyhow::ensure!(threshold >= 1, "threshold must be at least 1");
+    Ok(threshold)
+}
+
+/// The outcome of a single check of an artifact
+#[derive(Debug, Serialize)]
+struct Check {
+    check: String,
+    passed: bool,
+    detail: String,
+}
+
+impl Check {
+    fn new(check: impl Into<String>, result: std::result::Result<String, String>) -> Self {
+        let (passed, detail) = match result {
+            Ok(detail) => (true, detail),
+            Err(reason) => (false, reason),
+        };
+        Self {
+            check: check.into(),
+            passed,
+            detail,
+        }
+    }
+}
+
+/// The status of a signature of a component or provider archive
+#[derive(Debug, Serialize)]
+struct SignatureStatus {
+    issuer: String,
+    /// Whether the signature is a detached co-signature, rather than the claims
+    cosignature: bool,
+    valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl SignatureStatus {
+    fn new(issuer: &str, cosignature: bool, error: Option<String>) -> Self {
+        Self {
+            issuer: issuer.to_string(),
+            cosignature,
+            valid: error.is_none(),
+            error,
+        }
+    }
+}
+
+/// The claims and signatures of a verified artifact
+struct Verified {
+    kind: &'static str,
+    issuer: String,
+    subject: String,
+    signatures: Vec<SignatureStatus>,
+}
+
+/// Verifies the integrity and signatures of a signed component or provider archive end to end,
+/// failing with the reasons if any check fails
+pub async fn handle_command(cmd: VerifyCommand) -> Result<CommandOutput> {
+    // NOTE: The local OCI cache is never used, since cached artifacts are neither checked against
+    // `--digest` nor protected from tampering
+    let buf = get_oci_artifact(
+        cmd.target.clone(),
+        None,
+        OciPullOptions {
+            digest: cmd.digest.clone(),
+            allow_latest: cmd.allow_latest,
+            user: cmd.user.clone(),
+            password: cmd.password.clone(),
+            insecure: cmd.insecure,
+            insecure_skip_tls_verify: cmd.insecure_skip_tls_verify,
+        },
+    )
+    .await
+    .with_context(|| format!("failed to read artifact to verify '{}'", cmd.target))?;
+
+    let mut checks = Vec::new();
+    let verified = if buf.starts_with(WASM_MAGIC) {
+        verify_component(&buf, &mut checks)?
+    } else {
+        verify_provider(&buf, &mut checks).await?
+    };
+    if let Some(verified) = &verified {
+        checks.push(signers_check(&cmd, &verified.signatures));
+        if let Some(accounts) = trusted_accounts(&cmd, &mut checks) {
+            checks.push(Check::new(
+                "issuer",
+                if accounts.contains(&verified.issuer) {
+                    Ok(format!("issued by trusted account {}", verified.issuer))
+                } else {
+                    Err(format!("issued by untrusted account {}", verified.issuer))
+                },
+            ));
+        }
+    }
+
+    let mut text = match &verified {
+        Some(verified) => format!(
+            "Verifying {} ({} {})",
+            cmd.target, verified.kind, verified.subject
+        ),
+        None => format!("Verifying {}", cmd.target),
+    };
+    for check in &checks {
+        let status = if check.passed { "ok" } else { "FAILED" };
+        text.push_str(&format!("\n  [{status}] {}: {}", check.check, check.detail));
+    }
+    if let Some(verified) = &verified {
+        text.push_str("\nSignatures:");
+        for status in &verified.signatures {
+            let kind = if status.cosignature {
+                "co-signature"
+            } else {
+                "claims"
+            };
+            let trusted = if is_trusted_signer(&cmd, &status.issuer) {
+                ""
+            } else {
+                ", untrusted"
+            };
+            match &status.error {
+                None => text.push_str(&format!("\n  {} ({kind}{trusted}): valid", status.issuer)),
+                Some(error) => text.push_str(&format!(
+                    "\n  {} ({kind}{trusted}): invalid, {error}",
+                    status.issuer
+                )),
+            }
+        }
+    }
+    let failed = checks.iter().filter(|check| !check.passed).count();
+    if failed > 0 {
+        bail!("{text}\n{} failed {failed} check(s)", cmd.target);
+    }
+    if verified.is_some() && cmd.trusted_operators.is_empty() && cmd.trusted_accounts.is_empty() {
+        text.push_str(
+            "\nThe issuer was not checked, no trusted operators or accounts were provided",
+        );
+    }
+
+    let mut map = HashMap::new();
+    map.insert("target".to_string(), json!(cmd.target));
+    if let Some(verified) = verified {
+        map.insert("kind".to_string(), json!(verified.kind));
+        map.insert("issuer".to_string(), json!(verified.issuer));
+        map.insert("subject".to_string(), json!(verified.subject));
+        map.insert("signatures".to_string(), json!(verified.signatures));
+    }
+    map.insert("checks".to_string(), json!(checks));
+    Ok(CommandOutput::new(text, map))
+}
+
+/// Recomputes the module hash of a component and validates its claims. Returns `None` if the
+/// claims can't be trusted at all.
+fn verify_component(buf: &[u8], checks: &mut Vec<Check>) -> Result<Option<Verified>> {
+    let hash =
+        module_hash(buf).context("failed to compute module hash, is this a valid Wasm file?")?;
+    let token = match extract_claims(buf) {
+        Ok(Some(token)) => token,
+        Ok(None) => bail!("component is not signed"),
+        // NOTE: Extracting claims fails if the module hash doesn't match
+        Err(e) => {
+            checks.push(Check::new(
+                "claims",
+                Err(format!("failed to extract claims of module {hash}: {e}")),
+            ));
+            return Ok(None);
+        }
+    };
+
+    // NOTE: Claims of old wascap revisions are extracted without checking the module hash
+    let claimed_hash = token
+        .claims
+        .metadata
+        .as_ref()
+        .map(|component| component.module_hash.as_str())
+        .unwrap_or_default();
+    checks.push(Check::new(
+        "module hash",
+        if claimed_hash == hash {
+            Ok(format!("{hash} matches the claims"))
+        } else {
+            Err(format!(
+                "module hash {hash} does not match {claimed_hash} in the claims"
+            ))
+        },
+    ));
+    checks.push(jwt_check::<Component>(&token.jwt));
+    // NOTE: Extracting co-signatures fails if they were not issued over the hashes of the claims
+    let cosignatures = match extract_cosignatures(buf) {
+        Ok(cosignatures) => cosignatures,
+        Err(e) => {
+            checks.push(Check::new(
+                "co-signatures",
+                Err(format!("invalid co-signatures: {e}")),
+            ));
+            Vec::new()
+        }
+    };
+    Ok(Some(Verified {
+        kind: "component",
+        signatures: signature_statuses(&token, &cosignatures),
+        issuer: token.claims.issuer,
+        subject: token.claims.subject,
+    }))
+}
+
+/// Loads a provider archive, which verifies its binaries, WIT package and co-signatures against
+/// its claims, and validates the claims. Returns `None` if the archive can't be loaded.
+async fn verify_provider(buf: &[u8], checks: &mut Vec<Check>) -> Result<Option<Verified>> {
+    // NOTE: Loading an archive fails if any binary or the WIT package doesn't match its hash in the
+    // claims, or if any co-signature wasn't issued over them
+    let par = match ProviderArchive::try_load(buf).await {
+        Ok(par) => par,
+        Err(e) => {
+            checks.push(Check::new(
+                "provider archive",
+                Err(boxed_err_to_anyhow(e).to_string()),
+            ));
+            return Ok(None);
+        }
+    };
+    let token = par
+        .claims_token()
+        .context("provider archive is not signed")?;
+    let mut targets = par.targets();
+    targets.sort();
+    let wit = if par.wit().is_some() {
+        " and the WIT package"
+    } else {
+        ""
+    };
+    checks.push(Check::new(
+        "provider archive",
+        Ok(format!(
+            "binaries of {}{wit} match the claims",
+            targets.join(", ")
+        )),
+    ));
+    checks.push(jwt_check::<CapabilityProvider>(&token.jwt));
+    Ok(Some(Verified {
+        kind: "provider",
+        signatures: signature_statuses(&token, par.cosignatures()),
+        issuer: token.claims.issuer,
+        subject: token.claims.subject,
+    }))
+}
+
+/// Validates the signature and dates of the claims JWT of an artifact
+fn jwt_check<T>(jwt: &str) -> Check
+where
+    T: Serialize + DeserializeOwned + WascapEntity,
+{
+    Check::new(
+        "claims",
+        match validation_error(validate_token::<T>(jwt)) {
+            None => Ok("signature and dates are valid".to_string()),
+            Some(error) => Err(error),
+        },
+    )
+}
+
+/// Returns the status of the claims signature and of each co-signature of an artifact
+fn signature_statuses<T>(
+    token: &Token<T>,
+    cosignatures: &[Token<CoSignature>],
+) -> Vec<SignatureStatus>
+where
+    T: Serialize + DeserializeOwned + WascapEntity,
+{
+    let mut statuses = vec![SignatureStatus::new(
+        &token.claims.issuer,
+        false,
+        validation_error(validate_token::<T>(&token.jwt)),
+    )];
+    statuses.extend(cosignatures.iter().map(|cosignature| {
+        let error = if cosignature.claims.subject == token.claims.subject {
+            validation_error(validate_token::<CoSignature>(&cosignature.jwt))
+        } else {
+            Some(format!(
+                "issued for other subject `{}`",
+                cosignature.claims.subject
+            ))
+        };
+        SignatureStatus::new(&cosignature.claims.issuer, true, error)
+    }));
+    statuses
+}
+
+fn is_trusted_signer(cmd: &VerifyCommand, issuer: &str) -> bool {
+    cmd.signers.is_empty() || cmd.signers.iter().any(|signer| signer == issuer)
+}
+
+/// Checks that at least `--threshold` distinct trusted signers validly signed an artifact
+fn signers_check(cmd: &VerifyCommand, signatures: &[SignatureStatus]) -> Check {
+    let signers: BTreeSet<&str> = signatures
+        .iter()
+        .filter(|status| status.valid && is_trusted_signer(cmd, &status.issuer))
+        .map(|status| status.issuer.as_str())
+        .collect();
+    let detail = format!(
+        "signed by {} of the {} required signers",
+        signers.len(),
+        cmd.threshold
+    );
+    Check::new(
+        "signatures",
+        if signers.len() >= cmd.threshold {
+            Ok(detail)
+        } else {
+            Err(detail)
+        },
+    )
+}
+
+/// Builds the set of accounts trusted to issue artifacts, including the accounts and signing keys of
+/// account JWTs issued by trusted operators. Returns `None` if no operators or accounts are trusted.
+fn trusted_accounts(cmd: &VerifyCommand, checks: &mut Vec<Check>) -> Option<HashSet<String>> {
+    if cmd.trusted_operators.is_empty() && cmd.trusted_accounts.is_empty() {
+        return None;
+    }
+    let mut accounts: HashSet<String> = cmd.trusted_accounts.iter().cloned().collect();
+    for jwt in &cmd.trusted_account_jwts {
+        let claims = match Claims::<Account>::decode(jwt) {
+            Ok(claims) => claims,
+            Err(e) => {
+                checks.push(Check::new("account JWT", Err(format!("invalid JWT: {e}"))));
+                continue;
+            }
+        };
+        let check = format!("account {}", claims.subject);
+        if let Some(error) = validation_error(validate_token::<Account>(jwt)) {
+            checks.push(Check::new(check, Err(error)));
+            continue;
+        }
+        if !cmd.trusted_operators.contains(&claims.issuer) {
+            checks.push(Check::new(
+                check,
+                Err(format!("issued by untrusted operator {}", claims.issuer)),
+            ));
+            continue;
+        }
+        checks.push(Check::new(
+            check,
+            Ok(format!("issued by trusted operator {}", claims.issuer)),
+        ));
+        accounts.extend(
+            claims
+                .metadata
+                .and_then(|account| account.valid_signers)
+                .unwrap_or_default(),
+        );
+        accounts.insert(claims.subject);
+    }
+    Some(accounts)
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use nkeys::KeyPair;
+    use wascap::{
+        jwt::ClaimsBuilder,
+        wasm::{embed_claims, embed_cosignature},
+    };
+
+    use super::*;
+
+    #[derive(Parser, Debug)]
+    struct Cmd {
+        #[clap(flatten)]
+        command: VerifyCommand,
+    }
+
+    #[test]
+    /// Check all flags and options of the 'verify' command
+    /// so that the API does not change in between versions
+    fn test_verify_comprehensive() {
+        const OPERATOR: &str = "OCJ6OCH2TVM7K7H2NKYJ6IZ4WTAWC3LHJLTEKJSRJN4QJGZEBDBN2Y2W";
+        const ACCOUNT: &str = "ACOJJN6WUP4ODD75XEBKKTCCUJJCY5ZKQ56XVKYK4BEJWGVAOOQHZMCW";
+        const REMOTE: &str = "wasmcloud.azurecr.io/coolthing.par.gz";
+
+        let cmd: Cmd = Parser::try_parse_from([
+            "verify",
+            REMOTE,
+            "--trusted-operators",
+            OPERATOR,
+            "--trusted-accounts",
+            &format!("{ACCOUNT},{ACCOUNT}"),
+            "--trusted-account-jwts",
+            "eyJ0eXAi",
+            "--signer",
+            ACCOUNT,
+            "--threshold",
+            "2",
+            "--digest",
+            "sha256:blah",
+            "--user",
+            "name",
+            "--password",
+            "secret",
+            "--allow-latest",
+            "--insecure",
+            "--insecure-skip-tls-verify",
+        ])
+        .unwrap();
+        let VerifyCommand {
+            target,
+            trusted_operators,
+            trusted_accounts,
+            trusted_account_jwts,
+            signers,
+            threshold,
+            digest,
+            allow_latest,
+            user,
+            password,
+            insecure,
+            insecure_skip_tls_verify,
+        } = cmd.command;
+        assert_eq!(target, REMOTE);
+        assert_eq!(trusted_operators, vec![OPERATOR]);
+        assert_eq!(trusted_accounts, vec![ACCOUNT, ACCOUNT]);
+        assert_eq!(trusted_account_jwts, vec!["eyJ0eXAi"]);
+        assert_eq!(signers, vec![ACCOUNT]);
+        assert_eq!(threshold, 2);
+        assert_eq!(digest.unwrap(), "sha256:blah");
+        assert!(allow_latest);
+        assert_eq!(user.unwrap(), "name");
+        assert_eq!(password.unwrap(), "secret");
+        assert!(insecure);
+        assert!(insecure_skip_tls_verify);
+
+        assert!(
+            Cmd::try_parse_from(["verify", REMOTE, "--trusted-account-jwts", "eyJ0eXAi"]).is_err(),
+            "account JWTs require trusted operators"
+        );
+        assert!(
+            Cmd::try_parse_from(["verify", REMOTE, "--threshold", "0"]).is_err(),
+            "at least one signature must be required"
+        );
+    }
+
+    fn verify_command(target: &Path, args: &[&str]) -> VerifyCommand {
+        let target = target.to_str().unwrap();
+        Cmd::try_parse_from(["verify", target].iter().chain(args))
+            .expect("Should be able to parse verify command")
+            .command
+    }
+
+    /// Verifies an artifact that must fail verification, returning the reasons
+    async fn verification_error(cmd: VerifyCommand, reason: &str) -> String {
+        match handle_command(cmd).await {
+            Ok(_) => panic!("{reason}"),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    fn signed_component(issuer: &KeyPair) -> Vec<u8> {
+        let claims = ClaimsBuilder::<Component>::new()
+            .issuer(&issuer.public_key())
+            .subject(&KeyPair::new_module().public_key())
+            .with_metadata(Component {
+                name: Some("test".to_string()),
+                ..Default::default()
+            })
+            .build();
+        let wasm = wat::parse_str("(module)").expect("Should be able to parse module");
+        embed_claims(&wasm, &claims, issuer).expect("Should be able to sign component")
+    }
+
+    #[tokio::test]
+    async fn verify_component_signers() {
+        let issuer = KeyPair::new_account();
+        let cosigner = KeyPair::new_account();
+        let dir = tempfile::tempdir().expect("Should be able to create temp dir");
+        let path = dir.path().join("component.wasm");
+        let cosigned = embed_cosignature(&signed_component(&issuer), &cosigner, None)
+            .expect("Should be able to co-sign component");
+        std::fs::write(&path, cosigned).expect("Should be able to write component");
+
+        let output = handle_command(verify_command(&path, &[]))
+            .await
+            .expect("Should be able to verify component");
+        assert_eq!(output.map["issuer"], issuer.public_key());
+        assert_eq!(output.map["signatures"].as_array().unwrap().len(), 2);
+
+        let cosigner_key = cosigner.public_key();
+        let signers = format!("{},{cosigner_key}", issuer.public_key());
+        handle_command(verify_command(&path, &["--signer", &cosigner_key]))
+            .await
+            .expect("Co-signatures should count as signatures");
+        handle_command(verify_command(
+            &path,
+            &["--signer", &signers, "--threshold", "2"],
+        ))
+        .await
+        .expect("Claims and co-signatures should count as distinct signatures");
+        let err = verification_error(
+            verify_command(&path, &["--signer", &cosigner_key, "--threshold", "2"]),
+            "Signatures of untrusted signers should not count",
+        )
+        .await;
+        assert!(err.contains("[FAILED] signatures: signed by 1 of the 2 required signers"));
+    }
+
+    #[tokio::test]
+    async fn verify_untrusted_issuer() {
+        let issuer = KeyPair::new_account();
+        let dir = tempfile::tempdir().expect("Should be able to create temp dir");
+        let path = dir.path().join("component.wasm");
+        std::fs::write(&path, signed_component(&issuer))
+            .expect("Should be able to write component");
+
+        handle_command(verify_command(
+            &path,
+            &["--trusted-accounts", &issuer.public_key()],
+        ))
+        .await
+        .expect("Component issued by a trusted account should be verified");
+        let err = verification_error(
+            verify_command(
+                &path,
+                &["--trusted-accounts", &KeyPair::new_account().public_key()],
+            ),
+            "Component issued by an untrusted account should fail verification",
+        )
+        .await;
+        assert!(err.contains(&format!(
+            "[FAILED] issuer: issued by untrusted account {}",
+            issuer.public_key()
+        )));
+    }
+
+    #[tokio::test]
+    async fn verify_tampered_module() {
+        let dir = tempfile::tempdir().expect("Should be able to create temp dir");
+        let path = dir.path().join("component.wasm");
+        let mut wasm = signed_component(&KeyPair::new_account());
+        // Append a custom section named `tampered`, which changes the module hash
+        wasm.extend([0, 9, 8]);
+        wasm.extend(b"tampered");
+        std::fs::write(&path, wasm).expect("Should be able to write component");
+
+        let err = verification_error(
+            verify_command(&path, &[]),
+            "Tampered module should fail verification",
+        )
+        .await;
+        assert!(err.contains("[FAILED] module hash"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn verify_tampered_provider_archive() {
+        let dir = tempfile::tempdir().expect("Should be able to create temp dir");
+        let path = dir.path().join("provider.par");
+        let mut par = ProviderArchive::new("test", "wasmCloud", None, None);
+        par.add_library("x86_64-linux", b"original provider binary")
+            .expect("Should be able to add library");
+        par.write(
+            &path,
+            &KeyPair::new_account(),
+            &KeyPair::new_service(),
+            false,
+        )
+        .await
+        .expect("Should be able to write provider archive");
+
+        let output = handle_command(verify_command(&path, &[]))
+            .await
+            .expect("Should be able to verify provider archive");
+        assert_eq!(output.map["kind"], "provider");
+
+        // Binaries are stored as is in uncompressed archives, so they can be swapped in place
+        let buf = std::fs::read(&path).expect("Should be able to read provider archive");
+        let offset = buf
+            .windows(8)
+            .position(|window| window == b"original")
+            .expect("Archive should contain the binary");
+        let mut tampered = buf.clone();
+        tampered[offset..offset + 8].copy_from_slice(b"tampered");
+        std::fs::write(&path, tampered).expect("Should be able to write provider archive");
+
+        let err = verification_error(
+            verify_command(&path, &[]),
+            "Tampered provider archive should fail verification",
+        )
+        .await;
+        assert!(err.contains("[FAILED] provider archive"));
+    }
+}
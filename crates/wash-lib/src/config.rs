@@ -126,6 +126,10 @@ impl WashConnectionOptions {
             builder = builder.topic_prefix(topic_prefix);
         }
 
+        if let Some(js_domain) = self.js_domain.or_else(|| self.ctx.js_domain.clone()) {
+            builder = builder.js_domain(js_domain);
+        }
+
         let ctl_client = builder.build();
 
         Ok(ctl_client)
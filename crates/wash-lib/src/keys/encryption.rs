@@ -0,0 +1,137 @@
+//! Passphrase encryption of seeds in key files at rest
+//!
+//! An encrypted key file contains a JSON document with the parameters used to derive a key from
+//! the passphrase with scrypt, and the seed sealed with an XKey created from the derived key.
+
+use anyhow::{anyhow, ensure, Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use nkeys::XKey;
+use serde::{Deserialize, Serialize};
+
+/// Environment variable the passphrase of encrypted key files is read from
+pub const KEYS_PASSPHRASE_ENV: &str = "WASH_KEYS_PASSPHRASE";
+
+const FORMAT_VERSION: u8 = 1;
+/// The scrypt cost parameter, as a power of two, used when encrypting seeds
+const SCRYPT_LOG_N: u8 = 15;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+/// The largest scrypt parameters accepted in key files, so that a tampered file can't make key
+/// derivation use more than 1 GiB of memory or run for an unbounded time
+const MAX_SCRYPT_LOG_N: u8 = 20;
+const MAX_SCRYPT_R: u32 = 8;
+const MAX_SCRYPT_P: u32 = 4;
+
+/// The contents of an encrypted key file
+#[derive(Debug, Serialize, Deserialize)]
+struct EncryptedSeed {
+    version: u8,
+    log_n: u8,
+    r: u32,
+    p: u32,
+    /// Base64 encoded salt of the key derivation
+    salt: String,
+    /// Base64 encoded seed, sealed with the XKey derived from the passphrase
+    ciphertext: String,
+}
+
+/// Returns whether `contents` of a key file are an encrypted seed
+#[must_use]
+pub fn is_encrypted(contents: &str) -> bool {
+    contents.trim_start().starts_with('{')
+}
+
+fn derive_xkey(passphrase: &str, salt: &[u8], log_n: u8, r: u32, p: u32) -> Result<XKey> {
+    // NOTE: scrypt errors only implement `std::error::Error` with its `std` feature
+    let params =
+        scrypt::Params::new(log_n, r, p, 32).map_err(|_| anyhow!("invalid scrypt parameters"))?;
+    let mut key = [0; 32];
+    scrypt::scrypt(passphrase.as_bytes(), salt, &params, &mut key)
+        .map_err(|_| anyhow!("failed to derive key from passphrase"))?;
+    Ok(XKey::new_from_raw(key))
+}
+
+/// Encrypt `seed` with `passphrase`, returning the contents of an encrypted key file
+pub fn encrypt_seed(seed: &str, passphrase: &str) -> Result<String> {
+    ensure!(!passphrase.is_empty(), "passphrase must not be empty");
+    let salt: [u8; 16] = rand::random();
+    let xkey = derive_xkey(passphrase, &salt, SCRYPT_LOG_N, SCRYPT_R, SCRYPT_P)?;
+    let ciphertext = xkey
+        .seal(seed.as_bytes(), &xkey)
+        .context("failed to encrypt seed")?;
+    serde_json::to_string(&EncryptedSeed {
+        version: FORMAT_VERSION,
+        log_n: SCRYPT_LOG_N,
+        r: SCRYPT_R,
+        p: SCRYPT_P,
+        salt: STANDARD.encode(salt),
+        ciphertext: STANDARD.encode(ciphertext),
+    })
+    .context("failed to encode encrypted seed")
+}
+
+/// Decrypt the seed in the `contents` of an encrypted key file with `passphrase`
+pub fn decrypt_seed(contents: &str, passphrase: &str) -> Result<String> {
+    let encrypted: EncryptedSeed =
+        serde_json::from_str(contents).context("failed to parse encrypted key file")?;
+    ensure!(
+        encrypted.version == FORMAT_VERSION,
+        "unsupported encrypted key file version {}",
+        encrypted.version
+    );
+    let salt = STANDARD
+        .decode(&encrypted.salt)
+        .context("failed to decode salt")?;
+    let ciphertext = STANDARD
+        .decode(&encrypted.ciphertext)
+        .context("failed to decode encrypted seed")?;
+    ensure!(
+        encrypted.log_n <= MAX_SCRYPT_LOG_N && encrypted.r <= MAX_SCRYPT_R && encrypted.p <= MAX_SCRYPT_P,
+        "scrypt parameters of encrypted key file exceed the maximum of log_n={MAX_SCRYPT_LOG_N}, r={MAX_SCRYPT_R}, p={MAX_SCRYPT_P}"
+    );
+    let xkey = derive_xkey(passphrase, &salt, encrypted.log_n, encrypted.r, encrypted.p)?;
+    let seed = xkey
+        .open(&ciphertext, &xkey)
+        .map_err(|_| anyhow!("failed to decrypt key, is the passphrase correct?"))?;
+    String::from_utf8(seed).context("decrypted seed is not valid UTF-8")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_KEY: &str = "SMAAGJ4DY4FNV4VJWA6QU7UQIL7DKJR4Z3UH7NBMNTH22V6VEIJGJUBQN4";
+
+    #[test]
+    fn round_trip() {
+        let encrypted = encrypt_seed(TEST_KEY, "hunter2").expect("Should be able to encrypt");
+        assert!(is_encrypted(&encrypted), "Should detect encrypted seed");
+        assert!(!is_encrypted(TEST_KEY), "Plaintext seed is not encrypted");
+        assert!(
+            !encrypted.contains(TEST_KEY),
+            "Encrypted file must not contain the seed"
+        );
+
+        assert_eq!(
+            decrypt_seed(&encrypted, "hunter2").expect("Should be able to decrypt"),
+            TEST_KEY
+        );
+        assert!(
+            decrypt_seed(&encrypted, "hunter3").is_err(),
+            "Wrong passphrase should fail to decrypt"
+        );
+    }
+
+    #[test]
+    fn rejects_excessive_parameters() {
+        let encrypted = encrypt_seed(TEST_KEY, "hunter2").expect("Should be able to encrypt");
+        let mut tampered: serde_json::Value =
+            serde_json::from_str(&encrypted).expect("Should be able to parse encrypted seed");
+        tampered["log_n"] = 40.into();
+        assert!(
+            decrypt_seed(&tampered.to_string(), "hunter2").is_err(),
+            "Excessive scrypt parameters should be rejected"
+        );
+    }
+}
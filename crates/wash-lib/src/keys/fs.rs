@@ -8,15 +8,20 @@ use std::{
 use anyhow::Result;
 use nkeys::KeyPair;
 
+use super::encryption::{decrypt_seed, encrypt_seed, is_encrypted, KEYS_PASSPHRASE_ENV};
 use super::KeyManager;
 
 pub const KEY_FILE_EXTENSION: &str = "nk";
 
-pub struct KeyDir(PathBuf);
+pub struct KeyDir {
+    path: PathBuf,
+    /// Passphrase keys are encrypted with when saved, and encrypted keys are decrypted with
+    passphrase: Option<String>,
+}
 
 impl AsRef<Path> for KeyDir {
     fn as_ref(&self) -> &Path {
-        &self.0
+        &self.path
     }
 }
 
@@ -24,12 +29,15 @@ impl Deref for KeyDir {
     type Target = Path;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.path
     }
 }
 
 impl KeyDir {
     /// Creates a new `KeyDir`, erroring if it is unable to access or create the given directory.
+    ///
+    /// If the `WASH_KEYS_PASSPHRASE` environment variable is set, keys are encrypted with it when
+    /// saved, and encrypted keys are decrypted with it when read. Plaintext keys can always be read.
     pub fn new(path: impl AsRef<Path>) -> Result<KeyDir> {
         let p = path.as_ref();
         let exists = p.exists();
@@ -41,12 +49,33 @@ impl KeyDir {
         // Always ensure the directory has the proper permissions, even if it exists
         set_permissions_keys(p)?;
         // Make sure we have the fully qualified path at this point
-        Ok(KeyDir(p.canonicalize()?))
+        Ok(KeyDir {
+            path: p.canonicalize()?,
+            passphrase: std::env::var(KEYS_PASSPHRASE_ENV)
+                .ok()
+                .filter(|passphrase| !passphrase.is_empty()),
+        })
+    }
+
+    /// Sets the passphrase keys are encrypted with when saved, and encrypted keys are decrypted
+    /// with. Keys are saved in plaintext if `passphrase` is `None`.
+    #[must_use]
+    pub fn with_passphrase(self, passphrase: Option<String>) -> KeyDir {
+        KeyDir { passphrase, ..self }
+    }
+
+    /// Returns whether the named key is encrypted at rest. Returns None if the key doesn't exist
+    pub fn is_encrypted(&self, name: &str) -> Result<Option<bool>> {
+        match std::fs::read_to_string(self.generate_file_path(name)) {
+            Ok(contents) => Ok(Some(is_encrypted(&contents))),
+            Err(e) if matches!(e.kind(), std::io::ErrorKind::NotFound) => Ok(None),
+            Err(e) => Err(anyhow::anyhow!("Unable to load key from disk: {}", e)),
+        }
     }
 
     /// Returns a list of paths to all keyfiles in the directory
     pub fn list_paths(&self) -> Result<Vec<PathBuf>> {
-        let paths = std::fs::read_dir(&self.0)?;
+        let paths = std::fs::read_dir(&self.path)?;
 
         Ok(paths
             .filter_map(|p| {
@@ -64,14 +93,14 @@ impl KeyDir {
     }
 
     fn generate_file_path(&self, name: &str) -> PathBuf {
-        self.0.join(format!("{name}.{KEY_FILE_EXTENSION}"))
+        self.path.join(format!("{name}.{KEY_FILE_EXTENSION}"))
     }
 }
 
 impl KeyManager for KeyDir {
     fn get(&self, name: &str) -> Result<Option<KeyPair>> {
         let path = self.generate_file_path(name);
-        match read_key(path) {
+        match read_key_with_passphrase(path, self.passphrase.as_deref()) {
             Ok(k) => Ok(Some(k)),
             Err(e) if matches!(e.kind(), std::io::ErrorKind::NotFound) => Ok(None),
             Err(e) => Err(anyhow::anyhow!("Unable to load key from disk: {}", e)),
@@ -96,7 +125,8 @@ impl KeyManager for KeyDir {
         self.list_paths()?
             .into_iter()
             .map(|p| {
-                read_key(p).map_err(|e| anyhow::anyhow!("Unable to load key from disk: {}", e))
+                read_key_with_passphrase(p, self.passphrase.as_deref())
+                    .map_err(|e| anyhow::anyhow!("Unable to load key from disk: {}", e))
             })
             .collect()
     }
@@ -111,17 +141,41 @@ impl KeyManager for KeyDir {
 
     fn save(&self, name: &str, key: &KeyPair) -> Result<()> {
         let path = self.generate_file_path(name);
-        std::fs::write(&path, key.seed()?.as_bytes())
+        let contents = match &self.passphrase {
+            Some(passphrase) => encrypt_seed(&key.seed()?, passphrase)?,
+            None => key.seed()?,
+        };
+        std::fs::write(&path, contents.as_bytes())
             .map_err(|e| anyhow::anyhow!("Unable to write key to disk: {}", e))?;
         set_permissions_keys(path)
     }
 }
 
-/// Helper function for reading a key from disk
+/// Helper function for reading a key from disk. Encrypted keys are decrypted with the passphrase
+/// in the `WASH_KEYS_PASSPHRASE` environment variable
 pub fn read_key(p: impl AsRef<Path>) -> std::io::Result<KeyPair> {
+    let passphrase = std::env::var(KEYS_PASSPHRASE_ENV).ok();
+    read_key_with_passphrase(p, passphrase.as_deref())
+}
+
+/// Helper function for reading a key from disk, decrypting it with `passphrase` if it is encrypted
+pub fn read_key_with_passphrase(
+    p: impl AsRef<Path>,
+    passphrase: Option<&str>,
+) -> std::io::Result<KeyPair> {
     let raw = std::fs::read_to_string(p)?;
+    let seed = if is_encrypted(&raw) {
+        let passphrase = passphrase.ok_or_else(|| {
+            std::io::Error::other(format!(
+                "key is encrypted, set {KEYS_PASSPHRASE_ENV} to decrypt it"
+            ))
+        })?;
+        decrypt_seed(&raw, passphrase).map_err(|e| std::io::Error::other(format!("{e:#}")))?
+    } else {
+        raw
+    };
 
-    KeyPair::from_seed(&raw).map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
+    KeyPair::from_seed(&seed).map_err(std::io::Error::other)
 }
 
 #[cfg(unix)]
@@ -239,6 +293,39 @@ mod test {
         );
     }
 
+    #[test]
+    fn encrypted_round_trip() {
+        let tempdir = tempfile::tempdir().expect("Unable to create temp dir");
+        let key_dir = KeyDir::new(&tempdir)
+            .expect("Should be able to create key dir")
+            .with_passphrase(Some("hunter2".to_string()));
+
+        let key = KeyPair::from_seed(TEST_KEY).unwrap();
+        key_dir
+            .save("foobar_module", &key)
+            .expect("Should be able to save key");
+        let raw = std::fs::read_to_string(tempdir.path().join("foobar_module.nk")).unwrap();
+        assert!(!raw.contains(TEST_KEY), "Seed should be encrypted on disk");
+        assert_eq!(key_dir.is_encrypted("foobar_module").unwrap(), Some(true));
+
+        let loaded = key_dir
+            .get("foobar_module")
+            .expect("Should be able to decrypt key")
+            .expect("Key should exist");
+        assert_eq!(loaded.seed().unwrap(), TEST_KEY);
+
+        let key_dir = key_dir.with_passphrase(None);
+        assert!(
+            key_dir.get("foobar_module").is_err(),
+            "Encrypted keys can't be read without a passphrase"
+        );
+        let key_dir = key_dir.with_passphrase(Some("hunter3".to_string()));
+        assert!(
+            key_dir.get("foobar_module").is_err(),
+            "Encrypted keys can't be read with the wrong passphrase"
+        );
+    }
+
     #[test]
     fn delete_of_nonexistent_key_should_succeed() {
         let tempdir = tempfile::tempdir().expect("Unable to create temp dir");
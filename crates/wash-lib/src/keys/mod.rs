@@ -6,7 +6,9 @@ use nkeys::KeyPair;
 /// Convenience re-export of nkeys to make key functionality easier to manage
 pub use nkeys;
 
+pub mod encryption;
 pub mod fs;
+pub mod rotate;
 
 /// A trait that can be implemented by anything that needs to manage nkeys
 pub trait KeyManager {
@@ -0,0 +1,154 @@
+//! Rotation of issuer keys, re-signing components and provider archives with the new key
+
+use std::path::Path;
+
+use anyhow::{anyhow, ensure, Context, Result};
+use nkeys::KeyPair;
+use provider_archive::{Compression, ProviderArchive};
+use wascap::jwt::{ClaimsBuilder, Component};
+use wascap::wasm::{embed_claims, extract_claims};
+
+/// Prefix of the claims tags recording the previous issuers of re-signed components and providers
+pub const PREVIOUS_ISSUER_TAG_PREFIX: &str = "previous-issuer:";
+
+fn previous_issuer_tag(previous: &KeyPair) -> String {
+    format!("{PREVIOUS_ISSUER_TAG_PREFIX}{}", previous.public_key())
+}
+
+/// Re-signs the component `wasm`, which must be issued by `previous`, with `issuer`, recording
+/// the previous issuer in the claims tags. All other claims are preserved.
+pub fn resign_component(wasm: &[u8], previous: &KeyPair, issuer: &KeyPair) -> Result<Vec<u8>> {
+    let claims = extract_claims(wasm)
+        .context("failed to extract claims")?
+        .context("component is not signed")?
+        .claims;
+    ensure!(
+        claims.issuer == previous.public_key(),
+        "component is issued by `{}`, not by the rotated key `{}`",
+        claims.issuer,
+        previous.public_key()
+    );
+    let mut metadata = claims
+        .metadata
+        .context("component claims contain no metadata")?;
+    metadata
+        .tags
+        .get_or_insert_with(Vec::new)
+        .push(previous_issuer_tag(previous));
+    let mut resigned = ClaimsBuilder::<Component>::new()
+        .issuer(&issuer.public_key())
+        .subject(&claims.subject)
+        .with_metadata(metadata)
+        .build();
+    resigned.expires = claims.expires;
+    resigned.not_before = claims.not_before;
+    embed_claims(wasm, &resigned, issuer).context("failed to embed claims")
+}
+
+/// Re-signs the provider archive at `path`, which must be issued by `previous`, with `issuer` in
+/// place, recording the previous issuer in the claims tags. All other claims are preserved.
+pub async fn resign_provider_archive(
+    path: impl AsRef<Path>,
+    previous: &KeyPair,
+    issuer: &KeyPair,
+) -> Result<()> {
+    let path = path.as_ref();
+    let buf = tokio::fs::read(path)
+        .await
+        .with_context(|| format!("failed to read provider archive `{}`", path.display()))?;
+    let mut par = ProviderArchive::try_load(&buf)
+        .await
+        .map_err(|e| anyhow!(e.to_string()))
+        .with_context(|| format!("failed to load provider archive `{}`", path.display()))?;
+    let claims = par.claims().context("provider archive is not signed")?;
+    ensure!(
+        claims.issuer == previous.public_key(),
+        "provider archive is issued by `{}`, not by the rotated key `{}`",
+        claims.issuer,
+        previous.public_key()
+    );
+    let mut tags = par.tags().map(<[String]>::to_vec).unwrap_or_default();
+    tags.push(previous_issuer_tag(previous));
+    par.set_tags(tags);
+    // Only the public key of the subject is needed to write the archive
+    let subject = KeyPair::from_public_key(&claims.subject)?;
+    par.write_compressed(path, issuer, &subject, Compression::detect(&buf))
+        .await
+        .map_err(|e| anyhow!(e.to_string()))
+        .with_context(|| format!("failed to write provider archive `{}`", path.display()))
+}
+
+#[cfg(test)]
+mod test {
+    use wascap::jwt::Claims;
+
+    use super::*;
+
+    #[test]
+    fn resign_component_records_previous_issuer() {
+        let previous = KeyPair::new_account();
+        let issuer = KeyPair::new_account();
+        let subject = KeyPair::new_module();
+        let claims: Claims<Component> = ClaimsBuilder::new()
+            .issuer(&previous.public_key())
+            .subject(&subject.public_key())
+            .with_metadata(Component {
+                name: Some("test".to_string()),
+                tags: Some(vec!["test".to_string()]),
+                ..Default::default()
+            })
+            .build();
+        let wasm = wat::parse_str("(module)").expect("Should be able to parse module");
+        let signed = embed_claims(&wasm, &claims, &previous).expect("Should be able to sign");
+
+        let resigned =
+            resign_component(&signed, &previous, &issuer).expect("Should be able to re-sign");
+        let token = extract_claims(&resigned)
+            .expect("Should be able to extract claims")
+            .expect("Component should be signed");
+        assert_eq!(token.claims.issuer, issuer.public_key());
+        assert_eq!(token.claims.subject, subject.public_key());
+        assert_eq!(
+            token.claims.metadata.unwrap().tags.unwrap(),
+            vec!["test".to_string(), previous_issuer_tag(&previous)]
+        );
+
+        assert!(
+            resign_component(&resigned, &previous, &issuer).is_err(),
+            "Components not issued by the rotated key should not be re-signed"
+        );
+    }
+
+    #[tokio::test]
+    async fn resign_provider_archive_preserves_claims() {
+        let previous = KeyPair::new_account();
+        let issuer = KeyPair::new_account();
+        let subject = KeyPair::new_service();
+        let dir = tempfile::tempdir().expect("Should be able to create temp dir");
+        let path = dir.path().join("test.par");
+
+        let mut par = ProviderArchive::new("test", "wasmCloud", Some(1), Some("0.1.0".into()));
+        par.add_library("x86_64-linux", b"library")
+            .expect("Should be able to add library");
+        par.set_validity(Some(4_000_000_000), Some(1_000_000_000));
+        par.write(&path, &previous, &subject, false)
+            .await
+            .expect("Should be able to write archive");
+
+        resign_provider_archive(&path, &previous, &issuer)
+            .await
+            .expect("Should be able to re-sign");
+        let par = ProviderArchive::try_load_file(&path)
+            .await
+            .expect("Should be able to load archive");
+        let claims = par.claims().expect("Archive should be signed");
+        assert_eq!(claims.issuer, issuer.public_key());
+        assert_eq!(claims.subject, subject.public_key());
+        assert_eq!(claims.expires, Some(4_000_000_000));
+        assert_eq!(claims.not_before, Some(1_000_000_000));
+        assert_eq!(
+            par.tags().expect("Archive should have tags"),
+            [previous_issuer_tag(&previous)]
+        );
+    }
+}
@@ -169,6 +169,10 @@ pub struct ProviderConfig {
     pub bin_name: Option<String>,
     /// The directory to store the private signing keys in.
     pub key_directory: PathBuf,
+    /// Whether to bundle the WIT package in the `wit` directory of the project in the provider
+    /// archive. Archives bundling their WIT can't be loaded by hosts and tools using
+    /// `provider-archive` older than 0.13.0. Defaults to false.
+    pub bundle_wit: bool,
 }
 
 #[derive(Deserialize, Debug, PartialEq)]
@@ -187,6 +191,10 @@ struct RawProviderConfig {
     pub bin_name: Option<String>,
     /// The directory to store the private signing keys in.
     pub key_directory: Option<PathBuf>,
+    /// Whether to bundle the WIT package in the `wit` directory of the project in the provider
+    /// archive. Defaults to false.
+    #[serde(default)]
+    pub bundle_wit: bool,
 }
 
 impl TryFrom<RawProviderConfig> for ProviderConfig {
@@ -212,6 +220,7 @@ impl TryFrom<RawProviderConfig> for ProviderConfig {
             bin_name: raw_config.bin_name,
             wit_world: raw_config.wit_world,
             key_directory,
+            bundle_wit: raw_config.bundle_wit,
         })
     }
 }
@@ -1,5 +1,6 @@
 use std::collections::{HashMap, HashSet};
 use std::env;
+use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::{Arc, LazyLock};
 use std::time::Duration;
@@ -16,7 +17,11 @@ use wasmcloud_core::logging::Level as WasmcloudLogLevel;
 use wasmcloud_core::{OtelConfig, OtelProtocol};
 use wasmcloud_host::oci::Config as OciConfig;
 use wasmcloud_host::url::Url;
-use wasmcloud_host::wasmbus::host_config::PolicyService as PolicyServiceConfig;
+use wasmcloud_host::wasmbus::host_config::{
+    CircuitBreaker as CircuitBreakerConfig, ClaimsExpiry as ClaimsExpiryConfig, ClaimsExpiryAction,
+    EventStream as EventStreamConfig, LogForwarding as LogForwardingConfig,
+    PolicyService as PolicyServiceConfig, RequiredSignatures, TrustedIssuers,
+};
 use wasmcloud_host::WasmbusHostConfig;
 use wasmcloud_tracing::configure_observability;
 
@@ -226,6 +231,16 @@ struct Args {
     #[clap(long = "secrets-topic", env = "WASMCLOUD_SECRETS_TOPIC")]
     secrets_topic_prefix: Option<String>,
 
+    /// If provided, secrets of running components and providers are re-fetched from the secrets backend at this interval,
+    /// in addition to when the backend notifies that secrets changed. Provided value is interpreted as seconds.
+    #[clap(
+        long = "secrets-refresh-interval-seconds",
+        env = "WASMCLOUD_SECRETS_REFRESH_INTERVAL",
+        requires = "secrets_topic_prefix",
+        value_parser = parse_interval_secs,
+    )]
+    secrets_refresh_interval: Option<Duration>,
+
     /// Used in tandem with `oci_user` and `oci_password` to override credentials for a specific OCI registry.
     #[clap(
         long = "oci-registry",
@@ -299,6 +314,41 @@ struct Args {
     )]
     observability_protocol: Option<OtelProtocol>,
 
+    /// Ratio of traces to sample, between 0.0 and 1.0. All traces are sampled if not set.
+    #[clap(
+        long = "traces-sampling-ratio",
+        env = "WASMCLOUD_TRACES_SAMPLING_RATIO",
+        value_parser = parse_ratio
+    )]
+    traces_sampling_ratio: Option<f64>,
+
+    /// Respect the sampling decision of parent spans, only applying the sampling ratio to root spans
+    #[clap(
+        long = "traces-sampling-parent-based",
+        env = "WASMCLOUD_TRACES_SAMPLING_PARENT_BASED"
+    )]
+    traces_sampling_parent_based: bool,
+
+    /// If provided, traces are written as OTLP JSON lines to this file instead of being exported to an OpenTelemetry endpoint
+    #[clap(long = "traces-file", env = "WASMCLOUD_TRACES_FILE")]
+    traces_file: Option<PathBuf>,
+
+    /// The size in bytes at which the traces file is rotated. Requires `traces_file` to be set.
+    #[clap(
+        long = "traces-file-max-bytes",
+        env = "WASMCLOUD_TRACES_FILE_MAX_BYTES",
+        requires = "traces_file"
+    )]
+    traces_file_max_bytes: Option<u64>,
+
+    /// The number of rotated traces files to keep. Requires `traces_file` to be set.
+    #[clap(
+        long = "traces-file-max-files",
+        env = "WASMCLOUD_TRACES_FILE_MAX_FILES",
+        requires = "traces_file"
+    )]
+    traces_file_max_files: Option<usize>,
+
     /// Path to generate flame graph at
     #[clap(long = "flame-graph", env = "WASMCLOUD_FLAME_GRAPH")]
     flame_graph: Option<String>,
@@ -310,6 +360,163 @@ struct Args {
     /// If provided, overrides the default heartbeat interval of every 30 seconds. Provided value is interpreted as seconds.
     #[arg(long = "heartbeat-interval-seconds", env = "WASMCLOUD_HEARTBEAT_INTERVAL", value_parser = parse_duration_secs, hide = true)]
     heartbeat_interval: Option<Duration>,
+
+    /// If provided, serves the `/livez`, `/readyz` and `/inventory` admin HTTP endpoints on this address
+    #[arg(long = "http-admin", env = "WASMCLOUD_HTTP_ADMIN")]
+    http_admin: Option<SocketAddr>,
+
+    /// If provided, host events are additionally written to a JetStream stream, so that they can be replayed
+    #[clap(long = "event-stream", env = "WASMCLOUD_EVENT_STREAM")]
+    event_stream: bool,
+    /// Maximum age of events retained in the event stream, interpreted as seconds. Defaults to one week for a new stream. Requires `event_stream` to be set.
+    #[clap(
+        long = "event-stream-max-age",
+        env = "WASMCLOUD_EVENT_STREAM_MAX_AGE",
+        value_parser = parse_duration_secs,
+        requires = "event_stream"
+    )]
+    event_stream_max_age: Option<Duration>,
+    /// Maximum number of events retained in the event stream. Defaults to 1000000 for a new stream. Requires `event_stream` to be set.
+    #[clap(
+        long = "event-stream-max-messages",
+        env = "WASMCLOUD_EVENT_STREAM_MAX_MESSAGES",
+        requires = "event_stream"
+    )]
+    event_stream_max_messages: Option<i64>,
+    /// Maximum size of the event stream in bytes. Defaults to 1 GiB for a new stream. Requires `event_stream` to be set.
+    #[clap(
+        long = "event-stream-max-bytes",
+        env = "WASMCLOUD_EVENT_STREAM_MAX_BYTES",
+        requires = "event_stream"
+    )]
+    event_stream_max_bytes: Option<i64>,
+
+    /// If provided, logs of components are published to the lattice on `wasmbus.log.<lattice>.<component_id>`
+    #[clap(
+        long = "forward-component-logs",
+        env = "WASMCLOUD_FORWARD_COMPONENT_LOGS"
+    )]
+    forward_component_logs: bool,
+    /// Maximum number of log records forwarded per second for each component. Requires `forward_component_logs` to be set.
+    #[clap(
+        long = "component-log-rate-limit",
+        env = "WASMCLOUD_COMPONENT_LOG_RATE_LIMIT",
+        default_value_t = 100,
+        requires = "forward_component_logs"
+    )]
+    component_log_rate_limit: u32,
+
+    /// Reject links whose interfaces are not imported by the source or exported by the target in
+    /// compatible versions, instead of storing them marked as unhealthy
+    #[clap(
+        long = "enforce-link-compatibility",
+        env = "WASMCLOUD_ENFORCE_LINK_COMPATIBILITY"
+    )]
+    enforce_link_compatibility: bool,
+
+    /// If provided, invocations sent by components to a lattice target fail fast after this many consecutive failed invocations of the target
+    #[clap(
+        long = "circuit-breaker-failure-threshold",
+        env = "WASMCLOUD_CIRCUIT_BREAKER_FAILURE_THRESHOLD"
+    )]
+    circuit_breaker_failure_threshold: Option<u32>,
+    /// Time in milliseconds a circuit breaker stays open before a trial invocation is let through. Requires `circuit_breaker_failure_threshold` to be set.
+    #[clap(
+        long = "circuit-breaker-open-duration-ms",
+        env = "WASMCLOUD_CIRCUIT_BREAKER_OPEN_DURATION_MS",
+        default_value = "30000",
+        value_parser = parse_duration_millis,
+        requires = "circuit_breaker_failure_threshold"
+    )]
+    circuit_breaker_open_duration: Duration,
+
+    /// The number of revisions of each named config to keep for history and rollback. Only takes effect if the config bucket of the lattice does not keep this many revisions yet.
+    #[clap(
+        long = "config-history",
+        default_value_t = 10,
+        env = "WASMCLOUD_CONFIG_HISTORY",
+        value_parser = clap::value_parser!(u8).range(1..=64)
+    )]
+    config_history: u8,
+
+    /// A comma-separated list of operator public keys trusted to issue the accounts in `trusted_account_jwts`
+    #[clap(
+        long = "trusted-operators",
+        env = "WASMCLOUD_TRUSTED_OPERATORS",
+        value_delimiter = ','
+    )]
+    trusted_operators: Vec<String>,
+    /// A comma-separated list of account public keys trusted to issue components and providers. If any trusted operators or accounts are provided, only components and providers issued by trusted accounts are started.
+    #[clap(
+        long = "trusted-accounts",
+        env = "WASMCLOUD_TRUSTED_ACCOUNTS",
+        value_delimiter = ','
+    )]
+    trusted_accounts: Vec<String>,
+    /// A comma-separated list of account JWTs, which are trusted along with their signing keys if issued by one of `trusted_operators`
+    #[clap(
+        long = "trusted-account-jwts",
+        env = "WASMCLOUD_TRUSTED_ACCOUNT_JWTS",
+        value_delimiter = ',',
+        requires = "trusted_operators"
+    )]
+    trusted_account_jwts: Vec<String>,
+
+    /// A comma-separated list of public keys of trusted signers. If any are provided, only components and providers signed by at least `required_signature_threshold` of these signers are started, counting both the issuer of their claims and the issuers of their detached co-signatures.
+    #[clap(
+        long = "required-signers",
+        env = "WASMCLOUD_REQUIRED_SIGNERS",
+        value_delimiter = ','
+    )]
+    required_signers: Vec<String>,
+    /// The number of distinct trusted signers in `required_signers` that must have signed components and providers, at least 1
+    #[clap(
+        long = "required-signature-threshold",
+        env = "WASMCLOUD_REQUIRED_SIGNATURE_THRESHOLD",
+        default_value_t = 1,
+        value_parser = parse_threshold,
+        requires = "required_signers"
+    )]
+    required_signature_threshold: usize,
+
+    /// Stop running components and providers once their claims are revoked in the lattice revocation list. Revoked components and providers are never started.
+    #[clap(long = "stop-revoked", env = "WASMCLOUD_STOP_REVOKED")]
+    stop_revoked: bool,
+
+    /// Monitor the expiry of claims of running components and providers, publishing `claims_expiring` events before and once they expire
+    #[clap(
+        long = "monitor-claims-expiry",
+        env = "WASMCLOUD_MONITOR_CLAIMS_EXPIRY"
+    )]
+    monitor_claims_expiry: bool,
+    /// A comma-separated list of lead times in seconds before claims expire at which `claims_expiring` events are published. Requires `monitor_claims_expiry` to be set.
+    #[clap(
+        long = "claims-expiry-lead-times",
+        env = "WASMCLOUD_CLAIMS_EXPIRY_LEAD_TIMES",
+        default_value = "604800,86400",
+        value_delimiter = ',',
+        value_parser = parse_duration_secs,
+        requires = "monitor_claims_expiry"
+    )]
+    claims_expiry_lead_times: Vec<Duration>,
+    /// How often in seconds the claims of running components and providers are checked for expiry. Requires `monitor_claims_expiry` to be set.
+    #[clap(
+        long = "claims-expiry-check-interval-seconds",
+        env = "WASMCLOUD_CLAIMS_EXPIRY_CHECK_INTERVAL",
+        default_value = "60",
+        value_parser = parse_interval_secs,
+        requires = "monitor_claims_expiry"
+    )]
+    claims_expiry_check_interval: Duration,
+    /// What to do once the claims of a running component or provider expire: `warn` only publishes an event, `stop` stops it, and `block` keeps it running but refuses to start or scale components and providers with expired claims. Requires `monitor_claims_expiry` to be set.
+    #[clap(
+        long = "claims-expiry-action",
+        env = "WASMCLOUD_CLAIMS_EXPIRY_ACTION",
+        default_value = "warn",
+        value_parser = parse_claims_expiry_action,
+        requires = "monitor_claims_expiry"
+    )]
+    claims_expiry_action: ClaimsExpiryAction,
 }
 
 const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
@@ -336,6 +543,11 @@ async fn main() -> anyhow::Result<()> {
         protocol: args.observability_protocol.unwrap_or_default(),
         additional_ca_paths: args.tls_ca_paths.clone().unwrap_or_default(),
         trace_level,
+        traces_sampling_ratio: args.traces_sampling_ratio,
+        traces_sampling_parent_based: args.traces_sampling_parent_based,
+        traces_file: args.traces_file,
+        traces_file_max_bytes: args.traces_file_max_bytes,
+        traces_file_max_files: args.traces_file_max_files,
     };
     let log_level = WasmcloudLogLevel::from(args.log_level);
 
@@ -461,12 +673,46 @@ async fn main() -> anyhow::Result<()> {
         otel_config,
         policy_service_config,
         secrets_topic_prefix: args.secrets_topic_prefix,
+        secrets_refresh_interval: args.secrets_refresh_interval,
         version: env!("CARGO_PKG_VERSION").to_string(),
         max_execution_time: args.max_execution_time,
         max_linear_memory: args.max_linear_memory,
         max_component_size: args.max_component_size,
         max_components: args.max_components,
         heartbeat_interval: args.heartbeat_interval,
+        http_admin: args.http_admin,
+        event_stream: args.event_stream.then_some(EventStreamConfig {
+            max_age: args.event_stream_max_age,
+            max_messages: args.event_stream_max_messages,
+            max_bytes: args.event_stream_max_bytes,
+        }),
+        log_forwarding: args.forward_component_logs.then_some(LogForwardingConfig {
+            max_records_per_second: args.component_log_rate_limit,
+        }),
+        enforce_link_compatibility: args.enforce_link_compatibility,
+        circuit_breaker: args
+            .circuit_breaker_failure_threshold
+            .map(|failure_threshold| CircuitBreakerConfig {
+                failure_threshold,
+                open_duration: args.circuit_breaker_open_duration,
+            }),
+        config_history: args.config_history,
+        trusted_issuers: (!args.trusted_operators.is_empty() || !args.trusted_accounts.is_empty())
+            .then_some(TrustedIssuers {
+                operators: args.trusted_operators,
+                accounts: args.trusted_accounts,
+                account_jwts: args.trusted_account_jwts,
+            }),
+        stop_revoked: args.stop_revoked,
+        claims_expiry: args.monitor_claims_expiry.then_some(ClaimsExpiryConfig {
+            check_interval: args.claims_expiry_check_interval,
+            lead_times: args.claims_expiry_lead_times,
+            action: args.claims_expiry_action,
+        }),
+        required_signatures: (!args.required_signers.is_empty()).then_some(RequiredSignatures {
+            signers: args.required_signers,
+            threshold: args.required_signature_threshold,
+        }),
     }))
     .await
     .context("failed to initialize host")?;
@@ -528,6 +774,37 @@ fn parse_duration_secs(arg: &str) -> anyhow::Result<Duration> {
         .map_err(|e| anyhow::anyhow!(e))
 }
 
+/// Parses a non-zero interval, interpreted as seconds
+fn parse_interval_secs(arg: &str) -> anyhow::Result<Duration> {
+    let interval = parse_duration_secs(arg)?;
+    anyhow::ensure!(!interval.is_zero(), "interval must be at least 1 second");
+    Ok(interval)
+}
+
+fn parse_threshold(arg: &str) -> anyhow::Result<usize> {
+    let threshold: usize = arg.parse().map_err(|e| anyhow::anyhow!("{e}"))?;
+    anyhow::ensure!(threshold >= 1, "threshold must be at least 1");
+    Ok(threshold)
+}
+
+fn parse_ratio(arg: &str) -> anyhow::Result<f64> {
+    let ratio: f64 = arg.parse().map_err(|e| anyhow::anyhow!("{e}"))?;
+    anyhow::ensure!(
+        (0.0..=1.0).contains(&ratio),
+        "ratio must be between 0.0 and 1.0"
+    );
+    Ok(ratio)
+}
+
+fn parse_claims_expiry_action(arg: &str) -> anyhow::Result<ClaimsExpiryAction> {
+    match arg {
+        "warn" => Ok(ClaimsExpiryAction::Warn),
+        "stop" => Ok(ClaimsExpiryAction::Stop),
+        "block" => Ok(ClaimsExpiryAction::Block),
+        _ => bail!("invalid claims expiry action `{arg}`. Expected `warn`, `stop` or `block`"),
+    }
+}
+
 fn parse_label(labelpair: &str) -> anyhow::Result<(String, String)> {
     match labelpair.split('=').collect::<Vec<&str>>()[..] {
         [k, v] => Ok((k.to_string(), v.to_string())),
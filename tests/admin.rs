@@ -0,0 +1,113 @@
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::Duration;
+
+use anyhow::{ensure, Context as _, Result};
+use futures::StreamExt as _;
+use reqwest::StatusCode;
+use tokio::time::{sleep, timeout};
+use wasmcloud_control_interface::HostReadiness;
+use wasmcloud_host::wasmbus::{Host, HostConfig};
+
+pub mod common;
+use common::free_port;
+use common::nats::start_nats;
+
+const LATTICE: &str = "admin";
+
+/// Wait until `GET /readyz` returns `status`, returning the last reported readiness
+async fn await_readyz(
+    client: &reqwest::Client,
+    url: &str,
+    status: StatusCode,
+) -> Result<HostReadiness> {
+    timeout(Duration::from_secs(10), async {
+        loop {
+            let res = client.get(url).send().await?;
+            if res.status() == status {
+                return res
+                    .json::<HostReadiness>()
+                    .await
+                    .context("failed to decode readiness");
+            }
+            sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .with_context(|| format!("timed out waiting for `/readyz` to return {status}"))?
+}
+
+/// Ensure that the admin endpoints report liveness and readiness, and that supplemental config
+/// is requested again until it is loaded
+#[tokio::test(flavor = "multi_thread")]
+async fn admin_readiness() -> Result<()> {
+    let (nats_server, nats_url, nats_client, _) =
+        start_nats().await.context("failed to start NATS")?;
+
+    let admin_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, free_port().await?));
+    let (_host, shutdown) = Host::new(HostConfig {
+        ctl_nats_url: nats_url.clone(),
+        rpc_nats_url: nats_url.clone(),
+        lattice: LATTICE.into(),
+        heartbeat_interval: Some(Duration::from_millis(200)),
+        http_admin: Some(admin_addr),
+        config_service_enabled: true,
+        ..Default::default()
+    })
+    .await
+    .context("failed to start host")?;
+
+    let client = reqwest::Client::new();
+    let livez = client
+        .get(format!("http://{admin_addr}/livez"))
+        .send()
+        .await
+        .context("failed to query `/livez`")?;
+    ensure!(livez.status() == StatusCode::OK);
+
+    // Nothing responds on the config service topic yet
+    let readyz = format!("http://{admin_addr}/readyz");
+    let readiness = await_readyz(&client, &readyz, StatusCode::SERVICE_UNAVAILABLE).await?;
+    ensure!(readiness.nats_connected);
+    ensure!(readiness.buckets_opened);
+    ensure!(!readiness.supplemental_config_loaded);
+
+    // Once the config service responds, the host becomes ready without a restart
+    let mut requests = nats_client
+        .subscribe(format!("wasmbus.cfg.{LATTICE}.req"))
+        .await
+        .context("failed to subscribe to config service topic")?;
+    let config_service = tokio::spawn({
+        let nats_client = nats_client.clone();
+        async move {
+            while let Some(request) = requests.next().await {
+                if let Some(reply) = request.reply {
+                    nats_client
+                        .publish(reply, "{}".into())
+                        .await
+                        .context("failed to respond to config request")?;
+                }
+            }
+            anyhow::Ok(())
+        }
+    });
+    let readiness = await_readyz(&client, &readyz, StatusCode::OK).await?;
+    ensure!(readiness.is_ready());
+
+    // Heartbeats carry the cached readiness
+    let mut heartbeats = nats_client
+        .subscribe(format!("wasmbus.evt.{LATTICE}.host_heartbeat"))
+        .await
+        .context("failed to subscribe to heartbeats")?;
+    let heartbeat = timeout(Duration::from_secs(5), heartbeats.next())
+        .await
+        .context("timed out waiting for heartbeat")?
+        .context("heartbeat subscription ended")?;
+    let heartbeat: serde_json::Value =
+        serde_json::from_slice(&heartbeat.payload).context("failed to decode heartbeat")?;
+    ensure!(heartbeat["data"]["readiness"]["supplemental_config_loaded"] == true);
+
+    config_service.abort();
+    shutdown.await.context("failed to shutdown host")?;
+    nats_server.stop().await.context("failed to stop NATS")?;
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, ensure, Context as _, Result};
+use async_nats::jetstream;
+use nkeys::KeyPair;
+use tokio::time::timeout;
+use wasmcloud_control_interface::{
+    host_event_stream_name, host_event_stream_subject, ClientBuilder, EventReplayStart, HostEvent,
+};
+use wasmcloud_host::wasmbus::host_config::EventStream;
+use wasmcloud_host::wasmbus::{Host, HostConfig};
+
+pub mod common;
+use common::nats::start_nats;
+
+const LATTICE: &str = "events";
+
+/// Ensure that events written to the event stream can be replayed, and that an existing stream
+/// keeps the retention it was configured with
+#[tokio::test(flavor = "multi_thread")]
+async fn event_stream_replay() -> Result<()> {
+    let (nats_server, nats_url, nats_client, nats_client_0_33) =
+        start_nats().await.context("failed to start NATS")?;
+
+    // The stream was already configured by an operator
+    let stream_name = host_event_stream_name(LATTICE);
+    let jetstream = jetstream::new(nats_client.clone());
+    jetstream
+        .create_stream(jetstream::stream::Config {
+            name: stream_name.clone(),
+            subjects: vec![host_event_stream_subject(LATTICE, "*")],
+            max_age: Duration::from_secs(60),
+            max_bytes: 4096 * 1024,
+            ..Default::default()
+        })
+        .await
+        .context("failed to create event stream")?;
+
+    let host_key = Arc::new(KeyPair::new_server());
+    let (_host, shutdown) = Host::new(HostConfig {
+        ctl_nats_url: nats_url.clone(),
+        rpc_nats_url: nats_url.clone(),
+        lattice: LATTICE.into(),
+        host_key: Some(Arc::clone(&host_key)),
+        event_stream: Some(EventStream {
+            max_messages: Some(100),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })
+    .await
+    .context("failed to start host")?;
+
+    // Only the explicitly configured retention is applied
+    let mut stream = jetstream
+        .get_stream(&stream_name)
+        .await
+        .context("failed to get event stream")?;
+    let config = &stream
+        .info()
+        .await
+        .context("failed to get event stream info")?
+        .config;
+    ensure!(config.max_age == Duration::from_secs(60));
+    ensure!(config.max_bytes == 4096 * 1024);
+    ensure!(config.max_messages == 100);
+
+    let ctl_client = ClientBuilder::new(nats_client_0_33)
+        .lattice(LATTICE.to_string())
+        .build();
+    let res = ctl_client
+        .put_label(&host_key.public_key(), "replay", "test")
+        .await
+        .map_err(|e| anyhow!(e).context("failed to put label"))?;
+    ensure!(res.success);
+
+    // Replay from the start, only the label change
+    let mut events = ctl_client
+        .replay_events(EventReplayStart::All, vec!["labels_changed".to_string()])
+        .await
+        .map_err(|e| anyhow!(e).context("failed to replay events"))?;
+    let replayed = timeout(Duration::from_secs(5), events.recv())
+        .await
+        .context("timed out waiting for replayed event")?
+        .context("replay ended")?;
+    let HostEvent::LabelsChanged(labels_changed) = HostEvent::try_from(&replayed.event)
+        .map_err(|e| anyhow!(e).context("failed to parse replayed event"))?
+    else {
+        bail!("unexpected replayed event {:?}", replayed.event);
+    };
+    ensure!(labels_changed.labels.get("replay").map(String::as_str) == Some("test"));
+
+    // Resuming after the label change replays nothing older, like the host start
+    let mut events = ctl_client
+        .replay_events(
+            EventReplayStart::Sequence(replayed.sequence + 1),
+            Vec::new(),
+        )
+        .await
+        .map_err(|e| anyhow!(e).context("failed to replay events"))?;
+    if let Ok(Some(replayed)) = timeout(Duration::from_millis(500), events.recv()).await {
+        let event = HostEvent::try_from(&replayed.event)
+            .map_err(|e| anyhow!(e).context("failed to parse replayed event"))?;
+        ensure!(
+            !matches!(
+                event,
+                HostEvent::HostStarted(_) | HostEvent::LabelsChanged(_)
+            ),
+            "replayed event before the start sequence"
+        );
+    }
+
+    shutdown.await.context("failed to shutdown host")?;
+    nats_server.stop().await.context("failed to stop NATS")?;
+    Ok(())
+}
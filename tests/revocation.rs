@@ -0,0 +1,124 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, ensure, Context as _, Result};
+use async_nats::jetstream;
+use futures::StreamExt as _;
+use nkeys::KeyPair;
+use test_components::RUST_PONGER_CONFIG_COMPONENT_PREVIEW2_SIGNED;
+use tokio::time::{sleep, timeout};
+use wasmcloud_core::revocation::{revocation_bucket, Revocation, RevocationKind};
+use wasmcloud_host::wasmbus::{Host, HostConfig};
+use wasmcloud_test_util::component::assert_scale_component;
+
+pub mod common;
+use common::nats::start_nats;
+
+const LATTICE: &str = "revocation";
+const COMPONENT_ID: &str = "ponger_component";
+
+/// Ensure that a running component is stopped once its claims are revoked, and that it is refused
+/// to be started again
+#[tokio::test(flavor = "multi_thread")]
+async fn revoked_components() -> Result<()> {
+    let (nats_server, nats_url, nats_client, nats_client_0_33) =
+        start_nats().await.context("failed to start NATS")?;
+
+    let host_key = Arc::new(KeyPair::new_server());
+    let (_host, shutdown) = Host::new(HostConfig {
+        ctl_nats_url: nats_url.clone(),
+        rpc_nats_url: nats_url.clone(),
+        lattice: LATTICE.into(),
+        host_key: Some(Arc::clone(&host_key)),
+        allow_file_load: true,
+        stop_revoked: true,
+        ..Default::default()
+    })
+    .await
+    .context("failed to start host")?;
+    let ctl_client = wasmcloud_control_interface::ClientBuilder::new(nats_client_0_33)
+        .lattice(LATTICE.to_string())
+        .build();
+
+    let url = format!("file://{RUST_PONGER_CONFIG_COMPONENT_PREVIEW2_SIGNED}");
+    assert_scale_component(
+        &ctl_client,
+        &host_key,
+        &url,
+        COMPONENT_ID,
+        None,
+        1,
+        Vec::new(),
+    )
+    .await
+    .context("failed to scale component")?;
+
+    let component = tokio::fs::read(RUST_PONGER_CONFIG_COMPONENT_PREVIEW2_SIGNED).await?;
+    let subject = wascap::wasm::extract_claims(&component)?
+        .context("component should be signed")?
+        .claims
+        .subject;
+    let revocations = jetstream::new(nats_client.clone())
+        .get_key_value(revocation_bucket(LATTICE))
+        .await
+        .context("failed to open revocation bucket")?;
+    revocations
+        .put(
+            RevocationKind::Subject.key(&subject),
+            serde_json::to_vec(&Revocation {
+                reason: Some("compromised".into()),
+                ..Default::default()
+            })?
+            .into(),
+        )
+        .await
+        .context("failed to revoke component")?;
+
+    // The running component is stopped
+    timeout(Duration::from_secs(10), async {
+        loop {
+            let inventory = ctl_client
+                .get_host_inventory(&host_key.public_key())
+                .await
+                .map_err(|e| anyhow!(e).context("failed to get host inventory"))?
+                .response
+                .context("missing host inventory")?;
+            if inventory.components.is_empty() {
+                return anyhow::Ok(());
+            }
+            sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .context("timed out waiting for revoked component to stop")??;
+
+    // Starting it again is refused
+    let mut failures = nats_client
+        .subscribe(format!("wasmbus.evt.{LATTICE}.component_scale_failed"))
+        .await
+        .context("failed to subscribe to component_scale_failed events")?;
+    ctl_client
+        .scale_component(
+            &host_key.public_key(),
+            &url,
+            COMPONENT_ID,
+            1,
+            None,
+            Vec::new(),
+        )
+        .await
+        .map_err(|e| anyhow!(e).context("failed to request component scale"))?;
+    let failure = timeout(Duration::from_secs(10), failures.next())
+        .await
+        .context("timed out waiting for component_scale_failed event")?
+        .context("component_scale_failed subscription ended")?;
+    let failure: serde_json::Value =
+        serde_json::from_slice(&failure.payload).context("failed to decode event")?;
+    ensure!(failure["data"]["error"]
+        .as_str()
+        .is_some_and(|error| error.contains("revoked")));
+
+    shutdown.await.context("failed to shutdown host")?;
+    nats_server.stop().await.context("failed to stop NATS")?;
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use anyhow::{ensure, Context as _, Result};
+use common::secrets::NatsKvSecretsBackend;
+use futures::StreamExt as _;
+use secrets_nats_kv::PutSecretRequest;
+use test_components::RUST_PONGER_CONFIG_COMPONENT_PREVIEW2_SIGNED;
+use tokio::time::timeout;
+use wasmcloud_test_util::component::assert_scale_component;
+use wasmcloud_test_util::host::WasmCloudTestHost;
+use wasmcloud_test_util::lattice::config::assert_put_secret_reference;
+
+pub mod common;
+use common::nats::start_nats;
+
+const LATTICE: &str = "secrets";
+const COMPONENT_ID: &str = "ponger_component";
+
+/// Ensure that secrets of running components are re-fetched when the secrets backend notifies
+/// hosts that secrets changed
+#[tokio::test(flavor = "multi_thread")]
+async fn secrets_rotation() -> Result<()> {
+    let (nats_server, nats_url, nats_client, nats_client_0_33) =
+        start_nats().await.context("failed to start NATS")?;
+
+    let ctl_client = wasmcloud_control_interface::ClientBuilder::new(nats_client_0_33)
+        .lattice(LATTICE.to_string())
+        .build();
+    let host = WasmCloudTestHost::start_custom(
+        &nats_url,
+        LATTICE,
+        None,
+        None,
+        None,
+        Some("wasmcloud.secrets".to_string()),
+    )
+    .await
+    .context("failed to start test host")?;
+
+    let backend = NatsKvSecretsBackend::new(
+        "wasmcloud.secrets".to_string(),
+        "TEST_SECRET_rotation".to_string(),
+        nats_url.to_string(),
+    )
+    .await?;
+    backend.ensure_build().await?;
+    let backend_server = backend.start().await?;
+    backend
+        .put_secret(PutSecretRequest {
+            key: "rotated".to_string(),
+            string_secret: Some("first".to_string()),
+            ..Default::default()
+        })
+        .await?;
+
+    let component = tokio::fs::read(RUST_PONGER_CONFIG_COMPONENT_PREVIEW2_SIGNED).await?;
+    let component_claims = wascap::wasm::extract_claims(&component)?
+        .context("component should be signed")?
+        .claims;
+    backend
+        .add_mapping(
+            &component_claims.subject,
+            HashSet::from(["rotated".to_string()]),
+        )
+        .await?;
+    assert_put_secret_reference(
+        &ctl_client,
+        "rotated",
+        "rotated",
+        "nats-kv",
+        None,
+        None,
+        HashMap::with_capacity(0),
+    )
+    .await?;
+    assert_scale_component(
+        &ctl_client,
+        &host.host_key(),
+        format!("file://{RUST_PONGER_CONFIG_COMPONENT_PREVIEW2_SIGNED}"),
+        COMPONENT_ID,
+        None,
+        1,
+        vec!["SECRET_rotated".to_string()],
+    )
+    .await
+    .context("failed to scale component")?;
+
+    // Putting a new value notifies the host, which re-fetches the secrets of the component
+    let mut rotations = nats_client
+        .subscribe(format!("wasmbus.evt.{LATTICE}.secrets_rotated"))
+        .await
+        .context("failed to subscribe to secrets_rotated events")?;
+    backend
+        .put_secret(PutSecretRequest {
+            key: "rotated".to_string(),
+            string_secret: Some("second".to_string()),
+            ..Default::default()
+        })
+        .await?;
+    let rotated = timeout(Duration::from_secs(10), rotations.next())
+        .await
+        .context("timed out waiting for secrets_rotated event")?
+        .context("secrets_rotated subscription ended")?;
+    let rotated: serde_json::Value =
+        serde_json::from_slice(&rotated.payload).context("failed to decode event")?;
+    ensure!(rotated["data"]["entity_id"] == COMPONENT_ID);
+    ensure!(rotated["data"]["secret_names"] == serde_json::json!(["rotated"]));
+
+    backend_server
+        .stop()
+        .await
+        .context("failed to stop secrets backend")?;
+    nats_server.stop().await.context("failed to stop NATS")?;
+    Ok(())
+}